@@ -0,0 +1,75 @@
+//! Compares graph construction and traversal over three degree profiles,
+//! with arrows kept in [`ArrowStorage`](dagex::core::ArrowStorage).
+//!
+//! ```text
+//! cargo bench -p dagex --bench degree_profiles
+//! ```
+use std::time::{Duration, Instant};
+
+use dagex::core::{ArrowDTO, DirectedGraph, DirectedGraphDTO};
+
+const NUMBER_OF_NODES: i32 = 50_000;
+const ITERATIONS: u32 = 5;
+
+/// Deterministic linear congruential generator, good enough for building
+/// random DAGs.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: i32) -> i32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % (bound as u64)) as i32
+    }
+}
+
+/// Builds bipartite DAG where each of the last 90% of nodes has up to
+/// `in_degree` predecessors among the first 10% of nodes. Bipartite shape
+/// keeps the number of oriented paths linear, so construction cost is
+/// dominated by arrow maps rather than by acyclicity verification.
+fn build_dto(in_degree: i32, seed: u64) -> DirectedGraphDTO {
+    let mut rng = Lcg(seed);
+    let mut arrows = Vec::new();
+    let number_of_sources = NUMBER_OF_NODES / 10;
+    for target in number_of_sources..NUMBER_OF_NODES {
+        let mut sources: Vec<i32> = (0..in_degree)
+            .map(|_| rng.next(number_of_sources))
+            .collect();
+        sources.sort_unstable();
+        sources.dedup();
+        arrows.extend(sources.into_iter().map(|source| ArrowDTO::new(source, target)));
+    }
+    DirectedGraphDTO::new(NUMBER_OF_NODES, arrows)
+}
+
+fn traverse(graph: &DirectedGraph) -> usize {
+    graph.iter_nodes()
+        .map(|node| graph.get_successors(node).len() + graph.get_predecessors(node).len())
+        .sum()
+}
+
+fn measure(name: &str, in_degree: i32) {
+    let dto = build_dto(in_degree, 0x5eed);
+    let mut construction = Duration::ZERO;
+    let mut traversal = Duration::ZERO;
+    let mut checksum = 0;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        let graph = DirectedGraph::from_dto(&dto).unwrap();
+        construction += start.elapsed();
+
+        let start = Instant::now();
+        checksum += traverse(&graph);
+        traversal += start.elapsed();
+    }
+    println!(
+        "{name:>8}: arrows {:>9}, construction {:>10.2?}, traversal {:>10.2?}, checksum {checksum}",
+        dto.arrows().len(),
+        construction / ITERATIONS,
+        traversal / ITERATIONS);
+}
+
+fn main() {
+    measure("sparse", 1);
+    measure("binary", 2);
+    measure("dense", 20);
+}
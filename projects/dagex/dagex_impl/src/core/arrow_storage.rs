@@ -0,0 +1,185 @@
+use core::fmt::{Debug, Formatter};
+
+use super::{ArrowList, Node};
+
+/// Successors or predecessors of all nodes in compressed sparse row layout.
+/// Neighbours of all nodes are kept in a single buffer, node `i` owns
+/// `nodes[offsets[i]..offsets[i + 1]]`. Thus the whole storage takes two
+/// allocations regardless of degrees.
+#[derive(PartialEq, Eq, Clone, Default)]
+pub struct ArrowStorage {
+    offsets: Vec<u32>,
+    nodes: Vec<Node>,
+}
+
+static _EMPTY: &[Node] = &[];
+
+impl ArrowStorage {
+    /// Creates storage out of raw parts. `offsets` has one entry per node
+    /// plus a final one, equal to `nodes.len()`.
+    ///
+    /// # Panics
+    /// When `offsets` is empty, doesn't start with 0, is decreasing at some
+    /// point, or doesn't end with `nodes.len()`.
+    pub fn from_raw_parts(offsets: Vec<u32>, nodes: Vec<Node>) -> Self {
+        assert!(offsets.first() == Some(&0), "Offsets have to start with 0.");
+        assert!(
+            offsets.windows(2).all(|pair| pair[0] <= pair[1]),
+            "Offsets have to be non-decreasing.");
+        assert!(
+            offsets.last().map(|last| *last as usize) == Some(nodes.len()),
+            "Offsets have to end with the number of nodes.");
+        Self { offsets: offsets, nodes: nodes }
+    }
+
+    /// Converts per node lists, i.e. the former [`ArrowMap`](super::ArrowMap)
+    /// layout.
+    ///
+    /// # Panics
+    /// When total number of neighbours exceeds [`u32::MAX`].
+    pub fn from_lists(lists: &[ArrowList]) -> Self {
+        let mut offsets = Vec::with_capacity(lists.len() + 1);
+        let mut nodes = Vec::with_capacity(lists.iter().map(ArrowList::len).sum());
+        offsets.push(0);
+        for list in lists {
+            nodes.extend_from_slice(list);
+            offsets.push(u32::try_from(nodes.len()).expect("Too many arrows."));
+        }
+        Self { offsets: offsets, nodes: nodes }
+    }
+
+    /// Builds storage of `number_of_nodes` nodes out of `(key, value)`
+    /// pairs, in two passes over `pairs`. Lists are sorted by node id, but
+    /// duplicates are kept, see [`ArrowStorage::has_duplicates`]. Nodes
+    /// have to be within `0..number_of_nodes` and there can be at most
+    /// [`u32::MAX`] pairs.
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn from_pairs<I>(number_of_nodes: usize, pairs: I) -> Self
+        where I: Iterator<Item=(Node, Node)> + Clone
+    {
+        // Counts go one slot to the right, so that after prefix sums
+        // `offsets[i]` is the start of node `i`. Filling advances starts to
+        // ends, which are then shifted back into place.
+        let mut offsets = vec![0u32; number_of_nodes + 1];
+        for (key, _) in pairs.clone() {
+            offsets[key.id() as usize + 1] += 1;
+        }
+        for idx in 1..offsets.len() {
+            offsets[idx] += offsets[idx - 1];
+        }
+
+        let mut nodes = vec![Node::from(0); offsets[number_of_nodes] as usize];
+        for (key, value) in pairs {
+            let position = &mut offsets[key.id() as usize];
+            nodes[*position as usize] = value;
+            *position += 1;
+        }
+        offsets.copy_within(0..number_of_nodes, 1);
+        offsets[0] = 0;
+
+        let mut result = Self { offsets: offsets, nodes: nodes };
+        for idx in 0..number_of_nodes {
+            let range = result.range(idx);
+            result.nodes[range].sort_unstable_by_key(Node::id);
+        }
+        result
+    }
+
+    /// Number of nodes.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Total number of neighbours, over all nodes.
+    #[inline(always)]
+    pub fn number_of_arrows(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Neighbours of `node`. Empty if `node` is outside of storage.
+    #[allow(clippy::cast_sign_loss)]
+    #[inline(always)]
+    pub fn get(&self, node: Node) -> &[Node] {
+        let numeric_id = node.id();
+        if numeric_id < 0 || numeric_id as usize >= self.len() {
+            _EMPTY
+        }
+        else
+        {
+            &self.nodes[self.range(numeric_id as usize)]
+        }
+    }
+
+    /// Iterates over neighbours of all nodes, in order of node ids.
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item=&[Node]> + '_ {
+        self.offsets.windows(2)
+            .map(|pair| &self.nodes[pair[0] as usize..pair[1] as usize])
+    }
+
+    /// Checks whether some node has the same neighbour more than once.
+    /// Requires sorted lists.
+    pub(crate) fn has_duplicates(&self) -> bool {
+        self.iter().any(|list| list.windows(2).any(|pair| pair[0] == pair[1]))
+    }
+
+    /// Returns a copy with `value` inserted to sorted neighbours of `key`.
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn with_inserted(&self, key: Node, value: Node) -> Self {
+        let idx = key.id() as usize;
+        let range = self.range(idx);
+        let position = range.start + self.nodes[range]
+            .partition_point(|node| node.id() < value.id());
+        let mut result = self.clone();
+        result.nodes.insert(position, value);
+        for offset in &mut result.offsets[idx + 1..] {
+            *offset += 1;
+        }
+        result
+    }
+
+    /// Returns a copy with `value` removed from neighbours of `key`, if
+    /// present.
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn with_removed(&self, key: Node, value: Node) -> Self {
+        let idx = key.id() as usize;
+        let range = self.range(idx);
+        let mut result = self.clone();
+        if let Some(position) = self.nodes[range.clone()].iter().position(|node| *node == value) {
+            result.nodes.remove(range.start + position);
+            for offset in &mut result.offsets[idx + 1..] {
+                *offset -= 1;
+            }
+        }
+        result
+    }
+
+    /// Returns a copy with an additional node without neighbours.
+    pub(crate) fn with_node_added(&self) -> Self {
+        let mut result = self.clone();
+        let last = result.offsets.last().copied().unwrap_or(0);
+        if result.offsets.is_empty() {
+            result.offsets.push(0);
+        }
+        result.offsets.push(last);
+        result
+    }
+
+    #[inline(always)]
+    fn range(&self, idx: usize) -> core::ops::Range<usize> {
+        self.offsets[idx] as usize..self.offsets[idx + 1] as usize
+    }
+}
+
+/// Same as of per node lists, e.g. `[[1, 2], [], []]`.
+impl Debug for ArrowStorage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
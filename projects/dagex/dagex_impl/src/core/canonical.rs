@@ -0,0 +1,124 @@
+use std::collections::VecDeque;
+
+use super::{ArrowDTO, DirectedGraph, DirectedGraphDTO, Node};
+
+impl DirectedGraph {
+    /// Returns DTO of the graph with nodes renumbered in breadth-first
+    /// order and arrows sorted by `(source, target)`. Traversal starts at
+    /// the root, or, for unrooted graphs, at each weakly connected
+    /// component in turn, and follows successors and then predecessors.
+    /// Nodes are picked in the order of structural colors, obtained by
+    /// refining in- and out-degree signatures with colors of neighbours, so
+    /// the result depends neither on the order arrows were added in nor on
+    /// original ids. Arrows keep their weights.
+    ///
+    /// Only nodes which color refinement can't tell apart, e.g. nodes of
+    /// some regular graphs, are ordered by original ids. In typical graphs
+    /// such nodes are symmetric, and their order doesn't change the result.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn to_canonical_dto(&self) -> DirectedGraphDTO {
+        let colors = self.refined_colors();
+        self.to_canonical_dto_with_mapping_by(|node| (colors[node.id() as usize], node.id())).0
+    }
+
+    /// Returns DTO of the graph renumbered like in
+    /// [`DirectedGraph::to_canonical_dto`], but with nodes picked in
+    /// ascending order of `key`. Additionally returns the mapping from
+    /// original ids to new ids.
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub(crate) fn to_canonical_dto_with_mapping_by<K, F>(&self, mut key: F) -> (DirectedGraphDTO, Vec<i32>)
+        where K: Ord, F: FnMut(&Node) -> K
+    {
+        let size = self.number_of_nodes() as usize;
+        let mut new_ids = vec![-1; size];
+        let mut next_id = 0;
+        let mut queue = VecDeque::new();
+        let mut neighbours = Vec::<Node>::new();
+        let mut nodes: Vec<Node> = self.iter_nodes().collect();
+        nodes.sort_by_key(&mut key);
+        let starts = self.root().into_iter().chain(nodes);
+        for start in starts {
+            if new_ids[start.id() as usize] >= 0 {
+                continue;
+            }
+            new_ids[start.id() as usize] = next_id;
+            next_id += 1;
+            queue.push_back(start);
+            while let Some(node) = queue.pop_front() {
+                neighbours.clear();
+                neighbours.extend_from_slice(self.get_successors(node));
+                neighbours.sort_unstable_by_key(&mut key);
+                let successors_count = neighbours.len();
+                neighbours.extend_from_slice(self.get_predecessors(node));
+                neighbours[successors_count..].sort_unstable_by_key(&mut key);
+                for neighbour in &neighbours {
+                    let new_id = &mut new_ids[neighbour.id() as usize];
+                    if *new_id < 0 {
+                        *new_id = next_id;
+                        next_id += 1;
+                        queue.push_back(*neighbour);
+                    }
+                }
+            }
+        }
+
+        let mut arrows: Vec<ArrowDTO> = self.iter_arrows()
+            .map(|(source, target)| ArrowDTO::new(
+                new_ids[source.id() as usize],
+                new_ids[target.id() as usize])
+                .with_weight(self.arrow_weight(source, target)))
+            .collect();
+        arrows.sort_unstable_by_key(|arrow| (arrow.source(), arrow.target()));
+        (DirectedGraphDTO::new(self.number_of_nodes(), arrows), new_ids)
+    }
+
+    /// Colors nodes by structure only. Initially all nodes share a color.
+    /// Each round recolors nodes by their color together with sorted
+    /// colors of successors and of predecessors, each paired with the
+    /// weight of the connecting arrow, until the number of colors stops
+    /// growing. Colors are ranks of sorted signatures, so equal graphs with
+    /// differently numbered nodes get equal colors of corresponding nodes.
+    #[allow(clippy::cast_sign_loss)]
+    fn refined_colors(&self) -> Vec<usize> {
+        // Color of a neighbour together with bits of the weight of the arrow.
+        type Neighbour = (usize, Option<u64>);
+        type Signature = (usize, Vec<Neighbour>, Vec<Neighbour>);
+        let size = self.number_of_nodes() as usize;
+        let mut colors = vec![0; size];
+        let mut number_of_colors = 1;
+        loop {
+            let signatures: Vec<Signature> = self.iter_nodes()
+                .map(|node| {
+                    let mut successors: Vec<Neighbour> = self.get_successors(node)
+                        .iter()
+                        .map(|successor| (
+                            colors[successor.id() as usize],
+                            self.arrow_weight(node, *successor).map(f64::to_bits)))
+                        .collect();
+                    successors.sort_unstable();
+                    let mut predecessors: Vec<Neighbour> = self.get_predecessors(node)
+                        .iter()
+                        .map(|predecessor| (
+                            colors[predecessor.id() as usize],
+                            self.arrow_weight(*predecessor, node).map(f64::to_bits)))
+                        .collect();
+                    predecessors.sort_unstable();
+                    (colors[node.id() as usize], successors, predecessors)
+                })
+                .collect();
+            let mut order: Vec<usize> = (0..size).collect();
+            order.sort_unstable_by(|left, right| signatures[*left].cmp(&signatures[*right]));
+            let mut next_color = 0;
+            for (idx, node) in order.iter().enumerate() {
+                if idx > 0 && signatures[order[idx - 1]] != signatures[*node] {
+                    next_color += 1;
+                }
+                colors[*node] = next_color;
+            }
+            if next_color + 1 == number_of_colors {
+                return colors;
+            }
+            number_of_colors = next_color + 1;
+        }
+    }
+}
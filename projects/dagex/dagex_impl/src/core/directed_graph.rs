@@ -1,14 +1,41 @@
-use core::fmt::{Debug, Formatter};
+use core::fmt::{Debug, Display, Formatter};
+#[cfg(feature = "test-utils")]
+use core::cell::Cell;
 use core::hash::{Hash, Hasher};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use smallvec::SmallVec;
 
 use crate::create_u32_hasher;
 
-use super::{ArrowDTO, DirectedGraphDTO, GraphId, Node};
-
-type ArrowMap = Vec<SmallVec<[Node; 2]>>;
+use super::{
+    write_edge_list,
+    ArrowDTO,
+    ArrowIter,
+    ArrowStorage,
+    BfsIter,
+    DirectedGraphDTO,
+    DEFAULT_EDGE_LIST_LIMIT,
+    GraphId,
+    GraphRead,
+    LevelIter,
+    Node,
+    NodeIter,
+    PathsIter,
+    ReachableIter,
+    TopologicalOrderIter,
+    TraversalIter,
+    TraversalOrder};
+
+/// Successors or predecessors of a single node. Stores up to 2 arrows
+/// inline, which fits binary networks. Note that [`DirectedGraph`] itself
+/// uses [`ArrowStorage`].
+pub type ArrowList = SmallVec<[Node; 2]>;
+
+/// Successors or predecessors of all nodes, indexed by node id. Former
+/// storage of [`DirectedGraph`], accepted by
+/// [`DirectedGraph::from_arrow_maps_unchecked`].
+pub type ArrowMap = Vec<ArrowList>;
 
 #[allow(clippy::struct_excessive_bools)]
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
@@ -37,17 +64,16 @@ pub struct DirectedGraphBasicProperties {
 pub struct DirectedGraph {
     id: GraphId,
     number_of_nodes: i32,
-    successors_map: ArrowMap,
-    predecessors_map: ArrowMap,
+    number_of_arrows: usize,
+    successors: ArrowStorage,
+    predecessors: ArrowStorage,
     leaves: HashSet<Node>,
     root_node: Option<Node>,
     hash_value: u32,
     basic_properties: DirectedGraphBasicProperties,
+    arrow_weights: HashMap<(Node, Node), f64>,
 }
 
-static _EMPTY: &[Node] = &[];
-
-
 impl DirectedGraph {
     #[inline(always)]
     pub const fn max_size() -> i32 {
@@ -65,19 +91,232 @@ impl DirectedGraph {
         self.number_of_nodes
     }
 
+    /// Retrieves total number of arrows in the graph. Calculated once, at
+    /// construction.
+    #[inline(always)]
+    pub fn number_of_arrows(&self) -> usize {
+        self.number_of_arrows
+    }
+
+    /// Number of successors of `node`. 0 if `node` is not in the graph.
+    #[inline(always)]
+    pub fn out_degree(&self, node: Node) -> usize {
+        self.get_successors(node).len()
+    }
+
+    /// Number of predecessors of `node`. 0 if `node` is not in the graph.
+    #[inline(always)]
+    pub fn in_degree(&self, node: Node) -> usize {
+        self.get_predecessors(node).len()
+    }
+
+    #[inline(always)]
+    pub fn iter_nodes(&self) -> NodeIter {
+        NodeIter::new(self.number_of_nodes)
+    }
+
+    /// Returns compact, human-readable description of the graph, e.g.
+    /// `DG(n=4): 0->1, 0->2, 1->3, 2->3`. Arrows are sorted, and at most
+    /// `limit` of them are written followed by an ellipsis with the total
+    /// count. Used by the [`Display`] implementation.
+    pub fn to_edge_list_string(&self, limit: usize) -> String {
+        let arrows = self.iter_arrows()
+            .map(|(source, target)| (source.id(), target.id()))
+            .collect();
+        let mut result = String::new();
+        write_edge_list(&mut result, self.number_of_nodes, arrows, limit);
+        result
+    }
+
+    /// Lazily iterates over all arrows as `(source, target)` pairs, ordered
+    /// by source and then by target ids.
+    #[inline(always)]
+    pub fn iter_arrows(&self) -> ArrowIter<'_> {
+        ArrowIter::new(self)
+    }
+
+    /// Iterates over arrows leaving `node`, ordered by target ids. Empty if
+    /// `node` is not in the graph.
+    pub fn arrows_from(&self, node: Node) -> impl ExactSizeIterator<Item=(Node, Node)> + '_ {
+        self.get_successors(node).iter().map(move |target| (node, *target))
+    }
+
+    /// Iterates over arrows entering `node`, ordered by source ids. Empty if
+    /// `node` is not in the graph.
+    pub fn arrows_into(&self, node: Node) -> impl ExactSizeIterator<Item=(Node, Node)> + '_ {
+        self.get_predecessors(node).iter().map(move |source| (*source, node))
+    }
+
+    /// Checks whether arrow `source -> target` exists. Logarithmic in the
+    /// out-degree of `source`.
+    #[inline(always)]
+    pub fn contains_arrow(&self, source: Node, target: Node) -> bool {
+        self.get_successors(source)
+            .binary_search_by_key(&target.id(), Node::id)
+            .is_ok()
+    }
+
+    /// Iterates over nodes reachable from `start` (including `start`) in
+    /// breadth-first order. Empty if `start` is not in the graph.
+    #[inline(always)]
+    pub fn iter_bfs(&self, start: Node) -> BfsIter<'_> {
+        BfsIter::new(self, start)
+    }
+
+    /// Breadth-first traversal from `start` yielding nodes with their
+    /// depths, see [`TraversalIter`] for filters. Empty if `start` is not in
+    /// the graph.
+    #[inline(always)]
+    pub fn bfs_from(&self, start: Node) -> TraversalIter<'_> {
+        TraversalIter::new(self, start, TraversalOrder::BreadthFirst)
+    }
+
+    /// Same as [`DirectedGraph::bfs_from`], but depth-first, i.e. each node
+    /// is followed by its descendants before its next sibling.
     #[inline(always)]
-    pub fn iter_nodes(&self) -> impl Iterator<Item=Node> {
-        (0..self.number_of_nodes).map(Node::from)
+    pub fn dfs_from(&self, start: Node) -> TraversalIter<'_> {
+        TraversalIter::new(self, start, TraversalOrder::DepthFirst)
+    }
+
+    /// Lazily iterates over descendants of `node`, i.e. nodes reachable
+    /// from it by following arrows, in breadth-first order. `node` itself is
+    /// yielded only if it lies on an oriented cycle. Empty if `node` is not
+    /// in the graph.
+    #[inline(always)]
+    pub fn iter_descendants(&self, node: Node) -> ReachableIter<'_> {
+        ReachableIter::new(self, node, Self::get_successors)
+    }
+
+    /// Lazily iterates over ancestors of `node`, i.e. nodes `node` is
+    /// reachable from, in breadth-first order. `node` itself is yielded only
+    /// if it lies on an oriented cycle. Empty if `node` is not in the graph.
+    #[inline(always)]
+    pub fn iter_ancestors(&self, node: Node) -> ReachableIter<'_> {
+        ReachableIter::new(self, node, Self::get_predecessors)
+    }
+
+    /// Checks if there is an oriented path from `from` to `to`. Every node
+    /// of the graph is reachable from itself, nodes outside of the graph
+    /// are never reachable.
+    pub fn is_reachable(&self, from: Node, to: Node) -> bool {
+        if !(0..self.number_of_nodes).contains(&to.id()) {
+            return false;
+        }
+        from == to || self.iter_descendants(from).any(|node| node == to)
+    }
+
+    /// Lazily iterates over all oriented paths from `from` to `to`, see
+    /// [`PathsIter`]. Yields `[from]` once if `from == to`. Empty if either
+    /// node is not in the graph.
+    #[inline(always)]
+    pub fn paths_between(&self, from: Node, to: Node) -> PathsIter<'_> {
+        PathsIter::new(self, from, to)
+    }
+
+    /// Counts oriented paths from `from` to `to`, saturating at
+    /// [`u64::MAX`]. Linear in the size of the graph if it is acyclic,
+    /// otherwise paths are enumerated with [`DirectedGraph::paths_between`].
+    #[allow(clippy::cast_sign_loss)]
+    pub fn count_paths(&self, from: Node, to: Node) -> u64 {
+        let Ok(order) = self.iter_topological() else {
+            return self.paths_between(from, to)
+                .fold(0u64, |count, _| count.saturating_add(1));
+        };
+        let range = 0..self.number_of_nodes;
+        if !range.contains(&from.id()) || !range.contains(&to.id()) {
+            return 0;
+        }
+
+        let mut counts = vec![0u64; self.number_of_nodes as usize];
+        counts[from.id() as usize] = 1;
+        for node in order.skip_while(|node| *node != from) {
+            let count = counts[node.id() as usize];
+            if node == to {
+                return count;
+            }
+            if count == 0 {
+                continue;
+            }
+            for successor in self.get_successors(node) {
+                let target = &mut counts[successor.id() as usize];
+                *target = target.saturating_add(count);
+            }
+        }
+        0
+    }
+
+    /// Returns a path from `from` to `to` with the least number of arrows,
+    /// found with breadth-first search. `None` if `to` is not reachable
+    /// from `from`.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn shortest_path(&self, from: Node, to: Node) -> Option<Vec<Node>> {
+        let range = 0..self.number_of_nodes;
+        if !range.contains(&from.id()) || !range.contains(&to.id()) {
+            return None;
+        }
+
+        let mut parents: Vec<Option<Node>> = vec![None; self.number_of_nodes as usize];
+        let mut queue = VecDeque::from([from]);
+        parents[from.id() as usize] = Some(from);
+        while let Some(node) = queue.pop_front() {
+            if node == to {
+                let mut path = vec![to];
+                let mut current = to;
+                while current != from {
+                    current = parents[current.id() as usize]?;
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for successor in self.get_successors(node) {
+                let parent = &mut parents[successor.id() as usize];
+                if parent.is_none() {
+                    *parent = Some(node);
+                    queue.push_back(*successor);
+                }
+            }
+        }
+        None
     }
 
     #[inline(always)]
     pub fn get_successors(&self, node: Node) -> &[Node] {
-        get_from_arrow_map(node, &self.successors_map)
+        self.successors.get(node)
     }
 
     #[inline(always)]
     pub fn get_predecessors(&self, node: Node) -> &[Node] {
-        get_from_arrow_map(node, &self.predecessors_map)
+        self.predecessors.get(node)
+    }
+
+    /// Iterates over nodes in topological order (Kahn's algorithm), breaking
+    /// ties by ascending node id.
+    ///
+    /// # Errors
+    /// [`NotAcyclicError`] if the graph is not acyclic.
+    pub fn iter_topological(&self) -> Result<TopologicalOrderIter<'_>, NotAcyclicError> {
+        let iter = TopologicalOrderIter::new(self);
+        if self.basic_properties.acyclic {
+            Ok(iter)
+        }
+        else
+        {
+            Err(NotAcyclicError { cycle_arrow: iter.find_cycle_arrow() })
+        }
+    }
+
+    /// Iterates over levels of nodes, where level of a node is the length
+    /// of the longest path from the root. Suitable for processing all
+    /// predecessors of a node before the node itself. See [`LevelIter`].
+    /// Levels are calculated, and cycles detected, with a single pass over
+    /// the graph before returning.
+    ///
+    /// # Errors
+    /// [`LevelOrderError`] if the graph is not acyclic or not rooted,
+    /// checked in this order.
+    pub fn iter_levels(&self) -> Result<LevelIter<'_>, LevelOrderError> {
+        LevelIter::new(self)
     }
 
     #[inline(always)]
@@ -105,41 +344,46 @@ impl DirectedGraph {
         self.get_successors(node).is_empty()
     }
     
+    /// Weight of arrow `source -> target`, as given in
+    /// [`ArrowDTO::weight`] to [`DirectedGraph::from_dto`]. `None` if the
+    /// arrow has no weight or doesn't exist. Weights don't take part in
+    /// graph equality.
+    #[inline(always)]
+    pub fn arrow_weight(&self, source: Node, target: Node) -> Option<f64> {
+        self.arrow_weights.get(&(source, target)).copied()
+    }
+
+    /// Whether any arrow has weight.
+    #[inline(always)]
+    pub fn is_weighted(&self) -> bool {
+        !self.arrow_weights.is_empty()
+    }
+
     pub fn into_dto(&self) -> DirectedGraphDTO {
-        let max_arrows = core::cmp::max(
-            self.successors_map.len(),
-            8);
-        let mut arrows = Vec::<ArrowDTO>::with_capacity(max_arrows);
-        for idx in 0..self.number_of_nodes {
-            let node = Node::from(idx);
-            for successor in self.get_successors(node) {
-                let arrow = ArrowDTO::new(
-                    node.id(), 
-                    successor.id());
-                arrows.push(arrow);
-            }
-        }
+        let arrows = self.iter_arrows()
+            .map(|(source, target)| ArrowDTO::new(source.id(), target.id())
+                .with_weight(self.arrow_weight(source, target)))
+            .collect();
         DirectedGraphDTO::new(self.number_of_nodes, arrows)
     }
 }
 
 
-#[allow(
-    clippy::cast_possible_truncation,
-    clippy::cast_possible_wrap,
-    clippy::cast_sign_loss)]
-#[inline(always)]
-fn get_from_arrow_map(node: Node, arrow_map: &ArrowMap) -> &[Node] {
-    let numeric_id = node.id();
-    if numeric_id < 0 || numeric_id > (arrow_map.len() as i32) {
-        _EMPTY
-    }
-    else
-    {
-        &arrow_map[numeric_id as usize]
-    }
+/// Graph is not acyclic.
+#[derive(Debug)]
+pub struct NotAcyclicError {
+    /// Arrow `(source, target)` lying on an oriented cycle.
+    pub cycle_arrow: Option<(Node, Node)>,
 }
 
+#[derive(Debug)]
+pub enum LevelOrderError {
+    /// Graph doesn't have a single node of in-degree 0.
+    NotRooted,
+
+    /// Graph has an oriented cycle.
+    NotAcyclic(NotAcyclicError),
+}
 
 #[derive(Debug)]
 pub enum DirectedGraphFromError {
@@ -157,6 +401,9 @@ pub enum DirectedGraphFromError {
     /// with value exceeding the number of nodes. Returns the first
     /// conflicting arrow found.
     ArrowOutsideOfNodesRange(ArrowDTO),
+
+    /// Arrow to be removed doesn't exist in the graph.
+    ArrowNotFound(ArrowDTO),
 }
 
 
@@ -167,6 +414,56 @@ impl DirectedGraph {
     /// For specific errors read [`DirectedGraphFromError`] docs.
     pub fn from_dto(value: &DirectedGraphDTO)
         -> Result<Self, DirectedGraphFromError>
+    {
+        Self::from_dto_and_properties(value, None)
+    }
+
+    /// Same as [`DirectedGraph::from_dto`], but trusts `acyclic` and
+    /// `connected` flags of `properties` instead of verifying them with
+    /// graph traversals. Meant for trusted inputs, e.g. produced by
+    /// [`DirectedGraph::into_dto`] together with
+    /// [`DirectedGraph::basic_properties`]. Structural checks are still
+    /// performed, while degree based properties, root and leaves are
+    /// derived from arrows.
+    ///
+    /// # Errors
+    /// For specific errors read [`DirectedGraphFromError`] docs.
+    ///
+    /// # Panics
+    /// In debug builds only, when `properties` don't match the graph.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn from_dto_with_properties(value: &DirectedGraphDTO, properties: DirectedGraphBasicProperties)
+        -> Result<Self, DirectedGraphFromError>
+    {
+        let graph = Self::from_dto_and_properties(value, Some(&properties))?;
+        #[cfg(debug_assertions)]
+        {
+            let storages = ArrowStorages {
+                number_of_nodes: graph.number_of_nodes,
+                successors: &graph.successors,
+                predecessors: &graph.predecessors,
+            };
+            let actual = DirectedGraphBasicProperties {
+                acyclic: verify_acyclic(graph.number_of_nodes, &graph.successors),
+                connected: verify_connected(&storages),
+                ..graph.basic_properties.clone()
+            };
+            assert_eq!(actual, properties, "Passed properties don't match the graph.");
+        }
+        Ok(graph)
+    }
+
+    /// Number of acyclicity and connectivity traversals made on the current
+    /// thread while constructing graphs. Exposed for tests only.
+    #[cfg(feature = "test-utils")]
+    #[doc(hidden)]
+    pub fn property_verifications() -> usize {
+        PROPERTY_VERIFICATIONS.with(Cell::get)
+    }
+
+    fn from_dto_and_properties(
+        value: &DirectedGraphDTO,
+        properties: Option<&DirectedGraphBasicProperties>) -> Result<Self, DirectedGraphFromError>
     {
         let number_of_nodes = value.number_of_nodes();
         if number_of_nodes <= 0 {
@@ -177,10 +474,56 @@ impl DirectedGraph {
             return Err(DirectedGraphFromError::TooBigGraph);
         }
 
-        let mut successor_map_duplicates 
-            = HashMap::<Node, HashSet<Node>>::new();
-        let mut predecessor_map_duplicates 
-            = HashMap::<Node, HashSet<Node>>::new();
+        let arrows = value.arrows();
+        if u32::try_from(arrows.len()).is_err() {
+            return Err(DirectedGraphFromError::TooBigGraph);
+        }
+
+        // Arrows are laid out without intermediate sets. The exact failing
+        // arrow is searched for only when something is wrong.
+        let range = 0..number_of_nodes;
+        let in_range = |arrow: &ArrowDTO| range.contains(&arrow.source()) && range.contains(&arrow.target());
+        if !arrows.iter().all(in_range) {
+            return Err(find_invalid_arrow(number_of_nodes, arrows));
+        }
+
+        #[allow(clippy::cast_sign_loss)]
+        let size = number_of_nodes as usize;
+        let successors = ArrowStorage::from_pairs(
+            size,
+            arrows.iter().map(|arrow| (Node::from(arrow.source()), Node::from(arrow.target()))));
+        if successors.has_duplicates() {
+            return Err(find_invalid_arrow(number_of_nodes, arrows));
+        }
+        let predecessors = ArrowStorage::from_pairs(
+            size,
+            arrows.iter().map(|arrow| (Node::from(arrow.target()), Node::from(arrow.source()))));
+
+        let mut dg = Self::from_storages(
+            number_of_nodes,
+            successors,
+            predecessors,
+            properties.map(|props| props.acyclic),
+            properties.map(|props| props.connected));
+        dg.arrow_weights = arrows.iter()
+            .filter_map(|arrow| {
+                let key = (Node::from(arrow.source()), Node::from(arrow.target()));
+                arrow.weight().map(|weight| (key, weight))
+            })
+            .collect();
+        Ok(dg)
+    }
+
+    /// Creates [`DirectedGraph`] out of valid, sorted arrow storages. Degree
+    /// based properties are always calculated; `acyclic` and `connected`
+    /// only when not passed.
+    fn from_storages(
+        number_of_nodes: i32,
+        successors: ArrowStorage,
+        predecessors: ArrowStorage,
+        acyclic: Option<bool>,
+        connected: Option<bool>) -> Self
+    {
         let mut properties 
             = DirectedGraphBasicProperties {
                 acyclic: false,
@@ -193,45 +536,10 @@ impl DirectedGraph {
         let mut multiple_roots = false;
         let mut leaves = HashSet::with_capacity(8);
 
-        let arrows = value.arrows();
-        let mut multi_arrows = HashSet::<ArrowDTO>::with_capacity(arrows.len());
-
-        for arrow in arrows {
-            if multi_arrows.contains(arrow) {
-                return Err(DirectedGraphFromError::MultipleParallelArrows(arrow.clone()));
-            }
-            multi_arrows.insert(arrow.clone());
-            let source = arrow.source();
-            let target = arrow.target();
-            if source < 0
-                || source >= number_of_nodes
-                || target < 0
-                || target >= number_of_nodes
-            {
-                return Err(DirectedGraphFromError::ArrowOutsideOfNodesRange(arrow.clone()));
-            }
-            let source_node = Node::from(source);
-            let target_node = Node::from(target);
-            insert_node_to_arrow_map(
-                source_node,
-                target_node,
-                &mut successor_map_duplicates);
-            insert_node_to_arrow_map(
-                target_node,
-                source_node,
-                &mut predecessor_map_duplicates);
-        }
-
-        let successors_map
-            = to_arrow_map(number_of_nodes, &successor_map_duplicates);
-        let predecessors_map
-            = to_arrow_map(number_of_nodes, &predecessor_map_duplicates);
-
-        #[allow(clippy::cast_sign_loss)]
         for idx in 0..number_of_nodes {
             let node = Node::from(idx);
-            let preds_len = predecessors_map[idx as usize].len();
-            let succs_len = successors_map[idx as usize].len();
+            let preds_len = predecessors.get(node).len();
+            let succs_len = successors.get(node).len();
             if preds_len == 0 {
                 if root_node.is_none() {
                     root_node = Some(node);
@@ -264,22 +572,25 @@ impl DirectedGraph {
             properties.rooted = false;
         }
 
-        properties.acyclic = verify_acyclic(number_of_nodes, &successors_map);
-        if properties.rooted && properties.acyclic {
-            properties.connected = true;
-        }
-        else
-        {
-            properties.connected = verify_connected(
-                number_of_nodes, 
-                &predecessors_map,
-                &successors_map);
-        }
+        properties.acyclic = acyclic.unwrap_or_else(|| {
+            record_property_verification();
+            verify_acyclic(number_of_nodes, &successors)
+        });
+        properties.connected = connected.unwrap_or_else(|| {
+            if properties.rooted && properties.acyclic {
+                return true;
+            }
+            record_property_verification();
+            verify_connected(&ArrowStorages {
+                number_of_nodes: number_of_nodes,
+                successors: &successors,
+                predecessors: &predecessors,
+            })
+        });
 
-        let dg = unsafe {
-            Self::new_unchecked(number_of_nodes, successors_map, predecessors_map, properties, root_node, leaves)
-        };
-        Ok(dg)
+        unsafe {
+            Self::new_unchecked(number_of_nodes, successors, predecessors, properties, root_node, leaves)
+        }
     }
 
     /// Creates an unchecked [`DirectedGraph`].
@@ -290,11 +601,11 @@ impl DirectedGraph {
     /// [`DirectedGraph`]. Use with caution. The following invariants have to
     /// be satisfied:
     /// * `number_of_nodes > 0`.
-    /// * `successors_map` and `predecessors_map` are of length equalt
-    ///   to `number_of_nodes`.
-    /// * each value in `successors_map` and `predecessors_map` contains nodes within
-    ///   `(0..number_of_nodes)` range.
-    /// * each value in `successors_map` and `predecessors_map` is a vec ordered
+    /// * `successors` and `predecessors` are of length equal
+    ///   to `number_of_nodes`, and describe the same arrows.
+    /// * each list in `successors` and `predecessors` contains nodes within
+    ///   `(0..number_of_nodes)` range, without duplicates.
+    /// * each list in `successors` and `predecessors` is ordered
     ///   by i32 representation of nodes. This is important for hash calculation.
     /// * acyclic, rooted and connected pieces of `properties` have to match the
     ///   actual graph structure.
@@ -305,18 +616,18 @@ impl DirectedGraph {
     ///   in the graph. The order is irrelevant.
     pub unsafe fn new_unchecked(
             number_of_nodes: i32,
-            successors_map: Vec<SmallVec<[Node; 2]>>,
-            predecessors_map: Vec<SmallVec<[Node; 2]>>,
+            successors: ArrowStorage,
+            predecessors: ArrowStorage,
             properties: DirectedGraphBasicProperties,
             root_node: Option<Node>,
             leaves: HashSet<Node>) -> Self
     {
         #[allow(clippy::cast_possible_truncation)]
         let hash = {
-            fn update_vec<T: Hasher>(vec: &[SmallVec<[Node; 2]>], hasher: &mut T)
+            fn update_vec<T: Hasher>(storage: &ArrowStorage, hasher: &mut T)
             {
-                vec.len().hash(hasher);
-                for (idx, internal) in vec.iter().enumerate() {
+                storage.len().hash(hasher);
+                for (idx, internal) in storage.iter().enumerate() {
                     idx.hash(hasher);
                     internal.len().hash(hasher);
                     let mut res = 0;
@@ -331,167 +642,309 @@ impl DirectedGraph {
 
             let mut hasher = create_u32_hasher();
             number_of_nodes.hash(&mut hasher);
-            update_vec(&successors_map, &mut hasher);
-            update_vec(&predecessors_map, &mut hasher);
+            update_vec(&successors, &mut hasher);
+            update_vec(&predecessors, &mut hasher);
             hasher.finish() as u32
         };
 
+        let number_of_arrows = successors.number_of_arrows();
+
         Self {
             id: GraphId::generate_next(),
             number_of_nodes: number_of_nodes,
-            successors_map: successors_map,
-            predecessors_map: predecessors_map,
+            number_of_arrows: number_of_arrows,
+            successors: successors,
+            predecessors: predecessors,
             basic_properties: properties,
             root_node: root_node,
             leaves: leaves,
             hash_value: hash,
+            arrow_weights: HashMap::new(),
         }
     }
-}
 
+    /// Same as [`DirectedGraph::new_unchecked`], but with arrows in the
+    /// per node [`ArrowMap`] layout, which gets converted to
+    /// [`ArrowStorage`].
+    ///
+    /// # Safety
+    /// Same invariants as in [`DirectedGraph::new_unchecked`] have to be
+    /// satisfied.
+    pub unsafe fn from_arrow_maps_unchecked(
+            number_of_nodes: i32,
+            successors_map: &ArrowMap,
+            predecessors_map: &ArrowMap,
+            properties: DirectedGraphBasicProperties,
+            root_node: Option<Node>,
+            leaves: HashSet<Node>) -> Self
+    {
+        Self::new_unchecked(
+            number_of_nodes,
+            ArrowStorage::from_lists(successors_map),
+            ArrowStorage::from_lists(predecessors_map),
+            properties,
+            root_node,
+            leaves)
+    }
+
+    /// Returns copy of the graph with arrow `source -> target` added.
+    /// Acyclicity and connectivity are updated incrementally when possible.
+    ///
+    /// # Errors
+    /// [`DirectedGraphFromError::ArrowOutsideOfNodesRange`] or
+    /// [`DirectedGraphFromError::MultipleParallelArrows`] if the arrow
+    /// already exists.
+    pub fn with_arrow_added(&self, source: Node, target: Node)
+        -> Result<DirectedGraph, DirectedGraphFromError>
+    {
+        let arrow = ArrowDTO::new(source.id(), target.id());
+        self.verify_arrow_in_range(&arrow)?;
+        if self.contains_arrow(source, target) {
+            return Err(DirectedGraphFromError::MultipleParallelArrows(arrow));
+        }
 
-#[allow(clippy::cast_sign_loss)]
-fn verify_connected(
-    number_of_nodes: i32,
-    predecessor_map: &ArrowMap,
-    successors_map: &ArrowMap) -> bool
-{
-    let mut reachable_nodes: HashSet<Node> 
-        = (0..number_of_nodes).map(Node::from).collect();
-    let first = Node::from(0);
-
-    let mut seen
-        = HashSet::<Node>::with_capacity(number_of_nodes as usize);
-    verify_connected_remove_all_reachable(
-        first,
-        &mut reachable_nodes,
-        &mut seen,
-        predecessor_map,
-        successors_map);
-    reachable_nodes.is_empty()
-}
+        let successors = self.successors.with_inserted(source, target);
+        let predecessors = self.predecessors.with_inserted(target, source);
+
+        let acyclic = self.basic_properties.acyclic
+            && source != target
+            && !self.is_reachable(target, source);
+        let connected = if self.basic_properties.connected { Some(true) } else { None };
+        let mut graph = Self::from_storages(
+            self.number_of_nodes,
+            successors,
+            predecessors,
+            Some(acyclic),
+            connected);
+        graph.arrow_weights.clone_from(&self.arrow_weights);
+        Ok(graph)
+    }
 
-#[allow(clippy::cast_sign_loss)]
-fn verify_connected_remove_all_reachable(
-    node: Node,
-    reachable_nodes: &mut HashSet<Node>,
-    seen: &mut HashSet<Node>,
-    predecessor_map: &ArrowMap,
-    successors_map: &ArrowMap)
-{
-    if seen.contains(&node) {
-        return;
-    }
-    seen.insert(node);
-    reachable_nodes.remove(&node);
-    let idx = node.id() as usize;
-
-    for pred in &predecessor_map[idx] {
-        verify_connected_remove_all_reachable(
-            *pred,
-            reachable_nodes,
-            seen,
-            predecessor_map,
-            successors_map);
-    }
-
-    for succ in &successors_map[idx] {
-        verify_connected_remove_all_reachable(
-            *succ,
-            reachable_nodes,
-            seen,
-            predecessor_map,
-            successors_map);
+    /// Returns copy of the graph with arrow `source -> target` removed.
+    /// Acyclicity and connectivity are updated incrementally when possible.
+    ///
+    /// # Errors
+    /// [`DirectedGraphFromError::ArrowOutsideOfNodesRange`] or
+    /// [`DirectedGraphFromError::ArrowNotFound`].
+    pub fn with_arrow_removed(&self, source: Node, target: Node)
+        -> Result<DirectedGraph, DirectedGraphFromError>
+    {
+        let arrow = ArrowDTO::new(source.id(), target.id());
+        self.verify_arrow_in_range(&arrow)?;
+        if !self.contains_arrow(source, target) {
+            return Err(DirectedGraphFromError::ArrowNotFound(arrow));
+        }
+
+        let successors = self.successors.with_removed(source, target);
+        let predecessors = self.predecessors.with_removed(target, source);
+
+        let acyclic = if self.basic_properties.acyclic { Some(true) } else { None };
+        let connected = if self.basic_properties.connected { None } else { Some(false) };
+        let mut graph = Self::from_storages(
+            self.number_of_nodes,
+            successors,
+            predecessors,
+            acyclic,
+            connected);
+        graph.arrow_weights.clone_from(&self.arrow_weights);
+        graph.arrow_weights.remove(&(source, target));
+        Ok(graph)
     }
-}
 
-fn verify_acyclic(number_of_nodes: i32, successors_map: &ArrowMap) -> bool {
-    let mut nodes_stack: Vec<Node> 
-        = (0..number_of_nodes).map(Node::from).collect();
+    /// Returns copy of the graph with a new isolated node, together with
+    /// that node.
+    ///
+    /// # Panics
+    /// When the graph already has [`DirectedGraph::max_size()`] nodes.
+    pub fn with_node_added(&self) -> (DirectedGraph, Node) {
+        assert!(
+            self.number_of_nodes < Self::max_size(),
+            "DirectedGraph cannot exceed DirectedGraph::max_size().");
+        let node = Node::from(self.number_of_nodes);
+        let mut graph = Self::from_storages(
+            self.number_of_nodes + 1,
+            self.successors.with_node_added(),
+            self.predecessors.with_node_added(),
+            Some(self.basic_properties.acyclic),
+            Some(false));
+        graph.arrow_weights.clone_from(&self.arrow_weights);
+        (graph, node)
+    }
 
-    loop {
-        if let Some(top) = nodes_stack.pop() {
-            let mut seen = HashSet::<Node>::new();
-            if verify_acyclic_check_cycle(top, &mut seen, successors_map) {
-                return false;
-            }
+    fn verify_arrow_in_range(&self, arrow: &ArrowDTO) -> Result<(), DirectedGraphFromError> {
+        let range = 0..self.number_of_nodes;
+        if range.contains(&arrow.source()) && range.contains(&arrow.target()) {
+            Ok(())
         }
         else
         {
-            return true;
+            Err(DirectedGraphFromError::ArrowOutsideOfNodesRange(arrow.clone()))
         }
     }
-}
 
-#[allow(clippy::cast_sign_loss)]
-fn verify_acyclic_check_cycle(
-    node: Node,
-    seen: &mut HashSet<Node>,
-    successors_map: &ArrowMap) -> bool
-{
-    if seen.contains(&node) {
-        return true;
-    }
-
-    let succs = &successors_map[node.id() as usize];
-    if !succs.is_empty() {
-        seen.insert(node);
-        for successor in succs {
-            if verify_acyclic_check_cycle(*successor, seen, successors_map) {
-                return true;
+    /// Extracts the subgraph induced by `root` and all its descendants.
+    /// Nodes are renumbered compactly, preserving the relative order of
+    /// original ids. Returns the new graph together with the mapping from
+    /// new ids to original nodes. If the graph is acyclic, the result is
+    /// rooted at `root`.
+    ///
+    /// # Panics
+    /// When `root` is not in the graph.
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn induced_subgraph_from(&self, root: Node) -> (DirectedGraph, Vec<Node>) {
+        assert!(
+            (0..self.number_of_nodes).contains(&root.id()),
+            "Node {root:?} is not in the graph.");
+        let mut nodes: Vec<Node> = self.iter_descendants(root)
+            .chain(core::iter::once(root))
+            .collect();
+        nodes.sort_unstable_by_key(Node::id);
+        nodes.dedup();
+
+        let mut new_ids = vec![-1; self.number_of_nodes as usize];
+        for (idx, node) in nodes.iter().enumerate() {
+            new_ids[node.id() as usize] = idx as i32;
+        }
+        let mut arrows = Vec::new();
+        for node in &nodes {
+            let source = new_ids[node.id() as usize];
+            for successor in self.get_successors(*node) {
+                let arrow = ArrowDTO::new(source, new_ids[successor.id() as usize])
+                    .with_weight(self.arrow_weight(*node, *successor));
+                arrows.push(arrow);
             }
         }
-        seen.remove(&node);
+
+        let dto = DirectedGraphDTO::new(nodes.len() as i32, arrows);
+        let graph = DirectedGraph::from_dto(&dto)
+            .expect("Subgraph of a valid graph is valid.");
+        (graph, nodes)
+    }
+}
+
+
+/// Raw arrow storages of a graph under construction.
+struct ArrowStorages<'a> {
+    number_of_nodes: i32,
+    successors: &'a ArrowStorage,
+    predecessors: &'a ArrowStorage,
+}
+
+impl GraphRead for ArrowStorages<'_> {
+    #[inline(always)]
+    fn number_of_nodes(&self) -> i32 {
+        self.number_of_nodes
+    }
+
+    #[inline(always)]
+    fn get_successors(&self, node: Node) -> &[Node] {
+        self.successors.get(node)
     }
 
-    return false;
+    #[inline(always)]
+    fn get_predecessors(&self, node: Node) -> &[Node] {
+        self.predecessors.get(node)
+    }
+}
+
+#[cfg(feature = "test-utils")]
+thread_local! {
+    /// See [`DirectedGraph::property_verifications`].
+    static PROPERTY_VERIFICATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts traversals for [`DirectedGraph::property_verifications`]. No-op
+/// without the `test-utils` feature.
+#[inline(always)]
+fn record_property_verification() {
+    #[cfg(feature = "test-utils")]
+    PROPERTY_VERIFICATIONS.with(|count| count.set(count.get() + 1));
 }
 
 #[allow(clippy::cast_sign_loss)]
-fn to_arrow_map(number_of_nodes: i32, map: &HashMap<Node, HashSet<Node>>)
-    -> ArrowMap
-{
-    let mut result
-        = Vec::<SmallVec<[Node; 2]>>::with_capacity(number_of_nodes as usize);
-
-    for idx in 0..number_of_nodes {
-        let node = &Node::from(idx);
-        if let Some(set) = map.get(node) {
-            let mut vec 
-                = SmallVec::<[Node; 2]>::with_capacity(set.len());
-            for target in set {
-                vec.push(*target);
+fn verify_connected(graph: &impl GraphRead) -> bool {
+    let size = graph.number_of_nodes() as usize;
+    let mut seen = vec![false; size];
+    let mut stack = Vec::<Node>::with_capacity(size);
+    let mut seen_count = 1;
+    seen[0] = true;
+    stack.push(Node::from(0));
+
+    while let Some(node) = stack.pop() {
+        for neighbour in graph.iter_neighbours(node) {
+            let neighbour_idx = neighbour.id() as usize;
+            if !seen[neighbour_idx] {
+                seen[neighbour_idx] = true;
+                seen_count += 1;
+                stack.push(neighbour);
             }
-            result.push(vec);
-        }
-        else
-        {
-            result.push(SmallVec::new());
         }
     }
 
-    for internal in &mut result {
-        internal.sort_by_key(Node::id);
+    seen_count == size
+}
+
+/// Iterative DFS with three colors: 0 - not visited, 1 - on the current
+/// path, 2 - done. Graph has an oriented cycle iff DFS finds an arrow to
+/// a node on the current path. Linear in the size of the graph.
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn verify_acyclic(number_of_nodes: i32, successors: &ArrowStorage) -> bool {
+    const WHITE: u8 = 0;
+    const GRAY: u8 = 1;
+    const BLACK: u8 = 2;
+
+    let size = number_of_nodes as usize;
+    let mut colors = vec![WHITE; size];
+    let mut stack = Vec::<(usize, usize)>::new();
+
+    for start in 0..size {
+        if colors[start] != WHITE {
+            continue;
+        }
+
+        colors[start] = GRAY;
+        stack.push((start, 0));
+        while let Some(top) = stack.last_mut() {
+            let (idx, position) = *top;
+            let succs = successors.get(Node::from(idx as i32));
+            if position < succs.len() {
+                top.1 += 1;
+                let successor = succs[position].id() as usize;
+                match colors[successor] {
+                    WHITE => {
+                        colors[successor] = GRAY;
+                        stack.push((successor, 0));
+                    },
+                    GRAY => return false,
+                    _ => { },
+                }
+            }
+            else
+            {
+                colors[idx] = BLACK;
+                stack.pop();
+            }
+        }
     }
 
-    result
+    return true;
 }
 
-fn insert_node_to_arrow_map(
-    key: Node, 
-    value: Node,
-    map: &mut HashMap<Node, HashSet<Node>>)
-{
-    if let Some(vec) = map.get_mut(&key) {
-        vec.insert(value);
-    }
-    else
-    {
-        let mut targets
-            = HashSet::<Node>::with_capacity(2);
-        targets.insert(value);
-        map.insert(key, targets);
+/// Finds the first arrow of `arrows` which either repeats an earlier one
+/// or lies outside of nodes range. Meant for reporting, when it is known
+/// that such arrow exists.
+fn find_invalid_arrow(number_of_nodes: i32, arrows: &[ArrowDTO]) -> DirectedGraphFromError {
+    let range = 0..number_of_nodes;
+    let mut seen = HashSet::<(i32, i32)>::with_capacity(arrows.len());
+    for arrow in arrows {
+        if !seen.insert((arrow.source(), arrow.target())) {
+            return DirectedGraphFromError::MultipleParallelArrows(arrow.clone());
+        }
+        if !range.contains(&arrow.source()) || !range.contains(&arrow.target()) {
+            return DirectedGraphFromError::ArrowOutsideOfNodesRange(arrow.clone());
+        }
     }
+    unreachable!("No invalid arrow found.")
 }
 
 impl PartialEq for DirectedGraph {
@@ -500,8 +953,8 @@ impl PartialEq for DirectedGraph {
         || (
             self.hash_value == other.hash_value
             && self.number_of_nodes == other.number_of_nodes
-            && self.successors_map == other.successors_map
-            && self.predecessors_map == other.predecessors_map)
+            && self.successors == other.successors
+            && self.predecessors == other.predecessors)
     }
 }
 
@@ -515,15 +968,17 @@ impl Hash for DirectedGraph {
 
 impl Clone for DirectedGraph {
     fn clone(&self) -> Self {
-        unsafe {
+        let mut graph = unsafe {
             Self::new_unchecked(
                 self.number_of_nodes,
-                self.successors_map.clone(),
-                self.predecessors_map.clone(),
+                self.successors.clone(),
+                self.predecessors.clone(),
                 self.basic_properties.clone(),
                 self.root_node,
                 self.leaves.clone())
-        }
+        };
+        graph.arrow_weights.clone_from(&self.arrow_weights);
+        graph
     }
 }
 
@@ -534,11 +989,73 @@ impl Debug for DirectedGraph {
             .field("id", &self.id)
             .field("number_of_nodes", &self.number_of_nodes)
             .field("hash_value", &self.hash_value)
-            .field("successors_map", &self.successors_map)
-            .field("predecessors_map", &self.predecessors_map)
+            .field("successors", &self.successors)
+            .field("predecessors", &self.predecessors)
             .finish()
     }
 }
 
+/// Writes [`DirectedGraph::to_edge_list_string`] with formatter precision
+/// as the limit, [`DEFAULT_EDGE_LIST_LIMIT`] by default.
+impl Display for DirectedGraph {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let limit = f.precision().unwrap_or(DEFAULT_EDGE_LIST_LIMIT);
+        f.write_str(&self.to_edge_list_string(limit))
+    }
+}
+
 unsafe impl Sync for DirectedGraph { }
 unsafe impl Send for DirectedGraph { }
+
+impl Display for NotAcyclicError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.cycle_arrow {
+            Some((source, target))
+                => write!(f, "graph is not acyclic, arrow {} -> {} lies on a cycle", source.id(), target.id()),
+            None => f.write_str("graph is not acyclic"),
+        }
+    }
+}
+
+impl std::error::Error for NotAcyclicError { }
+
+impl From<NotAcyclicError> for LevelOrderError {
+    fn from(value: NotAcyclicError) -> Self { Self::NotAcyclic(value) }
+}
+
+impl Display for LevelOrderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LevelOrderError::NotRooted => f.write_str("graph is not rooted"),
+            LevelOrderError::NotAcyclic(_) => f.write_str("graph has no level order"),
+        }
+    }
+}
+
+impl std::error::Error for LevelOrderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LevelOrderError::NotRooted => None,
+            LevelOrderError::NotAcyclic(err) => Some(err),
+        }
+    }
+}
+
+impl Display for DirectedGraphFromError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DirectedGraphFromError::EmptyGraph
+                => f.write_str("graph has no nodes"),
+            DirectedGraphFromError::TooBigGraph
+                => write!(f, "graph exceeds the maximum of {} nodes", DirectedGraph::max_size()),
+            DirectedGraphFromError::MultipleParallelArrows(arrow)
+                => write!(f, "multiple arrows {} -> {}", arrow.source(), arrow.target()),
+            DirectedGraphFromError::ArrowOutsideOfNodesRange(arrow)
+                => write!(f, "arrow {} -> {} outside of nodes range", arrow.source(), arrow.target()),
+            DirectedGraphFromError::ArrowNotFound(arrow)
+                => write!(f, "arrow {} -> {} not found", arrow.source(), arrow.target()),
+        }
+    }
+}
+
+impl std::error::Error for DirectedGraphFromError { }
@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+
+use super::{ArrowDTO, DirectedGraph, DirectedGraphDTO, DirectedGraphFromError, Node};
+
+/// Incremental builder of [`DirectedGraphDTO`] and [`DirectedGraph`]. The
+/// number of nodes grows automatically to cover all added arrows.
+///
+/// Invalid input is not rejected eagerly: it is kept in the resulting
+/// [`DirectedGraphDTO`] so that [`DirectedGraphBuilder::build`] reports it
+/// the same way [`DirectedGraph::from_dto`] does.
+#[derive(Default, Debug)]
+pub struct DirectedGraphBuilder {
+    number_of_nodes: i32,
+    arrows: Vec<ArrowDTO>,
+    known_arrows: HashSet<(i32, i32)>,
+    report_parallel_arrows: bool,
+}
+
+impl DirectedGraphBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// By default parallel arrows are silently deduplicated. After calling
+    /// this method they are kept, and reported by
+    /// [`DirectedGraphBuilder::build`] as
+    /// [`DirectedGraphFromError::MultipleParallelArrows`].
+    #[must_use]
+    pub fn with_parallel_arrows_reported(mut self) -> Self {
+        self.report_parallel_arrows = true;
+        self
+    }
+
+    #[inline(always)]
+    pub fn number_of_nodes(&self) -> i32 {
+        self.number_of_nodes
+    }
+
+    /// Appends new node and returns it.
+    ///
+    /// # Panics
+    /// When the builder already has [`DirectedGraph::max_size()`] nodes.
+    pub fn add_node(&mut self) -> Node {
+        assert!(
+            self.number_of_nodes < DirectedGraph::max_size(),
+            "DirectedGraphBuilder exceeded DirectedGraph::max_size().");
+        let node = Node::from(self.number_of_nodes);
+        self.number_of_nodes += 1;
+        node
+    }
+
+    /// Adds arrow `source -> target`. See [`DirectedGraphBuilder::add_arrow_ids`].
+    #[inline(always)]
+    pub fn add_arrow(&mut self, source: Node, target: Node) -> &mut Self {
+        self.add_arrow_ids(source.id(), target.id())
+    }
+
+    /// Adds arrow `source -> target` given by raw ids, growing the number of
+    /// nodes if needed. Ids outside of `0..DirectedGraph::max_size()` don't
+    /// grow the graph, the arrow is then reported by
+    /// [`DirectedGraphBuilder::build`] as
+    /// [`DirectedGraphFromError::ArrowOutsideOfNodesRange`].
+    pub fn add_arrow_ids(&mut self, source: i32, target: i32) -> &mut Self {
+        if !self.known_arrows.insert((source, target)) && !self.report_parallel_arrows {
+            return self;
+        }
+
+        let valid_range = 0..DirectedGraph::max_size();
+        if valid_range.contains(&source) && valid_range.contains(&target) {
+            self.number_of_nodes = self.number_of_nodes
+                .max(source + 1)
+                .max(target + 1);
+        }
+        self.arrows.push(ArrowDTO::new(source, target));
+        self
+    }
+
+    pub fn build_dto(self) -> DirectedGraphDTO {
+        DirectedGraphDTO::new(self.number_of_nodes, self.arrows)
+    }
+
+    /// Builds and validates [`DirectedGraph`].
+    ///
+    /// # Errors
+    /// Forwarded from [`DirectedGraph::from_dto`].
+    pub fn build(self) -> Result<DirectedGraph, DirectedGraphFromError> {
+        DirectedGraph::from_dto(&self.build_dto())
+    }
+}
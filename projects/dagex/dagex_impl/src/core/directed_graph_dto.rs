@@ -1,14 +1,56 @@
+use core::fmt::{Display, Formatter};
+use core::hash::{Hash, Hasher};
+use std::collections::HashSet;
+
 use raf_readonly::readonly;
 
-/// Represents arrow between source node and target node in a directed graph.
+use super::{write_edge_list, DirectedGraph, DEFAULT_EDGE_LIST_LIMIT};
+
+/// Represents arrow between source node and target node in a directed graph,
+/// optionally with weight, e.g. branch length. Weights are compared
+/// bitwise.
 /// 
 /// # Notes
 /// Immutable once created.
-#[readonly]
-#[derive(PartialEq, Eq, Hash, Clone, Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct ArrowDTO {
-    pub source: i32,
-    pub target: i32,
+    source: i32,
+    target: i32,
+    weight: Option<f64>,
+}
+
+impl ArrowDTO {
+    /// Creates arrow without weight.
+    #[inline(always)]
+    pub fn new(source: i32, target: i32) -> Self {
+        Self { source: source, target: target, weight: None }
+    }
+
+    #[must_use]
+    pub fn with_weight(mut self, weight: Option<f64>) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    #[inline(always)]
+    pub fn source(&self) -> i32 {
+        self.source
+    }
+
+    #[inline(always)]
+    pub fn target(&self) -> i32 {
+        self.target
+    }
+
+    #[inline(always)]
+    pub fn weight(&self) -> Option<f64> {
+        self.weight
+    }
+
+    #[inline(always)]
+    fn weight_bits(&self) -> Option<u64> {
+        self.weight.map(f64::to_bits)
+    }
 }
 
 /// Represents directed graph as a pair consisting of number of nodes,
@@ -22,3 +64,137 @@ pub struct DirectedGraphDTO {
     pub number_of_nodes: i32,
     pub arrows: Vec<ArrowDTO>,
 }
+
+/// Error of [`DirectedGraphDTO::validate`] and
+/// [`PhylogeneticNetworkDTO::validate`](crate::phylo::PhylogeneticNetworkDTO::validate).
+/// Graph variants match those of
+/// [`DirectedGraphFromError`](super::DirectedGraphFromError) returned by
+/// [`DirectedGraph::from_dto`] for the same DTO.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DtoValidationError {
+    /// Graph has no nodes.
+    EmptyGraph,
+
+    /// Graph size exceeded [`DirectedGraph::max_size()`].
+    TooBigGraph,
+
+    /// Graph has at least two arrows with the same source and target.
+    /// Returns the first duplicate found.
+    MultipleParallelArrows(ArrowDTO),
+
+    /// Graph has arrows outside of range. Returns the first conflicting
+    /// arrow found.
+    ArrowOutsideOfNodesRange(ArrowDTO),
+
+    /// Taxon assigned to a node outside of range. Returns the node id.
+    TaxonOutsideOfNodesRange(i32),
+}
+
+impl DirectedGraphDTO {
+    /// Performs checks of [`DirectedGraph::from_dto`] without constructing
+    /// the graph. Arrows are checked in order, so the reported arrow is the
+    /// same.
+    ///
+    /// # Errors
+    /// For specific errors read [`DtoValidationError`] docs.
+    pub fn validate(&self) -> Result<(), DtoValidationError> {
+        let number_of_nodes = self.number_of_nodes;
+        if number_of_nodes <= 0 {
+            return Err(DtoValidationError::EmptyGraph);
+        }
+
+        if number_of_nodes > DirectedGraph::max_size() {
+            return Err(DtoValidationError::TooBigGraph);
+        }
+
+        let range = 0..number_of_nodes;
+        let mut seen = HashSet::<(i32, i32)>::with_capacity(self.arrows.len());
+        for arrow in &self.arrows {
+            if !seen.insert((arrow.source, arrow.target)) {
+                return Err(DtoValidationError::MultipleParallelArrows(arrow.clone()));
+            }
+            if !range.contains(&arrow.source) || !range.contains(&arrow.target) {
+                return Err(DtoValidationError::ArrowOutsideOfNodesRange(arrow.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether any arrow has weight.
+    pub fn is_weighted(&self) -> bool {
+        self.arrows.iter().any(|arrow| arrow.weight.is_some())
+    }
+
+    /// Returns a copy with arrows sorted by `(source, target)` and
+    /// duplicates merged. Of arrows with the same source and target only
+    /// the one of the minimal weight, compared with [`f64::total_cmp`], is
+    /// kept. Arrows without weight are kept only if none of their
+    /// duplicates has one. Equal graphs given with arrows in different
+    /// orders have equal normalized DTOs. Doesn't validate.
+    #[must_use]
+    pub fn normalized(&self) -> DirectedGraphDTO {
+        let mut arrows = self.arrows.clone();
+        arrows.sort_unstable_by(|left, right| {
+            (left.source, left.target).cmp(&(right.source, right.target))
+                .then_with(|| match (left.weight, right.weight) {
+                    (Some(left), Some(right)) => left.total_cmp(&right),
+                    (Some(_), None) => core::cmp::Ordering::Less,
+                    (None, Some(_)) => core::cmp::Ordering::Greater,
+                    (None, None) => core::cmp::Ordering::Equal,
+                })
+        });
+        arrows.dedup_by_key(|arrow| (arrow.source, arrow.target));
+        DirectedGraphDTO::new(self.number_of_nodes, arrows)
+    }
+}
+
+/// Same format as [`DirectedGraph`]'s, e.g. `DG(n=3): 0->1, 0->2`. Arrows
+/// are written as they are, even if the DTO is not valid.
+impl Display for DirectedGraphDTO {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let limit = f.precision().unwrap_or(DEFAULT_EDGE_LIST_LIMIT);
+        let arrows = self.arrows.iter()
+            .map(|arrow| (arrow.source, arrow.target))
+            .collect();
+        let mut result = String::new();
+        write_edge_list(&mut result, self.number_of_nodes, arrows, limit);
+        f.write_str(&result)
+    }
+}
+
+impl PartialEq for ArrowDTO {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+            && self.target == other.target
+            && self.weight_bits() == other.weight_bits()
+    }
+}
+
+impl Eq for ArrowDTO { }
+
+impl Hash for ArrowDTO {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source.hash(state);
+        self.target.hash(state);
+        self.weight_bits().hash(state);
+    }
+}
+
+impl Display for DtoValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DtoValidationError::EmptyGraph
+                => f.write_str("graph has no nodes"),
+            DtoValidationError::TooBigGraph
+                => write!(f, "graph exceeds the maximum of {} nodes", DirectedGraph::max_size()),
+            DtoValidationError::MultipleParallelArrows(arrow)
+                => write!(f, "multiple arrows {} -> {}", arrow.source, arrow.target),
+            DtoValidationError::ArrowOutsideOfNodesRange(arrow)
+                => write!(f, "arrow {} -> {} outside of nodes range", arrow.source, arrow.target),
+            DtoValidationError::TaxonOutsideOfNodesRange(node)
+                => write!(f, "taxon of node {node} outside of nodes range"),
+        }
+    }
+}
+
+impl std::error::Error for DtoValidationError { }
@@ -0,0 +1,31 @@
+use core::fmt::Write;
+
+/// Maximal number of arrows (and taxa) written by `Display` implementations
+/// of graphs, networks and their DTOs. Can be overridden with formatter
+/// precision, e.g. `format!("{graph:.5}")` writes at most 5 arrows.
+pub const DEFAULT_EDGE_LIST_LIMIT: usize = 32;
+
+/// Writes `DG(n=<number_of_nodes>): ` followed by at most `limit` arrows
+/// sorted by `(source, target)`. When some arrows are omitted, appends
+/// an ellipsis together with the total number of arrows.
+pub(crate) fn write_edge_list(
+    out: &mut String,
+    number_of_nodes: i32,
+    mut arrows: Vec<(i32, i32)>,
+    limit: usize)
+{
+    arrows.sort_unstable();
+    let _ = write!(out, "DG(n={number_of_nodes}):");
+    if arrows.is_empty() {
+        out.push_str(" no arrows");
+        return;
+    }
+    for (idx, (source, target)) in arrows.iter().take(limit).enumerate() {
+        let separator = if idx == 0 { " " } else { ", " };
+        let _ = write!(out, "{separator}{source}->{target}");
+    }
+    if arrows.len() > limit {
+        let separator = if limit == 0 { " " } else { ", " };
+        let _ = write!(out, "{separator}... ({} arrows)", arrows.len());
+    }
+}
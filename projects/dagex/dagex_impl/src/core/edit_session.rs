@@ -0,0 +1,302 @@
+use core::fmt::{Display, Formatter};
+
+use super::{
+    ArrowDTO,
+    DirectedGraph,
+    DirectedGraphBasicProperties,
+    DirectedGraphDTO,
+    DirectedGraphFromError,
+    Node};
+
+/// Single change applied during an [`EditSession`].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum GraphEvent {
+    /// Node was appended to the graph.
+    NodeAdded(Node),
+
+    /// Arrow `source -> target` was added.
+    ArrowAdded(Node, Node),
+
+    /// Arrow `source -> target` was removed.
+    ArrowRemoved(Node, Node),
+
+    /// Node was removed. Arrows incident to it are reported as separate
+    /// [`GraphEvent::ArrowRemoved`] events beforehand. To keep ids
+    /// contiguous the node with the highest id takes the id of the removed
+    /// node; the second value holds its previous id, or `None` if the
+    /// removed node was the last one.
+    NodeRemoved(Node, Option<Node>),
+
+    /// Basic properties differ between the original and the committed
+    /// graph. Always reported last, on commit.
+    PropertiesChanged(DirectedGraphBasicProperties, DirectedGraphBasicProperties),
+}
+
+#[derive(Debug)]
+pub enum EditError {
+    /// Node is outside of the current nodes range.
+    NodeNotFound(Node),
+
+    /// Arrow already exists.
+    ArrowAlreadyExists(Node, Node),
+
+    /// Arrow doesn't exist.
+    ArrowNotFound(Node, Node),
+
+    /// Replayed event doesn't match the current state of the session.
+    InconsistentEvent(GraphEvent),
+
+    /// Forwarded error of the final graph construction.
+    GraphError(DirectedGraphFromError),
+}
+
+impl From<DirectedGraphFromError> for EditError {
+    fn from(value: DirectedGraphFromError) -> Self { Self::GraphError(value) }
+}
+
+type Listener<'a> = Box<dyn FnMut(&[GraphEvent]) + 'a>;
+
+/// Mutable copy of a [`DirectedGraph`] recording every change as a
+/// [`GraphEvent`]. Created with [`DirectedGraph::edit`], finished with
+/// [`EditSession::commit`] which validates the result. Arrows keep their
+/// weights, see [`DirectedGraph::arrow_weight`], while added arrows are
+/// unweighted.
+pub struct EditSession<'a> {
+    original: &'a DirectedGraph,
+
+    /// Successors of each node together with weights of arrows to them.
+    successors: Vec<Vec<(Node, Option<f64>)>>,
+    events: Vec<GraphEvent>,
+    listeners: Vec<Listener<'a>>,
+}
+
+impl DirectedGraph {
+    /// Starts new [`EditSession`] over a copy of the current graph.
+    pub fn edit(&self) -> EditSession<'_> {
+        let successors = self.iter_nodes()
+            .map(|node| {
+                self.get_successors(node)
+                    .iter()
+                    .map(|target| (*target, self.arrow_weight(node, *target)))
+                    .collect()
+            })
+            .collect();
+        EditSession {
+            original: self,
+            successors: successors,
+            events: Vec::new(),
+            listeners: Vec::new(),
+        }
+    }
+}
+
+impl<'a> EditSession<'a> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    #[inline(always)]
+    pub fn number_of_nodes(&self) -> i32 {
+        self.successors.len() as i32
+    }
+
+    /// Events recorded so far, in application order.
+    #[inline(always)]
+    pub fn events(&self) -> &[GraphEvent] {
+        &self.events
+    }
+
+    /// Registers `listener` called synchronously with all events on
+    /// successful [`EditSession::commit`].
+    pub fn subscribe<F>(&mut self, listener: F)
+        where F: FnMut(&[GraphEvent]) + 'a
+    {
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// Appends new node without arrows.
+    pub fn add_node(&mut self) -> Node {
+        let node = Node::from(self.number_of_nodes());
+        self.successors.push(Vec::new());
+        self.events.push(GraphEvent::NodeAdded(node));
+        node
+    }
+
+    /// Adds arrow `source -> target`.
+    ///
+    /// # Errors
+    /// [`EditError::NodeNotFound`] or [`EditError::ArrowAlreadyExists`].
+    pub fn add_arrow(&mut self, source: Node, target: Node) -> Result<(), EditError> {
+        self.verify_node(target)?;
+        let successors = self.successors_mut(source)?;
+        if successors.iter().any(|(node, _)| *node == target) {
+            return Err(EditError::ArrowAlreadyExists(source, target));
+        }
+        successors.push((target, None));
+        self.events.push(GraphEvent::ArrowAdded(source, target));
+        Ok(())
+    }
+
+    /// Removes arrow `source -> target`.
+    ///
+    /// # Errors
+    /// [`EditError::NodeNotFound`] or [`EditError::ArrowNotFound`].
+    pub fn remove_arrow(&mut self, source: Node, target: Node) -> Result<(), EditError> {
+        self.verify_node(target)?;
+        let successors = self.successors_mut(source)?;
+        let Some(position) = successors.iter().position(|(node, _)| *node == target) else {
+            return Err(EditError::ArrowNotFound(source, target));
+        };
+        successors.remove(position);
+        self.events.push(GraphEvent::ArrowRemoved(source, target));
+        Ok(())
+    }
+
+    /// Removes `node` together with its arrows. The node with the highest
+    /// id is moved to `node`'s id; returns its previous id if it was moved.
+    ///
+    /// # Errors
+    /// [`EditError::NodeNotFound`].
+    pub fn remove_node(&mut self, node: Node) -> Result<Option<Node>, EditError> {
+        self.verify_node(node)?;
+        let outgoing: Vec<Node> = self.successors_mut(node)?
+            .iter()
+            .map(|(target, _)| *target)
+            .collect();
+        for target in outgoing {
+            self.remove_arrow(node, target)?;
+        }
+        let incoming: Vec<Node> = self.iter_current_nodes()
+            .filter(|source| self.successors_of(*source).iter().any(|(target, _)| *target == node))
+            .collect();
+        for source in incoming {
+            self.remove_arrow(source, node)?;
+        }
+
+        let last = Node::from(self.number_of_nodes() - 1);
+        #[allow(clippy::cast_sign_loss)]
+        self.successors.swap_remove(node.id() as usize);
+        let moved = if last == node {
+            None
+        }
+        else
+        {
+            for successors in &mut self.successors {
+                for (target, _) in successors.iter_mut() {
+                    if *target == last {
+                        *target = node;
+                    }
+                }
+            }
+            Some(last)
+        };
+        self.events.push(GraphEvent::NodeRemoved(node, moved));
+        Ok(moved)
+    }
+
+    /// Applies `event` recorded by another session, e.g. to replay changes
+    /// on a copy of the original graph.
+    ///
+    /// # Errors
+    /// [`EditError::InconsistentEvent`] if `event` doesn't match the
+    /// current state, otherwise forwarded from the corresponding operation.
+    pub fn apply(&mut self, event: &GraphEvent) -> Result<(), EditError> {
+        match event {
+            GraphEvent::NodeAdded(node) => {
+                if *node != Node::from(self.number_of_nodes()) {
+                    return Err(EditError::InconsistentEvent(event.clone()));
+                }
+                self.add_node();
+            },
+            GraphEvent::ArrowAdded(source, target) => self.add_arrow(*source, *target)?,
+            GraphEvent::ArrowRemoved(source, target) => self.remove_arrow(*source, *target)?,
+            GraphEvent::NodeRemoved(node, moved) => {
+                let last = Node::from(self.number_of_nodes() - 1);
+                let expected = if last == *node { None } else { Some(last) };
+                if expected != *moved {
+                    return Err(EditError::InconsistentEvent(event.clone()));
+                }
+                self.remove_node(*node)?;
+            },
+            GraphEvent::PropertiesChanged(_, _) => { },
+        }
+        Ok(())
+    }
+
+    /// Builds and validates the edited graph. On success notifies all
+    /// listeners and returns the graph together with recorded events.
+    ///
+    /// # Errors
+    /// [`EditError::GraphError`] forwarded from [`DirectedGraph::from_dto`].
+    pub fn commit(mut self) -> Result<(DirectedGraph, Vec<GraphEvent>), EditError> {
+        let mut arrows = Vec::new();
+        for source in self.iter_current_nodes() {
+            for (target, weight) in self.successors_of(source) {
+                arrows.push(ArrowDTO::new(source.id(), target.id()).with_weight(*weight));
+            }
+        }
+        let dto = DirectedGraphDTO::new(self.number_of_nodes(), arrows);
+        let graph = DirectedGraph::from_dto(&dto)?;
+
+        let old_properties = self.original.basic_properties();
+        let new_properties = graph.basic_properties();
+        if old_properties != new_properties {
+            self.events.push(GraphEvent::PropertiesChanged(
+                old_properties.clone(),
+                new_properties.clone()));
+        }
+
+        for listener in &mut self.listeners {
+            listener(&self.events);
+        }
+        Ok((graph, self.events))
+    }
+
+    fn iter_current_nodes(&self) -> impl Iterator<Item=Node> {
+        (0..self.number_of_nodes()).map(Node::from)
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn successors_of(&self, node: Node) -> &[(Node, Option<f64>)] {
+        &self.successors[node.id() as usize]
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn successors_mut(&mut self, node: Node) -> Result<&mut Vec<(Node, Option<f64>)>, EditError> {
+        self.verify_node(node)?;
+        Ok(&mut self.successors[node.id() as usize])
+    }
+
+    fn verify_node(&self, node: Node) -> Result<(), EditError> {
+        if (0..self.number_of_nodes()).contains(&node.id()) {
+            Ok(())
+        }
+        else
+        {
+            Err(EditError::NodeNotFound(node))
+        }
+    }
+}
+
+impl Display for EditError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EditError::NodeNotFound(node)
+                => write!(f, "node {} not found", node.id()),
+            EditError::ArrowAlreadyExists(source, target)
+                => write!(f, "arrow {} -> {} already exists", source.id(), target.id()),
+            EditError::ArrowNotFound(source, target)
+                => write!(f, "arrow {} -> {} not found", source.id(), target.id()),
+            EditError::InconsistentEvent(event)
+                => write!(f, "event {event:?} inconsistent with the session"),
+            EditError::GraphError(_)
+                => f.write_str("invalid edited graph"),
+        }
+    }
+}
+
+impl std::error::Error for EditError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EditError::GraphError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use core::fmt::{Display, Formatter};
+use std::collections::VecDeque;
+
+use raf_structural_logging::models::SLDict;
+
+use super::{DirectedGraph, DirectedGraphBasicProperties, Node};
+
+/// Summary of in- and out-degrees of all nodes in a graph.
+#[derive(PartialEq, Clone, Debug)]
+pub struct DegreeStatistics {
+    pub min_in_degree: usize,
+    pub max_in_degree: usize,
+    pub mean_in_degree: f64,
+    pub min_out_degree: usize,
+    pub max_out_degree: usize,
+    pub mean_out_degree: f64,
+
+    /// Number of nodes without predecessors and successors.
+    pub isolated_nodes: usize,
+}
+
+/// Structural issue detected while building a [`GraphReport`].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum GraphAnomaly {
+    /// Node without any arrows, in a graph with more than one node.
+    IsolatedNode(Node),
+
+    /// Leaf without a taxon. Only reported for phylogenetic networks.
+    UnlabeledLeaf(Node),
+
+    /// Graph has more than one node of in-degree 0. Holds all of them,
+    /// ordered by id.
+    MultipleRoots(Vec<Node>),
+}
+
+/// One-call health report of a [`DirectedGraph`]. Built with at most four
+/// linear traversals of the graph.
+#[derive(PartialEq, Clone, Debug)]
+pub struct GraphReport {
+    pub basic_properties: DirectedGraphBasicProperties,
+    pub number_of_nodes: i32,
+    pub number_of_arrows: usize,
+    pub degree_statistics: DegreeStatistics,
+
+    /// Number of connected components in unoriented sense.
+    pub number_of_components: usize,
+
+    /// Length of the longest oriented path. `None` if the graph is not
+    /// acyclic.
+    pub height: Option<usize>,
+
+    /// Number of nodes with in-degree at least 2.
+    pub reticulation_count: usize,
+
+    pub anomalies: Vec<GraphAnomaly>,
+}
+
+impl DirectedGraph {
+    /// Builds [`GraphReport`] for the current graph.
+    pub fn report(&self) -> GraphReport {
+        build_report(self)
+    }
+
+    /// Calculates [`DegreeStatistics`] with a single pass over nodes.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn degree_statistics(&self) -> DegreeStatistics {
+        let mut min_in = usize::MAX;
+        let mut max_in = 0;
+        let mut min_out = usize::MAX;
+        let mut max_out = 0;
+        let mut isolated_nodes = 0;
+        for node in self.iter_nodes() {
+            let in_degree = self.in_degree(node);
+            let out_degree = self.out_degree(node);
+            min_in = core::cmp::min(min_in, in_degree);
+            max_in = core::cmp::max(max_in, in_degree);
+            min_out = core::cmp::min(min_out, out_degree);
+            max_out = core::cmp::max(max_out, out_degree);
+            if in_degree == 0 && out_degree == 0 {
+                isolated_nodes += 1;
+            }
+        }
+
+        let mean = self.number_of_arrows() as f64 / f64::from(self.number_of_nodes());
+        DegreeStatistics {
+            min_in_degree: min_in,
+            max_in_degree: max_in,
+            mean_in_degree: mean,
+            min_out_degree: min_out,
+            max_out_degree: max_out,
+            mean_out_degree: mean,
+            isolated_nodes: isolated_nodes,
+        }
+    }
+}
+
+/// Per node data shared by [`GraphReport`] and [`super::GraphSummary`],
+/// gathered with a single pass over nodes.
+pub(super) struct NodeScan {
+    /// Nodes of in-degree 0, ordered by id.
+    pub roots: Vec<Node>,
+
+    /// Nodes of in- and out-degree 0, ordered by id.
+    pub isolated: Vec<Node>,
+
+    /// Number of nodes of out-degree 0.
+    pub leaf_count: usize,
+
+    /// Number of nodes of in-degree at least 2.
+    pub reticulation_count: usize,
+}
+
+pub(super) fn scan_nodes(graph: &DirectedGraph) -> NodeScan {
+    let mut scan = NodeScan {
+        roots: Vec::new(),
+        isolated: Vec::new(),
+        leaf_count: 0,
+        reticulation_count: 0,
+    };
+    for node in graph.iter_nodes() {
+        let in_degree = graph.in_degree(node);
+        let out_degree = graph.out_degree(node);
+        if in_degree == 0 {
+            scan.roots.push(node);
+            if out_degree == 0 {
+                scan.isolated.push(node);
+            }
+        }
+        if out_degree == 0 {
+            scan.leaf_count += 1;
+        }
+        if in_degree > 1 {
+            scan.reticulation_count += 1;
+        }
+    }
+    scan
+}
+
+#[allow(clippy::cast_sign_loss)]
+fn build_report(graph: &DirectedGraph) -> GraphReport {
+    let number_of_nodes = graph.number_of_nodes();
+    let size = number_of_nodes as usize;
+    let NodeScan { roots, isolated, reticulation_count, .. } = scan_nodes(graph);
+
+    let mut anomalies = Vec::new();
+    if size > 1 {
+        for node in isolated {
+            anomalies.push(GraphAnomaly::IsolatedNode(node));
+        }
+    }
+    if roots.len() > 1 {
+        anomalies.push(GraphAnomaly::MultipleRoots(roots));
+    }
+
+    let height = if graph.basic_properties().acyclic {
+        Some(longest_path(graph))
+    }
+    else
+    {
+        None
+    };
+
+    GraphReport {
+        basic_properties: graph.basic_properties().clone(),
+        number_of_nodes: number_of_nodes,
+        number_of_arrows: graph.number_of_arrows(),
+        degree_statistics: graph.degree_statistics(),
+        number_of_components: count_components(graph),
+        height: height,
+        reticulation_count: reticulation_count,
+        anomalies: anomalies,
+    }
+}
+
+#[allow(clippy::cast_sign_loss)]
+fn count_components(graph: &DirectedGraph) -> usize {
+    let size = graph.number_of_nodes() as usize;
+    let mut seen = vec![false; size];
+    let mut queue = VecDeque::new();
+    let mut components = 0;
+    for start in graph.iter_nodes() {
+        if seen[start.id() as usize] {
+            continue;
+        }
+        components += 1;
+        seen[start.id() as usize] = true;
+        queue.push_back(start);
+        while let Some(node) = queue.pop_front() {
+            let neighbours = graph.get_successors(node)
+                .iter()
+                .chain(graph.get_predecessors(node));
+            for next in neighbours {
+                let idx = next.id() as usize;
+                if !seen[idx] {
+                    seen[idx] = true;
+                    queue.push_back(*next);
+                }
+            }
+        }
+    }
+    components
+}
+
+/// Kahn-style pass computing the longest path. Requires an acyclic graph.
+#[allow(clippy::cast_sign_loss)]
+pub(super) fn longest_path(graph: &DirectedGraph) -> usize {
+    let size = graph.number_of_nodes() as usize;
+    let mut remaining: Vec<usize> = graph.iter_nodes()
+        .map(|node| graph.get_predecessors(node).len())
+        .collect();
+    let mut levels = vec![0usize; size];
+    let mut stack: Vec<Node> = graph.iter_nodes()
+        .filter(|node| remaining[node.id() as usize] == 0)
+        .collect();
+    let mut result = 0;
+    while let Some(node) = stack.pop() {
+        let level = levels[node.id() as usize];
+        result = core::cmp::max(result, level);
+        for succ in graph.get_successors(node) {
+            let idx = succ.id() as usize;
+            levels[idx] = core::cmp::max(levels[idx], level + 1);
+            remaining[idx] -= 1;
+            if remaining[idx] == 0 {
+                stack.push(*succ);
+            }
+        }
+    }
+    result
+}
+
+impl From<&GraphReport> for SLDict {
+    /// Flat dictionary of report values. Mean degrees are formatted with
+    /// three decimal places, `height` is missing for cyclic graphs and
+    /// `anomalies` are joined with `"; "`.
+    fn from(value: &GraphReport) -> Self {
+        let props = &value.basic_properties;
+        let stats = &value.degree_statistics;
+        let mut dict = SLDict::new();
+        dict.insert("number_of_nodes", value.number_of_nodes);
+        dict.insert("number_of_arrows", value.number_of_arrows);
+        dict.insert("acyclic", props.acyclic);
+        dict.insert("connected", props.connected);
+        dict.insert("rooted", props.rooted);
+        dict.insert("binary", props.binary);
+        dict.insert("tree", props.tree);
+        dict.insert("min_in_degree", stats.min_in_degree);
+        dict.insert("max_in_degree", stats.max_in_degree);
+        dict.insert("mean_in_degree", format!("{:.3}", stats.mean_in_degree));
+        dict.insert("min_out_degree", stats.min_out_degree);
+        dict.insert("max_out_degree", stats.max_out_degree);
+        dict.insert("mean_out_degree", format!("{:.3}", stats.mean_out_degree));
+        dict.insert("isolated_nodes", stats.isolated_nodes);
+        dict.insert("number_of_components", value.number_of_components);
+        if let Some(height) = value.height {
+            dict.insert("height", height);
+        }
+        dict.insert("reticulation_count", value.reticulation_count);
+        let anomalies: Vec<String> = value.anomalies.iter()
+            .map(ToString::to_string)
+            .collect();
+        dict.insert("anomalies", anomalies.join("; "));
+        dict
+    }
+}
+
+impl Display for GraphAnomaly {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            GraphAnomaly::IsolatedNode(node)
+                => write!(f, "isolated node {}", node.id()),
+            GraphAnomaly::UnlabeledLeaf(node)
+                => write!(f, "unlabeled leaf {}", node.id()),
+            GraphAnomaly::MultipleRoots(nodes) => {
+                f.write_str("multiple roots [")?;
+                for (idx, node) in nodes.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", node.id())?;
+                }
+                f.write_str("]")
+            },
+        }
+    }
+}
+
+impl Display for GraphReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let props = &self.basic_properties;
+        let stats = &self.degree_statistics;
+        writeln!(f, "nodes: {}, arrows: {}, components: {}",
+            self.number_of_nodes, self.number_of_arrows, self.number_of_components)?;
+        writeln!(f, "acyclic: {}, connected: {}, rooted: {}, binary: {}, tree: {}",
+            props.acyclic, props.connected, props.rooted, props.binary, props.tree)?;
+        writeln!(f, "in-degree: min {}, max {}, mean {:.3}",
+            stats.min_in_degree, stats.max_in_degree, stats.mean_in_degree)?;
+        writeln!(f, "out-degree: min {}, max {}, mean {:.3}",
+            stats.min_out_degree, stats.max_out_degree, stats.mean_out_degree)?;
+        match self.height {
+            Some(height) => writeln!(f, "height: {height}")?,
+            None => writeln!(f, "height: n/a")?,
+        }
+        writeln!(f, "reticulations: {}", self.reticulation_count)?;
+        if self.anomalies.is_empty() {
+            f.write_str("anomalies: none")
+        }
+        else
+        {
+            f.write_str("anomalies:")?;
+            for anomaly in &self.anomalies {
+                write!(f, "\n  - {anomaly}")?;
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use core::fmt::{Display, Formatter};
+use std::cell::OnceCell;
+
+use super::{
+    graph_report::{longest_path, scan_nodes},
+    DirectedGraph,
+    DirectedGraphBasicProperties,
+    Node};
+
+/// Compact overview of a [`DirectedGraph`], built with the same single pass
+/// over nodes as [`super::GraphReport`]. Unlike the report it borrows the
+/// graph, so that maximal depth is calculated only when needed, see
+/// [`GraphSummary::max_depth`].
+#[derive(Clone, Debug)]
+pub struct GraphSummary<'a> {
+    graph: &'a DirectedGraph,
+    number_of_nodes: i32,
+    number_of_arrows: usize,
+    basic_properties: DirectedGraphBasicProperties,
+    root: Option<Node>,
+    leaf_count: usize,
+    reticulation_count: usize,
+    max_depth: OnceCell<Option<usize>>,
+}
+
+impl DirectedGraph {
+    /// Builds [`GraphSummary`] for the current graph. Maximal depth is not
+    /// calculated until requested.
+    pub fn summary(&self) -> GraphSummary<'_> {
+        GraphSummary::new(self)
+    }
+}
+
+impl<'a> GraphSummary<'a> {
+    fn new(graph: &'a DirectedGraph) -> Self {
+        let scan = scan_nodes(graph);
+        Self {
+            graph: graph,
+            number_of_nodes: graph.number_of_nodes(),
+            number_of_arrows: graph.number_of_arrows(),
+            basic_properties: graph.basic_properties().clone(),
+            root: graph.root(),
+            leaf_count: scan.leaf_count,
+            reticulation_count: scan.reticulation_count,
+            max_depth: OnceCell::new(),
+        }
+    }
+
+    /// Calculates maximal depth eagerly, instead of on first
+    /// [`GraphSummary::max_depth`] call or on display.
+    #[must_use]
+    pub fn with_depth(self) -> Self {
+        self.max_depth();
+        self
+    }
+
+    #[inline(always)]
+    pub fn graph(&self) -> &'a DirectedGraph {
+        self.graph
+    }
+
+    #[inline(always)]
+    pub fn number_of_nodes(&self) -> i32 {
+        self.number_of_nodes
+    }
+
+    #[inline(always)]
+    pub fn number_of_arrows(&self) -> usize {
+        self.number_of_arrows
+    }
+
+    #[inline(always)]
+    pub fn basic_properties(&self) -> &DirectedGraphBasicProperties {
+        &self.basic_properties
+    }
+
+    /// Root of the graph, `None` if the graph is not rooted.
+    #[inline(always)]
+    pub fn root(&self) -> Option<Node> {
+        self.root
+    }
+
+    /// Number of nodes of out-degree 0.
+    #[inline(always)]
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    /// Number of nodes of in-degree at least 2.
+    #[inline(always)]
+    pub fn reticulation_count(&self) -> usize {
+        self.reticulation_count
+    }
+
+    /// Length of the longest path from the root, calculated on the first
+    /// call and cached. `None` if the graph is not rooted or not acyclic.
+    pub fn max_depth(&self) -> Option<usize> {
+        *self.max_depth.get_or_init(|| {
+            let props = &self.basic_properties;
+            if props.rooted && props.acyclic {
+                Some(longest_path(self.graph))
+            }
+            else
+            {
+                None
+            }
+        })
+    }
+
+    /// Whether maximal depth has been calculated already.
+    #[inline(always)]
+    pub fn is_depth_computed(&self) -> bool {
+        self.max_depth.get().is_some()
+    }
+}
+
+impl Display for GraphSummary<'_> {
+    /// Calculates maximal depth, unless already done.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let props = &self.basic_properties;
+        write!(f, "nodes: {}, arrows: {}, ", self.number_of_nodes, self.number_of_arrows)?;
+        match self.root {
+            Some(root) => writeln!(f, "root: {}", root.id())?,
+            None => writeln!(f, "root: none")?,
+        }
+        writeln!(f, "acyclic: {}, connected: {}, rooted: {}, binary: {}, tree: {}",
+            props.acyclic, props.connected, props.rooted, props.binary, props.tree)?;
+        writeln!(f, "leaves: {}, reticulations: {}", self.leaf_count, self.reticulation_count)?;
+        match self.max_depth() {
+            Some(depth) => write!(f, "max depth: {depth}"),
+            None => f.write_str("max depth: n/a"),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use core::{iter::{Chain, FusedIterator}, slice::Iter};
+
+use super::{DirectedGraph, Node, NodeIter};
+
+/// Read only access to the structure of a directed graph. Implemented by
+/// [`DirectedGraph`] and by its zero-copy views, so that traversals can be
+/// written once against the trait.
+pub trait GraphRead {
+    /// Total number of nodes. Nodes are `0..number_of_nodes`.
+    fn number_of_nodes(&self) -> i32;
+
+    /// Successors of `node`. Empty if `node` is not in the graph.
+    fn get_successors(&self, node: Node) -> &[Node];
+
+    /// Predecessors of `node`. Empty if `node` is not in the graph.
+    fn get_predecessors(&self, node: Node) -> &[Node];
+
+    #[inline(always)]
+    fn iter_nodes(&self) -> NodeIter {
+        NodeIter::new(self.number_of_nodes())
+    }
+
+    /// Iterates over predecessors of `node` followed by its successors,
+    /// i.e. over neighbours of `node` when arrows are treated as
+    /// undirected. A node connected by arrows in both directions appears
+    /// twice.
+    #[inline(always)]
+    fn iter_neighbours(&self, node: Node) -> NeighboursIter<'_> {
+        NeighboursIter {
+            inner: self.get_predecessors(node).iter().chain(self.get_successors(node).iter()),
+        }
+    }
+}
+
+impl GraphRead for DirectedGraph {
+    #[inline(always)]
+    fn number_of_nodes(&self) -> i32 {
+        DirectedGraph::number_of_nodes(self)
+    }
+
+    #[inline(always)]
+    fn get_successors(&self, node: Node) -> &[Node] {
+        DirectedGraph::get_successors(self, node)
+    }
+
+    #[inline(always)]
+    fn get_predecessors(&self, node: Node) -> &[Node] {
+        DirectedGraph::get_predecessors(self, node)
+    }
+}
+
+/// Neighbours of a single node, see [`GraphRead::iter_neighbours`].
+#[derive(Clone)]
+pub struct NeighboursIter<'a> {
+    inner: Chain<Iter<'a, Node>, Iter<'a, Node>>,
+}
+
+impl Iterator for NeighboursIter<'_> {
+    type Item = Node;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().copied()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl FusedIterator for NeighboursIter<'_> { }
+
+/// [`DirectedGraph`] with all arrows reversed, without copying. Created
+/// with [`DirectedGraph::reversed_view`].
+#[derive(Clone, Copy)]
+pub struct ReversedGraphView<'a> {
+    graph: &'a DirectedGraph,
+}
+
+impl<'a> ReversedGraphView<'a> {
+    /// The underlying, not reversed graph.
+    #[inline(always)]
+    pub fn graph(&self) -> &'a DirectedGraph {
+        self.graph
+    }
+}
+
+impl GraphRead for ReversedGraphView<'_> {
+    #[inline(always)]
+    fn number_of_nodes(&self) -> i32 {
+        self.graph.number_of_nodes()
+    }
+
+    #[inline(always)]
+    fn get_successors(&self, node: Node) -> &[Node] {
+        self.graph.get_predecessors(node)
+    }
+
+    #[inline(always)]
+    fn get_predecessors(&self, node: Node) -> &[Node] {
+        self.graph.get_successors(node)
+    }
+}
+
+/// [`DirectedGraph`] with arrows treated as undirected, without copying.
+/// Created with [`DirectedGraph::undirected_view`].
+#[derive(Clone, Copy)]
+pub struct UndirectedGraphView<'a> {
+    graph: &'a DirectedGraph,
+}
+
+impl<'a> UndirectedGraphView<'a> {
+    /// The underlying directed graph.
+    #[inline(always)]
+    pub fn graph(&self) -> &'a DirectedGraph {
+        self.graph
+    }
+
+    #[inline(always)]
+    pub fn number_of_nodes(&self) -> i32 {
+        self.graph.number_of_nodes()
+    }
+
+    #[inline(always)]
+    pub fn iter_nodes(&self) -> NodeIter {
+        self.graph.iter_nodes()
+    }
+
+    /// Predecessors followed by successors of `node` in the underlying
+    /// graph, see [`GraphRead::iter_neighbours`].
+    #[inline(always)]
+    pub fn get_neighbours(&self, node: Node) -> NeighboursIter<'a> {
+        GraphRead::iter_neighbours(self.graph, node)
+    }
+}
+
+impl DirectedGraph {
+    #[inline(always)]
+    pub fn reversed_view(&self) -> ReversedGraphView<'_> {
+        ReversedGraphView { graph: self }
+    }
+
+    #[inline(always)]
+    pub fn undirected_view(&self) -> UndirectedGraphView<'_> {
+        UndirectedGraphView { graph: self }
+    }
+}
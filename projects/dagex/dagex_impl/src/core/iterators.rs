@@ -0,0 +1,572 @@
+use core::cmp::Reverse;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ops::Range;
+use std::collections::{BinaryHeap, VecDeque};
+
+use super::{DirectedGraph, LevelOrderError, Node, NodeBitSet, NotAcyclicError};
+
+/// Iterator over all nodes of a [`DirectedGraph`], ordered by id. Returned
+/// by [`DirectedGraph::iter_nodes`].
+#[derive(Clone, Debug)]
+pub struct NodeIter {
+    range: Range<i32>,
+}
+
+impl NodeIter {
+    #[inline(always)]
+    pub(crate) fn new(number_of_nodes: i32) -> Self {
+        Self { range: 0..number_of_nodes }
+    }
+}
+
+impl Iterator for NodeIter {
+    type Item = Node;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(Node::from)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+
+    #[inline(always)]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.range.nth(n).map(Node::from)
+    }
+
+    #[inline(always)]
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+        where F: FnMut(B, Self::Item) -> B
+    {
+        self.range.fold(init, |acc, id| f(acc, Node::from(id)))
+    }
+}
+
+impl DoubleEndedIterator for NodeIter {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(Node::from)
+    }
+}
+
+impl ExactSizeIterator for NodeIter { }
+
+impl FusedIterator for NodeIter { }
+
+/// Iterator over all arrows of a [`DirectedGraph`] as `(source, target)`
+/// pairs, ordered by source and then by target ids. Returned by
+/// [`DirectedGraph::iter_arrows`].
+#[derive(Clone)]
+pub struct ArrowIter<'a> {
+    graph: &'a DirectedGraph,
+    source: i32,
+    position: usize,
+    remaining: usize,
+}
+
+impl<'a> ArrowIter<'a> {
+    #[inline(always)]
+    pub(crate) fn new(graph: &'a DirectedGraph) -> Self {
+        Self { graph, source: 0, position: 0, remaining: graph.number_of_arrows() }
+    }
+}
+
+impl Iterator for ArrowIter<'_> {
+    type Item = (Node, Node);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.source < self.graph.number_of_nodes() {
+            let source = Node::from(self.source);
+            let successors = self.graph.get_successors(source);
+            if let Some(target) = successors.get(self.position) {
+                self.position += 1;
+                self.remaining -= 1;
+                return Some((source, *target));
+            }
+            self.source += 1;
+            self.position = 0;
+        }
+        None
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for ArrowIter<'_> { }
+
+impl FusedIterator for ArrowIter<'_> { }
+
+/// Breadth-first iterator over nodes reachable from a given node, following
+/// arrows. Returned by [`DirectedGraph::iter_bfs`].
+#[derive(Clone)]
+pub struct BfsIter<'a> {
+    graph: &'a DirectedGraph,
+    queue: VecDeque<Node>,
+    visited: Vec<bool>,
+}
+
+impl<'a> BfsIter<'a> {
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn new(graph: &'a DirectedGraph, start: Node) -> Self {
+        let mut visited = vec![false; graph.number_of_nodes() as usize];
+        let mut queue = VecDeque::new();
+        if let Some(flag) = visited.get_mut(start.id() as usize) {
+            *flag = true;
+            queue.push_back(start);
+        }
+        Self { graph, queue, visited }
+    }
+}
+
+impl Iterator for BfsIter<'_> {
+    type Item = Node;
+
+    #[allow(clippy::cast_sign_loss)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.queue.pop_front()?;
+        for successor in self.graph.get_successors(node) {
+            let flag = &mut self.visited[successor.id() as usize];
+            if !*flag {
+                *flag = true;
+                self.queue.push_back(*successor);
+            }
+        }
+        Some(node)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.queue.len(), Some(self.visited.len()))
+    }
+}
+
+impl FusedIterator for BfsIter<'_> { }
+
+/// Lazy breadth-first iterator over nodes reachable from a given node by
+/// oriented paths of length at least one. Each node is yielded at most once,
+/// so it terminates on cyclic graphs. Returned by
+/// [`DirectedGraph::iter_descendants`] and [`DirectedGraph::iter_ancestors`].
+#[derive(Clone)]
+pub struct ReachableIter<'a> {
+    graph: &'a DirectedGraph,
+    follow: fn(&'a DirectedGraph, Node) -> &'a [Node],
+    queue: VecDeque<Node>,
+    visited: Vec<bool>,
+}
+
+impl<'a> ReachableIter<'a> {
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn new(
+        graph: &'a DirectedGraph,
+        start: Node,
+        follow: fn(&'a DirectedGraph, Node) -> &'a [Node]) -> Self
+    {
+        let mut iter = Self {
+            graph,
+            follow,
+            queue: VecDeque::new(),
+            visited: vec![false; graph.number_of_nodes() as usize],
+        };
+        iter.enqueue_neighbours(start);
+        iter
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn enqueue_neighbours(&mut self, node: Node) {
+        for neighbour in (self.follow)(self.graph, node) {
+            let flag = &mut self.visited[neighbour.id() as usize];
+            if !*flag {
+                *flag = true;
+                self.queue.push_back(*neighbour);
+            }
+        }
+    }
+}
+
+impl Iterator for ReachableIter<'_> {
+    type Item = Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.queue.pop_front()?;
+        self.enqueue_neighbours(node);
+        Some(node)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.queue.len(), Some(self.visited.len()))
+    }
+}
+
+impl FusedIterator for ReachableIter<'_> { }
+
+/// Iterator over nodes of an acyclic [`DirectedGraph`] in topological
+/// order, i.e. each node is yielded after all its predecessors. Ties are
+/// broken by ascending node id. Returned by [`DirectedGraph::iter_topological`].
+#[derive(Clone)]
+pub struct TopologicalOrderIter<'a> {
+    graph: &'a DirectedGraph,
+    in_degrees: Vec<usize>,
+    ready: BinaryHeap<Reverse<i32>>,
+    remaining: usize,
+}
+
+impl<'a> TopologicalOrderIter<'a> {
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn new(graph: &'a DirectedGraph) -> Self {
+        let in_degrees: Vec<usize> = graph.iter_nodes()
+            .map(|node| graph.get_predecessors(node).len())
+            .collect();
+        let ready = graph.iter_nodes()
+            .filter(|node| in_degrees[node.id() as usize] == 0)
+            .map(|node| Reverse(node.id()))
+            .collect();
+        let remaining = in_degrees.len();
+        Self { graph, in_degrees, ready, remaining }
+    }
+
+    /// Returns an arrow lying on an oriented cycle among nodes not yielded
+    /// yet, if any. Linear in the number of nodes.
+    pub(crate) fn find_cycle_arrow(mut self) -> Option<(Node, Node)> {
+        while self.next().is_some() { }
+        find_cycle_arrow(self.graph, &self.in_degrees)
+    }
+}
+
+/// Returns an arrow lying on an oriented cycle among nodes left with
+/// non-zero `in_degrees` by Kahn's algorithm, if any. Each such node has
+/// a predecessor among them, so walking predecessors has to close a cycle.
+#[allow(clippy::cast_sign_loss)]
+fn find_cycle_arrow(graph: &DirectedGraph, in_degrees: &[usize]) -> Option<(Node, Node)> {
+    let start = graph.iter_nodes().find(|node| in_degrees[node.id() as usize] > 0)?;
+    let mut visited = vec![false; in_degrees.len()];
+    let mut current = start;
+    loop {
+        visited[current.id() as usize] = true;
+        let previous = *graph.get_predecessors(current)
+            .iter()
+            .find(|pred| in_degrees[pred.id() as usize] > 0)?;
+        if visited[previous.id() as usize] {
+            return Some((previous, current));
+        }
+        current = previous;
+    }
+}
+
+impl Iterator for TopologicalOrderIter<'_> {
+    type Item = Node;
+
+    #[allow(clippy::cast_sign_loss)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse(id) = self.ready.pop()?;
+        let node = Node::from(id);
+        for successor in self.graph.get_successors(node) {
+            let degree = &mut self.in_degrees[successor.id() as usize];
+            *degree -= 1;
+            if *degree == 0 {
+                self.ready.push(Reverse(successor.id()));
+            }
+        }
+        self.remaining -= 1;
+        Some(node)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.ready.len(), Some(self.remaining))
+    }
+}
+
+impl FusedIterator for TopologicalOrderIter<'_> { }
+
+/// Iterator over nodes of a rooted, acyclic [`DirectedGraph`] grouped by
+/// level, i.e. the length of the longest path from the root. Each level
+/// is ordered by ascending node id and every node comes after all its
+/// predecessors. Returned by [`DirectedGraph::iter_levels`].
+#[derive(Clone)]
+pub struct LevelIter<'a> {
+    levels: std::vec::IntoIter<Vec<Node>>,
+    phantom: PhantomData<&'a DirectedGraph>,
+}
+
+impl<'a> LevelIter<'a> {
+    /// Kahn's algorithm processing a whole level at once, starting from
+    /// all nodes of in-degree 0: a node becomes ready when its last
+    /// predecessor gets processed, i.e. one level below the deepest
+    /// predecessor. Nodes left with non-zero in-degree afterwards lie on,
+    /// or below, an oriented cycle.
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn new(graph: &'a DirectedGraph) -> Result<Self, LevelOrderError> {
+        let mut in_degrees: Vec<usize> = graph.iter_nodes()
+            .map(|node| graph.get_predecessors(node).len())
+            .collect();
+        let mut current: Vec<Node> = graph.iter_nodes()
+            .filter(|node| in_degrees[node.id() as usize] == 0)
+            .collect();
+        let number_of_roots = current.len();
+        let mut levels = Vec::new();
+        let mut processed = 0;
+        while !current.is_empty() {
+            processed += current.len();
+            let mut next_level = Vec::new();
+            for node in &current {
+                for successor in graph.get_successors(*node) {
+                    let degree = &mut in_degrees[successor.id() as usize];
+                    *degree -= 1;
+                    if *degree == 0 {
+                        next_level.push(*successor);
+                    }
+                }
+            }
+            next_level.sort_unstable_by_key(Node::id);
+            levels.push(core::mem::replace(&mut current, next_level));
+        }
+
+        if processed < in_degrees.len() {
+            let cycle_arrow = find_cycle_arrow(graph, &in_degrees);
+            return Err(LevelOrderError::NotAcyclic(NotAcyclicError { cycle_arrow: cycle_arrow }));
+        }
+        if number_of_roots != 1 {
+            return Err(LevelOrderError::NotRooted);
+        }
+        Ok(Self { levels: levels.into_iter(), phantom: PhantomData })
+    }
+}
+
+impl Iterator for LevelIter<'_> {
+    type Item = Vec<Node>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.levels.next()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.levels.size_hint()
+    }
+}
+
+impl FusedIterator for LevelIter<'_> { }
+
+/// Lazy depth-first iterator over all oriented paths between two nodes,
+/// each yielded as a sequence of nodes starting with the first and ending
+/// with the second one. Paths never repeat nodes, so it terminates on
+/// cyclic graphs. Returned by [`DirectedGraph::paths_between`].
+///
+/// # Notes
+/// Number of paths may be exponential in the size of the graph, e.g. in
+/// networks with many reticulations. Use [`DirectedGraph::count_paths`]
+/// to only count them.
+#[derive(Clone)]
+pub struct PathsIter<'a> {
+    graph: &'a DirectedGraph,
+    to: Node,
+    path: Vec<Node>,
+    /// Index of the next successor to visit, per node of `path`.
+    positions: Vec<usize>,
+    on_path: Vec<bool>,
+}
+
+impl<'a> PathsIter<'a> {
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn new(graph: &'a DirectedGraph, from: Node, to: Node) -> Self {
+        let size = graph.number_of_nodes() as usize;
+        let mut iter = Self {
+            graph,
+            to,
+            path: Vec::new(),
+            positions: Vec::new(),
+            on_path: vec![false; size],
+        };
+        let in_range = |node: Node| (0..graph.number_of_nodes()).contains(&node.id());
+        if in_range(from) && in_range(to) {
+            iter.push(from);
+        }
+        iter
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn push(&mut self, node: Node) {
+        self.on_path[node.id() as usize] = true;
+        self.path.push(node);
+        self.positions.push(0);
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn pop(&mut self) {
+        if let Some(node) = self.path.pop() {
+            self.on_path[node.id() as usize] = false;
+            self.positions.pop();
+        }
+    }
+}
+
+impl Iterator for PathsIter<'_> {
+    type Item = Vec<Node>;
+
+    #[allow(clippy::cast_sign_loss)]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = *self.path.last()?;
+            let position = self.positions.last_mut()?;
+            if *position == 0 && node == self.to {
+                // Paths can't go past the target, it would have to repeat.
+                let result = self.path.clone();
+                self.pop();
+                return Some(result);
+            }
+            let successors = self.graph.get_successors(node);
+            match successors.get(*position) {
+                Some(successor) => {
+                    *position += 1;
+                    if !self.on_path[successor.id() as usize] {
+                        self.push(*successor);
+                    }
+                },
+                None => self.pop(),
+            }
+        }
+    }
+}
+
+impl FusedIterator for PathsIter<'_> { }
+
+/// Order of [`TraversalIter`].
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum TraversalOrder {
+    /// Breadth-first, see [`DirectedGraph::bfs_from`].
+    BreadthFirst,
+
+    /// Depth-first pre-order, see [`DirectedGraph::dfs_from`].
+    DepthFirst,
+}
+
+/// Lazy traversal of nodes reachable from a given node, following arrows.
+/// Yields `(node, depth)` pairs, where depth is the number of arrows
+/// followed from the start node. Successors are visited in ascending id
+/// order, so traversal is deterministic. Returned by
+/// [`DirectedGraph::bfs_from`] and [`DirectedGraph::dfs_from`].
+///
+/// By default each node is yielded once, on its first visit. In
+/// breadth-first order that is with its smallest depth. With [`TraversalIter::unique_visits`] set to `false`
+/// nodes are yielded once per path from the start node, as if the graph
+/// was unfolded into a tree. Such traversal terminates on cyclic graphs
+/// only with [`TraversalIter::max_depth`].
+#[derive(Clone)]
+pub struct TraversalIter<'a, P = fn(Node) -> bool> {
+    graph: &'a DirectedGraph,
+    order: TraversalOrder,
+    pending: VecDeque<(Node, u32)>,
+    visited: NodeBitSet,
+    max_depth: Option<u32>,
+    unique_visits: bool,
+    prune: P,
+}
+
+impl<'a> TraversalIter<'a> {
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn new(graph: &'a DirectedGraph, start: Node, order: TraversalOrder) -> Self {
+        let mut pending = VecDeque::new();
+        if (0..graph.number_of_nodes()).contains(&start.id()) {
+            pending.push_back((start, 0));
+        }
+        Self {
+            graph,
+            order,
+            pending,
+            visited: NodeBitSet::new(graph.number_of_nodes() as usize),
+            max_depth: None,
+            unique_visits: true,
+            prune: |_| false,
+        }
+    }
+}
+
+impl<'a, P> TraversalIter<'a, P>
+    where P: FnMut(Node) -> bool
+{
+    /// Doesn't descend below nodes of depth `depth`.
+    #[must_use]
+    pub fn max_depth(mut self, depth: u32) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Whether each node is yielded at most once, `true` by default. See
+    /// [`TraversalIter`] docs.
+    #[must_use]
+    pub fn unique_visits(mut self, value: bool) -> Self {
+        self.unique_visits = value;
+        self
+    }
+
+    /// Doesn't descend below nodes for which `prune` returns `true`. Such
+    /// nodes are still yielded. Replaces previously set predicate.
+    pub fn prune_when<Q>(self, prune: Q) -> TraversalIter<'a, Q>
+        where Q: FnMut(Node) -> bool
+    {
+        TraversalIter {
+            graph: self.graph,
+            order: self.order,
+            pending: self.pending,
+            visited: self.visited,
+            max_depth: self.max_depth,
+            unique_visits: self.unique_visits,
+            prune: prune,
+        }
+    }
+
+    #[inline(always)]
+    pub fn order(&self) -> TraversalOrder {
+        self.order
+    }
+
+    fn pop(&mut self) -> Option<(Node, u32)> {
+        match self.order {
+            TraversalOrder::BreadthFirst => self.pending.pop_front(),
+            TraversalOrder::DepthFirst => self.pending.pop_back(),
+        }
+    }
+}
+
+impl<P> Iterator for TraversalIter<'_, P>
+    where P: FnMut(Node) -> bool
+{
+    type Item = (Node, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, depth) = self.pop()?;
+            // Nodes are marked when yielded rather than when queued, so that
+            // depth-first traversal stays pre-order.
+            if self.unique_visits && !self.visited.insert(node) {
+                continue;
+            }
+            let descend = self.max_depth.map_or(true, |max_depth| depth < max_depth)
+                && !(self.prune)(node);
+            if descend {
+                let successors = self.graph.get_successors(node);
+                let unvisited = |successor: &&Node| !self.unique_visits || !self.visited.contains(**successor);
+                match self.order {
+                    TraversalOrder::BreadthFirst => self.pending.extend(
+                        successors.iter().filter(unvisited).map(|successor| (*successor, depth + 1))),
+                    TraversalOrder::DepthFirst => self.pending.extend(
+                        successors.iter().rev().filter(unvisited).map(|successor| (*successor, depth + 1))),
+                }
+            }
+            return Some((node, depth));
+        }
+    }
+}
+
+impl<P> FusedIterator for TraversalIter<'_, P>
+    where P: FnMut(Node) -> bool
+{ }
@@ -1,9 +1,34 @@
 mod graph_id;
 mod node;
 mod directed_graph_dto;
+mod arrow_storage;
 mod directed_graph;
+mod directed_graph_builder;
+mod iterators;
+mod node_set;
+mod node_map;
+mod edit_session;
+mod graph_report;
+mod graph_summary;
+mod graph_view;
+mod subgraph_search;
+mod canonical;
+mod edge_list;
 
 pub use graph_id::*;
 pub use node::*;
 pub use directed_graph_dto::*;
+pub use arrow_storage::*;
 pub use directed_graph::*;
+pub use directed_graph_builder::*;
+pub use iterators::*;
+pub use node_set::*;
+pub use node_map::*;
+pub use edit_session::*;
+pub use graph_report::*;
+pub use graph_summary::*;
+pub use graph_view::*;
+pub use edge_list::DEFAULT_EDGE_LIST_LIMIT;
+
+pub(crate) use edge_list::write_edge_list;
+pub(crate) use subgraph_search::find_occurrences;
@@ -16,3 +16,97 @@ impl From<i32> for Node {
         Self { id: value }
     }
 }
+
+/// Optional [`Node`] packed into 4 bytes, unlike `Option<Node>` which takes
+/// 8. Meant for big per node tables, e.g. of parents. Absence is encoded
+/// with the [`PackedOptionNode::SENTINEL`] id, which is never a valid node
+/// id since nodes of a graph are `0..DirectedGraph::max_size()`.
+///
+/// [`DirectedGraph::max_size()`]: super::DirectedGraph::max_size
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
+#[repr(transparent)]
+pub struct PackedOptionNode {
+    id: i32,
+}
+
+impl PackedOptionNode {
+    /// Id reserved for absence of a node.
+    pub const SENTINEL: i32 = i32::MIN;
+
+    /// Packed `None`.
+    pub const NONE: Self = Self { id: Self::SENTINEL };
+
+    /// # Panics
+    /// When the node id is [`PackedOptionNode::SENTINEL`].
+    #[inline(always)]
+    pub fn from_option(value: Option<Node>) -> Self {
+        match value {
+            Some(node) => {
+                assert!(node.id() != Self::SENTINEL, "Node id {} is reserved.", Self::SENTINEL);
+                Self { id: node.id() }
+            },
+            None => Self::NONE,
+        }
+    }
+
+    #[inline(always)]
+    pub fn to_option(self) -> Option<Node> {
+        if self.is_none() {
+            None
+        }
+        else
+        {
+            Some(Node::from(self.id))
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_none(self) -> bool {
+        self.id == Self::SENTINEL
+    }
+
+    #[inline(always)]
+    pub fn is_some(self) -> bool {
+        !self.is_none()
+    }
+
+    /// Takes the node out, leaving [`PackedOptionNode::NONE`] in its place.
+    #[inline(always)]
+    pub fn take(&mut self) -> Option<Node> {
+        core::mem::replace(self, Self::NONE).to_option()
+    }
+}
+
+impl Default for PackedOptionNode {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+impl From<Option<Node>> for PackedOptionNode {
+    #[inline(always)]
+    fn from(value: Option<Node>) -> Self {
+        Self::from_option(value)
+    }
+}
+
+impl From<Node> for PackedOptionNode {
+    #[inline(always)]
+    fn from(value: Node) -> Self {
+        Self::from_option(Some(value))
+    }
+}
+
+impl From<PackedOptionNode> for Option<Node> {
+    #[inline(always)]
+    fn from(value: PackedOptionNode) -> Self {
+        value.to_option()
+    }
+}
+
+impl core::fmt::Debug for PackedOptionNode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.to_option().fmt(f)
+    }
+}
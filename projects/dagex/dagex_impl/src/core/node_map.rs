@@ -0,0 +1,264 @@
+use super::{DirectedGraph, GraphId, Node};
+
+/// Dense storage of optional per node values of a single [`DirectedGraph`],
+/// e.g. branch lengths. Created with [`DirectedGraph::new_node_map`].
+///
+/// The map remembers [`GraphId`] of its graph, and every accessor takes
+/// the graph as well, so that in debug builds using the map with a
+/// different graph panics, see [`NodeMap::debug_check_graph`]. Note that
+/// cloned graphs get new ids.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct NodeMap<T> {
+    graph_id: GraphId,
+    values: Vec<Option<T>>,
+}
+
+impl<T> NodeMap<T> {
+    #[inline(always)]
+    pub fn graph_id(&self) -> GraphId {
+        self.graph_id
+    }
+
+    /// Number of nodes of the graph, i.e. the capacity of the map.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Number of nodes with a value.
+    pub fn len(&self) -> usize {
+        self.values.iter().filter(|value| value.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.iter().all(Option::is_none)
+    }
+
+    /// Returns value of `node` of `graph`. `None` if not set or `node` is
+    /// outside of the graph.
+    ///
+    /// # Panics
+    /// In debug builds, when the map was created for a different graph.
+    #[allow(clippy::cast_sign_loss)]
+    #[track_caller]
+    #[inline(always)]
+    pub fn get(&self, graph: &DirectedGraph, node: Node) -> Option<&T> {
+        self.debug_check_graph(graph);
+        let id = node.id();
+        if id < 0 {
+            return None;
+        }
+        self.values.get(id as usize).and_then(Option::as_ref)
+    }
+
+    /// # Panics
+    /// In debug builds, when the map was created for a different graph.
+    #[allow(clippy::cast_sign_loss)]
+    #[track_caller]
+    #[inline(always)]
+    pub fn get_mut(&mut self, graph: &DirectedGraph, node: Node) -> Option<&mut T> {
+        self.debug_check_graph(graph);
+        let id = node.id();
+        if id < 0 {
+            return None;
+        }
+        self.values.get_mut(id as usize).and_then(Option::as_mut)
+    }
+
+    /// Sets value of `node` of `graph`. Returns the previous value.
+    ///
+    /// # Panics
+    /// When `node` is outside of the graph. In debug builds also when the
+    /// map was created for a different graph.
+    #[track_caller]
+    #[inline(always)]
+    pub fn set(&mut self, graph: &DirectedGraph, node: Node, value: T) -> Option<T> {
+        self.debug_check_graph(graph);
+        let idx = self.position(node);
+        self.values[idx].replace(value)
+    }
+
+    /// Removes value of `node` of `graph`. Returns the previous value.
+    ///
+    /// # Panics
+    /// When `node` is outside of the graph. In debug builds also when the
+    /// map was created for a different graph.
+    #[track_caller]
+    #[inline(always)]
+    pub fn remove(&mut self, graph: &DirectedGraph, node: Node) -> Option<T> {
+        self.debug_check_graph(graph);
+        let idx = self.position(node);
+        self.values[idx].take()
+    }
+
+    /// Iterates over nodes with a value, ordered by id.
+    pub fn iter(&self) -> impl Iterator<Item=(Node, &T)> + '_ {
+        self.values.iter()
+            .enumerate()
+            .filter_map(|(idx, value)| {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                value.as_ref().map(|value| (Node::from(idx as i32), value))
+            })
+    }
+
+    /// Checks that the map was created for `graph`. Compiled out in release
+    /// builds.
+    ///
+    /// # Panics
+    /// In debug builds, when the map was created for a different graph.
+    #[track_caller]
+    #[inline(always)]
+    pub fn debug_check_graph(&self, graph: &DirectedGraph) {
+        debug_assert_eq!(
+            graph.id(),
+            self.graph_id,
+            "NodeMap used with a different graph than it was created for.");
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    #[track_caller]
+    #[inline(always)]
+    fn position(&self, node: Node) -> usize {
+        let id = node.id();
+        assert!(id >= 0 && (id as usize) < self.values.len(), "Node outside of NodeMap capacity.");
+        id as usize
+    }
+}
+
+/// Dense storage of optional per arrow values of a single
+/// [`DirectedGraph`]. Created with [`DirectedGraph::new_arrow_map_data`].
+///
+/// Values are laid out in the order of successors lists, so a lookup is
+/// a binary search among successors of the arrow's source, i.e. `O(1)`
+/// for graphs of bounded out-degree.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ArrowMapData<T> {
+    graph_id: GraphId,
+    offsets: Vec<usize>,
+    targets: Vec<Node>,
+    values: Vec<Option<T>>,
+}
+
+impl<T> ArrowMapData<T> {
+    #[inline(always)]
+    pub fn graph_id(&self) -> GraphId {
+        self.graph_id
+    }
+
+    /// Number of arrows of the graph, i.e. the capacity of the map.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Number of arrows with a value.
+    pub fn len(&self) -> usize {
+        self.values.iter().filter(|value| value.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.iter().all(Option::is_none)
+    }
+
+    /// Returns value of arrow `source -> target`. `None` if not set or
+    /// there is no such arrow.
+    #[inline(always)]
+    pub fn get(&self, source: Node, target: Node) -> Option<&T> {
+        self.index_of(source, target)
+            .and_then(|idx| self.values[idx].as_ref())
+    }
+
+    #[inline(always)]
+    pub fn get_mut(&mut self, source: Node, target: Node) -> Option<&mut T> {
+        self.index_of(source, target)
+            .and_then(|idx| self.values[idx].as_mut())
+    }
+
+    /// Sets value of arrow `source -> target`. Returns the previous value.
+    ///
+    /// # Panics
+    /// When there is no such arrow in the graph.
+    #[track_caller]
+    pub fn set(&mut self, source: Node, target: Node, value: T) -> Option<T> {
+        let idx = self.index_of(source, target)
+            .expect("Arrow not present in ArrowMapData graph.");
+        self.values[idx].replace(value)
+    }
+
+    /// Removes value of arrow `source -> target`. Returns the previous value.
+    pub fn remove(&mut self, source: Node, target: Node) -> Option<T> {
+        self.index_of(source, target)
+            .and_then(|idx| self.values[idx].take())
+    }
+
+    /// Iterates over `(source, target)` arrows with a value, ordered by
+    /// source and then target.
+    pub fn iter(&self) -> impl Iterator<Item=((Node, Node), &T)> + '_ {
+        self.offsets.windows(2)
+            .enumerate()
+            .flat_map(move |(source, range)| {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                let source = Node::from(source as i32);
+                (range[0]..range[1]).filter_map(move |idx| {
+                    self.values[idx].as_ref().map(|value| ((source, self.targets[idx]), value))
+                })
+            })
+    }
+
+    /// Checks that the map was created for `graph`. Compiled out in release
+    /// builds.
+    ///
+    /// # Panics
+    /// In debug builds, when the map was created for a different graph.
+    #[track_caller]
+    #[inline(always)]
+    pub fn debug_check_graph(&self, graph: &DirectedGraph) {
+        debug_assert!(
+            self.graph_id == graph.id(),
+            "ArrowMapData used with a different graph than it was created for.");
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn index_of(&self, source: Node, target: Node) -> Option<usize> {
+        let id = source.id();
+        if id < 0 || id as usize + 1 >= self.offsets.len() {
+            return None;
+        }
+        let start = self.offsets[id as usize];
+        let end = self.offsets[id as usize + 1];
+        self.targets[start..end]
+            .binary_search_by_key(&target.id(), Node::id)
+            .ok()
+            .map(|position| start + position)
+    }
+}
+
+impl DirectedGraph {
+    /// Creates empty [`NodeMap`] for the graph.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn new_node_map<T>(&self) -> NodeMap<T> {
+        let mut values = Vec::with_capacity(self.number_of_nodes() as usize);
+        values.resize_with(self.number_of_nodes() as usize, || None);
+        NodeMap { graph_id: self.id(), values: values }
+    }
+
+    /// Creates empty [`ArrowMapData`] for the graph.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn new_arrow_map_data<T>(&self) -> ArrowMapData<T> {
+        let mut offsets = Vec::with_capacity(self.number_of_nodes() as usize + 1);
+        let mut targets = Vec::with_capacity(self.number_of_arrows());
+        offsets.push(0);
+        for node in self.iter_nodes() {
+            targets.extend_from_slice(self.get_successors(node));
+            offsets.push(targets.len());
+        }
+        let mut values = Vec::with_capacity(targets.len());
+        values.resize_with(targets.len(), || None);
+        ArrowMapData {
+            graph_id: self.id(),
+            offsets: offsets,
+            targets: targets,
+            values: values,
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use core::fmt::{Debug, Formatter};
+use std::collections::HashMap;
+
+use super::{DirectedGraph, Node};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Fixed capacity set of [`Node`]s backed by a bitset. Capacity is
+/// typically the number of nodes of the corresponding graph.
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct NodeBitSet {
+    words: Vec<u64>,
+    capacity: usize,
+}
+
+impl NodeBitSet {
+    /// Creates empty set able to hold nodes with ids in `0..capacity`.
+    pub fn new(capacity: usize) -> Self {
+        let words = vec![0; capacity.div_ceil(WORD_BITS)];
+        Self { words, capacity }
+    }
+
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Inserts `node`. Returns `true` if it was not present.
+    ///
+    /// # Panics
+    /// When `node` is outside of `0..capacity` range.
+    #[inline(always)]
+    pub fn insert(&mut self, node: Node) -> bool {
+        let (word, mask) = self.position(node);
+        let previous = self.words[word];
+        self.words[word] = previous | mask;
+        previous & mask == 0
+    }
+
+    /// Removes `node`. Returns `true` if it was present.
+    ///
+    /// # Panics
+    /// When `node` is outside of `0..capacity` range.
+    #[inline(always)]
+    pub fn remove(&mut self, node: Node) -> bool {
+        let (word, mask) = self.position(node);
+        let previous = self.words[word];
+        self.words[word] = previous & !mask;
+        previous & mask != 0
+    }
+
+    /// Checks if `node` is in the set. Nodes outside of `0..capacity`
+    /// range are never in the set.
+    #[allow(clippy::cast_sign_loss)]
+    #[inline(always)]
+    pub fn contains(&self, node: Node) -> bool {
+        let id = node.id();
+        if id < 0 || id as usize >= self.capacity {
+            return false;
+        }
+        let (word, mask) = self.position(node);
+        self.words[word] & mask != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// # Panics
+    /// When capacities don't match.
+    #[must_use]
+    pub fn union(&self, other: &NodeBitSet) -> NodeBitSet {
+        self.combine(other, |left, right| left | right)
+    }
+
+    /// # Panics
+    /// When capacities don't match.
+    #[must_use]
+    pub fn intersection(&self, other: &NodeBitSet) -> NodeBitSet {
+        self.combine(other, |left, right| left & right)
+    }
+
+    /// # Panics
+    /// When capacities don't match.
+    #[must_use]
+    pub fn difference(&self, other: &NodeBitSet) -> NodeBitSet {
+        self.combine(other, |left, right| left & !right)
+    }
+
+    /// Iterates over nodes in the set, ordered by id.
+    pub fn iter(&self) -> impl Iterator<Item=Node> + '_ {
+        self.words.iter()
+            .enumerate()
+            .flat_map(|(idx, word)| {
+                let mut bits = *word;
+                core::iter::from_fn(move || {
+                    if bits == 0 {
+                        return None;
+                    }
+                    let offset = bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                    Some(Node::from((idx * WORD_BITS + offset) as i32))
+                })
+            })
+    }
+
+    fn combine<F>(&self, other: &NodeBitSet, op: F) -> NodeBitSet
+        where F: Fn(u64, u64) -> u64
+    {
+        assert_eq!(self.capacity, other.capacity, "NodeBitSet capacities don't match.");
+        let words = self.words.iter()
+            .zip(&other.words)
+            .map(|(left, right)| op(*left, *right))
+            .collect();
+        NodeBitSet { words, capacity: self.capacity }
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    #[inline(always)]
+    fn position(&self, node: Node) -> (usize, u64) {
+        let id = node.id() as usize;
+        assert!(id < self.capacity, "Node outside of NodeBitSet capacity.");
+        (id / WORD_BITS, 1 << (id % WORD_BITS))
+    }
+}
+
+impl Debug for NodeBitSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter().map(|node| node.id())).finish()
+    }
+}
+
+/// Ancestor/descendant queries over a [`DirectedGraph`] returning
+/// [`NodeBitSet`]s. Both relations are reflexive, i.e. each node is its own
+/// ancestor and descendant. Optionally caches computed sets per node.
+pub struct NodeSetQuery<'a> {
+    graph: &'a DirectedGraph,
+    cache: Option<(HashMap<Node, NodeBitSet>, HashMap<Node, NodeBitSet>)>,
+}
+
+impl<'a> NodeSetQuery<'a> {
+    /// Creates new [`NodeSetQuery`] without cache.
+    pub fn new(graph: &'a DirectedGraph) -> Self {
+        Self { graph, cache: None }
+    }
+
+    /// Creates new [`NodeSetQuery`] caching each computed set. Note that
+    /// the cache may take up to `O(n^2)` bits.
+    pub fn with_cache(graph: &'a DirectedGraph) -> Self {
+        Self { graph, cache: Some((HashMap::new(), HashMap::new())) }
+    }
+
+    #[inline(always)]
+    pub fn graph(&self) -> &'a DirectedGraph {
+        self.graph
+    }
+
+    /// Returns all nodes reachable from `node`, including `node`.
+    pub fn descendant_set(&mut self, node: Node) -> NodeBitSet {
+        if let Some((descendants, _)) = &self.cache {
+            if let Some(result) = descendants.get(&node) {
+                return result.clone();
+            }
+        }
+
+        let graph = self.graph;
+        let result = reachable(graph, node, |n| graph.get_successors(n));
+        if let Some((descendants, _)) = &mut self.cache {
+            descendants.insert(node, result.clone());
+        }
+        result
+    }
+
+    /// Returns all nodes `node` is reachable from, including `node`.
+    pub fn ancestor_set(&mut self, node: Node) -> NodeBitSet {
+        if let Some((_, ancestors)) = &self.cache {
+            if let Some(result) = ancestors.get(&node) {
+                return result.clone();
+            }
+        }
+
+        let graph = self.graph;
+        let result = reachable(graph, node, |n| graph.get_predecessors(n));
+        if let Some((_, ancestors)) = &mut self.cache {
+            ancestors.insert(node, result.clone());
+        }
+        result
+    }
+
+    /// Returns nodes lying on some oriented path from `source` to `target`,
+    /// including both ends. Empty if there is no such path.
+    pub fn nodes_between(&mut self, source: Node, target: Node) -> NodeBitSet {
+        let descendants = self.descendant_set(source);
+        if !descendants.contains(target) {
+            #[allow(clippy::cast_sign_loss)]
+            return NodeBitSet::new(self.graph.number_of_nodes() as usize);
+        }
+        descendants.intersection(&self.ancestor_set(target))
+    }
+}
+
+#[allow(clippy::cast_sign_loss)]
+fn reachable<'g, F>(graph: &'g DirectedGraph, start: Node, next: F) -> NodeBitSet
+    where F: Fn(Node) -> &'g [Node]
+{
+    let mut result = NodeBitSet::new(graph.number_of_nodes() as usize);
+    if !(0..graph.number_of_nodes()).contains(&start.id()) {
+        return result;
+    }
+    result.insert(start);
+    let mut stack = vec![start];
+    while let Some(current) = stack.pop() {
+        for neighbour in next(current) {
+            if result.insert(*neighbour) {
+                stack.push(*neighbour);
+            }
+        }
+    }
+    result
+}
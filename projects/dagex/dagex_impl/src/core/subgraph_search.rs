@@ -0,0 +1,213 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::{DirectedGraph, Node, PackedOptionNode};
+
+impl DirectedGraph {
+    /// Finds at most `limit` occurrences of `pattern` in the current graph.
+    /// Each occurrence is an injective mapping from `pattern` nodes to the
+    /// current graph nodes, such that every arrow of `pattern` is mapped
+    /// onto an arrow of the current graph. Note that the search is
+    /// exponential in the worst case and meant for small patterns.
+    pub fn find_subgraph_occurrences(&self, pattern: &DirectedGraph, limit: usize)
+        -> Vec<HashMap<Node, Node>>
+    {
+        self.find_subgraph_occurrences_cancellable(pattern, limit, || false)
+    }
+
+    /// Same as [`DirectedGraph::find_subgraph_occurrences`], but stops
+    /// early once `is_cancelled` returns `true`. In that case occurrences
+    /// found so far are returned.
+    pub fn find_subgraph_occurrences_cancellable<F>(
+        &self,
+        pattern: &DirectedGraph,
+        limit: usize,
+        is_cancelled: F)
+        -> Vec<HashMap<Node, Node>>
+        where F: FnMut() -> bool
+    {
+        find_occurrences(self, pattern, limit, |_, _| true, is_cancelled)
+    }
+}
+
+/// Iterative backtracking search in the VF2 spirit. Pattern nodes are
+/// matched in BFS order so that candidates for each next node are taken
+/// from the neighbourhood of already matched nodes. Candidates are pruned
+/// by degrees, by `is_compatible` and by arrows to already matched nodes.
+pub(crate) fn find_occurrences<C, F>(
+    host: &DirectedGraph,
+    pattern: &DirectedGraph,
+    limit: usize,
+    mut is_compatible: C,
+    mut is_cancelled: F)
+    -> Vec<HashMap<Node, Node>>
+    where C: FnMut(Node, Node) -> bool,
+          F: FnMut() -> bool
+{
+    let mut result = Vec::new();
+    if limit == 0 {
+        return result;
+    }
+
+    let order = matching_order(pattern);
+    if order.is_empty() {
+        result.push(HashMap::new());
+        return result;
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    let mut mapping = vec![PackedOptionNode::NONE; pattern.number_of_nodes() as usize];
+    #[allow(clippy::cast_sign_loss)]
+    let mut used = vec![false; host.number_of_nodes() as usize];
+    let mut stack = Vec::<(Vec<Node>, usize)>::with_capacity(order.len());
+    stack.push((candidates(host, pattern, order[0], &mapping), 0));
+
+    while !stack.is_empty() {
+        if is_cancelled() {
+            break;
+        }
+
+        let depth = stack.len() - 1;
+        let frame = &mut stack[depth];
+        let pattern_node = order[depth];
+        if let Some(previous) = mapping[index(pattern_node)].take() {
+            used[index(previous)] = false;
+        }
+
+        if frame.1 >= frame.0.len() {
+            stack.pop();
+            continue;
+        }
+
+        let host_node = frame.0[frame.1];
+        frame.1 += 1;
+        if used[index(host_node)]
+            || !is_feasible(host, pattern, pattern_node, host_node, &mapping)
+            || !is_compatible(pattern_node, host_node)
+        {
+            continue;
+        }
+
+        mapping[index(pattern_node)] = PackedOptionNode::from(host_node);
+        used[index(host_node)] = true;
+
+        if depth + 1 == order.len() {
+            let occurrence: HashMap<Node, Node> = order.iter()
+                .map(|node| (*node, mapping[index(*node)].to_option().unwrap()))
+                .collect();
+            result.push(occurrence);
+            if result.len() >= limit {
+                break;
+            }
+        }
+        else
+        {
+            let next = candidates(host, pattern, order[depth + 1], &mapping);
+            stack.push((next, 0));
+        }
+    }
+
+    result
+}
+
+#[allow(clippy::cast_sign_loss)]
+#[inline(always)]
+fn index(node: Node) -> usize {
+    node.id() as usize
+}
+
+/// BFS order over the pattern, in unoriented sense, starting each component
+/// from its node of the highest degree.
+fn matching_order(pattern: &DirectedGraph) -> Vec<Node> {
+    #[allow(clippy::cast_sign_loss)]
+    let size = pattern.number_of_nodes() as usize;
+    let degree = |node: Node| pattern.get_successors(node).len() + pattern.get_predecessors(node).len();
+    let mut starts: Vec<Node> = pattern.iter_nodes().collect();
+    starts.sort_by_key(|node| core::cmp::Reverse(degree(*node)));
+
+    let mut visited = vec![false; size];
+    let mut order = Vec::with_capacity(size);
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if visited[index(start)] {
+            continue;
+        }
+        visited[index(start)] = true;
+        queue.push_back(start);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            let neighbours = pattern.get_successors(node)
+                .iter()
+                .chain(pattern.get_predecessors(node));
+            for neighbour in neighbours {
+                if !visited[index(*neighbour)] {
+                    visited[index(*neighbour)] = true;
+                    queue.push_back(*neighbour);
+                }
+            }
+        }
+    }
+    order
+}
+
+fn candidates(
+    host: &DirectedGraph,
+    pattern: &DirectedGraph,
+    pattern_node: Node,
+    mapping: &[PackedOptionNode]) -> Vec<Node>
+{
+    for predecessor in pattern.get_predecessors(pattern_node) {
+        if let Some(mapped) = mapping[index(*predecessor)].to_option() {
+            return host.get_successors(mapped).to_vec();
+        }
+    }
+
+    for successor in pattern.get_successors(pattern_node) {
+        if let Some(mapped) = mapping[index(*successor)].to_option() {
+            return host.get_predecessors(mapped).to_vec();
+        }
+    }
+
+    host.iter_nodes().collect()
+}
+
+fn is_feasible(
+    host: &DirectedGraph,
+    pattern: &DirectedGraph,
+    pattern_node: Node,
+    host_node: Node,
+    mapping: &[PackedOptionNode]) -> bool
+{
+    let pattern_successors = pattern.get_successors(pattern_node);
+    let pattern_predecessors = pattern.get_predecessors(pattern_node);
+    let host_successors = host.get_successors(host_node);
+    if host_successors.len() < pattern_successors.len()
+        || host.get_predecessors(host_node).len() < pattern_predecessors.len()
+    {
+        return false;
+    }
+
+    for successor in pattern_successors {
+        let target = if *successor == pattern_node {
+            Some(host_node)
+        }
+        else
+        {
+            mapping[index(*successor)].to_option()
+        };
+        if let Some(target) = target {
+            if !host_successors.contains(&target) {
+                return false;
+            }
+        }
+    }
+
+    for predecessor in pattern_predecessors {
+        if let Some(source) = mapping[index(*predecessor)].to_option() {
+            if !host.contains_arrow(source, host_node) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
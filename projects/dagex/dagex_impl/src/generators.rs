@@ -0,0 +1,169 @@
+//! Random graphs for property based tests. Available with the `test-utils`
+//! feature. All generators are deterministic for a given state of `rng`.
+use std::collections::HashMap;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    core::{ArrowDTO, DirectedGraphDTO},
+    phylo::PhylogeneticNetworkDTO,
+    raf_array::immutable_string::ImmutableString};
+
+/// Generates acyclic [`DirectedGraphDTO`]. Nodes are put in a random order
+/// and every pair of nodes is connected, along that order, with
+/// `arrow_density` probability. The result doesn't have to be connected.
+///
+/// # Panics
+/// When `nodes` is not positive or `arrow_density` is outside of `[0, 1]`.
+pub fn generate_random_dag(rng: &mut impl Rng, nodes: i32, arrow_density: f64)
+    -> DirectedGraphDTO
+{
+    assert!(nodes > 0, "Number of nodes has to be positive.");
+    assert!((0.0..=1.0).contains(&arrow_density), "Arrow density has to be in [0, 1].");
+    let mut order: Vec<i32> = (0..nodes).collect();
+    order.shuffle(rng);
+    let mut arrows = Vec::new();
+    for (idx, source) in order.iter().enumerate() {
+        for target in &order[idx + 1..] {
+            if rng.gen_bool(arrow_density) {
+                arrows.push(ArrowDTO::new(*source, *target));
+            }
+        }
+    }
+    DirectedGraphDTO::new(nodes, arrows)
+}
+
+/// Generates rooted binary tree with `leaves` leaves, i.e. `2 * leaves - 1`
+/// nodes. Leaves to split are chosen uniformly (Yule model) and node ids
+/// are shuffled.
+///
+/// # Panics
+/// When `leaves` is not positive.
+pub fn generate_random_tree(rng: &mut impl Rng, leaves: i32) -> DirectedGraphDTO {
+    assert!(leaves > 0, "Number of leaves has to be positive.");
+    let network = RandomNetwork::tree(rng, leaves);
+    network.into_dto(rng).0
+}
+
+/// Generates rooted, acyclic and binary [`PhylogeneticNetworkDTO`] with
+/// `leaves` leaves and exactly `reticulations` nodes of in-degree 2. Each
+/// leaf gets a distinct taxon `t<k>`.
+///
+/// Starts with [`generate_random_tree`] and then repeatedly subdivides two
+/// random arrows, connecting the new nodes by an arrow which doesn't close
+/// a cycle.
+///
+/// # Panics
+/// When `leaves` is not positive, `reticulations` is negative, or
+/// `reticulations` is positive for a single leaf.
+pub fn generate_random_phylo_network(rng: &mut impl Rng, leaves: i32, reticulations: i32)
+    -> PhylogeneticNetworkDTO
+{
+    assert!(leaves > 0, "Number of leaves has to be positive.");
+    assert!(reticulations >= 0, "Number of reticulations can't be negative.");
+    assert!(leaves > 1 || reticulations == 0, "Single leaf network can't have reticulations.");
+    let mut network = RandomNetwork::tree(rng, leaves);
+    for _ in 0..reticulations {
+        network.add_reticulation(rng);
+    }
+    let (graph, leaf_ids) = network.into_dto(rng);
+    let taxa: HashMap<i32, ImmutableString> = leaf_ids
+        .into_iter()
+        .enumerate()
+        .map(|(idx, id)| (id, ImmutableString::new(&format!("t{idx}")).unwrap()))
+        .collect();
+    PhylogeneticNetworkDTO::new(graph, taxa)
+}
+
+struct RandomNetwork {
+    successors: Vec<Vec<usize>>,
+}
+
+impl RandomNetwork {
+    #[allow(clippy::cast_sign_loss)]
+    fn tree(rng: &mut impl Rng, leaves: i32) -> Self {
+        let mut successors = Vec::with_capacity(2 * leaves as usize - 1);
+        successors.push(Vec::new());
+        let mut current_leaves = vec![0];
+        for _ in 1..leaves {
+            let leaf = current_leaves.swap_remove(rng.gen_range(0..current_leaves.len()));
+            let left = successors.len();
+            let right = left + 1;
+            successors.push(Vec::new());
+            successors.push(Vec::new());
+            successors[leaf] = vec![left, right];
+            current_leaves.push(left);
+            current_leaves.push(right);
+        }
+        Self { successors: successors }
+    }
+
+    fn arrows(&self) -> Vec<(usize, usize)> {
+        self.successors.iter()
+            .enumerate()
+            .flat_map(|(source, targets)| targets.iter().map(move |target| (source, *target)))
+            .collect()
+    }
+
+    fn descendants(&self, node: usize) -> Vec<bool> {
+        let mut seen = vec![false; self.successors.len()];
+        seen[node] = true;
+        let mut stack = vec![node];
+        while let Some(current) = stack.pop() {
+            for next in &self.successors[current] {
+                if !seen[*next] {
+                    seen[*next] = true;
+                    stack.push(*next);
+                }
+            }
+        }
+        seen
+    }
+
+    /// Requires at least two leaves. Then the root has two arrows, and one
+    /// of them is always a valid upper arrow.
+    fn add_reticulation(&mut self, rng: &mut impl Rng) {
+        let arrows = self.arrows();
+        let lower = arrows[rng.gen_range(0..arrows.len())];
+        let below = self.descendants(lower.1);
+        let candidates: Vec<(usize, usize)> = arrows.into_iter()
+            .filter(|arrow| *arrow != lower && !below[arrow.0])
+            .collect();
+        let upper = *candidates.choose(rng).unwrap();
+        let source = self.subdivide(upper);
+        let target = self.subdivide(lower);
+        self.successors[source].push(target);
+    }
+
+    fn subdivide(&mut self, (source, target): (usize, usize)) -> usize {
+        let middle = self.successors.len();
+        self.successors.push(vec![target]);
+        for successor in &mut self.successors[source] {
+            if *successor == target {
+                *successor = middle;
+            }
+        }
+        middle
+    }
+
+    /// Shuffles node ids and arrows. Returns the graph together with new
+    /// ids of leaves, ordered by id.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn into_dto(self, rng: &mut impl Rng) -> (DirectedGraphDTO, Vec<i32>) {
+        let number_of_nodes = self.successors.len() as i32;
+        let mut ids: Vec<i32> = (0..number_of_nodes).collect();
+        ids.shuffle(rng);
+        let mut arrows: Vec<ArrowDTO> = self.arrows()
+            .into_iter()
+            .map(|(source, target)| ArrowDTO::new(ids[source], ids[target]))
+            .collect();
+        arrows.shuffle(rng);
+        let mut leaves: Vec<i32> = self.successors.iter()
+            .enumerate()
+            .filter(|(_, targets)| targets.is_empty())
+            .map(|(node, _)| ids[node])
+            .collect();
+        leaves.sort_unstable();
+        (DirectedGraphDTO::new(number_of_nodes, arrows), leaves)
+    }
+}
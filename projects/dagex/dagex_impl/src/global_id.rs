@@ -10,12 +10,33 @@ static _ATOMIC_COUNTER: AtomicI32 = AtomicI32::new(0);
 
 impl GlobalId {
 
-    /// Creates a new unique [`GlobalId`]. Thread safe.
+    /// Creates a new unique [`GlobalId`]. Generated ids are non-negative
+    /// and below [`i32::MAX`]. Thread safe.
+    ///
+    /// # Panics
+    /// When all ids have been used up.
     #[inline(always)]
     pub fn generate_next() -> Self {
-        let id = _ATOMIC_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let id = _ATOMIC_COUNTER
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |id| {
+                if id < i32::MAX - 1 { Some(id + 1) } else { None }
+            })
+            .expect("Global ids exhausted.");
         Self { id }
     }
+
+    /// Reserves explicitly passed `id`, e.g. loaded from a file, by moving
+    /// the counter past it, so that [`GlobalId::generate_next`] never
+    /// returns it afterwards. Returns `None` if `id` is not within the
+    /// range of generated ids. Thread safe.
+    #[inline(always)]
+    pub fn reserve_explicit(id: i32) -> Option<Self> {
+        if !(0..i32::MAX - 1).contains(&id) {
+            return None;
+        }
+        _ATOMIC_COUNTER.fetch_max(id + 1, Ordering::Relaxed);
+        Some(Self { id })
+    }
 }
 
 impl From<GlobalId> for i32 {
@@ -5,15 +5,20 @@ use crate::core::ArrowDTO;
 const STRUCT_NAME: &str = "ArrowDTO";
 const SOURCE_FIELD: &str = "source";
 const TARGET_FIELD: &str = "target";
+const WEIGHT_FIELD: &str = "weight";
 
 impl Serialize for ArrowDTO {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer
     {
-        let mut state = serializer.serialize_tuple(2)?;
+        let weight = self.weight();
+        let mut state = serializer.serialize_tuple(if weight.is_some() { 3 } else { 2 })?;
         state.serialize_element(&self.source())?;
         state.serialize_element(&self.target())?;
+        if let Some(weight) = weight {
+            state.serialize_element(&weight)?;
+        }
         state.end()
     }
 }
@@ -34,7 +39,8 @@ impl<'de> Visitor<'de> for ArrowDTOVisitor {
     {
         let source = seq.next_element()?.unwrap();
         let target = seq.next_element()?.unwrap();
-        Ok(ArrowDTO::new(source, target))
+        let weight = seq.next_element()?;
+        Ok(ArrowDTO::new(source, target).with_weight(weight))
     }
 }
 
@@ -43,6 +49,6 @@ impl<'de> Deserialize<'de> for ArrowDTO {
     where
         D: serde::Deserializer<'de>
     {
-        deserializer.deserialize_struct(STRUCT_NAME, &[SOURCE_FIELD, TARGET_FIELD], ArrowDTOVisitor)
+        deserializer.deserialize_struct(STRUCT_NAME, &[SOURCE_FIELD, TARGET_FIELD, WEIGHT_FIELD], ArrowDTOVisitor)
     }
 }
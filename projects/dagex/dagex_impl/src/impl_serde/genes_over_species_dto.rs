@@ -0,0 +1,82 @@
+use serde::{de::{self, Visitor}, ser::SerializeStruct, Deserialize, Serialize};
+
+use crate::phylo::{GenesOverSpeciesDTO, PhylogeneticNetworkDTO};
+
+const STRUCT_NAME: &str = "GenesOverSpeciesDTO";
+const SPECIES_NETWORK_FIELD: &str = "species_network";
+const GENE_NETWORKS_FIELD: &str = "gene_networks";
+
+impl Serialize for GenesOverSpeciesDTO {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer
+    {
+        let mut state = serializer.serialize_struct(STRUCT_NAME, 2)?;
+        state.serialize_field(SPECIES_NETWORK_FIELD, self.species_network())?;
+        state.serialize_field(GENE_NETWORKS_FIELD, self.gene_networks())?;
+        state.end()
+    }
+}
+
+struct GenesOverSpeciesDTOVisitor;
+
+impl<'de> Visitor<'de> for GenesOverSpeciesDTOVisitor {
+    type Value = GenesOverSpeciesDTO;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("struct ")?;
+        formatter.write_str(STRUCT_NAME)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+    {
+        let species_network: PhylogeneticNetworkDTO = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let gene_networks: Vec<PhylogeneticNetworkDTO> = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(GenesOverSpeciesDTO::new(species_network, gene_networks))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+    {
+        let mut species_network = None;
+        let mut gene_networks = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                SPECIES_NETWORK_FIELD => {
+                    if species_network.is_some() {
+                        return Err(de::Error::duplicate_field(SPECIES_NETWORK_FIELD));
+                    }
+                    species_network = Some(map.next_value()?);
+                },
+                GENE_NETWORKS_FIELD => {
+                    if gene_networks.is_some() {
+                        return Err(de::Error::duplicate_field(GENE_NETWORKS_FIELD));
+                    }
+                    gene_networks = Some(map.next_value()?);
+                },
+                _ => { }
+            }
+        }
+
+        let species_network = species_network.ok_or_else(|| de::Error::missing_field(SPECIES_NETWORK_FIELD))?;
+        let gene_networks = gene_networks.ok_or_else(|| de::Error::missing_field(GENE_NETWORKS_FIELD))?;
+        Ok(GenesOverSpeciesDTO::new(species_network, gene_networks))
+    }
+}
+
+impl<'de> Deserialize<'de> for GenesOverSpeciesDTO {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>
+    {
+        deserializer.deserialize_struct(
+            STRUCT_NAME,
+            &[SPECIES_NETWORK_FIELD, GENE_NETWORKS_FIELD],
+            GenesOverSpeciesDTOVisitor)
+    }
+}
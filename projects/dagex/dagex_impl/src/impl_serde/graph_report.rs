@@ -0,0 +1,72 @@
+use serde::{ser::SerializeStruct, Serialize};
+
+use crate::core::{DegreeStatistics, DirectedGraphBasicProperties, GraphAnomaly, GraphReport, Node};
+
+impl Serialize for DirectedGraphBasicProperties {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer
+    {
+        let mut state = serializer.serialize_struct("DirectedGraphBasicProperties", 5)?;
+        state.serialize_field("acyclic", &self.acyclic)?;
+        state.serialize_field("connected", &self.connected)?;
+        state.serialize_field("rooted", &self.rooted)?;
+        state.serialize_field("binary", &self.binary)?;
+        state.serialize_field("tree", &self.tree)?;
+        state.end()
+    }
+}
+
+impl Serialize for DegreeStatistics {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer
+    {
+        let mut state = serializer.serialize_struct("DegreeStatistics", 7)?;
+        state.serialize_field("min_in_degree", &self.min_in_degree)?;
+        state.serialize_field("max_in_degree", &self.max_in_degree)?;
+        state.serialize_field("mean_in_degree", &self.mean_in_degree)?;
+        state.serialize_field("min_out_degree", &self.min_out_degree)?;
+        state.serialize_field("max_out_degree", &self.max_out_degree)?;
+        state.serialize_field("mean_out_degree", &self.mean_out_degree)?;
+        state.serialize_field("isolated_nodes", &self.isolated_nodes)?;
+        state.end()
+    }
+}
+
+impl Serialize for GraphAnomaly {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer
+    {
+        const ENUM_NAME: &str = "GraphAnomaly";
+        match self {
+            GraphAnomaly::IsolatedNode(node)
+                => serializer.serialize_newtype_variant(ENUM_NAME, 0, "IsolatedNode", &node.id()),
+            GraphAnomaly::UnlabeledLeaf(node)
+                => serializer.serialize_newtype_variant(ENUM_NAME, 1, "UnlabeledLeaf", &node.id()),
+            GraphAnomaly::MultipleRoots(nodes) => {
+                let ids: Vec<i32> = nodes.iter().map(Node::id).collect();
+                serializer.serialize_newtype_variant(ENUM_NAME, 2, "MultipleRoots", &ids)
+            },
+        }
+    }
+}
+
+impl Serialize for GraphReport {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer
+    {
+        let mut state = serializer.serialize_struct("GraphReport", 8)?;
+        state.serialize_field("basic_properties", &self.basic_properties)?;
+        state.serialize_field("number_of_nodes", &self.number_of_nodes)?;
+        state.serialize_field("number_of_arrows", &self.number_of_arrows)?;
+        state.serialize_field("degree_statistics", &self.degree_statistics)?;
+        state.serialize_field("number_of_components", &self.number_of_components)?;
+        state.serialize_field("height", &self.height)?;
+        state.serialize_field("reticulation_count", &self.reticulation_count)?;
+        state.serialize_field("anomalies", &self.anomalies)?;
+        state.end()
+    }
+}
@@ -1,3 +1,8 @@
 mod arrow_dto;
 mod directed_graph_dto;
 mod phylogenetic_network_dto;
+mod graph_report;
+mod phylogenetic_forest_dto;
+mod taxon;
+mod phylogenetic_network_id;
+mod genes_over_species_dto;
@@ -0,0 +1,90 @@
+use serde::{de::{self, Visitor}, ser::SerializeStruct, Deserialize, Serialize};
+
+use crate::raf_array::immutable_string::ImmutableString;
+use crate::phylo::{PhylogeneticForestDTO, PhylogeneticNetworkDTO};
+
+const STRUCT_NAME: &str = "PhylogeneticForestDTO";
+const NETWORKS_FIELD: &str = "networks";
+const TAXA_FIELD: &str = "taxa";
+
+impl Serialize for PhylogeneticForestDTO {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer
+    {
+        let taxa: Vec<&str> = self.taxa()
+            .iter()
+            .map(ImmutableString::as_str)
+            .collect();
+        let mut state = serializer.serialize_struct(STRUCT_NAME, 2)?;
+        state.serialize_field(NETWORKS_FIELD, self.networks())?;
+        state.serialize_field(TAXA_FIELD, &taxa)?;
+        state.end()
+    }
+}
+
+fn to_immutable_strings<E: de::Error>(raw_taxa: &[String]) -> Result<Vec<ImmutableString>, E> {
+    raw_taxa.iter()
+        .map(|text| ImmutableString::new(text).map_err(|_| de::Error::custom("Invalid taxon.")))
+        .collect()
+}
+
+struct PhylogeneticForestDTOVisitor;
+
+impl<'de> Visitor<'de> for PhylogeneticForestDTOVisitor {
+    type Value = PhylogeneticForestDTO;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("struct ")?;
+        formatter.write_str(STRUCT_NAME)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+    {
+        let networks: Vec<PhylogeneticNetworkDTO> = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let raw_taxa: Vec<String> = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(PhylogeneticForestDTO::new(networks, to_immutable_strings(&raw_taxa)?))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+    {
+        let mut networks = None;
+        let mut raw_taxa: Option<Vec<String>> = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                NETWORKS_FIELD => {
+                    if networks.is_some() {
+                        return Err(de::Error::duplicate_field(NETWORKS_FIELD));
+                    }
+                    networks = Some(map.next_value()?);
+                },
+                TAXA_FIELD => {
+                    if raw_taxa.is_some() {
+                        return Err(de::Error::duplicate_field(TAXA_FIELD));
+                    }
+                    raw_taxa = Some(map.next_value()?);
+                },
+                _ => { }
+            }
+        }
+
+        let networks = networks.ok_or_else(|| de::Error::missing_field(NETWORKS_FIELD))?;
+        let raw_taxa = raw_taxa.ok_or_else(|| de::Error::missing_field(TAXA_FIELD))?;
+        Ok(PhylogeneticForestDTO::new(networks, to_immutable_strings(&raw_taxa)?))
+    }
+}
+
+impl<'de> Deserialize<'de> for PhylogeneticForestDTO {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>
+    {
+        deserializer.deserialize_struct(STRUCT_NAME, &[NETWORKS_FIELD, TAXA_FIELD], PhylogeneticForestDTOVisitor)
+    }
+}
@@ -9,6 +9,7 @@ const STRUCT_NAME: &str = "PhylogeneticNetworkDTO";
 const NODES_LEN_FIELD: &str = "number_of_nodes";
 const ARROWS_FIELD: &str = "arrows";
 const TAXA_FIELD: &str = "taxa";
+const ID_FIELD: &str = "id";
 
 impl Serialize for PhylogeneticNetworkDTO {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -19,16 +20,33 @@ impl Serialize for PhylogeneticNetworkDTO {
             .iter()
             .map(|p| (*p.0, p.1.as_str()))
             .collect();
-        taxa_content.sort_by(|l, r| l.0.cmp(&r.0));
-        let mut state = serializer.serialize_struct(STRUCT_NAME, 3)?;
+        taxa_content.sort_by_key(|p| p.0);
+        let len = if self.id().is_some() { 4 } else { 3 };
+        let mut state = serializer.serialize_struct(STRUCT_NAME, len)?;
         let graph = self.graph();
         state.serialize_field(NODES_LEN_FIELD, &graph.number_of_nodes())?;
         state.serialize_field(ARROWS_FIELD, &graph.arrows())?;
         state.serialize_field(TAXA_FIELD, &taxa_content)?;
+        // Optional and last, so that DTOs without id serialize as before.
+        if let Some(id) = self.id() {
+            state.serialize_field(ID_FIELD, &id)?;
+        }
         state.end()
     }
 }
 
+fn to_taxa_map<E: de::Error>(raw_taxa: Vec<(i32, String)>) -> Result<HashMap<i32, ImmutableString>, E> {
+    let mut taxa = HashMap::with_capacity(raw_taxa.len());
+    for (node, text) in raw_taxa {
+        let imm = ImmutableString::new(text.as_str())
+            .map_err(|_| de::Error::custom("Invalid taxon."))?;
+        if taxa.insert(node, imm).is_some() {
+            return Err(de::Error::custom("Taxa contains duplicate keys."));
+        }
+    }
+    Ok(taxa)
+}
+
 struct DirectedGraphDTOVisitor;
 
 impl<'de> Visitor<'de> for DirectedGraphDTOVisitor {
@@ -43,14 +61,15 @@ impl<'de> Visitor<'de> for DirectedGraphDTOVisitor {
         where
             A: serde::de::SeqAccess<'de>,
     {
-        let no = seq.next_element()?.unwrap();
-        let arrows = seq.next_element()?.unwrap();
-        let raw_taxa: HashMap<i32, String> = seq.next_element()?.unwrap();
-        let taxa = raw_taxa.iter()
-            .map(|kvp| { (*kvp.0, ImmutableString::new(kvp.1.as_str()).unwrap()) })
-            .collect();
-        
-        Ok(PhylogeneticNetworkDTO::new(DirectedGraphDTO::new(no, arrows), taxa))
+        let no = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let arrows = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let raw_taxa: Vec<(i32, String)> = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        let id: Option<i32> = seq.next_element()?;
+        let taxa = to_taxa_map(raw_taxa)?;
+        Ok(PhylogeneticNetworkDTO::new(DirectedGraphDTO::new(no, arrows), taxa).with_id(id))
     }
 
     fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
@@ -59,7 +78,8 @@ impl<'de> Visitor<'de> for DirectedGraphDTOVisitor {
     {
         let mut no = None;
         let mut arrows = None;
-        let mut raw_taxa: Option<Vec<(i32, ImmutableString)>> = None;
+        let mut raw_taxa: Option<Vec<(i32, String)>> = None;
+        let mut id: Option<i32> = None;
         while let Some(key) = map.next_key()? {
             match key {
                 NODES_LEN_FIELD => {
@@ -78,11 +98,13 @@ impl<'de> Visitor<'de> for DirectedGraphDTOVisitor {
                     if raw_taxa.is_some() {
                         return Err(de::Error::duplicate_field(TAXA_FIELD));
                     }
-                    let tmp_taxa = map.next_value::<Vec<(i32, String)>>()?;
-                    let taxa_iter = tmp_taxa.into_iter()
-                        .map(|kvp| { (kvp.0, ImmutableString::new(kvp.1.as_str()).unwrap()) })
-                        .collect();
-                    raw_taxa = Some(taxa_iter);
+                    raw_taxa = Some(map.next_value()?);
+                },
+                ID_FIELD => {
+                    if id.is_some() {
+                        return Err(de::Error::duplicate_field(ID_FIELD));
+                    }
+                    id = Some(map.next_value()?);
                 },
                 _ => { }
             }
@@ -91,13 +113,8 @@ impl<'de> Visitor<'de> for DirectedGraphDTOVisitor {
         let no = no.ok_or_else(|| de::Error::missing_field(NODES_LEN_FIELD))?;
         let arrows = arrows.ok_or_else(|| de::Error::missing_field(ARROWS_FIELD))?;
         let raw_taxa = raw_taxa.ok_or_else(|| de::Error::missing_field(TAXA_FIELD))?;
-        let mut taxa = HashMap::with_capacity(raw_taxa.len());
-        for (node, imm) in raw_taxa {
-            if taxa.insert(node, imm).is_some() {
-                return Err(de::Error::custom("Taxa contains duplicate keys."));
-            }
-        }
-        Ok(PhylogeneticNetworkDTO::new(DirectedGraphDTO::new(no, arrows), taxa))
+        let taxa = to_taxa_map(raw_taxa)?;
+        Ok(PhylogeneticNetworkDTO::new(DirectedGraphDTO::new(no, arrows), taxa).with_id(id))
     }
 }
 
@@ -106,6 +123,6 @@ impl<'de> Deserialize<'de> for PhylogeneticNetworkDTO {
     where
         D: serde::Deserializer<'de>
     {
-        deserializer.deserialize_struct(STRUCT_NAME, &[NODES_LEN_FIELD, ARROWS_FIELD, TAXA_FIELD], DirectedGraphDTOVisitor)
+        deserializer.deserialize_struct(STRUCT_NAME, &[NODES_LEN_FIELD, ARROWS_FIELD, TAXA_FIELD, ID_FIELD], DirectedGraphDTOVisitor)
     }
 }
@@ -0,0 +1,16 @@
+use serde::Serialize;
+
+use crate::phylo::PhylogeneticNetworkId;
+
+/// Serialized as its raw `i32` value. There is no matching `Deserialize`,
+/// ids are recreated explicitly with
+/// [`PhylogeneticNetworkId::from_explicit`] instead, e.g. from
+/// [`PhylogeneticNetworkDTO::id`](crate::phylo::PhylogeneticNetworkDTO::id).
+impl Serialize for PhylogeneticNetworkId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer
+    {
+        serializer.serialize_i32(i32::from(*self))
+    }
+}
@@ -0,0 +1,22 @@
+use serde::{de, Deserialize, Serialize};
+
+use crate::phylo::Taxon;
+
+impl Serialize for Taxon {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer
+    {
+        serializer.serialize_str(self.value().as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Taxon {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>
+    {
+        let text = String::deserialize(deserializer)?;
+        Taxon::new(text.as_str()).map_err(|_| de::Error::custom("Invalid taxon."))
+    }
+}
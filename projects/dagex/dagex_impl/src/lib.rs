@@ -14,6 +14,7 @@ pub extern crate raf_array;
 #[doc(hidden)]
 pub mod macro_helpers;
 
+#[cfg(feature = "serde")]
 mod impl_serde;
 mod hashing;
 mod global_id;
@@ -22,4 +23,7 @@ pub(crate) use global_id::GlobalId;
 pub(crate) use hashing::create_u32_hasher;
 
 pub mod core;
-pub mod phylo;
\ No newline at end of file
+pub mod phylo;
+
+#[cfg(feature = "test-utils")]
+pub mod generators;
\ No newline at end of file
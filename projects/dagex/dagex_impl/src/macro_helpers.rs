@@ -1,16 +1,92 @@
-use smallvec::SmallVec;
+use std::collections::{HashMap, HashSet};
 
-use crate::core::Node;
+use crate::{
+    core::{ArrowStorage, DirectedGraphBasicProperties, Node},
+    phylo::Taxon};
 
 #[doc(hidden)]
 #[inline(always)]
-pub fn empty_arow_map() -> Vec<SmallVec<[Node; 2]>> {
-    Vec::new()
+pub fn default_node() -> Node {
+    Node::from(-1)
 }
 
 
 #[doc(hidden)]
 #[inline(always)]
-pub fn default_node() -> Node {
-    Node::from(-1)
+pub fn arrow_storage(offsets: &[u32], nodes: &[i32]) -> ArrowStorage {
+    let nodes = nodes.iter().copied().map(Node::from).collect();
+    ArrowStorage::from_raw_parts(offsets.to_vec(), nodes)
+}
+
+
+/// Flat description of a network, emitted as a `static` by
+/// `const_parse_newick!` and friends.
+#[doc(hidden)]
+pub struct StaticNetwork {
+    pub number_of_nodes: i32,
+
+    /// Successors in [`ArrowStorage`] layout, i.e. `successors` of node
+    /// `i` are in `successor_offsets[i]..successor_offsets[i + 1]`.
+    pub successor_offsets: &'static [u32],
+    pub successors: &'static [i32],
+    pub properties: DirectedGraphBasicProperties,
+
+    /// Ordered by taxon, so that leaves with equal taxa share it.
+    pub taxa: &'static [(i32, &'static str)],
+}
+
+
+/// Successors, predecessors, root and leaves of `data`, in the form
+/// expected by [`DirectedGraph::new_unchecked`](crate::core::DirectedGraph::new_unchecked).
+/// Everything except successors is derived, without verifying anything.
+#[doc(hidden)]
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+pub fn static_arrows(data: &StaticNetwork)
+    -> (ArrowStorage, ArrowStorage, Option<Node>, HashSet<Node>)
+{
+    let number_of_nodes = data.number_of_nodes as usize;
+    let successors = arrow_storage(data.successor_offsets, data.successors);
+    let arrows = data.successor_offsets.windows(2)
+        .enumerate()
+        .flat_map(|(source, range)| {
+            data.successors[range[0] as usize..range[1] as usize]
+                .iter()
+                .map(move |target| (Node::from(*target), Node::from(source as i32)))
+        });
+    let predecessors = ArrowStorage::from_pairs(number_of_nodes, arrows);
+
+    let mut roots = Vec::with_capacity(1);
+    let mut leaves = HashSet::new();
+    for idx in 0..data.number_of_nodes {
+        let node = Node::from(idx);
+        if predecessors.get(node).is_empty() {
+            roots.push(node);
+        }
+        if successors.get(node).is_empty() {
+            leaves.insert(node);
+        }
+    }
+    let root = if roots.len() == 1 { Some(roots[0]) } else { None };
+    (successors, predecessors, root, leaves)
+}
+
+
+/// Taxa of `data`, sharing a single [`Taxon`] between leaves with equal
+/// names.
+///
+/// # Panics
+/// When a taxon is not a valid [`Taxon`].
+#[doc(hidden)]
+pub fn static_taxa(data: &StaticNetwork) -> HashMap<Node, Taxon> {
+    let mut taxa = HashMap::with_capacity(data.taxa.len());
+    let mut previous: Option<(&str, Taxon)> = None;
+    for (id, name) in data.taxa {
+        let taxon = match previous {
+            Some((previous_name, ref taxon)) if previous_name == *name => taxon.clone(),
+            _ => Taxon::new(name).expect("Taxon has to be valid."),
+        };
+        taxa.insert(Node::from(*id), taxon.clone());
+        previous = Some((name, taxon));
+    }
+    taxa
 }
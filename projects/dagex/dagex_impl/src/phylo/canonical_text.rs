@@ -0,0 +1,226 @@
+//! Canonical, line-oriented text format of [`PhylogeneticNetworkDTO`],
+//! meant for checked-in fixtures. Example:
+//!
+//! ```text
+//! # optional comment
+//! nodes 3
+//! 0 -> 1
+//! 0 -> 2
+//! leaf 1 = "Homo_sapiens"
+//! leaf 2 = "Pan \"troglodytes\""
+//! ```
+//!
+//! The writer emits the `nodes` header, then arrows sorted by
+//! `(source, target)`, then taxa sorted by node id. Hence the output depends
+//! only on the DTO content, not on the order of its arrows or taxa. The
+//! parser ignores blank lines and lines starting with `#`.
+use core::fmt::{Display, Formatter};
+use std::{collections::HashMap, io::Write};
+
+use crate::{
+    core::{ArrowDTO, DirectedGraphDTO},
+    raf_array::immutable_string::ImmutableString};
+
+use super::{parse_newick_from_str, NewickParseError, PhylogeneticNetworkDTO};
+
+const NODES_KEYWORD: &str = "nodes";
+const LEAF_KEYWORD: &str = "leaf";
+const ARROW_SEPARATOR: &str = "->";
+
+#[derive(Debug)]
+pub enum CanonicalTextParseError {
+    /// Content doesn't contain `nodes N` header before arrows and taxa.
+    MissingNodesHeader,
+
+    /// The `nodes N` header appears more than once. Holds 1-based line
+    /// number.
+    DuplicatedNodesHeader(usize),
+
+    /// Line couldn't be parsed. Holds 1-based line number and the line.
+    InvalidLine(usize, String),
+
+    /// Node has more than one taxon assigned. Holds 1-based line number and
+    /// the node id.
+    DuplicatedTaxon(usize, i32),
+}
+
+impl PhylogeneticNetworkDTO {
+    /// Writes current DTO in the canonical text format. Node ids are
+    /// written as they are, so networks should be converted with
+    /// [`PhylogeneticNetwork::to_canonical_dto`](super::PhylogeneticNetwork::to_canonical_dto)
+    /// for the text not to depend on their node numbering.
+    ///
+    /// # Errors
+    /// Forwarded from `output`.
+    pub fn write_canonical_text<TWrite: Write>(&self, output: &mut TWrite)
+        -> std::io::Result<()>
+    {
+        let graph = self.graph();
+        writeln!(output, "{NODES_KEYWORD} {}", graph.number_of_nodes())?;
+
+        let mut arrows: Vec<(i32, i32)> = graph.arrows()
+            .iter()
+            .map(|arrow| (arrow.source(), arrow.target()))
+            .collect();
+        arrows.sort_unstable();
+        for (source, target) in arrows {
+            writeln!(output, "{source} {ARROW_SEPARATOR} {target}")?;
+        }
+
+        let mut taxa: Vec<(i32, &str)> = self.taxa()
+            .iter()
+            .map(|kvp| (*kvp.0, kvp.1.as_str()))
+            .collect();
+        taxa.sort_unstable_by_key(|kvp| kvp.0);
+        for (node, taxon) in taxa {
+            writeln!(output, "{LEAF_KEYWORD} {node} = \"{}\"", escape(taxon))?;
+        }
+        Ok(())
+    }
+
+    /// Returns current DTO in the canonical text format.
+    ///
+    /// # Panics
+    /// Never, writing to `Vec<u8>` cannot fail.
+    pub fn to_canonical_text(&self) -> String {
+        let mut buffer = Vec::<u8>::new();
+        self.write_canonical_text(&mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    /// Parses DTO from the canonical text format. Note that the result is
+    /// not validated, use [`PhylogeneticNetwork::from_dto`](super::PhylogeneticNetwork::from_dto)
+    /// for that.
+    ///
+    /// # Errors
+    /// For concrete errors see [`CanonicalTextParseError`] docs.
+    pub fn parse_canonical_text(text: &str)
+        -> Result<Self, CanonicalTextParseError>
+    {
+        let mut number_of_nodes = Option::<i32>::None;
+        let mut arrows = Vec::<ArrowDTO>::new();
+        let mut taxa = HashMap::<i32, ImmutableString>::new();
+
+        for (idx, raw_line) in text.lines().enumerate() {
+            let line_no = idx + 1;
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || CanonicalTextParseError::InvalidLine(line_no, raw_line.to_owned());
+
+            if let Some(rest) = strip_keyword(line, NODES_KEYWORD) {
+                if number_of_nodes.is_some() {
+                    return Err(CanonicalTextParseError::DuplicatedNodesHeader(line_no));
+                }
+                number_of_nodes = Some(rest.parse().map_err(|_| invalid())?);
+                continue;
+            }
+
+            if number_of_nodes.is_none() {
+                return Err(CanonicalTextParseError::MissingNodesHeader);
+            }
+
+            if let Some(rest) = strip_keyword(line, LEAF_KEYWORD) {
+                let (node, taxon) = rest.split_once('=').ok_or_else(invalid)?;
+                let node: i32 = node.trim().parse().map_err(|_| invalid())?;
+                let taxon = unescape(taxon.trim()).ok_or_else(invalid)?;
+                let imm = ImmutableString::new(&taxon).map_err(|_| invalid())?;
+                if taxa.insert(node, imm).is_some() {
+                    return Err(CanonicalTextParseError::DuplicatedTaxon(line_no, node));
+                }
+                continue;
+            }
+
+            let (source, target) = line.split_once(ARROW_SEPARATOR).ok_or_else(invalid)?;
+            let source: i32 = source.trim().parse().map_err(|_| invalid())?;
+            let target: i32 = target.trim().parse().map_err(|_| invalid())?;
+            arrows.push(ArrowDTO::new(source, target));
+        }
+
+        let number_of_nodes = number_of_nodes
+            .ok_or(CanonicalTextParseError::MissingNodesHeader)?;
+        let graph = DirectedGraphDTO::new(number_of_nodes, arrows);
+        Ok(PhylogeneticNetworkDTO::new(graph, taxa))
+    }
+}
+
+/// Converts Newick formatted `&str` into the canonical text format, built
+/// from [`PhylogeneticNetwork::to_canonical_dto`](super::PhylogeneticNetwork::to_canonical_dto).
+/// Hence Newick strings describing the same network give equal text
+/// regardless of the order of children. Used for migrating Newick fixtures.
+///
+/// # Errors
+/// Forwarded from [`parse_newick_from_str`].
+pub fn newick_to_canonical_text(input: &str) -> Result<String, NewickParseError> {
+    let network = parse_newick_from_str(input)?.network;
+    Ok(network.to_canonical_dto().to_canonical_text())
+}
+
+fn strip_keyword<'a>(line: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(keyword)?;
+    if rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    }
+    else
+    {
+        None
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for chr in text.chars() {
+        match chr {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            _ => result.push(chr),
+        }
+    }
+    result
+}
+
+fn unescape(quoted: &str) -> Option<String> {
+    let inner = quoted.strip_prefix('"')?.strip_suffix('"')?;
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(chr) = chars.next() {
+        match chr {
+            '\\' => {
+                let escaped = match chars.next()? {
+                    '"' => '"',
+                    '\\' => '\\',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    _ => return None,
+                };
+                result.push(escaped);
+            },
+            '"' => return None,
+            _ => result.push(chr),
+        }
+    }
+    Some(result)
+}
+
+impl Display for CanonicalTextParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CanonicalTextParseError::MissingNodesHeader
+                => write!(f, "missing `{NODES_KEYWORD} N` header"),
+            CanonicalTextParseError::DuplicatedNodesHeader(line)
+                => write!(f, "line {line}: duplicated `{NODES_KEYWORD}` header"),
+            CanonicalTextParseError::InvalidLine(line, content)
+                => write!(f, "line {line}: invalid content `{content}`"),
+            CanonicalTextParseError::DuplicatedTaxon(line, node)
+                => write!(f, "line {line}: node {node} already has a taxon"),
+        }
+    }
+}
+
+impl std::error::Error for CanonicalTextParseError { }
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use crate::core::Node;
+
+use super::{PhylogeneticNetwork, TaxonBitSet, TaxonRegistry};
+
+impl PhylogeneticNetwork {
+    /// Calculates hardwired clusters, i.e. for each node the set of taxa
+    /// of the node and all its descendants. Taxa are registered in a new
+    /// [`TaxonRegistry`] in the order of ascending ids of their nodes.
+    pub fn hardwired_clusters(&self) -> HashMap<Node, TaxonBitSet> {
+        let mut registry = TaxonRegistry::new();
+        self.hardwired_clusters_with_registry(&mut registry)
+    }
+
+    /// Like [`PhylogeneticNetwork::hardwired_clusters`], with taxa taken
+    /// from `registry`. Taxa missing in `registry` get registered first, in
+    /// the order of ascending ids of their nodes. Pass the same registry
+    /// to compare clusters of different networks.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn hardwired_clusters_with_registry(&self, registry: &mut TaxonRegistry)
+        -> HashMap<Node, TaxonBitSet>
+    {
+        let graph = self.graph();
+        for node in graph.iter_nodes() {
+            if let Some(taxon) = self.taxa().get(&node) {
+                registry.register_taxon(taxon);
+            }
+        }
+
+        // Network is acyclic, so the topological order always exists.
+        let order: Vec<Node> = graph.iter_topological()
+            .map(Iterator::collect)
+            .unwrap_or_default();
+        let mut clusters: Vec<Option<TaxonBitSet>> = vec![None; graph.number_of_nodes() as usize];
+        for node in order.into_iter().rev() {
+            let mut cluster = TaxonBitSet::new(registry);
+            if let Some(taxon) = self.taxa().get(&node) {
+                cluster.insert(taxon);
+            }
+            for child in graph.get_successors(node) {
+                if let Some(child_cluster) = &clusters[child.id() as usize] {
+                    cluster.union_with(child_cluster);
+                }
+            }
+            clusters[node.id() as usize] = Some(cluster);
+        }
+
+        clusters.into_iter()
+            .enumerate()
+            .filter_map(|(idx, cluster)| {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                cluster.map(|cluster| (Node::from(idx as i32), cluster))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use super::{
+    restriction::{adjacency_lists, index, reduce_network},
+    PhylogeneticNetwork,
+    Taxon};
+
+impl PhylogeneticNetwork {
+    /// Lazily enumerates trees displayed by the network. Each tree is
+    /// obtained by keeping exactly one incoming arrow of every reticulation
+    /// node, pruning unlabeled leaves created that way and suppressing
+    /// resulting nodes of in- and out-degree 1. Labeled nodes and taxa are
+    /// always kept. Trees get fresh ids.
+    ///
+    /// # Notes
+    /// Yields one tree per combination of parents, i.e. `2^r` trees for
+    /// binary network with `r` reticulation nodes, see
+    /// [`DisplayedTreeIterator::combinations`]. Different combinations
+    /// may display the same tree, these are not deduplicated. Use
+    /// [`DisplayedTreeIterator::with_limit`] to bound the work.
+    pub fn iter_displayed_trees(&self) -> DisplayedTreeIterator<'_> {
+        DisplayedTreeIterator::new(self)
+    }
+}
+
+/// Iterator created by [`PhylogeneticNetwork::iter_displayed_trees`].
+pub struct DisplayedTreeIterator<'a> {
+    network: &'a PhylogeneticNetwork,
+    /// Reticulation nodes with their in-degrees.
+    reticulations: Vec<(usize, usize)>,
+    choices: Vec<usize>,
+    combinations: Option<usize>,
+    /// Trees left until the limit, if any.
+    remaining: Option<usize>,
+    finished: bool,
+}
+
+impl<'a> DisplayedTreeIterator<'a> {
+    fn new(network: &'a PhylogeneticNetwork) -> Self {
+        let graph = network.graph();
+        let reticulations: Vec<(usize, usize)> = graph.iter_nodes()
+            .map(|node| (index(node.id()), graph.get_predecessors(node).len()))
+            .filter(|(_, in_degree)| *in_degree >= 2)
+            .collect();
+        let combinations = reticulations.iter()
+            .try_fold(1usize, |result, (_, in_degree)| result.checked_mul(*in_degree));
+        Self {
+            choices: vec![0; reticulations.len()],
+            reticulations: reticulations,
+            network: network,
+            combinations: combinations,
+            remaining: None,
+            finished: false,
+        }
+    }
+
+    /// Stops the iteration after at most `limit` trees.
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.remaining = Some(limit);
+        self
+    }
+
+    /// Total number of parent combinations, i.e. `2^r` for binary network
+    /// with `r` reticulation nodes. `None` if it doesn't fit [`usize`].
+    #[inline(always)]
+    pub fn combinations(&self) -> Option<usize> {
+        self.combinations
+    }
+
+    fn build_current(&self) -> PhylogeneticNetwork {
+        let network = self.network;
+        let (mut successors, mut predecessors) = adjacency_lists(network);
+        for ((reticulation, _), choice) in self.reticulations.iter().zip(&self.choices) {
+            let kept = predecessors[*reticulation][*choice];
+            for parent in &predecessors[*reticulation] {
+                if *parent != kept {
+                    successors[*parent].retain(|n| n != reticulation);
+                }
+            }
+            predecessors[*reticulation] = vec![kept];
+        }
+
+        let labels: HashMap<usize, Taxon> = network.taxa()
+            .iter()
+            .map(|(node, taxon)| (index(node.id()), taxon.clone()))
+            .collect();
+        let seeds = network.graph().leaves()
+            .iter()
+            .map(|leaf| index(leaf.id()))
+            .chain(labels.keys().copied())
+            .collect();
+        reduce_network(network, successors, predecessors, seeds, labels)
+    }
+
+    /// Moves to the next combination, returns `false` if there is none.
+    fn advance(&mut self) -> bool {
+        for ((_, in_degree), choice) in self.reticulations.iter().zip(&mut self.choices) {
+            *choice += 1;
+            if *choice < *in_degree {
+                return true;
+            }
+            *choice = 0;
+        }
+        false
+    }
+
+    /// Number of combinations already yielded. Requires known
+    /// [`DisplayedTreeIterator::combinations`].
+    fn yielded(&self) -> usize {
+        // Combinations are enumerated in mixed radix order, with the first
+        // reticulation changing fastest.
+        let mut result = 0usize;
+        for ((_, in_degree), choice) in self.reticulations.iter().zip(&self.choices).rev() {
+            result = result * in_degree + choice;
+        }
+        result
+    }
+}
+
+impl Iterator for DisplayedTreeIterator<'_> {
+    type Item = PhylogeneticNetwork;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished || self.remaining == Some(0) {
+            return None;
+        }
+        let tree = self.build_current();
+        self.remaining = self.remaining.map(|remaining| remaining - 1);
+        self.finished = !self.advance();
+        Some(tree)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            return (0, Some(0));
+        }
+        let left = self.combinations.map(|combinations| combinations - self.yielded());
+        match (left, self.remaining) {
+            (Some(left), Some(remaining)) => (left.min(remaining), Some(left.min(remaining))),
+            (Some(left), None) => (left, Some(left)),
+            (None, Some(remaining)) => (remaining, Some(remaining)),
+            (None, None) => (usize::MAX, None),
+        }
+    }
+}
@@ -1,12 +1,35 @@
-use std::collections::{HashMap, HashSet};
+use core::fmt::{Display, Formatter};
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroUsize,
+    sync::{atomic::{AtomicUsize, Ordering}, Arc}};
 
-use super::{PhylogeneticNetwork, PhylogeneticNetworkId, Taxon};
+use raf_readonly::readonly;
 
-#[derive(Debug, PartialEq, Eq)]
+use super::{
+    PhylogeneticNetwork,
+    PhylogeneticNetworkDTO,
+    PhylogeneticNetworkFromError,
+    PhylogeneticNetworkId,
+    Taxon,
+    TaxonIdSet,
+    TaxonRegistry};
+
+/// Equality compares species and gene networks only, in particular it
+/// ignores network ids and the optional [`TaxonRegistry`].
+///
+/// Gene networks can be added and removed later, see
+/// [`GenesOverSpecies::add_gene_network`]. Networks are shared, so
+/// [`Arc`]s obtained from [`GenesOverSpecies::gene_networks`] stay valid
+/// after mutation, while borrowed references are ruled out by the borrow
+/// checker.
+#[derive(Debug)]
 pub struct GenesOverSpecies {
-    gene_networks: Vec<PhylogeneticNetwork>,
+    gene_networks: Vec<Arc<PhylogeneticNetwork>>,
     gene_networks_by_id: HashMap<PhylogeneticNetworkId, i32>,
     species_network: PhylogeneticNetwork,
+    species_taxa: HashSet<Taxon>,
+    taxon_registry: Option<TaxonRegistry>,
 }
 
 #[derive(Debug)]
@@ -26,6 +49,63 @@ pub enum GenesOverSpeciesNewError {
     SpeciesContainsTaxaDuplicates,
 }
 
+#[derive(Debug)]
+pub enum GenesOverSpeciesFromError {
+    /// Forwarded error of species network construction.
+    SpeciesNetworkError(PhylogeneticNetworkFromError),
+
+    /// Forwarded error of gene network construction, together with its
+    /// index.
+    GeneNetworkError(usize, PhylogeneticNetworkFromError),
+
+    /// Forwarded error of [`GenesOverSpecies::new`].
+    NewError(GenesOverSpeciesNewError),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AddGeneError {
+    /// Taxa of the new gene network are not a subset of species network's
+    /// taxa.
+    IncorrectTaxa,
+
+    /// Gene network with the same id is already present.
+    DuplicatedId,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RemoveGeneError {
+    /// There is no gene network with given id.
+    NotFound,
+
+    /// Removal would leave the instance without gene networks, see
+    /// [`GenesOverSpeciesNewError::EmptyGeneNetworks`].
+    EmptyGeneNetworks,
+}
+
+impl From<GenesOverSpeciesNewError> for GenesOverSpeciesFromError {
+    fn from(value: GenesOverSpeciesNewError) -> Self { Self::NewError(value) }
+}
+
+/// Represents [`GenesOverSpecies`] as species network followed by gene
+/// networks. Gene networks carry their ids, so that results keyed by
+/// [`PhylogeneticNetworkId`] stay valid after a round trip. Gene networks
+/// sharing an id are shared again on construction.
+#[readonly]
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct GenesOverSpeciesDTO {
+    pub species_network: PhylogeneticNetworkDTO,
+    pub gene_networks: Vec<PhylogeneticNetworkDTO>,
+}
+
+impl TryFrom<GenesOverSpeciesDTO> for GenesOverSpecies {
+    type Error = GenesOverSpeciesFromError;
+
+    /// Validates `value`, see [`GenesOverSpecies::from_dto`].
+    fn try_from(value: GenesOverSpeciesDTO) -> Result<Self, Self::Error> {
+        Self::from_dto(&value)
+    }
+}
+
 
 impl GenesOverSpecies {
     /// Creates an unchecked [`GenesOverSpecies`].
@@ -44,7 +124,28 @@ impl GenesOverSpecies {
         gene_networks_by_id: HashMap<PhylogeneticNetworkId, i32>,
         species_network: PhylogeneticNetwork) -> Self
     {
-        Self { gene_networks, gene_networks_by_id, species_network }
+        let gene_networks = gene_networks.into_iter().map(Arc::new).collect();
+        Self::new_shared_unchecked(gene_networks, gene_networks_by_id, species_network)
+    }
+
+    /// Creates an unchecked [`GenesOverSpecies`] out of shared gene networks.
+    /// 
+    /// # Safety
+    /// Same invariants as in [`GenesOverSpecies::new_unchecked`] have to
+    /// be satisfied.
+    pub unsafe fn new_shared_unchecked(
+        gene_networks: Vec<Arc<PhylogeneticNetwork>>,
+        gene_networks_by_id: HashMap<PhylogeneticNetworkId, i32>,
+        species_network: PhylogeneticNetwork) -> Self
+    {
+        let species_taxa = species_network.taxa().values().cloned().collect();
+        Self {
+            gene_networks: gene_networks,
+            gene_networks_by_id: gene_networks_by_id,
+            species_network: species_network,
+            species_taxa: species_taxa,
+            taxon_registry: None,
+        }
     }
 
     /// Creates new instance of [`GenesOverSpecies`] from list of gene networks
@@ -55,34 +156,141 @@ impl GenesOverSpecies {
     pub fn new(
         gene_networks: Vec<PhylogeneticNetwork>,
         species_network: PhylogeneticNetwork) -> Result<GenesOverSpecies, GenesOverSpeciesNewError>
+    {
+        let gene_networks = gene_networks.into_iter().map(Arc::new).collect();
+        Self::from_shared_networks(gene_networks, species_network)
+    }
+
+    /// Creates new instance of [`GenesOverSpecies`] from list of shared gene
+    /// networks and single species network. Unlike [`GenesOverSpecies::new`]
+    /// the same shared instance (e.g. produced by
+    /// [`NetworkInterner`](super::NetworkInterner)) may appear multiple
+    /// times, in which case [`GenesOverSpecies::get_gene_network_by_id`]
+    /// points to its first occurrence.
+    /// 
+    /// # Errors
+    /// For concrete errors see [`GenesOverSpeciesNewError`] docs.
+    pub fn from_shared_networks(
+        gene_networks: Vec<Arc<PhylogeneticNetwork>>,
+        species_network: PhylogeneticNetwork) -> Result<GenesOverSpecies, GenesOverSpeciesNewError>
     {
         if gene_networks.is_empty() {
             return Err(GenesOverSpeciesNewError::EmptyGeneNetworks);
         }
 
-        let species_taxa_map = species_network.taxa();
-        let mut species_taxa = HashSet::<Taxon>::with_capacity(species_taxa_map.len());
-        for taxon in species_taxa_map.values() {
-            if !species_taxa.insert(taxon.clone()) {
-                return Err(GenesOverSpeciesNewError::SpeciesContainsTaxaDuplicates);
+        let species_taxa = collect_species_taxa(&species_network)?;
+        for gene_network in &gene_networks {
+            if !has_valid_taxa(gene_network, &species_taxa) {
+                return Err(GenesOverSpeciesNewError::IncorrectTaxa);
             }
         }
 
-        let mut by_id = HashMap::<PhylogeneticNetworkId, i32>::with_capacity(gene_networks.len());
+        let by_id = index_by_id(&gene_networks)?;
+        let result = unsafe {
+            Self::new_shared_unchecked(gene_networks, by_id, species_network)
+        };
+
+        Ok(result)
+    }
+
+    /// Works like [`GenesOverSpecies::new`], but validates taxa of gene
+    /// networks on multiple threads, see
+    /// [`GenesOverSpecies::from_shared_networks_parallel`].
+    /// 
+    /// # Errors
+    /// For concrete errors see [`GenesOverSpeciesNewError`] docs.
+    pub fn new_parallel(
+        gene_networks: Vec<PhylogeneticNetwork>,
+        species_network: PhylogeneticNetwork) -> Result<GenesOverSpecies, GenesOverSpeciesNewError>
+    {
+        let gene_networks = gene_networks.into_iter().map(Arc::new).collect();
+        Self::from_shared_networks_parallel(gene_networks, species_network)
+    }
+
+    /// Works like [`GenesOverSpecies::from_shared_networks`], but validates
+    /// taxa of gene networks on multiple threads. Returned errors are the
+    /// same as in the serial version.
+    /// 
+    /// # Errors
+    /// For concrete errors see [`GenesOverSpeciesNewError`] docs.
+    pub fn from_shared_networks_parallel(
+        gene_networks: Vec<Arc<PhylogeneticNetwork>>,
+        species_network: PhylogeneticNetwork) -> Result<GenesOverSpecies, GenesOverSpeciesNewError>
+    {
+        if gene_networks.is_empty() {
+            return Err(GenesOverSpeciesNewError::EmptyGeneNetworks);
+        }
+
+        let species_taxa = collect_species_taxa(&species_network)?;
+        if find_first_invalid_parallel(&gene_networks, &species_taxa).is_some() {
+            return Err(GenesOverSpeciesNewError::IncorrectTaxa);
+        }
 
-        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-        for (idx, gene_network) in gene_networks.iter().enumerate() {
-            if !has_valid_taxa(gene_network, &species_taxa) {
-                return Err(GenesOverSpeciesNewError::IncorrectTaxa);
+        let by_id = index_by_id(&gene_networks)?;
+        let result = unsafe {
+            Self::new_shared_unchecked(gene_networks, by_id, species_network)
+        };
+
+        Ok(result)
+    }
+
+    /// Works like [`GenesOverSpecies::new`], but additionally builds
+    /// [`TaxonRegistry`] shared by all networks, see
+    /// [`GenesOverSpecies::taxon_registry`].
+    /// 
+    /// # Errors
+    /// For concrete errors see [`GenesOverSpeciesNewError`] docs.
+    pub fn new_with_registry(
+        gene_networks: Vec<PhylogeneticNetwork>,
+        species_network: PhylogeneticNetwork) -> Result<GenesOverSpecies, GenesOverSpeciesNewError>
+    {
+        let gene_networks = gene_networks.into_iter().map(Arc::new).collect();
+        Self::from_shared_networks_with_registry(gene_networks, species_network)
+    }
+
+    /// Works like [`GenesOverSpecies::from_shared_networks`], but
+    /// additionally builds [`TaxonRegistry`] shared by all networks. Species
+    /// taxa are registered first, ordered by node id, so ids are
+    /// deterministic. Taxa subset checks are done on [`TaxonIdSet`]s.
+    /// 
+    /// # Errors
+    /// For concrete errors see [`GenesOverSpeciesNewError`] docs.
+    pub fn from_shared_networks_with_registry(
+        gene_networks: Vec<Arc<PhylogeneticNetwork>>,
+        species_network: PhylogeneticNetwork) -> Result<GenesOverSpecies, GenesOverSpeciesNewError>
+    {
+        if gene_networks.is_empty() {
+            return Err(GenesOverSpeciesNewError::EmptyGeneNetworks);
+        }
+
+        let mut species_nodes: Vec<_> = species_network.taxa().iter().collect();
+        species_nodes.sort_unstable_by_key(|(node, _)| node.id());
+        let mut registry = TaxonRegistry::new();
+        let mut species_taxa = TaxonIdSet::new();
+        for (_, taxon) in species_nodes {
+            if !species_taxa.insert(registry.register_taxon(taxon)) {
+                return Err(GenesOverSpeciesNewError::SpeciesContainsTaxaDuplicates);
             }
-            if by_id.insert(gene_network.id(), idx as i32).is_some() {
-                return Err(GenesOverSpeciesNewError::DuplicatedIds);
+        }
+
+        for gene_network in &gene_networks {
+            let mut gene_taxa = TaxonIdSet::new();
+            for taxon in gene_network.taxa().values() {
+                let Some(id) = registry.id_of(taxon) else {
+                    return Err(GenesOverSpeciesNewError::IncorrectTaxa);
+                };
+                gene_taxa.insert(id);
+            }
+            if !gene_taxa.is_subset(&species_taxa) {
+                return Err(GenesOverSpeciesNewError::IncorrectTaxa);
             }
         }
 
-        let result = unsafe {
-            Self::new_unchecked(gene_networks, by_id, species_network)
+        let by_id = index_by_id(&gene_networks)?;
+        let mut result = unsafe {
+            Self::new_shared_unchecked(gene_networks, by_id, species_network)
         };
+        result.taxon_registry = Some(registry);
 
         Ok(result)
     }
@@ -99,8 +307,47 @@ impl GenesOverSpecies {
         Self::new(vec![gene_network], species_network)
     }
 
+    /// Constructs [`GenesOverSpecies`] out of [`GenesOverSpeciesDTO`] with
+    /// the validation of [`GenesOverSpecies::new`].
+    ///
+    /// # Errors
+    /// For concrete errors see [`GenesOverSpeciesFromError`] docs.
+    pub fn from_dto(dto: &GenesOverSpeciesDTO)
+        -> Result<Self, GenesOverSpeciesFromError>
+    {
+        let species_network = PhylogeneticNetwork::from_dto(dto.species_network())
+            .map_err(GenesOverSpeciesFromError::SpeciesNetworkError)?;
+        let mut gene_networks = Vec::with_capacity(dto.gene_networks().len());
+        let mut by_explicit_id = HashMap::<i32, Arc<PhylogeneticNetwork>>::new();
+        for (idx, gene_dto) in dto.gene_networks().iter().enumerate() {
+            let gene_network = Arc::new(PhylogeneticNetwork::from_dto(gene_dto)
+                .map_err(|err| GenesOverSpeciesFromError::GeneNetworkError(idx, err))?);
+            let Some(id) = gene_dto.id() else {
+                gene_networks.push(gene_network);
+                continue;
+            };
+            let shared = by_explicit_id.entry(id).or_insert_with(|| gene_network.clone());
+            if shared.graph() != gene_network.graph() || shared.taxa() != gene_network.taxa() {
+                return Err(GenesOverSpeciesNewError::DuplicatedIds.into());
+            }
+            gene_networks.push(shared.clone());
+        }
+        Ok(Self::from_shared_networks(gene_networks, species_network)?)
+    }
+
+    /// Converts [`GenesOverSpecies`] into [`GenesOverSpeciesDTO`]. Gene
+    /// networks keep their ids, see
+    /// [`PhylogeneticNetwork::into_dto_with_id`].
+    pub fn into_dto(&self) -> GenesOverSpeciesDTO {
+        let gene_networks = self.gene_networks
+            .iter()
+            .map(|network| network.into_dto_with_id())
+            .collect();
+        GenesOverSpeciesDTO::new(self.species_network.into_dto(), gene_networks)
+    }
+
     #[inline(always)]
-    pub fn gene_networks(&self) -> &[PhylogeneticNetwork] {
+    pub fn gene_networks(&self) -> &[Arc<PhylogeneticNetwork>] {
         &self.gene_networks
     }
 
@@ -110,7 +357,7 @@ impl GenesOverSpecies {
     {
         #[allow(clippy::cast_sign_loss)]
         if let Some(idx) = self.gene_networks_by_id.get(&id) {
-            Some(&self.gene_networks[*idx as usize])
+            Some(self.gene_networks[*idx as usize].as_ref())
         }
         else
         {
@@ -118,20 +365,188 @@ impl GenesOverSpecies {
         }
     }
 
+    /// Appends `gene_network`, validating only its taxa against the cached
+    /// species taxa. Returns id of the added network.
+    ///
+    /// # Errors
+    /// For concrete errors see [`AddGeneError`] docs.
+    pub fn add_gene_network(&mut self, gene_network: PhylogeneticNetwork)
+        -> Result<PhylogeneticNetworkId, AddGeneError>
+    {
+        self.add_shared_gene_network(Arc::new(gene_network))
+    }
+
+    /// Works like [`GenesOverSpecies::add_gene_network`], but for a shared
+    /// network. Unlike [`GenesOverSpecies::from_shared_networks`] adding
+    /// an instance which is already present is rejected.
+    ///
+    /// # Errors
+    /// For concrete errors see [`AddGeneError`] docs.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn add_shared_gene_network(&mut self, gene_network: Arc<PhylogeneticNetwork>)
+        -> Result<PhylogeneticNetworkId, AddGeneError>
+    {
+        let id = gene_network.id();
+        if self.gene_networks_by_id.contains_key(&id) {
+            return Err(AddGeneError::DuplicatedId);
+        }
+        let valid_taxa = gene_network.taxa()
+            .values()
+            .all(|taxon| self.species_taxa.contains(taxon));
+        if !valid_taxa {
+            return Err(AddGeneError::IncorrectTaxa);
+        }
+        self.gene_networks_by_id.insert(id, self.gene_networks.len() as i32);
+        self.gene_networks.push(gene_network);
+        Ok(id)
+    }
+
+    /// Removes all occurrences of gene network with given `id` and returns
+    /// it. The last network is moved into the freed position, so the order
+    /// of gene networks is not kept.
+    ///
+    /// # Errors
+    /// For concrete errors see [`RemoveGeneError`] docs. The instance is
+    /// left unchanged then.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss)]
+    pub fn remove_gene_network(&mut self, id: PhylogeneticNetworkId)
+        -> Result<Arc<PhylogeneticNetwork>, RemoveGeneError>
+    {
+        let Some(first_idx) = self.gene_networks_by_id.get(&id) else {
+            return Err(RemoveGeneError::NotFound);
+        };
+        let first_idx = *first_idx as usize;
+        if self.gene_networks.iter().all(|network| network.id() == id) {
+            return Err(RemoveGeneError::EmptyGeneNetworks);
+        }
+        self.gene_networks_by_id.remove(&id);
+        let mut removed = None;
+        for idx in (first_idx..self.gene_networks.len()).rev() {
+            if self.gene_networks[idx].id() != id {
+                continue;
+            }
+            removed = Some(self.gene_networks.swap_remove(idx));
+            let moved_idx = self.gene_networks
+                .get(idx)
+                .and_then(|moved| self.gene_networks_by_id.get_mut(&moved.id()));
+            if let Some(moved_idx) = moved_idx {
+                *moved_idx = (*moved_idx).min(idx as i32);
+            }
+        }
+        removed.ok_or(RemoveGeneError::NotFound)
+    }
+
     #[inline(always)]
     pub fn species_network(&self) -> &PhylogeneticNetwork {
         &self.species_network
     }
+
+    /// Registry of all taxa, available if the instance was created with
+    /// [`GenesOverSpecies::new_with_registry`] or
+    /// [`GenesOverSpecies::from_shared_networks_with_registry`].
+    #[inline(always)]
+    pub fn taxon_registry(&self) -> Option<&TaxonRegistry> {
+        self.taxon_registry.as_ref()
+    }
+}
+
+impl PartialEq for GenesOverSpecies {
+    fn eq(&self, other: &Self) -> bool {
+        self.species_network == other.species_network
+            && self.gene_networks == other.gene_networks
+    }
+}
+
+impl Eq for GenesOverSpecies { }
+
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss)]
+fn index_by_id(gene_networks: &[Arc<PhylogeneticNetwork>])
+    -> Result<HashMap<PhylogeneticNetworkId, i32>, GenesOverSpeciesNewError>
+{
+    let mut by_id = HashMap::<PhylogeneticNetworkId, i32>::with_capacity(gene_networks.len());
+    for (idx, gene_network) in gene_networks.iter().enumerate() {
+        if let Some(first_idx) = by_id.get(&gene_network.id()) {
+            let first = &gene_networks[*first_idx as usize];
+            if !Arc::ptr_eq(first, gene_network) {
+                return Err(GenesOverSpeciesNewError::DuplicatedIds);
+            }
+        }
+        else
+        {
+            by_id.insert(gene_network.id(), idx as i32);
+        }
+    }
+    Ok(by_id)
 }
 
 
+fn collect_species_taxa(species_network: &PhylogeneticNetwork)
+    -> Result<HashSet<&Taxon>, GenesOverSpeciesNewError>
+{
+    let species_taxa_map = species_network.taxa();
+    let mut species_taxa = HashSet::<&Taxon>::with_capacity(species_taxa_map.len());
+    for taxon in species_taxa_map.values() {
+        if !species_taxa.insert(taxon) {
+            return Err(GenesOverSpeciesNewError::SpeciesContainsTaxaDuplicates);
+        }
+    }
+    Ok(species_taxa)
+}
+
 fn has_valid_taxa(
     gene_network: &PhylogeneticNetwork,
-    species_taxa: &HashSet<Taxon>) -> bool
+    species_taxa: &HashSet<&Taxon>) -> bool
 {
-    let gene_taxa: HashSet<Taxon>
-        = gene_network.taxa().values().cloned().collect();
-    gene_taxa.is_subset(species_taxa)
+    gene_network.taxa().values().all(|taxon| species_taxa.contains(taxon))
+}
+
+/// Minimal number of gene networks validated by a single thread.
+const MIN_NETWORKS_PER_THREAD: usize = 64;
+
+/// Returns index of the first (in input order) gene network with taxa
+/// outside of `species_taxa`. Networks are split into contiguous chunks,
+/// one per thread; a thread stops once an earlier failure is known.
+fn find_first_invalid_parallel(
+    gene_networks: &[Arc<PhylogeneticNetwork>],
+    species_taxa: &HashSet<&Taxon>) -> Option<usize>
+{
+    let threads = std::thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(gene_networks.len().div_ceil(MIN_NETWORKS_PER_THREAD));
+    if threads <= 1 {
+        return gene_networks.iter()
+            .position(|gene_network| !has_valid_taxa(gene_network, species_taxa));
+    }
+
+    let chunk_size = gene_networks.len().div_ceil(threads);
+    let first_invalid = AtomicUsize::new(usize::MAX);
+    std::thread::scope(|scope| {
+        for (chunk_idx, chunk) in gene_networks.chunks(chunk_size).enumerate() {
+            let first_invalid = &first_invalid;
+            scope.spawn(move || {
+                let offset = chunk_idx * chunk_size;
+                for (idx, gene_network) in chunk.iter().enumerate() {
+                    let idx = offset + idx;
+                    if idx > first_invalid.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    if !has_valid_taxa(gene_network, species_taxa) {
+                        first_invalid.fetch_min(idx, Ordering::Relaxed);
+                        return;
+                    }
+                }
+            });
+        }
+    });
+
+    let first_invalid = first_invalid.into_inner();
+    if first_invalid == usize::MAX { None } else { Some(first_invalid) }
 }
 
 impl core::hash::Hash for GenesOverSpecies {
@@ -140,3 +555,70 @@ impl core::hash::Hash for GenesOverSpecies {
         self.species_network.hash(state);
     }
 }
+
+impl Display for GenesOverSpeciesNewError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            GenesOverSpeciesNewError::EmptyGeneNetworks
+                => f.write_str("no gene networks"),
+            GenesOverSpeciesNewError::IncorrectTaxa
+                => f.write_str("gene network has taxa outside of species network taxa"),
+            GenesOverSpeciesNewError::DuplicatedIds
+                => f.write_str("gene networks have duplicated ids"),
+            GenesOverSpeciesNewError::SpeciesContainsTaxaDuplicates
+                => f.write_str("species network has duplicated taxa"),
+        }
+    }
+}
+
+impl std::error::Error for GenesOverSpeciesNewError { }
+
+impl Display for AddGeneError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AddGeneError::IncorrectTaxa
+                => f.write_str("gene network has taxa outside of species network taxa"),
+            AddGeneError::DuplicatedId
+                => f.write_str("gene network with the same id is already present"),
+        }
+    }
+}
+
+impl std::error::Error for AddGeneError { }
+
+impl Display for RemoveGeneError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RemoveGeneError::NotFound
+                => f.write_str("gene network not found"),
+            RemoveGeneError::EmptyGeneNetworks
+                => f.write_str("cannot remove the last gene network"),
+        }
+    }
+}
+
+impl std::error::Error for RemoveGeneError { }
+
+impl Display for GenesOverSpeciesFromError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            GenesOverSpeciesFromError::SpeciesNetworkError(_)
+                => f.write_str("invalid species network"),
+            GenesOverSpeciesFromError::GeneNetworkError(idx, _)
+                => write!(f, "invalid gene network {idx}"),
+            GenesOverSpeciesFromError::NewError(_)
+                => f.write_str("invalid genes over species"),
+        }
+    }
+}
+
+impl std::error::Error for GenesOverSpeciesFromError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GenesOverSpeciesFromError::SpeciesNetworkError(err)
+                | GenesOverSpeciesFromError::GeneNetworkError(_, err)
+                => Some(err),
+            GenesOverSpeciesFromError::NewError(err) => Some(err),
+        }
+    }
+}
@@ -1,13 +1,45 @@
 mod taxon;
+mod taxon_policy;
+mod taxon_registry;
 mod phylogenetic_network_id;
 mod phylogenetic_network_dto;
+mod network_statistics;
+mod network_properties;
+mod network_summary;
+mod network_options;
 mod phylogenetic_network;
 mod genes_over_species;
+mod restriction;
+mod network_edits;
+mod phylogenetic_forest;
+mod network_interner;
 mod newick_parser;
+mod newick_writer;
+mod canonical_text;
+mod clusters;
+mod displayed_trees;
 
 pub use taxon::*;
+pub use taxon_policy::*;
+pub use taxon_registry::*;
 pub use phylogenetic_network_id::*;
 pub use phylogenetic_network_dto::*;
+pub use network_statistics::*;
+pub use network_properties::*;
+pub use network_summary::*;
+pub use network_options::*;
 pub use phylogenetic_network::*;
 pub use genes_over_species::*;
+pub use network_edits::*;
+pub use phylogenetic_forest::*;
+pub use network_interner::*;
 pub use newick_parser::*;
+pub use newick_writer::*;
+pub use canonical_text::*;
+pub use displayed_trees::*;
+
+#[cfg(feature = "simulation")]
+pub mod simulation;
+
+pub use restriction::RestrictionError;
+pub(crate) use restriction::restrict_network;
@@ -0,0 +1,202 @@
+use core::fmt::{Display, Formatter};
+use std::collections::HashMap;
+
+use crate::core::{ArrowDTO, DirectedGraphDTO, Node};
+use crate::raf_array::immutable_string::ImmutableString;
+
+use super::{
+    PhylogeneticNetwork,
+    PhylogeneticNetworkDTO,
+    PhylogeneticNetworkFromError,
+    PhylogeneticNetworkKind,
+    PhylogeneticNetworkOptions,
+    Taxon};
+
+/// Error of [`PhylogeneticNetwork`] edits, e.g.
+/// [`PhylogeneticNetwork::with_reticulation_added`].
+#[derive(Debug)]
+pub enum NetworkEditError {
+    /// Node is not in the network.
+    NodeNotFound(Node),
+
+    /// Arrow `source -> target` is not in the network.
+    ArrowNotFound(Node, Node),
+
+    /// Relabeled node is not a leaf.
+    NotLeaf(Node),
+
+    /// Suppressed node is the root, or doesn't have in- and out-degree 1.
+    NotSuppressible(Node),
+
+    /// Both arrows to subdivide are the same arrow `source -> target`.
+    SameArrow(Node, Node),
+
+    /// Edited network violates network invariants, e.g. new reticulation
+    /// closes a cycle.
+    NetworkError(PhylogeneticNetworkFromError),
+}
+
+impl From<PhylogeneticNetworkFromError> for NetworkEditError {
+    fn from(value: PhylogeneticNetworkFromError) -> Self { Self::NetworkError(value) }
+}
+
+impl PhylogeneticNetwork {
+    /// Returns copy of the network with leaf `node` labeled with `taxon`,
+    /// replacing its previous taxon if any. The copy gets fresh id.
+    ///
+    /// # Errors
+    /// [`NetworkEditError::NodeNotFound`] or [`NetworkEditError::NotLeaf`].
+    pub fn with_leaf_relabeled(&self, node: Node, taxon: Taxon)
+        -> Result<PhylogeneticNetwork, NetworkEditError>
+    {
+        self.verify_node(node)?;
+        if !self.is_leaf(node) {
+            return Err(NetworkEditError::NotLeaf(node));
+        }
+        let mut taxa = self.taxa().clone();
+        taxa.insert(node, taxon);
+        let graph = self.graph().clone();
+        Ok(PhylogeneticNetwork::from_graph_and_taxa_with_options(graph, taxa, &self.edit_options())?)
+    }
+
+    /// Returns copy of the network with both arrows subdivided, i.e.
+    /// `a -> b` replaced by `a -> x -> b` and `c -> d` by `c -> y -> d`, and
+    /// new arrow `x -> y`. Thus `y` is a new reticulation node. New nodes
+    /// get ids following existing ones, `x` first. Weights of subdivided
+    /// arrows are dropped. The copy gets fresh id.
+    ///
+    /// # Errors
+    /// [`NetworkEditError::ArrowNotFound`] or
+    /// [`NetworkEditError::SameArrow`]. [`NetworkEditError::NetworkError`]
+    /// with [`PhylogeneticNetworkFromError::NotAcyclic`] when `c -> d` lies
+    /// below `a -> b`, i.e. `d` is reachable from `a`.
+    pub fn with_reticulation_added(
+        &self,
+        arrow_to_split_a: (Node, Node),
+        arrow_to_split_b: (Node, Node))
+        -> Result<PhylogeneticNetwork, NetworkEditError>
+    {
+        for (source, target) in [arrow_to_split_a, arrow_to_split_b] {
+            if !self.graph().contains_arrow(source, target) {
+                return Err(NetworkEditError::ArrowNotFound(source, target));
+            }
+        }
+        if arrow_to_split_a == arrow_to_split_b {
+            let (source, target) = arrow_to_split_a;
+            return Err(NetworkEditError::SameArrow(source, target));
+        }
+
+        let dto = self.into_dto();
+        let x = dto.graph().number_of_nodes();
+        let y = x + 1;
+        let split = |arrow: (Node, Node), middle: i32| {
+            [ArrowDTO::new(arrow.0.id(), middle), ArrowDTO::new(middle, arrow.1.id())]
+        };
+        let mut arrows: Vec<ArrowDTO> = dto.graph().arrows()
+            .iter()
+            .filter(|arrow| {
+                let pair = (Node::from(arrow.source()), Node::from(arrow.target()));
+                pair != arrow_to_split_a && pair != arrow_to_split_b
+            })
+            .cloned()
+            .collect();
+        arrows.extend(split(arrow_to_split_a, x));
+        arrows.extend(split(arrow_to_split_b, y));
+        arrows.push(ArrowDTO::new(x, y));
+        let graph = DirectedGraphDTO::new(y + 1, arrows);
+        self.rebuild(graph, dto.taxa().clone())
+    }
+
+    /// Returns copy of the network with `node` of in- and out-degree 1
+    /// removed, and its parent connected to its child instead. Taxon of
+    /// `node`, if any, is dropped. Nodes of higher ids are shifted down by
+    /// one. The copy gets fresh id.
+    ///
+    /// # Errors
+    /// [`NetworkEditError::NodeNotFound`] or
+    /// [`NetworkEditError::NotSuppressible`].
+    /// [`NetworkEditError::NetworkError`] if the parent is already connected
+    /// to the child.
+    pub fn with_node_suppressed(&self, node: Node)
+        -> Result<PhylogeneticNetwork, NetworkEditError>
+    {
+        self.verify_node(node)?;
+        let graph = self.graph();
+        let (&[parent], &[child]) = (graph.get_predecessors(node), graph.get_successors(node)) else {
+            return Err(NetworkEditError::NotSuppressible(node));
+        };
+
+        let shift = |id: i32| if id > node.id() { id - 1 } else { id };
+        let dto = self.into_dto();
+        let mut arrows: Vec<ArrowDTO> = dto.graph().arrows()
+            .iter()
+            .filter(|arrow| arrow.source() != node.id() && arrow.target() != node.id())
+            .map(|arrow| ArrowDTO::new(shift(arrow.source()), shift(arrow.target()))
+                .with_weight(arrow.weight()))
+            .collect();
+        arrows.push(ArrowDTO::new(shift(parent.id()), shift(child.id())));
+        let taxa = dto.taxa()
+            .iter()
+            .filter(|(id, _)| **id != node.id())
+            .map(|(id, taxon)| (shift(*id), taxon.clone()))
+            .collect();
+        let graph = DirectedGraphDTO::new(dto.graph().number_of_nodes() - 1, arrows);
+        self.rebuild(graph, taxa)
+    }
+
+    fn verify_node(&self, node: Node) -> Result<(), NetworkEditError> {
+        if (0..self.graph().number_of_nodes()).contains(&node.id()) {
+            Ok(())
+        }
+        else
+        {
+            Err(NetworkEditError::NodeNotFound(node))
+        }
+    }
+
+    /// Edits keep the network multifurcating, or with taxa on internal
+    /// nodes, if it was.
+    fn edit_options(&self) -> PhylogeneticNetworkOptions {
+        PhylogeneticNetworkOptions::default()
+            .with_allow_multifurcations(self.kind() == PhylogeneticNetworkKind::Multifurcating)
+            .with_allow_internal_taxa(self.has_internal_taxa())
+    }
+
+    fn rebuild(
+        &self,
+        graph: DirectedGraphDTO,
+        taxa: HashMap<i32, ImmutableString>)
+        -> Result<PhylogeneticNetwork, NetworkEditError>
+    {
+        let dto = PhylogeneticNetworkDTO::new(graph, taxa);
+        Ok(PhylogeneticNetwork::from_dto_with_options(&dto, &self.edit_options())?)
+    }
+}
+
+impl Display for NetworkEditError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            NetworkEditError::NodeNotFound(node)
+                => write!(f, "node {} is not in the network", node.id()),
+            NetworkEditError::ArrowNotFound(source, target)
+                => write!(f, "arrow {} -> {} is not in the network", source.id(), target.id()),
+            NetworkEditError::NotLeaf(node)
+                => write!(f, "node {} is not a leaf", node.id()),
+            NetworkEditError::NotSuppressible(node)
+                => write!(f, "node {} doesn't have in- and out-degree 1", node.id()),
+            NetworkEditError::SameArrow(source, target)
+                => write!(f, "arrow {} -> {} cannot be subdivided twice", source.id(), target.id()),
+            NetworkEditError::NetworkError(_)
+                => f.write_str("edited network is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for NetworkEditError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NetworkEditError::NetworkError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
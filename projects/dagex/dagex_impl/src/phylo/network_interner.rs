@@ -0,0 +1,147 @@
+use std::{collections::{BTreeMap, HashMap}, sync::{Arc, Mutex}};
+
+use crate::core::DirectedGraphDTO;
+use crate::raf_array::immutable_string::ImmutableString;
+
+use super::PhylogeneticNetwork;
+
+/// Hit/miss counters of a [`NetworkInterner`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct InternerStatistics {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+/// Canonical form of a network, see
+/// [`PhylogeneticNetwork::to_canonical_dto`], with taxa sorted by node.
+#[derive(PartialEq, Eq, Hash)]
+struct InternKey {
+    graph: DirectedGraphDTO,
+    taxa: Vec<(i32, ImmutableString)>,
+}
+
+impl InternKey {
+    fn new(network: &PhylogeneticNetwork) -> Self {
+        let dto = network.to_canonical_dto();
+        let mut taxa: Vec<(i32, ImmutableString)> = dto.taxa()
+            .iter()
+            .map(|(node, taxon)| (*node, taxon.clone()))
+            .collect();
+        taxa.sort_unstable_by_key(|(node, _)| *node);
+        Self { graph: dto.graph().clone(), taxa: taxa }
+    }
+}
+
+struct InternerState {
+    entries: HashMap<Arc<InternKey>, (Arc<PhylogeneticNetwork>, u64)>,
+    lru: BTreeMap<u64, Arc<InternKey>>,
+    tick: u64,
+    statistics: InternerStatistics,
+}
+
+/// Thread safe, bounded cache deduplicating structurally equal
+/// [`PhylogeneticNetwork`] instances. Two networks are considered equal
+/// when their [`PhylogeneticNetwork::to_canonical_dto`] are, so that dedup
+/// doesn't depend on node numbering of the inputs. When the cache is full
+/// the least recently used network is evicted.
+pub struct NetworkInterner {
+    capacity: usize,
+    state: Mutex<InternerState>,
+}
+
+impl NetworkInterner {
+    /// Creates new [`NetworkInterner`] holding at most `capacity` networks.
+    ///
+    /// # Panics
+    /// When `capacity` is 0.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "NetworkInterner capacity has to be positive.");
+        let state = InternerState {
+            entries: HashMap::with_capacity(capacity),
+            lru: BTreeMap::new(),
+            tick: 0,
+            statistics: InternerStatistics::default(),
+        };
+        Self { capacity, state: Mutex::new(state) }
+    }
+
+    /// Returns shared instance of a network equal to `network`. If no such
+    /// network is cached, `network` itself is cached and returned. Note that
+    /// on hit the returned network keeps the id of the cached instance.
+    ///
+    /// # Panics
+    /// When the internal lock is poisoned.
+    pub fn intern(&self, network: PhylogeneticNetwork) -> Arc<PhylogeneticNetwork> {
+        self.intern_shared(Arc::new(network))
+    }
+
+    /// Same as [`NetworkInterner::intern`] but for already shared networks.
+    ///
+    /// # Panics
+    /// When the internal lock is poisoned.
+    pub fn intern_shared(&self, network: Arc<PhylogeneticNetwork>) -> Arc<PhylogeneticNetwork> {
+        let key = InternKey::new(&network);
+        let mut state = self.state.lock().unwrap();
+        state.tick += 1;
+        let tick = state.tick;
+        if let Some((key, (existing, old_tick))) = state.entries.get_key_value(&key) {
+            let key = key.clone();
+            let existing = existing.clone();
+            let old_tick = *old_tick;
+            state.lru.remove(&old_tick);
+            state.lru.insert(tick, key.clone());
+            state.entries.insert(key, (existing.clone(), tick));
+            state.statistics.hits += 1;
+            return existing;
+        }
+
+        state.statistics.misses += 1;
+        if state.entries.len() >= self.capacity {
+            if let Some((_, evicted)) = state.lru.pop_first() {
+                state.entries.remove(&evicted);
+                state.statistics.evictions += 1;
+            }
+        }
+
+        let key = Arc::new(key);
+        state.lru.insert(tick, key.clone());
+        state.entries.insert(key, (network.clone(), tick));
+        network
+    }
+
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of currently cached networks.
+    ///
+    /// # Panics
+    /// When the internal lock is poisoned.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    /// # Panics
+    /// When the internal lock is poisoned.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// # Panics
+    /// When the internal lock is poisoned.
+    pub fn statistics(&self) -> InternerStatistics {
+        self.state.lock().unwrap().statistics
+    }
+
+    /// Removes all cached networks. Statistics are preserved.
+    ///
+    /// # Panics
+    /// When the internal lock is poisoned.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.entries.clear();
+        state.lru.clear();
+    }
+}
@@ -0,0 +1,69 @@
+use super::TaxonPolicy;
+
+/// Shape of nodes of a [`PhylogeneticNetwork`](super::PhylogeneticNetwork),
+/// determined at construction.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum PhylogeneticNetworkKind {
+    /// Every node has at most two successors and at most two predecessors.
+    Binary,
+
+    /// Some node has more than two successors or predecessors. Only
+    /// constructed on explicit request, see
+    /// [`PhylogeneticNetworkOptions::allow_multifurcations`].
+    Multifurcating,
+}
+
+/// Strictness of
+/// [`PhylogeneticNetwork::from_graph_and_taxa_with_options`](super::PhylogeneticNetwork::from_graph_and_taxa_with_options).
+/// Default values match
+/// [`PhylogeneticNetwork::from_graph_and_taxa`](super::PhylogeneticNetwork::from_graph_and_taxa).
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+pub struct PhylogeneticNetworkOptions {
+    allow_multifurcations: bool,
+    allow_internal_taxa: bool,
+    taxon_policy: Option<TaxonPolicy>,
+}
+
+impl PhylogeneticNetworkOptions {
+    /// Whether non-binary graphs are accepted, resulting in
+    /// [`PhylogeneticNetworkKind::Multifurcating`] networks. `false` by
+    /// default.
+    #[inline(always)]
+    pub fn allow_multifurcations(&self) -> bool {
+        self.allow_multifurcations
+    }
+
+    /// Whether taxa may be assigned to nodes which are not leaves, as Newick
+    /// names of internal nodes are. `false` by default.
+    #[inline(always)]
+    pub fn allow_internal_taxa(&self) -> bool {
+        self.allow_internal_taxa
+    }
+
+    /// Policy every taxon is normalized and validated with, see
+    /// [`Taxon::new_validated`](super::Taxon::new_validated). Networks
+    /// loaded from differently formatted sources compare equal then.
+    /// Taxa are kept as they are by default.
+    #[inline(always)]
+    pub fn taxon_policy(&self) -> Option<&TaxonPolicy> {
+        self.taxon_policy.as_ref()
+    }
+
+    #[must_use]
+    pub fn with_allow_multifurcations(mut self, value: bool) -> Self {
+        self.allow_multifurcations = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_allow_internal_taxa(mut self, value: bool) -> Self {
+        self.allow_internal_taxa = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_taxon_policy(mut self, value: Option<TaxonPolicy>) -> Self {
+        self.taxon_policy = value;
+        self
+    }
+}
@@ -0,0 +1,124 @@
+use crate::core::{DirectedGraph, Node};
+
+/// Network classes calculated once, during
+/// [`PhylogeneticNetwork`](super::PhylogeneticNetwork) construction. Nodes
+/// of in-degree at least 2 are treated as reticulations.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+pub struct PhylogeneticNetworkProperties {
+    /// Every non-leaf node has at least one child which is not
+    /// a reticulation.
+    pub tree_child: bool,
+
+    /// Every reticulation has at least one sibling, i.e. other child of one
+    /// of its parents, which is not a reticulation.
+    pub tree_sibling: bool,
+
+    /// There is a time assignment to nodes, strictly increasing along tree
+    /// arrows and constant along reticulation arrows. In other words
+    /// a reticulation happens at the same time as all its parents.
+    pub time_consistent: bool,
+}
+
+impl PhylogeneticNetworkProperties {
+    pub(super) fn calculate(graph: &DirectedGraph) -> Self {
+        Self {
+            tree_child: is_tree_child(graph),
+            tree_sibling: is_tree_sibling(graph),
+            time_consistent: is_time_consistent(graph),
+        }
+    }
+}
+
+#[inline(always)]
+fn is_reticulation(graph: &DirectedGraph, node: Node) -> bool {
+    graph.in_degree(node) >= 2
+}
+
+fn is_tree_child(graph: &DirectedGraph) -> bool {
+    graph.iter_nodes().all(|node| {
+        let successors = graph.get_successors(node);
+        successors.is_empty()
+            || successors.iter().any(|child| !is_reticulation(graph, *child))
+    })
+}
+
+fn is_tree_sibling(graph: &DirectedGraph) -> bool {
+    graph.iter_nodes()
+        .filter(|node| is_reticulation(graph, *node))
+        .all(|node| {
+            graph.get_predecessors(node)
+                .iter()
+                .flat_map(|parent| graph.get_successors(*parent))
+                .any(|sibling| *sibling != node && !is_reticulation(graph, *sibling))
+        })
+}
+
+/// Merges every reticulation with its parents, since they have to share
+/// time, and checks that tree arrows between the merged classes form
+/// an acyclic graph. Tree arrows inside a single class make it
+/// inconsistent as well.
+#[allow(clippy::cast_sign_loss)]
+fn is_time_consistent(graph: &DirectedGraph) -> bool {
+    let size = graph.number_of_nodes() as usize;
+    let mut classes = UnionFind::new(size);
+    for node in graph.iter_nodes().filter(|node| is_reticulation(graph, *node)) {
+        for parent in graph.get_predecessors(node) {
+            classes.union(node.id() as usize, parent.id() as usize);
+        }
+    }
+
+    let mut successors = vec![Vec::<usize>::new(); size];
+    let mut in_degrees = vec![0usize; size];
+    for node in graph.iter_nodes().filter(|node| graph.in_degree(*node) == 1) {
+        let parent = graph.get_predecessors(node)[0];
+        let source = classes.find(parent.id() as usize);
+        let target = classes.find(node.id() as usize);
+        if source == target {
+            return false;
+        }
+        successors[source].push(target);
+        in_degrees[target] += 1;
+    }
+
+    let mut stack: Vec<usize> = (0..size)
+        .filter(|idx| classes.find(*idx) == *idx && in_degrees[*idx] == 0)
+        .collect();
+    let mut remaining = (0..size).filter(|idx| classes.find(*idx) == *idx).count();
+    while let Some(class) = stack.pop() {
+        remaining -= 1;
+        for target in &successors[class] {
+            in_degrees[*target] -= 1;
+            if in_degrees[*target] == 0 {
+                stack.push(*target);
+            }
+        }
+    }
+    remaining == 0
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        Self { parents: (0..size).collect() }
+    }
+
+    fn find(&mut self, mut idx: usize) -> usize {
+        while self.parents[idx] != idx {
+            self.parents[idx] = self.parents[self.parents[idx]];
+            idx = self.parents[idx];
+        }
+        idx
+    }
+
+    fn union(&mut self, first: usize, second: usize) {
+        let first = self.find(first);
+        let second = self.find(second);
+        if first != second {
+            self.parents[first] = second;
+        }
+    }
+}
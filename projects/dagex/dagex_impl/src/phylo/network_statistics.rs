@@ -0,0 +1,20 @@
+/// Kind of an arrow in a [`PhylogeneticNetwork`](super::PhylogeneticNetwork).
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum ArrowKind {
+    /// The unique incoming arrow of a node of in-degree 1.
+    Tree,
+
+    /// Arrow incoming into a node of in-degree at least 2.
+    Reticulation,
+}
+
+/// Counters calculated once, during [`PhylogeneticNetwork`](super::PhylogeneticNetwork)
+/// construction.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+pub struct NetworkStatistics {
+    pub number_of_tree_arrows: usize,
+    pub number_of_reticulation_arrows: usize,
+
+    /// Number of nodes of in-degree at least 2.
+    pub number_of_reticulation_nodes: usize,
+}
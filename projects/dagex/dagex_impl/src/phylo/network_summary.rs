@@ -0,0 +1,139 @@
+use core::fmt::{Display, Formatter};
+use std::collections::HashSet;
+
+use crate::core::GraphSummary;
+
+use super::{PhylogeneticNetwork, PhylogeneticNetworkKind, Taxon};
+
+/// Maximal number of taxa kept in [`NetworkSummary::sample_taxa`].
+pub const SUMMARY_SAMPLE_TAXA: usize = 3;
+
+/// Compact overview of a [`PhylogeneticNetwork`], extending
+/// [`GraphSummary`] with node kinds and taxa.
+#[derive(Clone, Debug)]
+pub struct NetworkSummary<'a> {
+    graph_summary: GraphSummary<'a>,
+    kind: PhylogeneticNetworkKind,
+    tree_node_count: usize,
+    reticulation_node_count: usize,
+    cross_node_count: usize,
+    taxa_count: usize,
+    sample_taxa: Vec<Taxon>,
+}
+
+impl PhylogeneticNetwork {
+    /// Builds [`NetworkSummary`] for the current network. Maximal depth is
+    /// not calculated until requested.
+    pub fn summary(&self) -> NetworkSummary<'_> {
+        NetworkSummary::new(self)
+    }
+}
+
+impl<'a> NetworkSummary<'a> {
+    fn new(network: &'a PhylogeneticNetwork) -> Self {
+        let mut tree_node_count = 0;
+        let mut reticulation_node_count = 0;
+        let mut cross_node_count = 0;
+        for node in network.graph().iter_nodes() {
+            if network.is_tree_node(node) {
+                tree_node_count += 1;
+            }
+            else if network.is_reticulation_node(node) {
+                reticulation_node_count += 1;
+            }
+            else if network.is_cross_node(node) {
+                cross_node_count += 1;
+            }
+        }
+
+        let distinct: HashSet<&Taxon> = network.taxa().values().collect();
+        let mut sample_taxa: Vec<Taxon> = distinct.iter().map(|taxon| (*taxon).clone()).collect();
+        sample_taxa.sort_by(|left, right| left.value().as_str().cmp(right.value().as_str()));
+        sample_taxa.truncate(SUMMARY_SAMPLE_TAXA);
+
+        Self {
+            graph_summary: network.graph().summary(),
+            kind: network.kind(),
+            tree_node_count: tree_node_count,
+            reticulation_node_count: reticulation_node_count,
+            cross_node_count: cross_node_count,
+            taxa_count: distinct.len(),
+            sample_taxa: sample_taxa,
+        }
+    }
+
+    /// Calculates maximal depth eagerly, see [`GraphSummary::with_depth`].
+    #[must_use]
+    pub fn with_depth(mut self) -> Self {
+        self.graph_summary = self.graph_summary.with_depth();
+        self
+    }
+
+    #[inline(always)]
+    pub fn graph_summary(&self) -> &GraphSummary<'a> {
+        &self.graph_summary
+    }
+
+    #[inline(always)]
+    pub fn kind(&self) -> PhylogeneticNetworkKind {
+        self.kind
+    }
+
+    /// Number of nodes satisfying [`PhylogeneticNetwork::is_tree_node`].
+    #[inline(always)]
+    pub fn tree_node_count(&self) -> usize {
+        self.tree_node_count
+    }
+
+    /// Number of nodes satisfying
+    /// [`PhylogeneticNetwork::is_reticulation_node`].
+    #[inline(always)]
+    pub fn reticulation_node_count(&self) -> usize {
+        self.reticulation_node_count
+    }
+
+    /// Number of nodes satisfying [`PhylogeneticNetwork::is_cross_node`].
+    #[inline(always)]
+    pub fn cross_node_count(&self) -> usize {
+        self.cross_node_count
+    }
+
+    /// Number of distinct taxa.
+    #[inline(always)]
+    pub fn taxa_count(&self) -> usize {
+        self.taxa_count
+    }
+
+    /// Alphabetically first [`SUMMARY_SAMPLE_TAXA`] distinct taxa.
+    #[inline(always)]
+    pub fn sample_taxa(&self) -> &[Taxon] {
+        &self.sample_taxa
+    }
+
+    /// Maximal depth of the network, see [`GraphSummary::max_depth`].
+    #[inline(always)]
+    pub fn max_depth(&self) -> Option<usize> {
+        self.graph_summary.max_depth()
+    }
+}
+
+impl Display for NetworkSummary<'_> {
+    /// Calculates maximal depth, unless already done.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "{}", self.graph_summary)?;
+        writeln!(f, "kind: {:?}, tree nodes: {}, reticulation nodes: {}, cross nodes: {}",
+            self.kind, self.tree_node_count, self.reticulation_node_count, self.cross_node_count)?;
+        write!(f, "taxa: {}", self.taxa_count)?;
+        if !self.sample_taxa.is_empty() {
+            let sample: Vec<&str> = self.sample_taxa.iter()
+                .map(|taxon| taxon.value().as_str())
+                .collect();
+            write!(f, " [{}", sample.join(", "))?;
+            if self.taxa_count > self.sample_taxa.len() {
+                f.write_str(", ...")?;
+            }
+            f.write_str("]")?;
+        }
+        Ok(())
+    }
+}
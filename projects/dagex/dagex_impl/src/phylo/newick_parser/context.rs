@@ -2,56 +2,265 @@ use std::collections::{hash_map::Entry, HashMap, HashSet};
 
 use crate::raf_array::immutable_string::ImmutableString;
 
-use raf_newick::ast::{NewickGraph, NewickNodeId};
+use raf_newick::ast::{NewickGraph, NewickNode, NewickNodeId};
 
-use crate::{core::{ArrowDTO, DirectedGraphDTO}, phylo::{PhylogeneticNetwork, PhylogeneticNetworkDTO}};
+use crate::{
+    core::{ArrowDTO, DirectedGraphDTO, Node, NodeMap},
+    phylo::{PhylogeneticNetwork, PhylogeneticNetworkDTO, PhylogeneticNetworkOptions}};
 
-use super::NewickParseError;
+use super::{scanner::ScannedNode, NewickContentError, NewickParseError, NewickParseOptions};
 
 
+pub(super) struct NewickParseData {
+    pub network: PhylogeneticNetwork,
+    pub branch_lengths: NodeMap<f64>,
+    pub internal_labels: NodeMap<ImmutableString>,
+    pub annotations: HashMap<Node, HashMap<ImmutableString, ImmutableString>>,
+}
+
 pub(super) struct NewickParseContext<'a> {
     graph: &'a NewickGraph,
+    text: &'a [u8],
+    options: NewickParseOptions,
+    positions: HashMap<NewickNodeId, usize>,
     number_of_nodes: i32,
     reticulation_map: HashMap<u32, HashSet<NewickNodeId>>,
     reticulation_ids: HashMap<u32, i32>,
     node_map: HashMap<NewickNodeId, i32>,
-    taxa: HashMap<i32, ImmutableString>,
+    names: HashMap<i32, ImmutableString>,
     arrows: Vec<ArrowDTO>,
 }
 
 
+/// Prefix of New Hampshire eXtended comments.
+const NHX_PREFIX: &str = "&&NHX";
+
+
 macro_rules! perr {
-    ( $e: expr ) => {
+    ( $self: ident, $node: expr, $e: expr ) => {
         {
             let msg = format!($e);
-            return Err(NewickParseError::ContentError(msg));
+            return Err($self.content_error(msg, $node));
         }
     };
 }
 
 
 impl<'a> NewickParseContext<'a> {
-    pub fn new(graph: &'a NewickGraph) -> Self {
+    /// `text` is the parsed entry, used for error locations.
+    pub fn new(graph: &'a NewickGraph, text: &'a [u8], options: NewickParseOptions) -> Self {
         Self {
             graph: graph,
+            text: text,
+            options: options,
+            positions: HashMap::new(),
             reticulation_map: calculate_reticulation_map(graph),
             reticulation_ids: HashMap::new(),
             number_of_nodes: 0,
             node_map: HashMap::new(),
-            taxa: HashMap::new(),
+            names: HashMap::new(),
             arrows: Vec::new(),
         }
     }
 
+    /// Builds the network. `scanned` are Newick nodes in preorder, as
+    /// returned by [`scan_nodes`](super::scanner::scan_nodes). Names of
+    /// all nodes become taxa, names of non-leaf nodes are additionally
+    /// returned as internal labels. Lengths become weights of arrows into
+    /// their nodes.
     #[inline(always)]
-    pub fn parse(mut self) -> Result<PhylogeneticNetwork, NewickParseError>
+    pub fn parse(mut self, scanned: &[ScannedNode])
+        -> Result<NewickParseData, NewickParseError>
     {
+        let preorder = self.preorder();
+        if preorder.len() != scanned.len() {
+            let message = format!(
+                "Scanned {} nodes, while the parsed graph has {}.",
+                scanned.len(),
+                preorder.len());
+            return Err(NewickParseError::ContentError(NewickContentError::new(message, self.text, 0)));
+        }
+        self.positions = preorder.iter()
+            .zip(scanned)
+            .map(|(newick_id, node)| (*newick_id, node.position))
+            .collect();
+        if !self.options.allow_repeated_hybrids() {
+            self.check_repeated_hybrids(&preorder)?;
+        }
         self.calculate_reticulation_ids()?;
-        self.calculate_arrows();
-        let dag_dto = DirectedGraphDTO::new(self.number_of_nodes, self.arrows);
-        let phylo_dto = PhylogeneticNetworkDTO::new(dag_dto, self.taxa);
-        let network = PhylogeneticNetwork::from_dto(&phylo_dto)?;
-        Ok(network)
+        let lengths: HashMap<NewickNodeId, f64> = preorder.iter()
+            .zip(scanned)
+            .filter_map(|(newick_id, node)| node.length.map(|length| (*newick_id, length)))
+            .collect();
+        self.calculate_arrows(&lengths);
+        let sources: HashSet<i32> = self.arrows.iter()
+            .map(ArrowDTO::source)
+            .collect();
+        let taxa = std::mem::take(&mut self.names);
+        let labels: Vec<(i32, ImmutableString)> = taxa.iter()
+            .filter(|(idx, _)| sources.contains(idx))
+            .map(|(idx, label)| (*idx, label.clone()))
+            .collect();
+        self.check_leaves(&preorder, &sources, &taxa)?;
+        let dag_dto = DirectedGraphDTO::new(self.number_of_nodes, std::mem::take(&mut self.arrows));
+        let phylo_dto = PhylogeneticNetworkDTO::new(dag_dto, taxa);
+        let network_options = PhylogeneticNetworkOptions::default()
+            .with_allow_multifurcations(!self.options.require_binary())
+            .with_allow_internal_taxa(true);
+        let network = PhylogeneticNetwork::from_dto_with_options(&phylo_dto, &network_options)?;
+        let mut internal_labels = network.graph().new_node_map();
+        for (idx, label) in labels {
+            internal_labels.set(network.graph(), Node::from(idx), label);
+        }
+        let annotations = if self.options.parse_nhx() {
+            self.parse_nhx_annotations(&preorder, scanned)?
+        }
+        else
+        {
+            HashMap::new()
+        };
+        let mut branch_lengths = network.graph().new_node_map();
+        for (newick_id, scanned_node) in preorder.into_iter().zip(scanned) {
+            let Some(length) = scanned_node.length else {
+                continue;
+            };
+            let node = Node::from(self.node_map[&newick_id]);
+            if branch_lengths.get(network.graph(), node).is_none() {
+                branch_lengths.set(network.graph(), node, length);
+            }
+        }
+        Ok(NewickParseData {
+            network: network,
+            branch_lengths: branch_lengths,
+            internal_labels: internal_labels,
+            annotations: annotations,
+        })
+    }
+
+    /// Collects key-value pairs of NHX comments, skipping other comments.
+    fn parse_nhx_annotations(&self, preorder: &[NewickNodeId], scanned: &[ScannedNode])
+        -> Result<HashMap<Node, HashMap<ImmutableString, ImmutableString>>, NewickParseError>
+    {
+        let mut result = HashMap::<Node, HashMap<ImmutableString, ImmutableString>>::new();
+        for (newick_id, scanned_node) in preorder.iter().zip(scanned) {
+            for comment in &scanned_node.comments {
+                let content = std::str::from_utf8(&self.text[comment.clone()])?;
+                let Some(pairs) = content.strip_prefix(NHX_PREFIX) else {
+                    continue;
+                };
+                let node = Node::from(self.node_map[newick_id]);
+                let start = comment.start + NHX_PREFIX.len();
+                self.parse_nhx_pairs(pairs, start, result.entry(node).or_default())?;
+            }
+        }
+        result.retain(|_, pairs| !pairs.is_empty());
+        Ok(result)
+    }
+
+    /// Parses `:key=value` pairs of NHX comment, `start` being position of
+    /// `pairs` in the text.
+    fn parse_nhx_pairs(
+        &self,
+        pairs: &str,
+        start: usize,
+        target: &mut HashMap<ImmutableString, ImmutableString>) -> Result<(), NewickParseError>
+    {
+        let mut position = start;
+        for pair in pairs.split(':') {
+            let pair_position = position;
+            position += pair.len() + 1;
+            if pair.trim().is_empty() {
+                continue;
+            }
+            let Some((key, value)) = pair.split_once('=') else {
+                return Err(self.nhx_error(pair, pair_position));
+            };
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(self.nhx_error(pair, pair_position));
+            }
+            let (Ok(key), Ok(value)) = (ImmutableString::new(key), ImmutableString::new(value.trim())) else {
+                return Err(self.nhx_error(pair, pair_position));
+            };
+            target.entry(key).or_insert(value);
+        }
+        Ok(())
+    }
+
+    fn nhx_error(&self, pair: &str, position: usize) -> NewickParseError {
+        let message = format!("Malformed NHX annotation '{pair}', expected key=value.");
+        NewickParseError::ContentError(NewickContentError::new(message, self.text, position))
+    }
+
+    fn content_error(&self, message: String, node: NewickNodeId) -> NewickParseError {
+        let position = self.positions.get(&node).copied().unwrap_or_default();
+        NewickParseError::ContentError(NewickContentError::new(message, self.text, position))
+    }
+
+    /// Rejects the third occurrence of any hybrid label.
+    fn check_repeated_hybrids(&self, preorder: &[NewickNodeId]) -> Result<(), NewickParseError> {
+        let hybrids: HashMap<NewickNodeId, u32> = self.graph.nodes()
+            .filter_map(|node| node.reticulation().map(|reticulation| (node.id(), reticulation.id())))
+            .collect();
+        let mut counts = HashMap::<u32, usize>::new();
+        for newick_id in preorder {
+            let Some(id) = hybrids.get(newick_id) else {
+                continue;
+            };
+            let count = counts.entry(*id).or_default();
+            *count += 1;
+            if *count > 2 {
+                perr!(self, *newick_id, "Reticulation {id} occurs more than twice.");
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifies leaves against [`NewickParseOptions`], reporting the first
+    /// offending occurrence in textual order.
+    fn check_leaves(
+        &self,
+        preorder: &[NewickNodeId],
+        sources: &HashSet<i32>,
+        taxa: &HashMap<i32, ImmutableString>) -> Result<(), NewickParseError>
+    {
+        let mut seen = HashMap::<&ImmutableString, i32>::new();
+        for newick_id in preorder {
+            let idx = self.node_map[newick_id];
+            if sources.contains(&idx) {
+                continue;
+            }
+            let Some(name) = taxa.get(&idx) else {
+                if !self.options.allow_unlabeled_leaves() {
+                    perr!(self, *newick_id, "Leaf without a name.");
+                }
+                continue;
+            };
+            if !self.options.allow_duplicate_taxa() && *seen.entry(name).or_insert(idx) != idx {
+                let name = name.as_str();
+                perr!(self, *newick_id, "Duplicate taxon '{name}'.");
+            }
+        }
+        Ok(())
+    }
+
+    /// Newick nodes in preorder, children in textual order.
+    fn preorder(&self) -> Vec<NewickNodeId> {
+        let mut children = HashSet::new();
+        for node in self.graph.nodes() {
+            children.extend(self.graph.get_children(node.id()).iter().copied());
+        }
+        let mut result = Vec::with_capacity(self.node_map.len());
+        let mut stack: Vec<NewickNodeId> = self.graph.nodes()
+            .map(NewickNode::id)
+            .filter(|id| !children.contains(id))
+            .take(1)
+            .collect();
+        while let Some(id) = stack.pop() {
+            result.push(id);
+            stack.extend(self.graph.get_children(id).iter().rev().copied());
+        }
+        result
     }
 
     fn calculate_reticulation_ids(&mut self) -> Result<(), NewickParseError> {
@@ -75,9 +284,9 @@ impl<'a> NewickParseContext<'a> {
 
             let node_name = node.name().as_immutable_string();
             if !node_name.as_str().is_empty() {
-                if let Some(old_value) = self.taxa.insert(idx, node_name.clone()) {
+                if let Some(old_value) = self.names.insert(idx, node_name.clone()) {
                     if &old_value != node_name {
-                        perr!("Conflict in node names, likely two reticulation entries have different name but point to the same id.");
+                        perr!(self, node.id(), "Conflict in node names, likely two reticulation entries have different name but point to the same id.");
                     }
                 }
             }
@@ -85,13 +294,16 @@ impl<'a> NewickParseContext<'a> {
         Ok(())
     }
 
-    fn calculate_arrows(&mut self) {
+    /// Builds arrows, `lengths` of Newick nodes becoming weights of the
+    /// arrows into them.
+    fn calculate_arrows(&mut self, lengths: &HashMap<NewickNodeId, f64>) {
         for node in self.graph.nodes() {
             let source_id = *self.node_map.get(&node.id()).unwrap();
             let successors = self.graph.get_children(node.id());
             for successor in successors {
                 let target_id = *self.node_map.get(successor).unwrap();
-                self.arrows.push(ArrowDTO::new(source_id, target_id));
+                let weight = lengths.get(successor).copied();
+                self.arrows.push(ArrowDTO::new(source_id, target_id).with_weight(weight));
             }
         }
     }
@@ -1,28 +1,86 @@
+use core::fmt::{Display, Formatter};
+
 use raf_newick::deserializer::DeserializeError;
 
 use crate::phylo::PhylogeneticNetworkFromError;
 
+/// Number of bytes taken on each side of
+/// [`NewickContentError::position`] into its excerpt.
+const EXCERPT_RADIUS: usize = 16;
+
 #[derive(Debug)]
 pub enum NewickParseError {
-    ContentError(String),
+    ContentError(NewickContentError),
     InputError(std::io::Error),
     Utf8(std::str::Utf8Error),
     PhylogeneticNetworkError(PhylogeneticNetworkFromError),
 }
 
-impl From<DeserializeError> for NewickParseError {
-    fn from(value: DeserializeError) -> Self {
-        match value {
-            DeserializeError::FormatError(text)
-                => Self::ContentError(text),
+/// Malformed or rejected part of Newick input, together with its location.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct NewickContentError {
+    message: String,
+    position: usize,
+    excerpt: String,
+}
+
+impl NewickContentError {
+    /// Creates error at byte `position` of `text`, with excerpt of the
+    /// surrounding bytes.
+    pub(super) fn new(message: String, text: &[u8], position: usize) -> Self {
+        let start = position.saturating_sub(EXCERPT_RADIUS).min(text.len());
+        let end = position.saturating_add(EXCERPT_RADIUS).min(text.len());
+        Self {
+            message: message,
+            position: position,
+            excerpt: String::from_utf8_lossy(&text[start..end]).into_owned(),
+        }
+    }
+
+    /// Converts error of the underlying deserializer, which doesn't report
+    /// location. Position of the last byte it consumed is used instead, so
+    /// for syntax errors it points at, or right after, the offending byte.
+    pub(super) fn from_deserialize_error(error: DeserializeError, text: &[u8])
+        -> NewickParseError
+    {
+        let position = text.len().saturating_sub(1);
+        match error {
+            DeserializeError::FormatError(message)
+                => NewickParseError::ContentError(Self::new(message, text, position)),
             DeserializeError::GraphError(err) => {
-                let msg = format!("Invalid graph: {err:?}");
-                Self::ContentError(msg)
+                let message = format!("Invalid graph: {err:?}");
+                NewickParseError::ContentError(Self::new(message, text, position))
             },
-            DeserializeError::InputError(err) => Self::InputError(err),
-            DeserializeError::Utf8(err) => Self::Utf8(err),
+            DeserializeError::InputError(err) => NewickParseError::InputError(err),
+            DeserializeError::Utf8(err) => NewickParseError::Utf8(err),
         }
     }
+
+    /// Moves the error by `offset` bytes, for entries parsed out of a bigger
+    /// stream.
+    #[must_use]
+    pub(super) fn shifted(mut self, offset: usize) -> Self {
+        self.position += offset;
+        self
+    }
+
+    #[inline(always)]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Byte offset in the input, counted from the beginning of the stream.
+    #[inline(always)]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Up to 16 bytes on each side of [`NewickContentError::position`].
+    /// Invalid UTF-8 sequences, e.g. cut in the middle, are replaced.
+    #[inline(always)]
+    pub fn excerpt(&self) -> &str {
+        &self.excerpt
+    }
 }
 
 impl From<PhylogeneticNetworkFromError> for NewickParseError {
@@ -30,3 +88,45 @@ impl From<PhylogeneticNetworkFromError> for NewickParseError {
         Self::PhylogeneticNetworkError(value)
     }
 }
+
+impl From<std::io::Error> for NewickParseError {
+    fn from(value: std::io::Error) -> Self { Self::InputError(value) }
+}
+
+impl From<std::str::Utf8Error> for NewickParseError {
+    fn from(value: std::str::Utf8Error) -> Self { Self::Utf8(value) }
+}
+
+impl Display for NewickParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            NewickParseError::ContentError(err)
+                => write!(f, "invalid newick content: {err}"),
+            NewickParseError::InputError(_)
+                => f.write_str("failed to read newick input"),
+            NewickParseError::Utf8(_)
+                => f.write_str("newick input is not valid utf-8"),
+            NewickParseError::PhylogeneticNetworkError(_)
+                => f.write_str("newick input is not a valid phylogenetic network"),
+        }
+    }
+}
+
+impl Display for NewickContentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} at byte {}, near {:?}", self.message, self.position, self.excerpt)
+    }
+}
+
+impl std::error::Error for NewickContentError { }
+
+impl std::error::Error for NewickParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NewickParseError::ContentError(_) => None,
+            NewickParseError::InputError(err) => Some(err),
+            NewickParseError::Utf8(err) => Some(err),
+            NewickParseError::PhylogeneticNetworkError(err) => Some(err),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::io::Read;
+
+use super::{
+    parse_newick_with_options,
+    NewickContentError,
+    NewickParseError,
+    NewickParseOk,
+    NewickParseOptions};
+
+const CHUNK_SIZE: usize = 8192;
+
+/// Lazy iterator over `;`-terminated Newick networks of a stream. Returned
+/// by [`parse_newick_forest`].
+///
+/// Only the currently parsed entry is buffered, plus a fixed size chunk of
+/// the underlying stream. Thus the stream is read ahead of the last
+/// yielded entry.
+pub struct NewickForestIterator<'a, TRead: Read> {
+    input: &'a mut TRead,
+    chunk: Box<[u8]>,
+    chunk_length: usize,
+    chunk_position: usize,
+    read_bytes: usize,
+    stop_on_error: bool,
+    finished: bool,
+    options: NewickParseOptions,
+}
+
+/// Parses stream of multiple Newick networks, e.g. a file with one tree per
+/// line. Whitespace and, possibly nested, `[...]` comments between entries
+/// are skipped. Each entry is parsed with [`parse_newick`](super::parse_newick),
+/// unless changed with [`NewickForestIterator::with_options`];
+/// [`NewickParseOk::read_bytes`] is the number of bytes consumed from the
+/// beginning of the stream up to and including the entry's `;`. Positions
+/// of errors are counted from the beginning of the stream as well.
+///
+/// By default parsing continues after a malformed entry, see
+/// [`NewickForestIterator::stop_on_error`]. Errors of the underlying stream
+/// and unterminated trailing entries always end the iteration.
+pub fn parse_newick_forest<TRead: Read>(input: &mut TRead) -> NewickForestIterator<'_, TRead> {
+    NewickForestIterator {
+        input: input,
+        chunk: vec![0; CHUNK_SIZE].into_boxed_slice(),
+        chunk_length: 0,
+        chunk_position: 0,
+        read_bytes: 0,
+        stop_on_error: false,
+        finished: false,
+        options: NewickParseOptions::default(),
+    }
+}
+
+/// Parses all Newick networks of `input`, continuing after malformed
+/// entries. See [`parse_newick_forest`].
+pub fn parse_newick_forest_from_str(input: &str) -> Vec<Result<NewickParseOk, NewickParseError>> {
+    let mut stream = input.as_bytes();
+    parse_newick_forest(&mut stream).collect()
+}
+
+impl<TRead: Read> NewickForestIterator<'_, TRead> {
+    /// Makes the iterator end right after yielding the first error.
+    #[must_use]
+    pub fn stop_on_error(mut self, value: bool) -> Self {
+        self.stop_on_error = value;
+        self
+    }
+
+    /// Parses entries with [`parse_newick_with_options`].
+    #[must_use]
+    pub fn with_options(mut self, options: NewickParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Total number of bytes consumed so far.
+    #[inline(always)]
+    pub fn read_bytes(&self) -> usize {
+        self.read_bytes
+    }
+
+    fn next_byte(&mut self) -> Result<Option<u8>, NewickParseError> {
+        if self.chunk_position == self.chunk_length {
+            self.chunk_length = self.input.read(&mut self.chunk)
+                .map_err(NewickParseError::InputError)?;
+            self.chunk_position = 0;
+            if self.chunk_length == 0 {
+                return Ok(None);
+            }
+        }
+        let byte = self.chunk[self.chunk_position];
+        self.chunk_position += 1;
+        self.read_bytes += 1;
+        Ok(Some(byte))
+    }
+
+    /// Error at the current position, `entry` being the bytes of the current
+    /// entry read so far.
+    fn content_error(&self, message: &str, entry: &[u8]) -> NewickParseError {
+        let error = NewickContentError::new(message.to_owned(), entry, entry.len())
+            .shifted(self.read_bytes - entry.len());
+        NewickParseError::ContentError(error)
+    }
+
+    /// Skips comment after its opening `[`, including nested comments.
+    fn skip_comment(&mut self, entry: Option<&mut Vec<u8>>) -> Result<(), NewickParseError> {
+        let mut entry = entry;
+        let mut depth = 1;
+        loop {
+            let Some(byte) = self.next_byte()? else {
+                let entry = entry.as_deref().map_or(&[][..], Vec::as_slice);
+                return Err(self.content_error("Unexpected end of input, unterminated comment.", entry));
+            };
+            if let Some(entry) = entry.as_mut() {
+                entry.push(byte);
+            }
+            match byte {
+                b'[' => depth += 1,
+                b']' if depth == 1 => return Ok(()),
+                b']' => depth -= 1,
+                _ => { },
+            }
+        }
+    }
+
+    /// Reads bytes of the next entry, including the terminating `;`.
+    /// Returns `None` if only whitespace and comments are left.
+    fn read_entry(&mut self) -> Result<Option<Vec<u8>>, NewickParseError> {
+        let mut entry = Vec::new();
+        loop {
+            match self.next_byte()? {
+                None => return Ok(None),
+                Some(b'[') => self.skip_comment(None)?,
+                Some(byte) if byte.is_ascii_whitespace() => { },
+                Some(byte) => {
+                    entry.push(byte);
+                    break;
+                },
+            }
+        }
+
+        let mut quoted = entry[0] == b'\'';
+        if entry[0] == b';' {
+            return Ok(Some(entry));
+        }
+        loop {
+            let Some(byte) = self.next_byte()? else {
+                return Err(self.content_error("Unexpected end of input, missing ';'.", &entry));
+            };
+            entry.push(byte);
+            match byte {
+                b'\'' => quoted = !quoted,
+                b'[' if !quoted => self.skip_comment(Some(&mut entry))?,
+                b';' if !quoted => return Ok(Some(entry)),
+                _ => { },
+            }
+        }
+    }
+}
+
+impl<TRead: Read> Iterator for NewickForestIterator<'_, TRead> {
+    type Item = Result<NewickParseOk, NewickParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        match self.read_entry() {
+            Ok(None) => {
+                self.finished = true;
+                None
+            },
+            Ok(Some(entry)) => {
+                let offset = self.read_bytes - entry.len();
+                let result = parse_newick_with_options(&mut entry.as_slice(), &self.options)
+                    .map(|ok| NewickParseOk {
+                        read_bytes: self.read_bytes,
+                        ..ok
+                    })
+                    .map_err(|err| match err {
+                        NewickParseError::ContentError(err)
+                            => NewickParseError::ContentError(err.shifted(offset)),
+                        _ => err,
+                    });
+                if result.is_err() && self.stop_on_error {
+                    self.finished = true;
+                }
+                Some(result)
+            },
+            Err(err) => {
+                self.finished = true;
+                Some(Err(err))
+            },
+        }
+    }
+}
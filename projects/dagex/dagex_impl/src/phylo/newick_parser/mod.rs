@@ -3,41 +3,142 @@ use std::io::Read;
 mod error;
 mod ok;
 mod context;
+mod forest;
+mod options;
+mod scanner;
 
 use context::NewickParseContext;
+use scanner::scan_nodes;
 pub use error::*;
 pub use ok::*;
+pub use forest::*;
+pub use options::*;
 
-use raf_newick::deserializer::deserialize;
+use raf_newick::deserializer::{deserialize, DeserializeError};
 
 #[allow(unused_imports)]
 use crate::phylo::PhylogeneticNetwork;
 
-/// Parses Newick formatted stream into [`PhylogeneticNetwork`].
+/// Parses Newick formatted stream into [`PhylogeneticNetwork`], with
+/// default [`NewickParseOptions`]. Possibly nested `[...]` comments are
+/// skipped.
 /// 
 /// # Errors
 /// * [`NewickParseError::ContentError`] if invalid graph
 /// * [`NewickParseError::InputError`] forwarded from underlying stream
 /// * [`NewickParseError::Utf8`] if content is not a valid UTF-8 string
+/// * [`NewickParseError::PhylogeneticNetworkError`] if the graph is not
+///   a valid network
+#[inline(always)]
 pub fn parse_newick<TRead: Read>(input: &mut TRead)
     -> Result<NewickParseOk, NewickParseError>
 {
-    let deserialize_ok = deserialize(input)?;
+    parse_newick_with_options(input, &NewickParseOptions::default())
+}
+
+/// Parses Newick formatted stream into [`PhylogeneticNetwork`], rejecting
+/// content disallowed by `options`.
+/// 
+/// # Errors
+/// Same as [`parse_newick`]. Additionally
+/// [`NewickParseError::ContentError`] pointing at the first offending node
+/// or comment if the content violates `options`, or at the offending
+/// key-value pair of a malformed NHX comment.
+pub fn parse_newick_with_options<TRead: Read>(input: &mut TRead, options: &NewickParseOptions)
+    -> Result<NewickParseOk, NewickParseError>
+{
+    let mut recording = RecordingRead::new(input);
+    let deserialize_ok = match deserialize(&mut recording) {
+        Ok(deserialize_ok) => deserialize_ok,
+        Err(DeserializeError::FormatError(_)) if recording.comment_depth > 0 => {
+            let message = "Unexpected end of input, unterminated comment.".to_owned();
+            let error = NewickContentError::new(message, &recording.buffer, recording.open_comment);
+            return Err(NewickParseError::ContentError(error));
+        },
+        Err(err) => return Err(NewickContentError::from_deserialize_error(err, &recording.buffer)),
+    };
+    let text = &recording.buffer[..deserialize_ok.read_bytes.min(recording.buffer.len())];
+    if let Some(position) = recording.first_comment {
+        if !options.allow_comments() && position < text.len() {
+            let error = NewickContentError::new("Comments are not allowed.".to_owned(), text, position);
+            return Err(NewickParseError::ContentError(error));
+        }
+    }
+    let scanned = scan_nodes(text)?;
     let graph = &deserialize_ok.graph;
-    let ctx = NewickParseContext::new(graph);
-    let network = ctx.parse()?;
+    let ctx = NewickParseContext::new(graph, text, *options);
+    let data = ctx.parse(&scanned)?;
     Ok(NewickParseOk {
-        network: network,
+        network: data.network,
+        branch_lengths: data.branch_lengths,
+        internal_labels: data.internal_labels,
+        annotations: data.annotations,
         read_bytes: deserialize_ok.read_bytes,
     })
 }
 
+/// Keeps copy of all bytes read from `input`. Passes `[...]` comments on
+/// replaced with spaces, so that the deserializer never sees them while
+/// byte positions stay intact.
+struct RecordingRead<'a, TRead: Read> {
+    input: &'a mut TRead,
+    buffer: Vec<u8>,
+    quoted: bool,
+    comment_depth: usize,
+    /// Position of the currently open outermost `[`.
+    open_comment: usize,
+    first_comment: Option<usize>,
+}
+
+impl<'a, TRead: Read> RecordingRead<'a, TRead> {
+    fn new(input: &'a mut TRead) -> Self {
+        Self {
+            input: input,
+            buffer: Vec::new(),
+            quoted: false,
+            comment_depth: 0,
+            open_comment: 0,
+            first_comment: None,
+        }
+    }
+}
+
+impl<TRead: Read> Read for RecordingRead<'_, TRead> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.input.read(buf)?;
+        for byte in &mut buf[..read] {
+            let position = self.buffer.len();
+            self.buffer.push(*byte);
+            if self.comment_depth > 0 {
+                match *byte {
+                    b'[' => self.comment_depth += 1,
+                    b']' => self.comment_depth -= 1,
+                    _ => { },
+                }
+                *byte = b' ';
+            }
+            else if *byte == b'\'' {
+                self.quoted = !self.quoted;
+            }
+            else if *byte == b'[' && !self.quoted {
+                self.comment_depth = 1;
+                self.open_comment = position;
+                self.first_comment.get_or_insert(position);
+                *byte = b' ';
+            }
+        }
+        Ok(read)
+    }
+}
+
 /// Parses Newick formatted `&str` into [`PhylogeneticNetwork`].
 /// 
 /// # Errors
 /// * [`NewickParseError::ContentError`] if invalid graph
 /// * [`NewickParseError::InputError`] forwarded from underlying stream
 /// * [`NewickParseError::Utf8`] if content is not a valid UTF-8 string
+/// * [`NewickParseError::PhylogeneticNetworkError`] if the graph is not
+///   a valid network
 #[inline(always)]
 pub fn parse_newick_from_str(input: &str)
     -> Result<NewickParseOk, NewickParseError>
@@ -45,3 +146,16 @@ pub fn parse_newick_from_str(input: &str)
     let mut stream = input.as_bytes();
     parse_newick(&mut stream)
 }
+
+/// Parses Newick formatted `&str` into [`PhylogeneticNetwork`], see
+/// [`parse_newick_with_options`].
+/// 
+/// # Errors
+/// Same as [`parse_newick_with_options`].
+#[inline(always)]
+pub fn parse_newick_from_str_with_options(input: &str, options: &NewickParseOptions)
+    -> Result<NewickParseOk, NewickParseError>
+{
+    let mut stream = input.as_bytes();
+    parse_newick_with_options(&mut stream, options)
+}
@@ -1,7 +1,41 @@
-use crate::phylo::PhylogeneticNetwork;
+use std::collections::HashMap;
+
+use crate::{
+    core::{Node, NodeMap},
+    phylo::{NewickAnnotations, PhylogeneticNetwork},
+    raf_array::immutable_string::ImmutableString};
 
 #[derive(Debug)]
 pub struct NewickParseOk {
     pub network: PhylogeneticNetwork,
+
+    /// Lengths of branches leading to nodes, i.e. `:length` suffixes. For
+    /// reticulation nodes the first occurrence with a length wins. Each
+    /// length is also the weight of its arrow in the network, see
+    /// [`DirectedGraph::arrow_weight`](crate::core::DirectedGraph::arrow_weight),
+    /// so lengths of all reticulation occurrences are kept there.
+    pub branch_lengths: NodeMap<f64>,
+
+    /// Names of non-leaf nodes. These are taxa of the network as well,
+    /// like names of leaves.
+    pub internal_labels: NodeMap<ImmutableString>,
+
+    /// Key-value pairs of `[&&NHX:key=value:...]` comments, by node. Empty
+    /// unless [`NewickParseOptions::parse_nhx`](super::NewickParseOptions::parse_nhx)
+    /// is set. For reticulation nodes pairs of all occurrences are merged,
+    /// the first occurrence of a key wins.
+    pub annotations: HashMap<Node, HashMap<ImmutableString, ImmutableString>>,
+
     pub read_bytes: usize,
 }
+
+impl NewickParseOk {
+    /// Branch lengths and internal labels for
+    /// [`write_newick_annotated`](crate::phylo::write_newick_annotated).
+    pub fn newick_annotations(&self) -> NewickAnnotations<'_> {
+        NewickAnnotations {
+            branch_lengths: Some(&self.branch_lengths),
+            internal_labels: Some(&self.internal_labels),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+/// Strictness of [`parse_newick_with_options`](super::parse_newick_with_options).
+/// Default values match [`parse_newick`](super::parse_newick), i.e. only
+/// non-binary networks are rejected.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct NewickParseOptions {
+    allow_duplicate_taxa: bool,
+    allow_unlabeled_leaves: bool,
+    allow_repeated_hybrids: bool,
+    require_binary: bool,
+    allow_comments: bool,
+    parse_nhx: bool,
+}
+
+impl Default for NewickParseOptions {
+    fn default() -> Self {
+        Self {
+            allow_duplicate_taxa: true,
+            allow_unlabeled_leaves: true,
+            allow_repeated_hybrids: true,
+            require_binary: true,
+            allow_comments: true,
+            parse_nhx: false,
+        }
+    }
+}
+
+impl NewickParseOptions {
+    /// Whether multiple leaves may have the same name. `true` by default.
+    #[inline(always)]
+    pub fn allow_duplicate_taxa(&self) -> bool {
+        self.allow_duplicate_taxa
+    }
+
+    /// Whether leaves may have no name, and thus no taxon. `true` by
+    /// default.
+    #[inline(always)]
+    pub fn allow_unlabeled_leaves(&self) -> bool {
+        self.allow_unlabeled_leaves
+    }
+
+    /// Whether a hybrid label, e.g. `#H1`, may occur more than twice. `true`
+    /// by default, note however that the resulting reticulation is not
+    /// binary then.
+    #[inline(always)]
+    pub fn allow_repeated_hybrids(&self) -> bool {
+        self.allow_repeated_hybrids
+    }
+
+    /// Whether non-binary networks are rejected with
+    /// [`PhylogeneticNetworkFromError::NotBinary`](crate::phylo::PhylogeneticNetworkFromError::NotBinary).
+    /// Otherwise they become
+    /// [`PhylogeneticNetworkKind::Multifurcating`](crate::phylo::PhylogeneticNetworkKind::Multifurcating).
+    /// `true` by default.
+    #[inline(always)]
+    pub fn require_binary(&self) -> bool {
+        self.require_binary
+    }
+
+    /// Whether `[...]` comments are skipped. Otherwise the first comment is
+    /// rejected. Comments may be nested, brackets inside quoted labels are
+    /// not comments. `true` by default.
+    #[inline(always)]
+    pub fn allow_comments(&self) -> bool {
+        self.allow_comments
+    }
+
+    /// Whether New Hampshire eXtended comments, e.g. `[&&NHX:S=HUMAN:D=Y]`,
+    /// are parsed into
+    /// [`NewickParseOk::annotations`](super::NewickParseOk::annotations).
+    /// Other comments are skipped regardless. `false` by default.
+    #[inline(always)]
+    pub fn parse_nhx(&self) -> bool {
+        self.parse_nhx
+    }
+
+    #[must_use]
+    pub fn with_allow_duplicate_taxa(mut self, value: bool) -> Self {
+        self.allow_duplicate_taxa = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_allow_unlabeled_leaves(mut self, value: bool) -> Self {
+        self.allow_unlabeled_leaves = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_allow_repeated_hybrids(mut self, value: bool) -> Self {
+        self.allow_repeated_hybrids = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_require_binary(mut self, value: bool) -> Self {
+        self.require_binary = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_allow_comments(mut self, value: bool) -> Self {
+        self.allow_comments = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_parse_nhx(mut self, value: bool) -> Self {
+        self.parse_nhx = value;
+        self
+    }
+}
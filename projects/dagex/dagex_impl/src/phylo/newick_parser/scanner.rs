@@ -0,0 +1,168 @@
+use core::ops::Range;
+
+use super::{NewickContentError, NewickParseError};
+
+/// Node occurrence found by [`scan_nodes`].
+#[derive(Clone, Default)]
+pub(super) struct ScannedNode {
+    /// Byte offset where the node's label starts, or would start if
+    /// unlabeled. For inner nodes it is right after the closing `)`.
+    pub position: usize,
+
+    /// Length of the branch leading to the node, i.e. `:length` suffix.
+    pub length: Option<f64>,
+
+    /// Contents of comments around the node's label and length, without
+    /// the outermost brackets. For leaves these start right after the
+    /// preceding `(` or `,`, for inner nodes after the closing `)`.
+    pub comments: Vec<Range<usize>>,
+}
+
+/// Extracts label positions and branch lengths of a single, already
+/// validated, Newick entry. Returned nodes are ordered by preorder of the
+/// Newick tree, i.e. a node comes before its children and children keep
+/// their textual order. Reticulation occurrences are separate entries.
+/// Comments preceding a `(` don't belong to any node.
+///
+/// # Errors
+/// [`NewickParseError::ContentError`] if a length is not a valid number.
+pub(super) fn scan_nodes(text: &[u8])
+    -> Result<Vec<ScannedNode>, NewickParseError>
+{
+    let mut scanner = Scanner { text: text, position: 0, comments: Vec::new() };
+    let mut nodes = Vec::<ScannedNode>::new();
+    let mut open = Vec::<usize>::new();
+    loop {
+        scanner.skip_insignificant();
+        if scanner.peek() == Some(b'(') {
+            scanner.position += 1;
+            scanner.comments.clear();
+            open.push(nodes.len());
+            nodes.push(ScannedNode::default());
+            continue;
+        }
+
+        nodes.push(ScannedNode::default());
+        let mut current = nodes.len() - 1;
+        loop {
+            let suffix = scanner.scan_suffix()?;
+            scanner.skip_insignificant();
+            nodes[current] = ScannedNode {
+                comments: std::mem::take(&mut scanner.comments),
+                ..suffix
+            };
+            match scanner.peek() {
+                Some(b')') => {
+                    scanner.position += 1;
+                    let Some(parent) = open.pop() else {
+                        return Ok(nodes);
+                    };
+                    current = parent;
+                },
+                Some(b',') => {
+                    scanner.position += 1;
+                    break;
+                },
+                _ => return Ok(nodes),
+            }
+        }
+    }
+}
+
+struct Scanner<'a> {
+    text: &'a [u8],
+    position: usize,
+    /// Comments skipped since the last node was completed.
+    comments: Vec<Range<usize>>,
+}
+
+impl Scanner<'_> {
+    #[inline(always)]
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.position).copied()
+    }
+
+    /// Skips whitespace and, possibly nested, `[...]` comments.
+    fn skip_insignificant(&mut self) {
+        while let Some(byte) = self.peek() {
+            if byte.is_ascii_whitespace() {
+                self.position += 1;
+            }
+            else if byte == b'[' {
+                self.position += 1;
+                let start = self.position;
+                let mut depth = 1;
+                while let Some(byte) = self.peek() {
+                    self.position += 1;
+                    match byte {
+                        b'[' => depth += 1,
+                        b']' => depth -= 1,
+                        _ => { },
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                let end = if depth == 0 { self.position - 1 } else { self.position };
+                self.comments.push(start..end);
+            }
+            else
+            {
+                break;
+            }
+        }
+    }
+
+    /// Skips node's name and reticulation marker, then reads its length.
+    fn scan_suffix(&mut self) -> Result<ScannedNode, NewickParseError> {
+        self.skip_insignificant();
+        let position = self.position;
+        if self.peek() == Some(b'\'') {
+            self.position += 1;
+            while let Some(byte) = self.peek() {
+                self.position += 1;
+                if byte == b'\'' {
+                    if self.peek() == Some(b'\'') {
+                        self.position += 1;
+                    }
+                    else
+                    {
+                        break;
+                    }
+                }
+            }
+        }
+        self.skip_token();
+        self.skip_insignificant();
+        if self.peek() != Some(b':') {
+            return Ok(ScannedNode { position: position, length: None, comments: Vec::new() });
+        }
+
+        self.position += 1;
+        self.skip_insignificant();
+        let start = self.position;
+        self.skip_token();
+        let token = String::from_utf8_lossy(&self.text[start..self.position]);
+        if token.is_empty() {
+            return Ok(ScannedNode { position: position, length: None, comments: Vec::new() });
+        }
+        match token.parse::<f64>() {
+            Ok(value) if value.is_finite()
+                => Ok(ScannedNode { position: position, length: Some(value), comments: Vec::new() }),
+            _ => {
+                let message = format!("Invalid branch length '{token}'.");
+                let error = NewickContentError::new(message, self.text, start);
+                Err(NewickParseError::ContentError(error))
+            },
+        }
+    }
+
+    fn skip_token(&mut self) {
+        while let Some(byte) = self.peek() {
+            if b"(),:;[".contains(&byte) || byte.is_ascii_whitespace() {
+                break;
+            }
+            self.position += 1;
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use core::fmt::{Display, Formatter};
+use std::{collections::HashMap, io::Write};
+
+use crate::{core::{Node, NodeMap}, raf_array::immutable_string::ImmutableString};
+
+use super::PhylogeneticNetwork;
+
+#[derive(Debug)]
+pub struct NewickWriteOk {
+    pub written_bytes: usize,
+}
+
+/// Optional per node data written along with the network by
+/// [`write_newick_annotated`]. Maps have to be created for the graph of
+/// the written network.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NewickAnnotations<'a> {
+    /// Written as `:length` suffixes, with the shortest representation
+    /// that parses back to the same value.
+    pub branch_lengths: Option<&'a NodeMap<f64>>,
+
+    /// Names of nodes without a taxon, typically internal nodes.
+    pub internal_labels: Option<&'a NodeMap<ImmutableString>>,
+}
+
+#[derive(Debug)]
+pub enum NewickWriteError {
+    /// Forwarded error of the underlying stream.
+    OutputError(std::io::Error),
+}
+
+impl From<std::io::Error> for NewickWriteError {
+    fn from(value: std::io::Error) -> Self { Self::OutputError(value) }
+}
+
+/// Writes [`PhylogeneticNetwork`] in Newick format, terminated with `;`.
+/// Nodes of in-degree at least 2 are written with `#H<n>` hybrid labels:
+/// the subnetwork below such node is written once, under its predecessor
+/// with the lowest id, and other occurrences only repeat the label. Taxa
+/// containing special characters or whitespace are single-quoted.
+///
+/// Children are ordered so that for networks produced by
+/// [`parse_newick`](super::parse_newick) parsing the output again yields an
+/// equal network.
+///
+/// # Errors
+/// [`NewickWriteError::OutputError`] forwarded from `output`.
+#[inline(always)]
+pub fn write_newick<TWrite: Write>(network: &PhylogeneticNetwork, output: &mut TWrite)
+    -> Result<NewickWriteOk, NewickWriteError>
+{
+    write_newick_annotated(network, NewickAnnotations::default(), output)
+}
+
+/// Writes [`PhylogeneticNetwork`] in Newick format together with
+/// `annotations`, see [`write_newick`]. Branch length of a reticulation
+/// node is written only at the occurrence defining its subnetwork.
+///
+/// Together with [`NewickParseOk::newick_annotations`](super::NewickParseOk::newick_annotations)
+/// parsing the output again yields equal network, branch lengths and
+/// internal labels.
+///
+/// # Errors
+/// [`NewickWriteError::OutputError`] forwarded from `output`.
+pub fn write_newick_annotated<TWrite: Write>(
+    network: &PhylogeneticNetwork,
+    annotations: NewickAnnotations<'_>,
+    output: &mut TWrite)
+    -> Result<NewickWriteOk, NewickWriteError>
+{
+    if let Some(branch_lengths) = annotations.branch_lengths {
+        branch_lengths.debug_check_graph(network.graph());
+    }
+    if let Some(internal_labels) = annotations.internal_labels {
+        internal_labels.debug_check_graph(network.graph());
+    }
+    let mut writer = CountingWriter { output: output, written_bytes: 0 };
+    let context = WriterContext::new(network, annotations);
+    context.write(&mut writer)?;
+    Ok(NewickWriteOk { written_bytes: writer.written_bytes })
+}
+
+/// Returns [`PhylogeneticNetwork`] in Newick format. See [`write_newick`].
+///
+/// # Panics
+/// Never, writing to `Vec<u8>` cannot fail.
+#[inline(always)]
+pub fn to_newick_string(network: &PhylogeneticNetwork) -> String {
+    to_newick_string_annotated(network, NewickAnnotations::default())
+}
+
+/// Returns [`PhylogeneticNetwork`] in Newick format together with
+/// `annotations`. See [`write_newick_annotated`].
+///
+/// # Panics
+/// Never, writing to `Vec<u8>` cannot fail.
+pub fn to_newick_string_annotated(
+    network: &PhylogeneticNetwork,
+    annotations: NewickAnnotations<'_>) -> String
+{
+    let mut buffer = Vec::<u8>::new();
+    write_newick_annotated(network, annotations, &mut buffer).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+struct CountingWriter<'a, TWrite: Write> {
+    output: &'a mut TWrite,
+    written_bytes: usize,
+}
+
+impl<TWrite: Write> CountingWriter<'_, TWrite> {
+    fn write_str(&mut self, text: &str) -> std::io::Result<()> {
+        self.output.write_all(text.as_bytes())?;
+        self.written_bytes += text.len();
+        Ok(())
+    }
+}
+
+enum Action {
+    Enter(Node),
+    Text(&'static str),
+    Label(Node),
+    Length(Node),
+}
+
+struct WriterContext<'a> {
+    network: &'a PhylogeneticNetwork,
+    annotations: NewickAnnotations<'a>,
+    hybrid_labels: HashMap<Node, usize>,
+    defining_parents: HashMap<Node, Node>,
+    keys: Vec<Option<i32>>,
+}
+
+impl<'a> WriterContext<'a> {
+    fn new(network: &'a PhylogeneticNetwork, annotations: NewickAnnotations<'a>) -> Self {
+        let graph = network.graph();
+        let mut hybrid_labels = HashMap::new();
+        let mut defining_parents = HashMap::new();
+        for node in graph.iter_nodes() {
+            let predecessors = graph.get_predecessors(node);
+            if predecessors.len() >= 2 {
+                hybrid_labels.insert(node, hybrid_labels.len() + 1);
+                let parent = predecessors.iter().min_by_key(|n| n.id()).unwrap();
+                defining_parents.insert(node, *parent);
+            }
+        }
+
+        let mut context = Self {
+            network: network,
+            annotations: annotations,
+            hybrid_labels: hybrid_labels,
+            defining_parents: defining_parents,
+            keys: Vec::new(),
+        };
+        context.calculate_keys();
+        context
+    }
+
+    /// Key of a node is the lowest id of a non-hybrid node written inside
+    /// its subtree. Parsers number nodes in the order of appearance (either
+    /// pre- or post-order) with hybrid nodes first, so ordering children by
+    /// keys reproduces ids of a parsed network.
+    #[allow(clippy::cast_sign_loss)]
+    fn calculate_keys(&mut self) {
+        let graph = self.network.graph();
+        let mut keys = vec![None; graph.number_of_nodes() as usize];
+        let order: Vec<Node> = graph.iter_topological()
+            .map(Iterator::collect)
+            .unwrap_or_default();
+        for node in order.into_iter().rev() {
+            let mut key = if self.hybrid_labels.contains_key(&node) {
+                None
+            }
+            else
+            {
+                Some(node.id())
+            };
+            for child in graph.get_successors(node) {
+                if let Some(child_key) = self.child_key(&keys, node, *child) {
+                    key = Some(key.map_or(child_key, |k: i32| k.min(child_key)));
+                }
+            }
+            keys[node.id() as usize] = key;
+        }
+        self.keys = keys;
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn child_key(&self, keys: &[Option<i32>], parent: Node, child: Node) -> Option<i32> {
+        if self.is_defining(parent, child) {
+            keys[child.id() as usize]
+        }
+        else
+        {
+            None
+        }
+    }
+
+    #[inline(always)]
+    fn is_defining(&self, parent: Node, child: Node) -> bool {
+        self.defining_parents.get(&child).map_or(true, |p| *p == parent)
+    }
+
+    fn write<TWrite: Write>(&self, writer: &mut CountingWriter<'_, TWrite>)
+        -> std::io::Result<()>
+    {
+        let graph = self.network.graph();
+        let mut stack = vec![Action::Text(";"), Action::Enter(self.network.root())];
+        while let Some(action) = stack.pop() {
+            match action {
+                Action::Text(text) => writer.write_str(text)?,
+                Action::Label(node) => self.write_label(node, writer)?,
+                Action::Length(node) => self.write_length(node, writer)?,
+                Action::Enter(node) => {
+                    stack.push(Action::Length(node));
+                    stack.push(Action::Label(node));
+                    let mut children: Vec<(Option<i32>, Node)> = graph.get_successors(node)
+                        .iter()
+                        .map(|child| (self.child_key(&self.keys, node, *child), *child))
+                        .collect();
+                    if children.is_empty() {
+                        continue;
+                    }
+                    children.sort_by_key(|(key, child)| (key.is_none(), *key, child.id()));
+                    stack.push(Action::Text(")"));
+                    for (idx, (_, child)) in children.iter().enumerate().rev() {
+                        if self.is_defining(node, *child) {
+                            stack.push(Action::Enter(*child));
+                        }
+                        else
+                        {
+                            stack.push(Action::Label(*child));
+                        }
+                        if idx > 0 {
+                            stack.push(Action::Text(","));
+                        }
+                    }
+                    stack.push(Action::Text("("));
+                },
+            }
+        }
+        Ok(())
+    }
+
+    fn write_label<TWrite: Write>(&self, node: Node, writer: &mut CountingWriter<'_, TWrite>)
+        -> std::io::Result<()>
+    {
+        if let Some(taxon) = self.network.taxa().get(&node) {
+            writer.write_str(&quote_label(taxon.value().as_str()))?;
+        }
+        else if let Some(label) = self.annotations.internal_labels.and_then(|labels| labels.get(self.network.graph(), node)) {
+            writer.write_str(&quote_label(label.as_str()))?;
+        }
+        if let Some(hybrid) = self.hybrid_labels.get(&node) {
+            writer.write_str(&format!("#H{hybrid}"))?;
+        }
+        Ok(())
+    }
+
+    fn write_length<TWrite: Write>(&self, node: Node, writer: &mut CountingWriter<'_, TWrite>)
+        -> std::io::Result<()>
+    {
+        if let Some(length) = self.annotations.branch_lengths.and_then(|lengths| lengths.get(self.network.graph(), node)) {
+            writer.write_str(&format!(":{length}"))?;
+        }
+        Ok(())
+    }
+}
+
+fn quote_label(label: &str) -> String {
+    let needs_quotes = label.chars()
+        .any(|c| c.is_whitespace() || "()[],:;#'".contains(c));
+    if needs_quotes {
+        format!("'{}'", label.replace('\'', "''"))
+    }
+    else
+    {
+        label.to_owned()
+    }
+}
+
+impl Display for NewickWriteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            NewickWriteError::OutputError(_) => f.write_str("failed to write newick output"),
+        }
+    }
+}
+
+impl std::error::Error for NewickWriteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NewickWriteError::OutputError(err) => Some(err),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use core::fmt::{Display, Formatter};
+use std::collections::{HashMap, HashSet};
+
+use raf_readonly::readonly;
+
+use crate::raf_array::immutable_string::ImmutableString;
+
+use super::{
+    restrict_network,
+    GenesOverSpecies,
+    GenesOverSpeciesNewError,
+    PhylogeneticNetwork,
+    PhylogeneticNetworkDTO,
+    PhylogeneticNetworkFromError,
+    Taxon};
+
+/// Ordered, non-empty collection of [`PhylogeneticNetwork`]s sharing a
+/// common taxa universe, e.g. trees parsed from a single file.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PhylogeneticForest {
+    networks: Vec<PhylogeneticNetwork>,
+    taxa_index: HashMap<Taxon, Vec<usize>>,
+    universe: HashSet<Taxon>,
+}
+
+#[derive(Debug)]
+pub enum PhylogeneticForestNewError {
+    /// Collection of networks is empty. This is not allowed.
+    EmptyForest,
+
+    /// Network under given index has taxon outside of the passed taxa
+    /// universe.
+    IncompatibleTaxa(usize),
+
+    /// Forwarded error of member network construction, together with its
+    /// index.
+    NetworkError(usize, PhylogeneticNetworkFromError),
+}
+
+#[derive(Debug)]
+pub enum PhylogeneticForestRestrictionError {
+    /// Networks don't have any taxon in common.
+    EmptyCommonTaxa,
+}
+
+#[readonly]
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct PhylogeneticForestDTO {
+    pub networks: Vec<PhylogeneticNetworkDTO>,
+
+    /// Taxa universe shared by all networks, ordered.
+    pub taxa: Vec<ImmutableString>,
+}
+
+impl PhylogeneticForest {
+    /// Creates new [`PhylogeneticForest`] out of `networks`. The taxa
+    /// universe is the union of all networks' taxa.
+    ///
+    /// # Errors
+    /// [`PhylogeneticForestNewError::EmptyForest`] only.
+    pub fn new(networks: Vec<PhylogeneticNetwork>)
+        -> Result<Self, PhylogeneticForestNewError>
+    {
+        if networks.is_empty() {
+            return Err(PhylogeneticForestNewError::EmptyForest);
+        }
+
+        Ok(Self::build(networks, None))
+    }
+
+    /// Indexes taxa of `networks`. `universe` defaults to the union of all
+    /// networks' taxa.
+    fn build(networks: Vec<PhylogeneticNetwork>, universe: Option<HashSet<Taxon>>) -> Self {
+        let mut taxa_index = HashMap::<Taxon, Vec<usize>>::new();
+        for (idx, network) in networks.iter().enumerate() {
+            let taxa: HashSet<&Taxon> = network.taxa().values().collect();
+            for taxon in taxa {
+                taxa_index.entry(taxon.clone()).or_default().push(idx);
+            }
+        }
+
+        let universe = universe.unwrap_or_else(|| taxa_index.keys().cloned().collect());
+        Self { networks, taxa_index, universe }
+    }
+
+    /// Creates new [`PhylogeneticForest`] with `taxa` as the taxa universe,
+    /// verifying that each network's taxa are a subset of it.
+    ///
+    /// # Errors
+    /// For concrete errors see [`PhylogeneticForestNewError`] docs.
+    pub fn new_with_taxa(networks: Vec<PhylogeneticNetwork>, taxa: &HashSet<Taxon>)
+        -> Result<Self, PhylogeneticForestNewError>
+    {
+        for (idx, network) in networks.iter().enumerate() {
+            if network.taxa().values().any(|taxon| !taxa.contains(taxon)) {
+                return Err(PhylogeneticForestNewError::IncompatibleTaxa(idx));
+            }
+        }
+        if networks.is_empty() {
+            return Err(PhylogeneticForestNewError::EmptyForest);
+        }
+
+        Ok(Self::build(networks, Some(taxa.clone())))
+    }
+
+    /// Constructs [`PhylogeneticForest`] out of [`PhylogeneticForestDTO`],
+    /// verifying member networks against the DTO's taxa universe.
+    ///
+    /// # Errors
+    /// For concrete errors see [`PhylogeneticForestNewError`] docs.
+    pub fn from_dto(dto: &PhylogeneticForestDTO)
+        -> Result<Self, PhylogeneticForestNewError>
+    {
+        let mut networks = Vec::with_capacity(dto.networks().len());
+        for (idx, network_dto) in dto.networks().iter().enumerate() {
+            let network = PhylogeneticNetwork::from_dto(network_dto)
+                .map_err(|err| PhylogeneticForestNewError::NetworkError(idx, err))?;
+            networks.push(network);
+        }
+        let taxa: HashSet<Taxon> = dto.taxa()
+            .iter()
+            .map(|imm| Taxon::from(imm.clone()))
+            .collect();
+        Self::new_with_taxa(networks, &taxa)
+    }
+
+    /// Converts [`PhylogeneticForest`] into [`PhylogeneticForestDTO`],
+    /// keeping the taxa universe, see [`PhylogeneticForest::taxa_universe`].
+    pub fn into_dto(&self) -> PhylogeneticForestDTO {
+        let networks = self.networks.iter()
+            .map(PhylogeneticNetwork::into_dto)
+            .collect();
+        let mut taxa: Vec<ImmutableString> = self.universe
+            .iter()
+            .map(|taxon| taxon.value().clone())
+            .collect();
+        taxa.sort_by(|left, right| left.as_str().cmp(right.as_str()));
+        PhylogeneticForestDTO::new(networks, taxa)
+    }
+
+    #[inline(always)]
+    pub fn networks(&self) -> &[PhylogeneticNetwork] {
+        &self.networks
+    }
+
+    #[inline(always)]
+    pub fn into_networks(self) -> Vec<PhylogeneticNetwork> {
+        self.networks
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.networks.len()
+    }
+
+    /// Always `false`, forest cannot be empty.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.networks.is_empty()
+    }
+
+    /// Iterates over the union of all networks' taxa.
+    pub fn taxa(&self) -> impl Iterator<Item=&Taxon> {
+        self.taxa_index.keys()
+    }
+
+    /// Taxa universe of the forest. Passed to
+    /// [`PhylogeneticForest::new_with_taxa`], and the union of all networks'
+    /// taxa otherwise. May contain taxa absent from all networks.
+    #[inline(always)]
+    pub fn taxa_universe(&self) -> &HashSet<Taxon> {
+        &self.universe
+    }
+
+    /// Returns taxa present in every network.
+    pub fn common_taxa(&self) -> HashSet<Taxon> {
+        self.taxa_index.iter()
+            .filter(|kvp| kvp.1.len() == self.networks.len())
+            .map(|kvp| kvp.0.clone())
+            .collect()
+    }
+
+    /// Iterates over networks containing `taxon`, in forest order.
+    pub fn networks_with_taxon<'a>(&'a self, taxon: &Taxon)
+        -> impl Iterator<Item=&'a PhylogeneticNetwork> + 'a
+    {
+        let indexes: &[usize] = self.taxa_index
+            .get(taxon)
+            .map_or(&[], Vec::as_slice);
+        indexes.iter().map(|idx| &self.networks[*idx])
+    }
+
+    /// Creates new forest with each network restricted to
+    /// [`PhylogeneticForest::common_taxa`]. Leaves outside of common taxa
+    /// are pruned and nodes that became redundant are suppressed.
+    ///
+    /// # Errors
+    /// For concrete errors see [`PhylogeneticForestRestrictionError`] docs.
+    pub fn restrict_all_to_common_taxa(&self)
+        -> Result<Self, PhylogeneticForestRestrictionError>
+    {
+        let common = self.common_taxa();
+        let mut networks = Vec::with_capacity(self.networks.len());
+        for network in &self.networks {
+            let restricted = restrict_network(network, &common)
+                .ok_or(PhylogeneticForestRestrictionError::EmptyCommonTaxa)?;
+            networks.push(restricted);
+        }
+        Ok(Self::build(networks, None))
+    }
+
+    /// Converts forest into [`GenesOverSpecies`], with all networks being
+    /// gene networks over `species_network`.
+    ///
+    /// # Errors
+    /// Forwarded from [`GenesOverSpecies::new`].
+    pub fn into_genes_over_species(self, species_network: PhylogeneticNetwork)
+        -> Result<GenesOverSpecies, GenesOverSpeciesNewError>
+    {
+        GenesOverSpecies::new(self.networks, species_network)
+    }
+}
+
+impl Display for PhylogeneticForestNewError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PhylogeneticForestNewError::EmptyForest
+                => f.write_str("phylogenetic forest has no networks"),
+            PhylogeneticForestNewError::IncompatibleTaxa(idx)
+                => write!(f, "network {idx} has taxa outside of the forest taxa"),
+            PhylogeneticForestNewError::NetworkError(idx, _)
+                => write!(f, "network {idx} is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for PhylogeneticForestNewError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PhylogeneticForestNewError::NetworkError(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for PhylogeneticForestRestrictionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PhylogeneticForestRestrictionError::EmptyCommonTaxa
+                => f.write_str("networks have no taxa in common"),
+        }
+    }
+}
+
+impl std::error::Error for PhylogeneticForestRestrictionError { }
@@ -1,11 +1,33 @@
-use core::fmt::{Debug, Formatter};
+use core::fmt::{Debug, Display, Formatter};
 use core::hash::{Hash, Hasher};
+use core::sync::atomic::{AtomicBool, Ordering};
 use std::collections::HashMap;
 
-use crate::core::{DirectedGraph, DirectedGraphFromError, Node};
-use crate::create_u32_hasher;
+use smallvec::SmallVec;
 
-use super::{PhylogeneticNetworkDTO, PhylogeneticNetworkId, Taxon};
+use crate::core::{
+    find_occurrences,
+    DirectedGraph,
+    DirectedGraphFromError,
+    GraphAnomaly,
+    GraphReport,
+    Node,
+    DEFAULT_EDGE_LIST_LIMIT};
+use crate::create_u32_hasher;
+use crate::raf_array::immutable_string::ImmutableString;
+
+use super::{
+    phylogenetic_network_dto::write_taxa,
+    ArrowKind,
+    NetworkStatistics,
+    PhylogeneticNetworkDTO,
+    PhylogeneticNetworkKind,
+    PhylogeneticNetworkOptions,
+    PhylogeneticNetworkProperties,
+    PhylogeneticNetworkId,
+    Taxon,
+    TaxonError,
+    TaxonPolicy};
 
 /// Represents phylogenetic network, which is a directed graph
 /// with additional labels (taxons) on leaves.
@@ -14,6 +36,14 @@ pub struct PhylogeneticNetwork {
     taxa: HashMap<Node, Taxon>,
     id: PhylogeneticNetworkId,
     hash_value: u32,
+    statistics: NetworkStatistics,
+    properties: PhylogeneticNetworkProperties,
+    kind: PhylogeneticNetworkKind,
+    taxa_index: HashMap<Taxon, SmallVec<[Node; 1]>>,
+
+    /// Whether `id` is bound to the network, see
+    /// [`PhylogeneticNetwork::into_dto_with_id`]. Released on drop.
+    id_bound: AtomicBool,
 }
 
 
@@ -25,24 +55,52 @@ pub enum PhylogeneticNetworkFromError {
     /// Passed graph is not rooted. Returns passed value.
     NotRooted,
 
-    /// Passed graph is not binary. Returns passed value.
+    /// Passed graph is not binary, while
+    /// [`PhylogeneticNetworkOptions::allow_multifurcations`] is disabled.
     NotBinary,
 
+    /// Taxon assigned to a node which is not a leaf of the graph, while
+    /// [`PhylogeneticNetworkOptions::allow_internal_taxa`] is disabled.
+    /// Holds the node of the lowest id among such.
+    TaxonNotOnLeaf(Node),
+
+    /// Taxon rejected by [`PhylogeneticNetworkOptions::taxon_policy`].
+    /// Holds the node of the lowest id among such, and the error.
+    InvalidTaxon(Node, TaxonError),
+
     /// Forwarded internal error of graph construction.
     GraphError(DirectedGraphFromError),
+
+    /// Explicit [`PhylogeneticNetworkDTO::id`] is out of range, see
+    /// [`PhylogeneticNetworkId::from_explicit`], or is bound to a different
+    /// live network, see [`PhylogeneticNetwork::into_dto_with_id`]. Holds
+    /// the id.
+    InvalidId(i32),
 }
 
 impl From<DirectedGraphFromError> for PhylogeneticNetworkFromError {
     fn from(value: DirectedGraphFromError) -> Self { Self::GraphError(value) }
 }
 
+impl TryFrom<PhylogeneticNetworkDTO> for PhylogeneticNetwork {
+    type Error = PhylogeneticNetworkFromError;
+
+    /// Validates `value`, see [`PhylogeneticNetwork::from_dto`].
+    fn try_from(value: PhylogeneticNetworkDTO) -> Result<Self, Self::Error> {
+        Self::from_dto(&value)
+    }
+}
+
 
 impl PhylogeneticNetwork {
     /// Constructs [`PhylogeneticNetwork`] directly.
     /// 
     /// # Safety
     /// This method is unsafe since it doesn't verify invariants:
-    /// * `graph` has to be acyclic, rooted and binary.
+    /// * `graph` has to be acyclic and rooted. If it is not binary, the
+    ///   network is [`PhylogeneticNetworkKind::Multifurcating`], which
+    ///   normally happens only on explicit request, see
+    ///   [`PhylogeneticNetworkOptions::allow_multifurcations`].
     /// * leaves have to be of in-degree 1.
     /// * `taxa` has to map leaves only.
     /// * `taxa` cannot contain duplicate nodes.
@@ -51,7 +109,16 @@ impl PhylogeneticNetwork {
         graph: DirectedGraph,
         taxa: HashMap<Node, Taxon>) -> Self
     {
-        let id = PhylogeneticNetworkId::generate_next();
+        Self::new_unchecked_with_id(graph, taxa, PhylogeneticNetworkId::generate_next())
+    }
+
+    /// Same as [`PhylogeneticNetwork::new_unchecked`], with passed `id`,
+    /// which is not bound to the network.
+    unsafe fn new_unchecked_with_id(
+        graph: DirectedGraph,
+        taxa: HashMap<Node, Taxon>,
+        id: PhylogeneticNetworkId) -> Self
+    {
         let hash_value: u32;
 
         {
@@ -75,7 +142,46 @@ impl PhylogeneticNetwork {
             }
         }
 
-        Self { graph, taxa, id, hash_value }
+        let mut statistics = NetworkStatistics::default();
+        for node in graph.iter_nodes() {
+            let in_degree = graph.get_predecessors(node).len();
+            if in_degree == 1 {
+                statistics.number_of_tree_arrows += 1;
+            }
+            else if in_degree >= 2 {
+                statistics.number_of_reticulation_arrows += in_degree;
+                statistics.number_of_reticulation_nodes += 1;
+            }
+        }
+
+        let mut taxa_index = HashMap::<Taxon, SmallVec<[Node; 1]>>::with_capacity(taxa.len());
+        for (node, taxon) in &taxa {
+            taxa_index.entry(taxon.clone()).or_default().push(*node);
+        }
+        for nodes in taxa_index.values_mut() {
+            nodes.sort_unstable_by_key(Node::id);
+        }
+
+        let properties = PhylogeneticNetworkProperties::calculate(&graph);
+        let kind = if graph.basic_properties().binary {
+            PhylogeneticNetworkKind::Binary
+        }
+        else
+        {
+            PhylogeneticNetworkKind::Multifurcating
+        };
+
+        Self {
+            graph: graph,
+            taxa: taxa,
+            id: id,
+            hash_value: hash_value,
+            statistics: statistics,
+            properties: properties,
+            kind: kind,
+            taxa_index: taxa_index,
+            id_bound: AtomicBool::new(false),
+        }
     }
 
     /// Constructs [`PhylogeneticNetwork`] directly and
@@ -83,10 +189,35 @@ impl PhylogeneticNetwork {
     /// 
     /// # Errors
     /// For the meaning of errors see [`PhylogeneticNetworkFromError`] docs.
+    #[inline(always)]
     pub fn from_graph_and_taxa(
         graph: DirectedGraph,
         taxa: HashMap<Node, Taxon>)
         -> Result<Self, PhylogeneticNetworkFromError>
+    {
+        Self::from_graph_and_taxa_with_options(graph, taxa, &PhylogeneticNetworkOptions::default())
+    }
+
+    /// Same as [`PhylogeneticNetwork::from_graph_and_taxa`], with
+    /// strictness controlled by `options`.
+    ///
+    /// # Errors
+    /// For the meaning of errors see [`PhylogeneticNetworkFromError`] docs.
+    pub fn from_graph_and_taxa_with_options(
+        graph: DirectedGraph,
+        taxa: HashMap<Node, Taxon>,
+        options: &PhylogeneticNetworkOptions)
+        -> Result<Self, PhylogeneticNetworkFromError>
+    {
+        Self::from_graph_and_taxa_with_id(graph, taxa, options, PhylogeneticNetworkId::generate_next())
+    }
+
+    fn from_graph_and_taxa_with_id(
+        graph: DirectedGraph,
+        taxa: HashMap<Node, Taxon>,
+        options: &PhylogeneticNetworkOptions,
+        id: PhylogeneticNetworkId)
+        -> Result<Self, PhylogeneticNetworkFromError>
     {
         let props = graph.basic_properties();
         if !props.acyclic {
@@ -97,28 +228,208 @@ impl PhylogeneticNetwork {
             return Err(PhylogeneticNetworkFromError::NotRooted);
         }
 
-        if !props.binary {
+        if !options.allow_multifurcations() && !props.binary {
             return Err(PhylogeneticNetworkFromError::NotBinary);
         }
 
-        let network = unsafe { Self::new_unchecked(graph, taxa) };
+        if !options.allow_internal_taxa() {
+            let not_on_leaf = taxa.keys()
+                .filter(|node| !graph.leaves().contains(node))
+                .min_by_key(|node| node.id());
+            if let Some(node) = not_on_leaf {
+                return Err(PhylogeneticNetworkFromError::TaxonNotOnLeaf(*node));
+            }
+        }
+
+        let taxa = match options.taxon_policy() {
+            Some(policy) => apply_taxon_policy(&taxa, policy)?,
+            None => taxa,
+        };
+        let network = unsafe { Self::new_unchecked_with_id(graph, taxa, id) };
         Ok(network)
     }
 
     /// Constructs [`PhylogeneticNetwork`] out of [`PhylogeneticNetworkDTO`].
+    /// Uses [`PhylogeneticNetworkDTO::id`] if set, see
+    /// [`PhylogeneticNetworkId::from_explicit`], and generates new id
+    /// otherwise. Explicit id is bound to the network, as with
+    /// [`PhylogeneticNetwork::into_dto_with_id`].
     /// 
     /// # Errors
     /// For the meaning of errors see [`PhylogeneticNetworkFromError`] docs.
+    #[inline(always)]
     pub fn from_dto(dto: &PhylogeneticNetworkDTO)
         -> Result<Self, PhylogeneticNetworkFromError>
     {
+        Self::from_dto_with_options(dto, &PhylogeneticNetworkOptions::default())
+    }
+
+    /// Same as [`PhylogeneticNetwork::from_dto`], with strictness
+    /// controlled by `options`.
+    ///
+    /// # Errors
+    /// For the meaning of errors see [`PhylogeneticNetworkFromError`] docs.
+    pub fn from_dto_with_options(dto: &PhylogeneticNetworkDTO, options: &PhylogeneticNetworkOptions)
+        -> Result<Self, PhylogeneticNetworkFromError>
+    {
+        // Explicit id goes first, so that ids generated below are past it.
+        let explicit_id = match dto.id() {
+            Some(id) => Some(PhylogeneticNetworkId::from_explicit(id)
+                .ok_or(PhylogeneticNetworkFromError::InvalidId(id))?),
+            None => None,
+        };
         let graph = DirectedGraph::from_dto(dto.graph())?;
         let taxa: HashMap<Node, Taxon>
             = dto.taxa()
                 .iter()
                 .map(|kvp| (Node::from(*kvp.0), Taxon::from(kvp.1.clone())))
                 .collect();
-        Self::from_graph_and_taxa(graph, taxa)
+        let Some(id) = explicit_id else {
+            return Self::from_graph_and_taxa_with_options(graph, taxa, options);
+        };
+        let network = Self::from_graph_and_taxa_with_id(graph, taxa, options, id)?;
+        if !network.bind_id() {
+            return Err(PhylogeneticNetworkFromError::InvalidId(i32::from(id)));
+        }
+        Ok(network)
+    }
+
+    /// Converts [`PhylogeneticNetwork`] into [`PhylogeneticNetworkDTO`]
+    /// without id, see [`PhylogeneticNetwork::into_dto_with_id`].
+    pub fn into_dto(&self) -> PhylogeneticNetworkDTO {
+        let taxa: HashMap<i32, ImmutableString>
+            = self.taxa
+                .iter()
+                .map(|kvp| (kvp.0.id(), kvp.1.value().clone()))
+                .collect();
+        PhylogeneticNetworkDTO::new(self.graph.into_dto(), taxa)
+    }
+
+    /// Converts [`PhylogeneticNetwork`] into [`PhylogeneticNetworkDTO`]
+    /// keeping its id, so that [`PhylogeneticNetwork::from_dto`] recreates
+    /// network with the same id. The id gets bound to the network: as long
+    /// as it, or a network loaded with its id, is alive, other networks
+    /// can't be loaded with that id, see
+    /// [`PhylogeneticNetworkFromError::InvalidId`].
+    pub fn into_dto_with_id(&self) -> PhylogeneticNetworkDTO {
+        self.bind_id();
+        self.into_dto().with_id(Some(i32::from(self.id)))
+    }
+
+    /// Binds `id` to the network, unless bound already. Returns `false` if
+    /// it is bound to a different network.
+    fn bind_id(&self) -> bool {
+        if self.id_bound.load(Ordering::Acquire) {
+            return true;
+        }
+        let bound = self.id.acquire(self.hash_value);
+        if bound && self.id_bound.swap(true, Ordering::AcqRel) {
+            // Bound concurrently by another thread.
+            self.id.release();
+        }
+        bound
+    }
+
+    /// Converts [`PhylogeneticNetwork`] into [`PhylogeneticNetworkDTO`] with
+    /// the graph in canonical form, see [`DirectedGraph::to_canonical_dto`].
+    /// Taxa keys are renumbered accordingly. Instead of structural colors
+    /// children are visited in the order of their subnetworks, compared by
+    /// height, then taxa and then children, and only equal subnetworks are
+    /// ordered by original ids. Thus the result doesn't depend on the order
+    /// of children in the input either, e.g. `((A,B),C);` and `(C,(B,A));`
+    /// give equal canonical DTOs.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn to_canonical_dto(&self) -> PhylogeneticNetworkDTO {
+        let ranks = self.subnetwork_ranks();
+        let (graph, new_ids) = self.graph
+            .to_canonical_dto_with_mapping_by(|node| (ranks[node.id() as usize], node.id()));
+        let taxa: HashMap<i32, ImmutableString>
+            = self.taxa
+                .iter()
+                .map(|kvp| (new_ids[kvp.0.id() as usize], kvp.1.value().clone()))
+                .collect();
+        PhylogeneticNetworkDTO::new(graph, taxa)
+    }
+
+    /// Ranks subnetworks rooted at each node, independently of node ids.
+    /// Nodes are grouped by height, i.e. the length of the longest path down
+    /// to a leaf, and within each group ordered by taxon and then by sorted
+    /// ranks of children, each paired with the weight of the arrow to it.
+    /// Nodes with equal subnetworks get equal ranks.
+    #[allow(clippy::cast_sign_loss)]
+    fn subnetwork_ranks(&self) -> Vec<usize> {
+        // Rank of a child together with bits of the weight of the arrow to it.
+        type ChildKey = (usize, Option<u64>);
+        let size = self.graph.number_of_nodes() as usize;
+        let order: Vec<Node> = self.graph.iter_topological().into_iter().flatten().collect();
+        let mut heights = vec![0; size];
+        for node in order.iter().rev() {
+            heights[node.id() as usize] = self.graph.get_successors(*node)
+                .iter()
+                .map(|child| heights[child.id() as usize] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+
+        let mut levels: Vec<Vec<Node>> = Vec::new();
+        for node in &order {
+            let height = heights[node.id() as usize];
+            if levels.len() <= height {
+                levels.resize_with(height + 1, Vec::new);
+            }
+            levels[height].push(*node);
+        }
+
+        let mut ranks = vec![0; size];
+        let mut next_rank = 0;
+        for level in levels {
+            let mut keys: Vec<(Option<&str>, Vec<ChildKey>, Node)> = level.into_iter()
+                .map(|node| {
+                    let taxon = self.taxa.get(&node).map(|taxon| taxon.value().as_str());
+                    let mut children: Vec<ChildKey> = self.graph.get_successors(node)
+                        .iter()
+                        .map(|child| (
+                            ranks[child.id() as usize],
+                            self.graph.arrow_weight(node, *child).map(f64::to_bits)))
+                        .collect();
+                    children.sort_unstable();
+                    (taxon, children, node)
+                })
+                .collect();
+            keys.sort_unstable_by(|left, right| (left.0, &left.1).cmp(&(right.0, &right.1)));
+            for (idx, (taxon, children, node)) in keys.iter().enumerate() {
+                if idx > 0 && (keys[idx - 1].0, &keys[idx - 1].1) != (*taxon, children) {
+                    next_rank += 1;
+                }
+                ranks[node.id() as usize] = next_rank;
+            }
+            next_rank += 1;
+        }
+        ranks
+    }
+
+    /// Extracts the subnetwork rooted at `root`, i.e. `root` with all its
+    /// descendants, keeping taxa of the extracted leaves. See
+    /// [`DirectedGraph::induced_subgraph_from`] for the renumbering; the
+    /// returned vector maps new ids to original nodes.
+    ///
+    /// # Panics
+    /// When `root` is not in the network.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn subnetwork_from(&self, root: Node) -> (PhylogeneticNetwork, Vec<Node>) {
+        let (graph, mapping) = self.graph.induced_subgraph_from(root);
+        let taxa: HashMap<Node, Taxon> = mapping.iter()
+            .enumerate()
+            .filter_map(|(idx, original)| {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                let node = Node::from(idx as i32);
+                self.taxa.get(original).map(|taxon| (node, taxon.clone()))
+            })
+            .collect();
+        // Subgraph of a rooted, acyclic and binary graph rooted at one of
+        // its nodes keeps all these properties.
+        let network = unsafe { Self::new_unchecked(graph, taxa) };
+        (network, mapping)
     }
 
     #[inline(always)]
@@ -136,6 +447,47 @@ impl PhylogeneticNetwork {
         &self.taxa
     }
 
+    #[inline(always)]
+    pub fn statistics(&self) -> &NetworkStatistics {
+        &self.statistics
+    }
+
+    #[inline(always)]
+    pub fn properties(&self) -> &PhylogeneticNetworkProperties {
+        &self.properties
+    }
+
+    /// Whether the network is binary, see [`PhylogeneticNetworkKind`].
+    #[inline(always)]
+    pub fn kind(&self) -> PhylogeneticNetworkKind {
+        self.kind
+    }
+
+    /// See [`PhylogeneticNetworkProperties::tree_child`].
+    #[inline(always)]
+    pub fn is_tree_child(&self) -> bool {
+        self.properties.tree_child
+    }
+
+    /// See [`PhylogeneticNetworkProperties::tree_sibling`].
+    #[inline(always)]
+    pub fn is_tree_sibling(&self) -> bool {
+        self.properties.tree_sibling
+    }
+
+    /// See [`PhylogeneticNetworkProperties::time_consistent`].
+    #[inline(always)]
+    pub fn is_time_consistent(&self) -> bool {
+        self.properties.time_consistent
+    }
+
+    /// Returns the number of reticulation nodes, i.e. nodes of in-degree
+    /// at least 2. Calculated during construction.
+    #[inline(always)]
+    pub fn reticulation_count(&self) -> usize {
+        self.statistics.number_of_reticulation_nodes
+    }
+
     /// Returns root of the [`PhylogeneticNetwork`].
     /// 
     /// # Panics
@@ -178,20 +530,138 @@ impl PhylogeneticNetwork {
         self.graph.is_leaf(node)
     }
 
-    pub fn iter_by_taxon<'a>(&'a self, taxon: &'a str) -> impl Iterator<Item=Node> + 'a {
-        self.taxa.iter()
-            .filter(move |p| p.1.value().as_str() == taxon)
-            .map(|p| *p.0)
+    /// Whether some taxon is assigned to a node which is not a leaf, see
+    /// [`PhylogeneticNetworkOptions::allow_internal_taxa`].
+    pub fn has_internal_taxa(&self) -> bool {
+        self.taxa.keys().any(|node| !self.is_leaf(*node))
+    }
+
+    /// Classifies arrow `source -> target`. Returns `None` if there is no
+    /// such arrow.
+    pub fn classify_arrow(&self, source: Node, target: Node) -> Option<ArrowKind> {
+        let predecessors = self.graph.get_predecessors(target);
+        if !predecessors.contains(&source) {
+            return None;
+        }
+
+        if predecessors.len() == 1 {
+            Some(ArrowKind::Tree)
+        }
+        else
+        {
+            Some(ArrowKind::Reticulation)
+        }
+    }
+
+    /// Iterates over all arrows of [`ArrowKind::Reticulation`] kind as
+    /// `(source, target)` pairs.
+    pub fn iter_reticulation_arrows(&self) -> impl Iterator<Item=(Node, Node)> + '_ {
+        self.graph.iter_nodes()
+            .filter(|node| self.graph.get_predecessors(*node).len() >= 2)
+            .flat_map(|node| {
+                self.graph.get_predecessors(node)
+                    .iter()
+                    .map(move |source| (*source, node))
+            })
+    }
+
+    /// Returns all nodes labeled with `taxon`, ordered by id.
+    #[inline(always)]
+    pub fn get_nodes_by_taxon(&self, taxon: &Taxon) -> &[Node] {
+        self.taxa_index.get(taxon).map_or(&[], SmallVec::as_slice)
+    }
+
+    /// Iterates over all nodes labeled with `taxon`, ordered by id.
+    pub fn iter_by_taxon(&self, taxon: &str) -> impl Iterator<Item=Node> + '_ {
+        let nodes = match Taxon::new(taxon) {
+            Ok(taxon) => self.get_nodes_by_taxon(&taxon),
+            Err(_) => &[],
+        };
+        nodes.iter().copied()
+    }
+
+    /// Lazily iterates over leaves reachable from `node`, including `node`
+    /// itself if it is a leaf. Each leaf is yielded once, in breadth-first
+    /// order, see [`DirectedGraph::bfs_from`].
+    pub fn iter_leaves_below(&self, node: Node) -> impl Iterator<Item=Node> + '_ {
+        self.graph.bfs_from(node)
+            .map(|(node, _)| node)
+            .filter(|node| self.graph.is_leaf(*node))
+    }
+
+    /// Lazily iterates over all paths from the root to nodes labeled with
+    /// `taxon`, i.e. its evolutionary histories. Nodes are processed in
+    /// ascending id order, see [`DirectedGraph::paths_between`].
+    pub fn paths_to_taxon(&self, taxon: &str) -> impl Iterator<Item=Vec<Node>> + '_ {
+        let root = self.root();
+        self.iter_by_taxon(taxon)
+            .flat_map(move |node| self.graph.paths_between(root, node))
+    }
+
+    /// Returns the node labeled with `taxon`, or `None` if there are zero or
+    /// multiple such nodes.
+    pub fn get_single_by_taxon(&self, taxon: &str) -> Option<Node> {
+        let mut iter = self.iter_by_taxon(taxon);
+        let first = iter.next()?;
+        if iter.next().is_some() {
+            None
+        }
+        else
+        {
+            Some(first)
+        }
+    }
+
+    /// Builds [`GraphReport`] of the underlying graph, additionally
+    /// reporting leaves without taxa.
+    pub fn report(&self) -> GraphReport {
+        let mut report = self.graph.report();
+        let mut unlabeled: Vec<Node> = self.graph.leaves()
+            .iter()
+            .filter(|leaf| !self.taxa.contains_key(leaf))
+            .copied()
+            .collect();
+        unlabeled.sort_by_key(Node::id);
+        report.anomalies.extend(unlabeled.into_iter().map(GraphAnomaly::UnlabeledLeaf));
+        report
+    }
+
+    /// Same as [`DirectedGraph::find_subgraph_occurrences`], but additionally
+    /// requires that every labeled `pattern` node is mapped onto a node with
+    /// equal taxon.
+    pub fn find_subnetwork_occurrences(&self, pattern: &PhylogeneticNetwork, limit: usize)
+        -> Vec<HashMap<Node, Node>>
+    {
+        self.find_subnetwork_occurrences_cancellable(pattern, limit, || false)
+    }
+
+    /// Same as [`PhylogeneticNetwork::find_subnetwork_occurrences`], but
+    /// stops early once `is_cancelled` returns `true`. In that case
+    /// occurrences found so far are returned.
+    pub fn find_subnetwork_occurrences_cancellable<F>(
+        &self,
+        pattern: &PhylogeneticNetwork,
+        limit: usize,
+        is_cancelled: F)
+        -> Vec<HashMap<Node, Node>>
+        where F: FnMut() -> bool
+    {
+        let is_compatible = |pattern_node: Node, host_node: Node| {
+            match pattern.taxa.get(&pattern_node) {
+                Some(taxon) => self.taxa.get(&host_node) == Some(taxon),
+                None => true,
+            }
+        };
+        find_occurrences(&self.graph, &pattern.graph, limit, is_compatible, is_cancelled)
     }
 }
 
+/// Compares structure and taxa, ids are irrelevant.
 impl PartialEq for PhylogeneticNetwork {
     fn eq(&self, other: &Self) -> bool {
-        self.id == other.id
-        || (
-            self.hash_value == other.hash_value
+        self.hash_value == other.hash_value
             && self.graph == other.graph
-            && self.taxa == other.taxa)
+            && self.taxa == other.taxa
     }
 }
 
@@ -203,12 +673,28 @@ impl Hash for PhylogeneticNetwork {
     }
 }
 
+/// Clones keep the id, bound to them as well if it is bound to `self`.
 impl Clone for PhylogeneticNetwork {
     fn clone(&self) -> Self {
-        unsafe {
-            Self::new_unchecked(
-                self.graph.clone(),
-                self.taxa.clone())
+        let id_bound = self.id_bound.load(Ordering::Acquire) && self.id.acquire(self.hash_value);
+        Self {
+            graph: self.graph.clone(),
+            taxa: self.taxa.clone(),
+            id: self.id,
+            hash_value: self.hash_value,
+            statistics: self.statistics,
+            properties: self.properties,
+            kind: self.kind,
+            taxa_index: self.taxa_index.clone(),
+            id_bound: AtomicBool::new(id_bound),
+        }
+    }
+}
+
+impl Drop for PhylogeneticNetwork {
+    fn drop(&mut self) {
+        if *self.id_bound.get_mut() {
+            self.id.release();
         }
     }
 }
@@ -226,5 +712,66 @@ impl Debug for PhylogeneticNetwork {
     }
 }
 
+/// Same format as [`PhylogeneticNetworkDTO`]'s, e.g.
+/// `DG(n=3): 0->1, 0->2, taxa: {1: "A", 2: "B"}`.
+impl Display for PhylogeneticNetwork {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let limit = f.precision().unwrap_or(DEFAULT_EDGE_LIST_LIMIT);
+        let mut result = self.graph.to_edge_list_string(limit);
+        let taxa = self.taxa.iter()
+            .map(|(node, taxon)| (node.id(), taxon.value().as_str()))
+            .collect();
+        write_taxa(&mut result, taxa, limit);
+        f.write_str(&result)
+    }
+}
+
 unsafe impl Sync for PhylogeneticNetwork { }
 unsafe impl Send for PhylogeneticNetwork { }
+
+/// Normalizes and validates all taxa, reporting the node of the lowest id
+/// on failure.
+fn apply_taxon_policy(taxa: &HashMap<Node, Taxon>, policy: &TaxonPolicy)
+    -> Result<HashMap<Node, Taxon>, PhylogeneticNetworkFromError>
+{
+    let mut nodes: Vec<Node> = taxa.keys().copied().collect();
+    nodes.sort_unstable_by_key(Node::id);
+    let mut result = HashMap::with_capacity(taxa.len());
+    for node in nodes {
+        let taxon = Taxon::new_validated(taxa[&node].value().as_str(), policy)
+            .map_err(|err| PhylogeneticNetworkFromError::InvalidTaxon(node, err))?;
+        result.insert(node, taxon);
+    }
+    Ok(result)
+}
+
+impl Display for PhylogeneticNetworkFromError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PhylogeneticNetworkFromError::NotAcyclic
+                => f.write_str("phylogenetic network is not acyclic"),
+            PhylogeneticNetworkFromError::NotRooted
+                => f.write_str("phylogenetic network is not rooted"),
+            PhylogeneticNetworkFromError::NotBinary
+                => f.write_str("phylogenetic network is not binary"),
+            PhylogeneticNetworkFromError::TaxonNotOnLeaf(node)
+                => write!(f, "taxon assigned to node {}, which is not a leaf", node.id()),
+            PhylogeneticNetworkFromError::InvalidTaxon(node, err)
+                => write!(f, "invalid taxon of node {}: {err}", node.id()),
+            PhylogeneticNetworkFromError::GraphError(_)
+                => f.write_str("invalid phylogenetic network graph"),
+            PhylogeneticNetworkFromError::InvalidId(id)
+                => write!(f, "explicit network id {id} is invalid or used by a different network"),
+        }
+    }
+}
+
+impl std::error::Error for PhylogeneticNetworkFromError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PhylogeneticNetworkFromError::GraphError(err) => Some(err),
+            PhylogeneticNetworkFromError::InvalidTaxon(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -1,14 +1,219 @@
-use std::collections::HashMap;
-
-use raf_readonly::readonly;
+use core::fmt::{Display, Formatter, Write};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 
 use crate::raf_array::immutable_string::ImmutableString;
 
-use crate::core::DirectedGraphDTO;
+use crate::core::{
+    ArrowDTO,
+    DirectedGraph,
+    DirectedGraphDTO,
+    DtoValidationError,
+    DEFAULT_EDGE_LIST_LIMIT};
 
-#[readonly]
+/// Represents [`PhylogeneticNetwork`](super::PhylogeneticNetwork) as graph
+/// with taxa assigned to nodes, and optionally the network's id.
+///
+/// # Notes
+/// Immutable once created.
 #[derive(PartialEq, Eq, Clone, Debug, Default)]
 pub struct PhylogeneticNetworkDTO {
-    pub graph: DirectedGraphDTO,
-    pub taxa: HashMap<i32, ImmutableString>,
+    graph: DirectedGraphDTO,
+    taxa: HashMap<i32, ImmutableString>,
+    id: Option<i32>,
+}
+
+/// Error of [`PhylogeneticNetworkDTO::from_named_arrows`]. Nodes are
+/// referred to by their names.
+#[derive(Debug, PartialEq, Eq)]
+pub enum NamedDtoError {
+    /// Node name or taxon is not a valid [`ImmutableString`]. Holds the
+    /// passed text.
+    InvalidName(String),
+
+    /// Arrow from a node to itself. Holds name of the node.
+    SelfLoop(ImmutableString),
+
+    /// Arrow given more than once. Holds names of its source and target.
+    MultipleParallelArrows(ImmutableString, ImmutableString),
+
+    /// Taxon assigned to a name which doesn't appear in any arrow.
+    UnknownNode(ImmutableString),
+
+    /// More than one taxon assigned to the same node. Holds its name.
+    MultipleTaxa(ImmutableString),
+
+    /// Number of distinct names exceeds [`DirectedGraph::max_size()`].
+    TooBigGraph,
+}
+
+impl PhylogeneticNetworkDTO {
+    /// Creates DTO without id, i.e.
+    /// [`PhylogeneticNetwork::from_dto`](super::PhylogeneticNetwork::from_dto)
+    /// generates a fresh one.
+    #[inline(always)]
+    pub fn new(graph: DirectedGraphDTO, taxa: HashMap<i32, ImmutableString>) -> Self {
+        Self { graph: graph, taxa: taxa, id: None }
+    }
+
+    /// Sets explicit network id, respected by
+    /// [`PhylogeneticNetwork::from_dto`](super::PhylogeneticNetwork::from_dto),
+    /// see [`PhylogeneticNetworkId::from_explicit`](super::PhylogeneticNetworkId::from_explicit).
+    #[must_use]
+    pub fn with_id(mut self, id: Option<i32>) -> Self {
+        self.id = id;
+        self
+    }
+
+    #[inline(always)]
+    pub fn graph(&self) -> &DirectedGraphDTO {
+        &self.graph
+    }
+
+    #[inline(always)]
+    pub fn taxa(&self) -> &HashMap<i32, ImmutableString> {
+        &self.taxa
+    }
+
+    #[inline(always)]
+    pub fn id(&self) -> Option<i32> {
+        self.id
+    }
+
+    /// Builds DTO out of arrows between named nodes and `(node, taxon)`
+    /// pairs. Names get dense ids in the order of their first appearance in
+    /// `arrows`, sources before targets. Returns the DTO together with the
+    /// mapping from names to ids. Properties required by
+    /// [`PhylogeneticNetwork`](super::PhylogeneticNetwork), e.g. being
+    /// rooted, are not checked.
+    ///
+    /// # Errors
+    /// For specific errors read [`NamedDtoError`] docs.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn from_named_arrows(arrows: &[(&str, &str)], taxa: &[(&str, &str)])
+        -> Result<(PhylogeneticNetworkDTO, HashMap<ImmutableString, i32>), NamedDtoError>
+    {
+        fn to_name(text: &str) -> Result<ImmutableString, NamedDtoError> {
+            ImmutableString::new(text).map_err(|_| NamedDtoError::InvalidName(text.to_owned()))
+        }
+
+        let mut ids = HashMap::<ImmutableString, i32>::new();
+        let mut get_id = |text: &str| -> Result<i32, NamedDtoError> {
+            let name = to_name(text)?;
+            let next_id = ids.len() as i32;
+            let id = *ids.entry(name).or_insert(next_id);
+            if id >= DirectedGraph::max_size() {
+                return Err(NamedDtoError::TooBigGraph);
+            }
+            Ok(id)
+        };
+
+        let mut seen = HashSet::<(i32, i32)>::with_capacity(arrows.len());
+        let mut arrow_dtos = Vec::with_capacity(arrows.len());
+        for (source, target) in arrows {
+            let source_id = get_id(source)?;
+            let target_id = get_id(target)?;
+            if source_id == target_id {
+                return Err(NamedDtoError::SelfLoop(to_name(source)?));
+            }
+            if !seen.insert((source_id, target_id)) {
+                return Err(NamedDtoError::MultipleParallelArrows(to_name(source)?, to_name(target)?));
+            }
+            arrow_dtos.push(ArrowDTO::new(source_id, target_id));
+        }
+
+        let mut taxa_map = HashMap::<i32, ImmutableString>::with_capacity(taxa.len());
+        for (node, taxon) in taxa {
+            let name = to_name(node)?;
+            let Some(id) = ids.get(&name) else {
+                return Err(NamedDtoError::UnknownNode(name));
+            };
+            match taxa_map.entry(*id) {
+                Entry::Occupied(_) => return Err(NamedDtoError::MultipleTaxa(name)),
+                Entry::Vacant(entry) => { entry.insert(to_name(taxon)?); },
+            }
+        }
+
+        let graph = DirectedGraphDTO::new(ids.len() as i32, arrow_dtos);
+        Ok((PhylogeneticNetworkDTO::new(graph, taxa_map), ids))
+    }
+
+    /// Validates the graph like [`DirectedGraphDTO::validate`], and then
+    /// checks that all taxa are assigned to nodes within range. Properties
+    /// required by [`PhylogeneticNetwork`](super::PhylogeneticNetwork),
+    /// e.g. being rooted, are not checked.
+    ///
+    /// # Errors
+    /// For specific errors read [`DtoValidationError`] docs. Taxa are
+    /// checked in the order of node ids.
+    pub fn validate(&self) -> Result<(), DtoValidationError> {
+        self.graph.validate()?;
+        let range = 0..self.graph.number_of_nodes();
+        let outside = self.taxa.keys()
+            .filter(|node| !range.contains(node))
+            .min();
+        match outside {
+            Some(node) => Err(DtoValidationError::TaxonOutsideOfNodesRange(*node)),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns a copy with the graph normalized, see
+    /// [`DirectedGraphDTO::normalized`]. Taxa and id are kept as they are.
+    #[must_use]
+    pub fn normalized(&self) -> PhylogeneticNetworkDTO {
+        PhylogeneticNetworkDTO::new(self.graph.normalized(), self.taxa.clone())
+            .with_id(self.id)
+    }
 }
+
+/// Appends `, taxa: {<node>: "<taxon>", ...}` with at most `limit` taxa
+/// sorted by node id. Shared by network and DTO [`Display`] implementations.
+pub(super) fn write_taxa(out: &mut String, mut taxa: Vec<(i32, &str)>, limit: usize) {
+    taxa.sort_unstable_by_key(|(node, _)| *node);
+    out.push_str(", taxa: {");
+    for (idx, (node, taxon)) in taxa.iter().take(limit).enumerate() {
+        let separator = if idx == 0 { "" } else { ", " };
+        let _ = write!(out, "{separator}{node}: {taxon:?}");
+    }
+    if taxa.len() > limit {
+        let separator = if limit == 0 { "" } else { ", " };
+        let _ = write!(out, "{separator}... ({} taxa)", taxa.len());
+    }
+    out.push('}');
+}
+
+/// Graph as in [`DirectedGraphDTO`]'s [`Display`], followed by taxa, e.g.
+/// `DG(n=3): 0->1, 0->2, taxa: {1: "A", 2: "B"}`. Formatter precision
+/// limits both arrows and taxa.
+impl Display for PhylogeneticNetworkDTO {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let limit = f.precision().unwrap_or(DEFAULT_EDGE_LIST_LIMIT);
+        let mut result = format!("{:.*}", limit, self.graph);
+        let taxa = self.taxa.iter()
+            .map(|(node, taxon)| (*node, taxon.as_str()))
+            .collect();
+        write_taxa(&mut result, taxa, limit);
+        f.write_str(&result)
+    }
+}
+
+impl Display for NamedDtoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            NamedDtoError::InvalidName(text)
+                => write!(f, "invalid name {text:?}"),
+            NamedDtoError::SelfLoop(name)
+                => write!(f, "arrow {0} -> {0} is a self loop", name.as_str()),
+            NamedDtoError::MultipleParallelArrows(source, target)
+                => write!(f, "multiple arrows {} -> {}", source.as_str(), target.as_str()),
+            NamedDtoError::UnknownNode(name)
+                => write!(f, "taxon assigned to unknown node {}", name.as_str()),
+            NamedDtoError::MultipleTaxa(name)
+                => write!(f, "multiple taxa assigned to node {}", name.as_str()),
+            NamedDtoError::TooBigGraph
+                => write!(f, "graph exceeds the maximum of {} nodes", DirectedGraph::max_size()),
+        }
+    }
+}
+
+impl std::error::Error for NamedDtoError { }
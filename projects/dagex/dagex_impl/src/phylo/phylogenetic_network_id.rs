@@ -1,3 +1,7 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    sync::{Mutex, MutexGuard, PoisonError}};
+
 use crate::GlobalId;
 
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
@@ -5,11 +9,61 @@ pub struct PhylogeneticNetworkId {
     global_id: GlobalId
 }
 
+/// Ids bound to live networks, see [`PhylogeneticNetworkId::acquire`],
+/// together with hash value of those networks and their number.
+static _BOUND_IDS: Mutex<BTreeMap<i32, (u32, usize)>> = Mutex::new(BTreeMap::new());
+
 impl PhylogeneticNetworkId {    
     #[inline(always)]
     pub fn generate_next() -> Self {
         Self { global_id: GlobalId::generate_next() }
     }
+
+    /// Recreates id from its `i32` value, e.g. one stored in
+    /// [`PhylogeneticNetworkDTO::id`](super::PhylogeneticNetworkDTO::id).
+    /// Moves the id counter past `id`, so that ids generated later in the
+    /// process never equal it. Returns `None` if `id` is negative or at
+    /// least `i32::MAX - 1`.
+    pub fn from_explicit(id: i32) -> Option<Self> {
+        GlobalId::reserve_explicit(id).map(|global_id| Self { global_id: global_id })
+    }
+
+    /// Binds id to a live network with `hash_value`, until it is released
+    /// with [`PhylogeneticNetworkId::release`]. Returns `false` if id is
+    /// bound to networks with different hash value already.
+    pub(super) fn acquire(self, hash_value: u32) -> bool {
+        match bound_ids().entry(i32::from(self)) {
+            Entry::Occupied(mut entry) => {
+                let (bound_hash_value, count) = entry.get_mut();
+                if *bound_hash_value != hash_value {
+                    return false;
+                }
+                *count += 1;
+                true
+            },
+            Entry::Vacant(entry) => {
+                entry.insert((hash_value, 1));
+                true
+            },
+        }
+    }
+
+    /// Releases id acquired with [`PhylogeneticNetworkId::acquire`]. The id
+    /// is unbound once all networks bound to it are released.
+    pub(super) fn release(self) {
+        if let Entry::Occupied(mut entry) = bound_ids().entry(i32::from(self)) {
+            let count = &mut entry.get_mut().1;
+            *count -= 1;
+            if *count == 0 {
+                entry.remove();
+            }
+        }
+    }
+}
+
+/// The map is never left inconsistent, thus poisoning is ignored.
+fn bound_ids() -> MutexGuard<'static, BTreeMap<i32, (u32, usize)>> {
+    _BOUND_IDS.lock().unwrap_or_else(PoisonError::into_inner)
 }
 
 impl From<PhylogeneticNetworkId> for i32 {
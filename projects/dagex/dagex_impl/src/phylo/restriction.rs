@@ -0,0 +1,182 @@
+use core::fmt::{Display, Formatter};
+use std::collections::{HashMap, HashSet};
+
+use crate::core::{ArrowDTO, DirectedGraphDTO};
+
+use super::{
+    PhylogeneticNetwork,
+    PhylogeneticNetworkDTO,
+    PhylogeneticNetworkKind,
+    PhylogeneticNetworkOptions,
+    Taxon};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RestrictionError {
+    /// None of the requested taxa is present in the network.
+    EmptyIntersection,
+}
+
+impl PhylogeneticNetwork {
+    /// Restricts network to leaves labeled with `taxa`. Other leaves and
+    /// branches leading only to them are pruned, parallel arrows are merged
+    /// and nodes that became redundant, including reticulations left with
+    /// a single parent, are suppressed. Taxa missing in the network are
+    /// ignored. Restricting to all taxa gives a network equal to the
+    /// current one, up to id.
+    ///
+    /// # Errors
+    /// For concrete errors see [`RestrictionError`] docs.
+    pub fn restrict_to_taxa(&self, taxa: &HashSet<Taxon>)
+        -> Result<PhylogeneticNetwork, RestrictionError>
+    {
+        restrict_network(self, taxa).ok_or(RestrictionError::EmptyIntersection)
+    }
+}
+
+/// Restricts `network` to nodes labeled with `taxa`, see [`reduce_network`].
+/// Returns `None` if no node is labeled with `taxa`.
+pub(crate) fn restrict_network(network: &PhylogeneticNetwork, taxa: &HashSet<Taxon>)
+    -> Option<PhylogeneticNetwork>
+{
+    let mut labels = HashMap::<usize, Taxon>::new();
+    for (node, taxon) in network.taxa() {
+        if taxa.contains(taxon) {
+            labels.insert(index(node.id()), taxon.clone());
+        }
+    }
+    if labels.is_empty() {
+        return None;
+    }
+
+    let (successors, predecessors) = adjacency_lists(network);
+    let seeds: Vec<usize> = labels.keys().copied().collect();
+    Some(reduce_network(network, successors, predecessors, seeds, labels))
+}
+
+/// Successors and predecessors of all nodes of `network`, indexed by ids.
+pub(super) fn adjacency_lists(network: &PhylogeneticNetwork) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+    let graph = network.graph();
+    let successors = graph.iter_nodes()
+        .map(|node| graph.get_successors(node).iter().map(|n| index(n.id())).collect())
+        .collect();
+    let predecessors = graph.iter_nodes()
+        .map(|node| graph.get_predecessors(node).iter().map(|n| index(n.id())).collect())
+        .collect();
+    (successors, predecessors)
+}
+
+/// Builds a new network out of `network`'s nodes connected by `successors`
+/// and `predecessors`: removes all nodes that don't lead to one of `seeds`,
+/// then repeatedly merges parallel arrows and suppresses nodes not in
+/// `labels` of in- and out-degree 1, as well as such root of out-degree 1.
+/// Nodes keep taxa given in `labels`, which have to be seeds.
+pub(super) fn reduce_network(
+    network: &PhylogeneticNetwork,
+    mut successors: Vec<Vec<usize>>,
+    mut predecessors: Vec<Vec<usize>>,
+    seeds: Vec<usize>,
+    labels: HashMap<usize, Taxon>)
+    -> PhylogeneticNetwork
+{
+    let size = successors.len();
+
+    // Keep only nodes that lead to a seed.
+    let mut alive = vec![false; size];
+    let mut stack = seeds;
+    for node in &stack {
+        alive[*node] = true;
+    }
+    while let Some(node) = stack.pop() {
+        for predecessor in &predecessors[node] {
+            if !alive[*predecessor] {
+                alive[*predecessor] = true;
+                stack.push(*predecessor);
+            }
+        }
+    }
+    for node in 0..size {
+        successors[node].retain(|n| alive[*n]);
+        predecessors[node].retain(|n| alive[*n]);
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for node in 0..size {
+            if !alive[node] || labels.contains_key(&node) {
+                continue;
+            }
+
+            let in_degree = predecessors[node].len();
+            let out_degree = successors[node].len();
+            if in_degree == 0 && out_degree == 1 {
+                let child = successors[node][0];
+                predecessors[child].retain(|n| *n != node);
+                successors[node].clear();
+                alive[node] = false;
+                changed = true;
+            }
+            else if in_degree == 1 && out_degree == 1 {
+                let parent = predecessors[node][0];
+                let child = successors[node][0];
+                successors[parent].retain(|n| *n != node);
+                predecessors[child].retain(|n| *n != node);
+                if !successors[parent].contains(&child) {
+                    successors[parent].push(child);
+                    predecessors[child].push(parent);
+                }
+                successors[node].clear();
+                predecessors[node].clear();
+                alive[node] = false;
+                changed = true;
+            }
+        }
+    }
+
+    let mut new_ids = vec![-1; size];
+    let mut number_of_nodes = 0;
+    for node in 0..size {
+        if alive[node] {
+            new_ids[node] = number_of_nodes;
+            number_of_nodes += 1;
+        }
+    }
+
+    let mut arrows = Vec::new();
+    for node in 0..size {
+        if alive[node] {
+            for successor in &successors[node] {
+                arrows.push(ArrowDTO::new(new_ids[node], new_ids[*successor]));
+            }
+        }
+    }
+    let dto_taxa = labels.into_iter()
+        .map(|(node, taxon)| (new_ids[node], taxon.value().clone()))
+        .collect();
+    let dto = PhylogeneticNetworkDTO::new(DirectedGraphDTO::new(number_of_nodes, arrows), dto_taxa);
+    // Pruning and suppression never increase degrees, keep the single root
+    // and preserve reachability, so the result stays acyclic and rooted,
+    // and binary if the original network was.
+    let options = PhylogeneticNetworkOptions::default()
+        .with_allow_multifurcations(network.kind() == PhylogeneticNetworkKind::Multifurcating)
+        .with_allow_internal_taxa(network.has_internal_taxa());
+    PhylogeneticNetwork::from_dto_with_options(&dto, &options)
+        .expect("Reduction of a valid network is valid.")
+}
+
+#[allow(clippy::cast_sign_loss)]
+#[inline(always)]
+pub(super) fn index(id: i32) -> usize {
+    id as usize
+}
+
+impl Display for RestrictionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RestrictionError::EmptyIntersection
+                => f.write_str("network has none of the requested taxa"),
+        }
+    }
+}
+
+impl std::error::Error for RestrictionError { }
@@ -0,0 +1,287 @@
+//! Synthetic species trees and gene trees evolving within them, e.g. for
+//! benchmarks. Available with the `simulation` feature. All simulations
+//! are deterministic for a given state of `rng`.
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::{
+    core::{ArrowDTO, DirectedGraphDTO, Node},
+    raf_array::immutable_string::ImmutableString};
+
+use super::{
+    GenesOverSpecies,
+    PhylogeneticNetwork,
+    PhylogeneticNetworkDTO,
+    PhylogeneticNetworkKind,
+    PhylogeneticNetworkOptions,
+    Taxon};
+
+/// Rates of gene evolution events, see [`simulate_gene_trees`]. Default
+/// parameters produce gene trees equal to the species tree.
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct GeneSimulationParams {
+    duplication_rate: f64,
+    loss_rate: f64,
+}
+
+impl GeneSimulationParams {
+    /// Probability that a gene lineage duplicates on a species branch.
+    /// Each lineage duplicates at most once per branch. 0 by default.
+    #[inline(always)]
+    pub fn duplication_rate(&self) -> f64 {
+        self.duplication_rate
+    }
+
+    /// Probability that a gene lineage is lost on a species branch other
+    /// than the one above the root. 0 by default.
+    #[inline(always)]
+    pub fn loss_rate(&self) -> f64 {
+        self.loss_rate
+    }
+
+    #[must_use]
+    pub fn with_duplication_rate(mut self, value: f64) -> Self {
+        self.duplication_rate = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_loss_rate(mut self, value: f64) -> Self {
+        self.loss_rate = value;
+        self
+    }
+}
+
+/// Result of [`simulate_gene_trees`].
+#[derive(Debug)]
+pub struct GeneSimulation {
+    genes_over_species: GenesOverSpecies,
+    duplications: usize,
+    losses: usize,
+}
+
+impl GeneSimulation {
+    #[inline(always)]
+    pub fn genes_over_species(&self) -> &GenesOverSpecies {
+        &self.genes_over_species
+    }
+
+    #[inline(always)]
+    pub fn into_genes_over_species(self) -> GenesOverSpecies {
+        self.genes_over_species
+    }
+
+    /// Number of duplication nodes placed in all gene trees, i.e.
+    /// duplications with both copies surviving.
+    #[inline(always)]
+    pub fn duplications(&self) -> usize {
+        self.duplications
+    }
+
+    /// Number of gene lineages lost in all gene trees.
+    #[inline(always)]
+    pub fn losses(&self) -> usize {
+        self.losses
+    }
+}
+
+/// Simulates rooted binary species tree with `leaves` leaves under the Yule
+/// model, i.e. by repeatedly splitting a uniformly chosen leaf. The root
+/// gets id 0 and leaves get distinct taxa `t<k>`, ordered by id.
+///
+/// # Panics
+/// When `leaves` is not positive.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+pub fn simulate_species_tree(rng: &mut impl Rng, leaves: i32) -> PhylogeneticNetwork {
+    assert!(leaves > 0, "Number of leaves has to be positive.");
+    let mut arrows = Vec::with_capacity(2 * leaves as usize - 2);
+    let mut current_leaves = vec![0];
+    let mut number_of_nodes = 1;
+    for _ in 1..leaves {
+        let leaf = current_leaves.swap_remove(rng.gen_range(0..current_leaves.len()));
+        for child in [number_of_nodes, number_of_nodes + 1] {
+            arrows.push(ArrowDTO::new(leaf, child));
+            current_leaves.push(child);
+        }
+        number_of_nodes += 2;
+    }
+    current_leaves.sort_unstable();
+    let taxa = current_leaves.into_iter()
+        .enumerate()
+        .map(|(idx, id)| (id, ImmutableString::new(&format!("t{idx}")).unwrap()))
+        .collect();
+    let dto = PhylogeneticNetworkDTO::new(DirectedGraphDTO::new(number_of_nodes, arrows), taxa);
+    PhylogeneticNetwork::from_dto(&dto).expect("Simulated species tree has to be valid.")
+}
+
+/// Simulates `genes` gene trees evolving within `species_network`. A gene
+/// lineage follows species branches from above the root: on each branch
+/// it may be lost or duplicated according to `params`, at speciation
+/// nodes it splits into all child branches, and at species leaves it
+/// becomes a gene leaf inheriting the species taxon. At species
+/// reticulations each gene tree follows a single, uniformly chosen
+/// parent. Gene nodes left with a single child are suppressed, and gene
+/// trees lost entirely are simulated again.
+///
+/// # Panics
+/// When `duplication_rate` is outside of `[0, 1]` or `loss_rate` outside
+/// of `[0, 1)`.
+pub fn simulate_gene_trees(
+    rng: &mut impl Rng,
+    species_network: &PhylogeneticNetwork,
+    genes: usize,
+    params: &GeneSimulationParams) -> GeneSimulation
+{
+    assert!((0.0..=1.0).contains(&params.duplication_rate), "Duplication rate has to be in [0, 1].");
+    assert!((0.0..1.0).contains(&params.loss_rate), "Loss rate has to be in [0, 1).");
+    let mut gene_networks = Vec::with_capacity(genes);
+    let mut duplications = 0;
+    let mut losses = 0;
+    for _ in 0..genes {
+        let gene = loop {
+            let mut simulator = GeneSimulator::new(rng, species_network, params);
+            let root = simulator.lineage(species_network.root());
+            if let Some(root) = root {
+                break simulator.finish(root);
+            }
+        };
+        duplications += gene.duplications;
+        losses += gene.losses;
+        gene_networks.push(gene.network);
+    }
+
+    let species_dto = species_network.into_dto();
+    let species = PhylogeneticNetwork::from_dto_with_options(&species_dto, &options(species_network))
+        .expect("Copy of valid network has to be valid.");
+    let genes_over_species = GenesOverSpecies::new(gene_networks, species)
+        .expect("Simulated gene trees have to match species network.");
+    GeneSimulation {
+        genes_over_species: genes_over_species,
+        duplications: duplications,
+        losses: losses,
+    }
+}
+
+/// Gene trees are multifurcating only if the species network is.
+fn options(species_network: &PhylogeneticNetwork) -> PhylogeneticNetworkOptions {
+    PhylogeneticNetworkOptions::default()
+        .with_allow_multifurcations(species_network.kind() == PhylogeneticNetworkKind::Multifurcating)
+}
+
+struct SimulatedGene {
+    network: PhylogeneticNetwork,
+    duplications: usize,
+    losses: usize,
+}
+
+struct GeneSimulator<'a, R: Rng> {
+    rng: &'a mut R,
+    species: &'a PhylogeneticNetwork,
+    params: &'a GeneSimulationParams,
+    /// Parent followed by the gene tree at each species reticulation.
+    chosen_parents: HashMap<Node, Node>,
+    successors: Vec<Vec<usize>>,
+    taxa: HashMap<usize, Taxon>,
+    duplications: usize,
+    losses: usize,
+}
+
+impl<'a, R: Rng> GeneSimulator<'a, R> {
+    fn new(rng: &'a mut R, species: &'a PhylogeneticNetwork, params: &'a GeneSimulationParams) -> Self {
+        let graph = species.graph();
+        let chosen_parents = graph.iter_nodes()
+            .filter(|node| graph.in_degree(*node) > 1)
+            .map(|node| {
+                let parents = graph.get_predecessors(node);
+                (node, parents[rng.gen_range(0..parents.len())])
+            })
+            .collect();
+        Self {
+            rng: rng,
+            species: species,
+            params: params,
+            chosen_parents: chosen_parents,
+            successors: Vec::new(),
+            taxa: HashMap::new(),
+            duplications: 0,
+            losses: 0,
+        }
+    }
+
+    /// Gene lineage on the branch entering `species_node`.
+    fn lineage(&mut self, species_node: Node) -> Option<usize> {
+        if species_node != self.species.root() && self.rng.gen_bool(self.params.loss_rate) {
+            self.losses += 1;
+            return None;
+        }
+        if self.rng.gen_bool(self.params.duplication_rate) {
+            let copies = vec![self.at_node(species_node), self.at_node(species_node)];
+            return self.join(copies, true);
+        }
+        self.at_node(species_node)
+    }
+
+    /// Gene lineage reaching `species_node`.
+    fn at_node(&mut self, species_node: Node) -> Option<usize> {
+        let graph = self.species.graph();
+        if graph.is_leaf(species_node) {
+            let gene_node = self.add_node(Vec::new());
+            if let Some(taxon) = self.species.taxa().get(&species_node) {
+                self.taxa.insert(gene_node, taxon.clone());
+            }
+            return Some(gene_node);
+        }
+        let children: Vec<Node> = graph.get_successors(species_node)
+            .iter()
+            .copied()
+            .filter(|child| self.chosen_parents.get(child).map_or(true, |parent| *parent == species_node))
+            .collect();
+        let subtrees = children.into_iter()
+            .map(|child| self.lineage(child))
+            .collect();
+        self.join(subtrees, false)
+    }
+
+    fn join(&mut self, subtrees: Vec<Option<usize>>, duplication: bool) -> Option<usize> {
+        let alive: Vec<usize> = subtrees.into_iter().flatten().collect();
+        match alive.len() {
+            0 => None,
+            1 => Some(alive[0]),
+            _ => {
+                if duplication {
+                    self.duplications += 1;
+                }
+                Some(self.add_node(alive))
+            },
+        }
+    }
+
+    fn add_node(&mut self, successors: Vec<usize>) -> usize {
+        self.successors.push(successors);
+        self.successors.len() - 1
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn finish(self, root: usize) -> SimulatedGene {
+        // Nodes are created bottom-up, so the root is the last one.
+        debug_assert_eq!(root, self.successors.len() - 1);
+        let arrows = self.successors.iter()
+            .enumerate()
+            .flat_map(|(source, targets)| {
+                targets.iter().map(move |target| ArrowDTO::new(source as i32, *target as i32))
+            })
+            .collect();
+        let taxa = self.taxa.into_iter()
+            .map(|(node, taxon)| (node as i32, taxon.value().clone()))
+            .collect();
+        let graph = DirectedGraphDTO::new(self.successors.len() as i32, arrows);
+        let dto = PhylogeneticNetworkDTO::new(graph, taxa);
+        SimulatedGene {
+            network: PhylogeneticNetwork::from_dto_with_options(&dto, &options(self.species))
+                .expect("Simulated gene tree has to be valid."),
+            duplications: self.duplications,
+            losses: self.losses,
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use core::fmt::{Display, Formatter};
+
+use crate::raf_array::immutable_string::{ImmutableString, NewImmutableStringError};
+
+use super::Taxon;
+
+/// Characters allowed in taxa by [`TaxonPolicy`].
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+pub enum TaxonCharacters {
+    /// Every character.
+    #[default]
+    Any,
+
+    /// Every character except control characters.
+    Printable,
+
+    /// Alphanumeric characters, `_`, `-` and `.`, none of which requires
+    /// quoting in Newick.
+    Alphanumeric,
+}
+
+impl TaxonCharacters {
+    /// Checks whether `c` belongs to the class.
+    pub fn contains(self, c: char) -> bool {
+        match self {
+            TaxonCharacters::Any => true,
+            TaxonCharacters::Printable => !c.is_control(),
+            TaxonCharacters::Alphanumeric => c.is_alphanumeric() || "_-.".contains(c),
+        }
+    }
+}
+
+/// Normalization and validation rules of taxa, see
+/// [`Taxon::new_validated`] and [`Taxon::normalized`]. Default policy
+/// accepts every taxon as it is, like [`Taxon::new`].
+#[allow(clippy::struct_excessive_bools)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+pub struct TaxonPolicy {
+    trim_whitespace: bool,
+    collapse_whitespace: bool,
+    fold_case: bool,
+    max_length: Option<usize>,
+    allowed_characters: TaxonCharacters,
+}
+
+/// Error of [`Taxon::new_validated`].
+#[derive(Debug)]
+pub enum TaxonError {
+    /// Normalized taxon has more characters than
+    /// [`TaxonPolicy::max_length`].
+    TooLong { length: usize, limit: usize },
+
+    /// Normalized taxon contains character outside of
+    /// [`TaxonPolicy::allowed_characters`]. Holds the first such.
+    InvalidCharacter(char),
+
+    /// Forwarded error of [`ImmutableString::new`].
+    InvalidString(NewImmutableStringError),
+}
+
+impl TaxonPolicy {
+    /// Whether leading and trailing whitespace is removed. `false` by
+    /// default.
+    #[inline(always)]
+    pub fn trim_whitespace(&self) -> bool {
+        self.trim_whitespace
+    }
+
+    /// Whether each run of internal whitespace is replaced by a single `_`,
+    /// following the Newick convention. `false` by default.
+    #[inline(always)]
+    pub fn collapse_whitespace(&self) -> bool {
+        self.collapse_whitespace
+    }
+
+    /// Whether taxa are lowercased, so that taxa differing only in case
+    /// compare equal. `false` by default.
+    #[inline(always)]
+    pub fn fold_case(&self) -> bool {
+        self.fold_case
+    }
+
+    /// Maximal number of characters of normalized taxon. Unbounded by
+    /// default.
+    #[inline(always)]
+    pub fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+
+    /// Characters allowed in normalized taxon. [`TaxonCharacters::Any`] by
+    /// default.
+    #[inline(always)]
+    pub fn allowed_characters(&self) -> TaxonCharacters {
+        self.allowed_characters
+    }
+
+    #[must_use]
+    pub fn with_trim_whitespace(mut self, value: bool) -> Self {
+        self.trim_whitespace = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_collapse_whitespace(mut self, value: bool) -> Self {
+        self.collapse_whitespace = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_fold_case(mut self, value: bool) -> Self {
+        self.fold_case = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_length(mut self, value: Option<usize>) -> Self {
+        self.max_length = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_allowed_characters(mut self, value: TaxonCharacters) -> Self {
+        self.allowed_characters = value;
+        self
+    }
+
+    /// Applies trimming, whitespace collapsing and case folding to `text`.
+    pub fn normalize(&self, text: &str) -> String {
+        let text = if self.trim_whitespace { text.trim() } else { text };
+        let mut result = String::with_capacity(text.len());
+        let mut in_whitespace = false;
+        for c in text.chars() {
+            if self.collapse_whitespace && c.is_whitespace() {
+                if !in_whitespace {
+                    result.push('_');
+                }
+                in_whitespace = true;
+                continue;
+            }
+            in_whitespace = false;
+            if self.fold_case {
+                result.extend(c.to_lowercase());
+            }
+            else
+            {
+                result.push(c);
+            }
+        }
+        result
+    }
+
+    /// Checks already normalized `text` against length and character rules.
+    ///
+    /// # Errors
+    /// For specific errors read [`TaxonError`] docs.
+    pub fn validate(&self, text: &str) -> Result<(), TaxonError> {
+        if let Some(limit) = self.max_length {
+            let length = text.chars().count();
+            if length > limit {
+                return Err(TaxonError::TooLong { length: length, limit: limit });
+            }
+        }
+        match text.chars().find(|c| !self.allowed_characters.contains(*c)) {
+            Some(c) => Err(TaxonError::InvalidCharacter(c)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Taxon {
+    /// Constructs new [`Taxon`] out of `text` normalized and then
+    /// validated according to `policy`.
+    ///
+    /// # Errors
+    /// For specific errors read [`TaxonError`] docs.
+    pub fn new_validated(text: &str, policy: &TaxonPolicy) -> Result<Self, TaxonError> {
+        let text = policy.normalize(text);
+        policy.validate(&text)?;
+        let imm = ImmutableString::new(&text).map_err(TaxonError::InvalidString)?;
+        Ok(Self::from(imm))
+    }
+
+    /// Returns taxon normalized according to `policy`, without validation.
+    /// Taxon is returned unchanged if normalization makes it too long to
+    /// be stored, which is possible only due to case folding.
+    #[must_use]
+    pub fn normalized(&self, policy: &TaxonPolicy) -> Taxon {
+        let text = policy.normalize(self.value().as_str());
+        if text == self.value().as_str() {
+            return self.clone();
+        }
+        ImmutableString::new(&text).map_or_else(|_| self.clone(), Taxon::from)
+    }
+}
+
+impl Display for TaxonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TaxonError::TooLong { length, limit }
+                => write!(f, "taxon has {length} characters, exceeding the limit of {limit}"),
+            TaxonError::InvalidCharacter(c)
+                => write!(f, "taxon contains disallowed character {c:?}"),
+            TaxonError::InvalidString(_)
+                => f.write_str("taxon cannot be stored as immutable string"),
+        }
+    }
+}
+
+impl std::error::Error for TaxonError { }
@@ -0,0 +1,273 @@
+use core::hash::{Hash, Hasher};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::raf_array::immutable_string::NewImmutableStringError;
+
+use super::Taxon;
+
+/// Dense identifier of a [`Taxon`] within a [`TaxonRegistry`].
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug)]
+pub struct TaxonId(u32);
+
+impl TaxonId {
+    #[inline(always)]
+    pub fn value(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<TaxonId> for usize {
+    #[inline(always)]
+    fn from(value: TaxonId) -> Self {
+        value.0 as usize
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+struct TaxonRegistryInner {
+    taxa: Vec<Taxon>,
+    ids: HashMap<Taxon, TaxonId>,
+}
+
+/// Interns [`Taxon`]s to dense [`TaxonId`]s, assigned in registration order
+/// starting from 0. Cheap to clone: the data is shared until one of the
+/// clones registers a new taxon.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct TaxonRegistry {
+    inner: Arc<TaxonRegistryInner>,
+}
+
+impl TaxonRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.inner.taxa.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.inner.taxa.is_empty()
+    }
+
+    /// Registers `taxon` and returns its id. Returns the existing id if
+    /// `taxon` is already registered.
+    ///
+    /// # Panics
+    /// When the registry already holds `u32::MAX` taxa.
+    pub fn register_taxon(&mut self, taxon: &Taxon) -> TaxonId {
+        if let Some(id) = self.id_of(taxon) {
+            return id;
+        }
+        let inner = Arc::make_mut(&mut self.inner);
+        let id = TaxonId(u32::try_from(inner.taxa.len()).expect("Too many taxa."));
+        inner.taxa.push(taxon.clone());
+        inner.ids.insert(taxon.clone(), id);
+        id
+    }
+
+    /// Registers taxon given by its name. See [`TaxonRegistry::register_taxon`].
+    ///
+    /// # Errors
+    /// [`NewImmutableStringError`] forwarded from [`Taxon::new`].
+    pub fn register(&mut self, taxon: &str) -> Result<TaxonId, NewImmutableStringError> {
+        let taxon = Taxon::new(taxon)?;
+        Ok(self.register_taxon(&taxon))
+    }
+
+    /// # Panics
+    /// When `id` doesn't come from this registry.
+    #[inline(always)]
+    pub fn get(&self, id: TaxonId) -> &Taxon {
+        &self.inner.taxa[usize::from(id)]
+    }
+
+    #[inline(always)]
+    pub fn id_of(&self, taxon: &Taxon) -> Option<TaxonId> {
+        self.inner.ids.get(taxon).copied()
+    }
+
+    /// Iterates over registered taxa ordered by id.
+    pub fn iter(&self) -> impl Iterator<Item=(TaxonId, &Taxon)> + '_ {
+        self.inner.taxa
+            .iter()
+            .enumerate()
+            .map(|(idx, taxon)| {
+                #[allow(clippy::cast_possible_truncation)]
+                (TaxonId(idx as u32), taxon)
+            })
+    }
+}
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Set of [`TaxonId`]s backed by a growable bitset. Words are kept without
+/// trailing zeros, so equal sets compare and hash equally.
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Default)]
+pub struct TaxonIdSet {
+    words: Vec<u64>,
+}
+
+impl TaxonIdSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts `id`. Returns `true` if it was not present.
+    pub fn insert(&mut self, id: TaxonId) -> bool {
+        let (word, mask) = position(id);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let previous = self.words[word];
+        self.words[word] = previous | mask;
+        previous & mask == 0
+    }
+
+    pub fn contains(&self, id: TaxonId) -> bool {
+        let (word, mask) = position(id);
+        self.words.get(word).is_some_and(|value| value & mask != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    pub fn is_subset(&self, other: &TaxonIdSet) -> bool {
+        self.words.iter()
+            .enumerate()
+            .all(|(idx, word)| word & !other.words.get(idx).copied().unwrap_or(0) == 0)
+    }
+
+    pub fn union_with(&mut self, other: &TaxonIdSet) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other_word) in self.words.iter_mut().zip(&other.words) {
+            *word |= other_word;
+        }
+    }
+
+    pub fn intersect_with(&mut self, other: &TaxonIdSet) {
+        self.words.truncate(other.words.len());
+        for (word, other_word) in self.words.iter_mut().zip(&other.words) {
+            *word &= other_word;
+        }
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+
+    /// Iterates over ids in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item=TaxonId> + '_ {
+        self.words.iter()
+            .enumerate()
+            .flat_map(|(idx, word)| {
+                let mut word = *word;
+                core::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    #[allow(clippy::cast_possible_truncation)]
+                    Some(TaxonId((idx * WORD_BITS + bit) as u32))
+                })
+            })
+    }
+}
+
+/// Set of [`Taxon`]s of a fixed [`TaxonRegistry`], backed by [`TaxonIdSet`].
+/// Set operations, equality and hashing consider ids only and thus require
+/// sets built over the same registry.
+#[derive(Clone, Debug)]
+pub struct TaxonBitSet {
+    registry: TaxonRegistry,
+    ids: TaxonIdSet,
+}
+
+impl TaxonBitSet {
+    pub fn new(registry: &TaxonRegistry) -> Self {
+        Self { registry: registry.clone(), ids: TaxonIdSet::new() }
+    }
+
+    #[inline(always)]
+    pub fn registry(&self) -> &TaxonRegistry {
+        &self.registry
+    }
+
+    #[inline(always)]
+    pub fn ids(&self) -> &TaxonIdSet {
+        &self.ids
+    }
+
+    /// Inserts `taxon`. Returns `true` if it was not present.
+    ///
+    /// # Panics
+    /// When `taxon` is not registered in the set's registry.
+    pub fn insert(&mut self, taxon: &Taxon) -> bool {
+        let id = self.registry.id_of(taxon).expect("Taxon not present in TaxonBitSet registry.");
+        self.ids.insert(id)
+    }
+
+    /// Returns `false` for taxa not registered in the set's registry.
+    pub fn contains(&self, taxon: &Taxon) -> bool {
+        self.registry.id_of(taxon).is_some_and(|id| self.ids.contains(id))
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn union_with(&mut self, other: &TaxonBitSet) {
+        self.ids.union_with(&other.ids);
+    }
+
+    #[inline(always)]
+    pub fn intersect_with(&mut self, other: &TaxonBitSet) {
+        self.ids.intersect_with(&other.ids);
+    }
+
+    #[inline(always)]
+    pub fn is_subset_of(&self, other: &TaxonBitSet) -> bool {
+        self.ids.is_subset(&other.ids)
+    }
+
+    /// Iterates over taxa ordered by their ids.
+    pub fn iter(&self) -> impl Iterator<Item=&Taxon> + '_ {
+        self.ids.iter().map(|id| self.registry.get(id))
+    }
+}
+
+impl PartialEq for TaxonBitSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.ids == other.ids
+    }
+}
+
+impl Eq for TaxonBitSet { }
+
+impl Hash for TaxonBitSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ids.hash(state);
+    }
+}
+
+#[inline(always)]
+fn position(id: TaxonId) -> (usize, u64) {
+    let idx = usize::from(id);
+    (idx / WORD_BITS, 1 << (idx % WORD_BITS))
+}
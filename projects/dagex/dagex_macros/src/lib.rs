@@ -9,10 +9,13 @@
 )]
 mod converter;
 
+use std::path::PathBuf;
+
 #[allow(unused_imports)]
-use dagex_impl::phylo::{parse_newick_from_str, PhylogeneticNetwork};
+use dagex_impl::phylo::{parse_newick_forest_from_str, parse_newick_from_str, PhylogeneticNetwork};
 
 use proc_macro::TokenStream;
+use quote::quote;
 use syn::{parse_macro_input, LitStr};
 
 /// Constructs [`PhylogeneticNetwork`] from Newick string at compile time.
@@ -29,3 +32,76 @@ pub fn const_parse_newick(input: TokenStream) -> TokenStream {
         .network;
     converter::convert(&network).into()
 }
+
+/// Constructs [`PhylogeneticNetwork`] at compile time from a file containing
+/// a single Newick network. The path is relative to `CARGO_MANIFEST_DIR` of
+/// the calling crate, and the file is tracked for rebuilds.
+///
+/// Fails to compile, pointing at the path, when the file cannot be read,
+/// cannot be parsed according to [`parse_newick_from_str`], or contains
+/// more than one network. For the latter see
+/// [`const_parse_newick_forest_file!`].
+#[proc_macro]
+pub fn const_parse_newick_file(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as LitStr);
+    let (path, mut networks) = match read_newick_file(&input) {
+        Ok(result) => result,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    if networks.len() != 1 {
+        let msg = format!(
+            "Newick file `{path}` contains {} networks, expected exactly one. \
+            Use const_parse_newick_forest_file! for multiple networks.",
+            networks.len());
+        return syn::Error::new(input.span(), msg).to_compile_error().into();
+    }
+    let network = converter::convert(&networks.remove(0));
+    quote! {
+        {
+            const _: &str = include_str!(#path);
+            #network
+        }
+    }.into()
+}
+
+/// Constructs `Vec<PhylogeneticNetwork>` at compile time from a file
+/// containing Newick networks, in file order. Works like
+/// [`const_parse_newick_file!`], except that any number of networks is
+/// allowed.
+#[proc_macro]
+pub fn const_parse_newick_forest_file(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as LitStr);
+    let (path, networks) = match read_newick_file(&input) {
+        Ok(result) => result,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let networks = networks.iter().map(converter::convert);
+    quote! {
+        {
+            const _: &str = include_str!(#path);
+            vec![#(#networks),*]
+        }
+    }.into()
+}
+
+/// Returns the resolved path together with parsed networks.
+fn read_newick_file(input: &LitStr) -> Result<(String, Vec<PhylogeneticNetwork>), syn::Error> {
+    let mut path = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    path.push(input.value());
+    let path = path.to_string_lossy().into_owned();
+    let text = std::fs::read_to_string(&path)
+        .map_err(|err| syn::Error::new(input.span(), format!("Cannot read Newick file `{path}`: {err}")))?;
+    let mut networks = Vec::new();
+    for (idx, result) in parse_newick_forest_from_str(&text).into_iter().enumerate() {
+        match result {
+            Ok(ok) => networks.push(ok.network),
+            Err(err) => {
+                let msg = format!("Cannot parse network {idx} of Newick file `{path}`: {err}");
+                return Err(syn::Error::new(input.span(), msg));
+            },
+        }
+    }
+    Ok((path, networks))
+}
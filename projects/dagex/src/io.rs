@@ -0,0 +1,213 @@
+//! Loading and saving single networks from and to files.
+//!
+//! With the `gzip` feature enabled, files whose name ends with `.gz`, e.g.
+//! `network.nwk.gz`, are decompressed on load and compressed on save.
+use core::fmt::{Display, Formatter};
+use std::{fs, io::{BufWriter, Write}, path::Path};
+
+#[cfg(feature = "gzip")]
+use std::io::Read;
+
+#[cfg(feature = "gzip")]
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use dagex_impl::phylo::{
+    parse_newick_from_str,
+    write_newick,
+    CanonicalTextParseError,
+    NewickParseError,
+    NewickWriteError,
+    PhylogeneticNetwork,
+    PhylogeneticNetworkDTO,
+    PhylogeneticNetworkFromError};
+
+/// File format of a single network.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Format {
+    /// Newick text, see [`parse_newick_from_str`] and [`write_newick`].
+    Newick,
+
+    /// Canonical text format, see
+    /// [`PhylogeneticNetworkDTO::parse_canonical_text`]. Unlike Newick it
+    /// keeps node ids.
+    CanonicalText,
+
+    /// When loading, [`Format::CanonicalText`] if the first line which is
+    /// neither blank nor a `#` comment is the `nodes` header, and
+    /// [`Format::Newick`] otherwise. When saving, [`Format::Newick`].
+    Auto,
+}
+
+impl Format {
+    /// Detects format of `content`, see [`Format::Auto`]. Never returns
+    /// [`Format::Auto`].
+    pub fn detect(content: &str) -> Format {
+        let first_line = content.lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'));
+        match first_line {
+            Some(line) if line.strip_prefix("nodes").is_some_and(|rest| rest.starts_with(char::is_whitespace))
+                => Format::CanonicalText,
+            _ => Format::Newick,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    /// File couldn't be read, decompressed, or is not valid utf-8.
+    Io(std::io::Error),
+
+    /// Content is not a valid Newick network.
+    Newick(NewickParseError),
+
+    /// Content is not valid canonical text.
+    CanonicalText(CanonicalTextParseError),
+
+    /// Content is valid canonical text, but doesn't describe a valid
+    /// network.
+    CanonicalTextNetwork(PhylogeneticNetworkFromError),
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    /// File couldn't be created, written or compressed.
+    Io(std::io::Error),
+
+    /// Forwarded error of [`write_newick`].
+    Newick(NewickWriteError),
+}
+
+/// Loads a single network from file at `path`, detecting its format with
+/// [`Format::detect`].
+///
+/// # Errors
+/// For concrete errors see [`LoadError`] docs.
+pub fn load_network(path: &Path) -> Result<PhylogeneticNetwork, LoadError> {
+    load_network_as(path, Format::Auto)
+}
+
+/// Loads a single network from file at `path` in the given `format`.
+///
+/// # Errors
+/// For concrete errors see [`LoadError`] docs.
+pub fn load_network_as(path: &Path, format: Format) -> Result<PhylogeneticNetwork, LoadError> {
+    let content = read_content(path).map_err(LoadError::Io)?;
+    let format = match format {
+        Format::Auto => Format::detect(&content),
+        _ => format,
+    };
+    match format {
+        Format::CanonicalText => {
+            let dto = PhylogeneticNetworkDTO::parse_canonical_text(&content)
+                .map_err(LoadError::CanonicalText)?;
+            PhylogeneticNetwork::from_dto(&dto).map_err(LoadError::CanonicalTextNetwork)
+        },
+        _ => {
+            parse_newick_from_str(&content)
+                .map(|ok| ok.network)
+                .map_err(LoadError::Newick)
+        },
+    }
+}
+
+/// Saves `network` to file at `path` in the given `format`, replacing the
+/// file if it exists.
+///
+/// # Errors
+/// For concrete errors see [`SaveError`] docs.
+pub fn save_network(path: &Path, network: &PhylogeneticNetwork, format: Format)
+    -> Result<(), SaveError>
+{
+    let file = fs::File::create(path).map_err(SaveError::Io)?;
+    let mut output = BufWriter::new(file);
+    #[cfg(feature = "gzip")]
+    if is_gzip(path) {
+        let mut encoder = GzEncoder::new(&mut output, Compression::default());
+        write_content(&mut encoder, network, format)?;
+        encoder.finish().map_err(SaveError::Io)?;
+        return sync(output);
+    }
+    write_content(&mut output, network, format)?;
+    sync(output)
+}
+
+fn read_content(path: &Path) -> std::io::Result<String> {
+    #[cfg(feature = "gzip")]
+    if is_gzip(path) {
+        let mut content = String::new();
+        GzDecoder::new(fs::File::open(path)?).read_to_string(&mut content)?;
+        return Ok(content);
+    }
+    fs::read_to_string(path)
+}
+
+fn write_content<TWrite: Write>(output: &mut TWrite, network: &PhylogeneticNetwork, format: Format)
+    -> Result<(), SaveError>
+{
+    match format {
+        Format::CanonicalText => {
+            network.into_dto()
+                .write_canonical_text(output)
+                .map_err(SaveError::Io)
+        },
+        Format::Newick | Format::Auto => {
+            write_newick(network, output)
+                .map(|_| ())
+                .map_err(SaveError::Newick)
+        },
+    }
+}
+
+fn sync(output: BufWriter<fs::File>) -> Result<(), SaveError> {
+    output.into_inner()
+        .map_err(|err| SaveError::Io(err.into_error()))?
+        .sync_all()
+        .map_err(SaveError::Io)
+}
+
+#[cfg(feature = "gzip")]
+fn is_gzip(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "gz")
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LoadError::Io(_) => f.write_str("failed to read network file"),
+            LoadError::Newick(_) => f.write_str("failed to load network as Newick"),
+            LoadError::CanonicalText(_) => f.write_str("failed to load network as canonical text"),
+            LoadError::CanonicalTextNetwork(_)
+                => f.write_str("canonical text doesn't describe a valid network"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(err) => Some(err),
+            LoadError::Newick(err) => Some(err),
+            LoadError::CanonicalText(err) => Some(err),
+            LoadError::CanonicalTextNetwork(err) => Some(err),
+        }
+    }
+}
+
+impl Display for SaveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SaveError::Io(_) => f.write_str("failed to write network file"),
+            SaveError::Newick(_) => f.write_str("failed to save network as Newick"),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveError::Io(err) => Some(err),
+            SaveError::Newick(err) => Some(err),
+        }
+    }
+}
@@ -16,3 +16,31 @@ extern crate dagex_macros;
 
 pub use dagex_impl::*;
 pub use dagex_macros::*;
+
+pub mod io;
+pub mod prelude;
+
+/// Compile time diagnostics of file based macros. Paths are relative to the
+/// `dagex` crate.
+///
+/// ```
+/// let network = dagex::const_parse_newick_file!("tests/data/species.nwk");
+/// assert_eq!(network.taxa().len(), 4);
+/// ```
+///
+/// Missing file:
+/// ```compile_fail
+/// let network = dagex::const_parse_newick_file!("tests/data/missing.nwk");
+/// ```
+///
+/// More than one network:
+/// ```compile_fail
+/// let network = dagex::const_parse_newick_file!("tests/data/two_networks.nwk");
+/// ```
+///
+/// Malformed file:
+/// ```compile_fail
+/// let networks = dagex::const_parse_newick_forest_file!("Cargo.toml");
+/// ```
+#[doc(hidden)]
+pub mod macro_diagnostics { }
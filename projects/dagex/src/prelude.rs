@@ -0,0 +1,29 @@
+//! Commonly used types, functions and macros, meant to be glob imported.
+//!
+//! ```
+//! use dagex::prelude::*;
+//!
+//! let network = const_parse_newick!("((A, B), C);");
+//! let leaf = network.get_single_by_taxon("A").unwrap();
+//! assert!(network.graph().is_leaf(leaf));
+//! ```
+pub use dagex_impl::core::{
+    ArrowDTO,
+    DirectedGraph,
+    DirectedGraphDTO,
+    Node};
+pub use dagex_impl::phylo::{
+    parse_newick,
+    parse_newick_forest,
+    parse_newick_forest_from_str,
+    parse_newick_from_str,
+    write_newick,
+    GenesOverSpecies,
+    PhylogeneticNetwork,
+    PhylogeneticNetworkDTO,
+    Taxon};
+pub use dagex_impl::raf_array::immutable_string::ImmutableString;
+pub use dagex_macros::{
+    const_parse_newick,
+    const_parse_newick_file,
+    const_parse_newick_forest_file};
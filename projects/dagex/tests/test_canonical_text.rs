@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use dagex::{
+    core::{ArrowDTO, DirectedGraphDTO},
+    phylo::{
+        newick_to_canonical_text,
+        parse_newick_from_str,
+        CanonicalTextParseError,
+        PhylogeneticNetwork,
+        PhylogeneticNetworkDTO,
+        PhylogeneticNetworkOptions},
+    raf_array::immutable_string::ImmutableString};
+use rstest::rstest;
+
+fn build_dto(arrows: &[(i32, i32)], taxa: &[(i32, &str)], number_of_nodes: i32) -> PhylogeneticNetworkDTO {
+    let arrows: Vec<ArrowDTO> = arrows.iter()
+        .map(|p| ArrowDTO::new(p.0, p.1))
+        .collect();
+    let taxa: HashMap<i32, ImmutableString> = taxa.iter()
+        .map(|p| (p.0, ImmutableString::new(p.1).unwrap()))
+        .collect();
+    PhylogeneticNetworkDTO::new(DirectedGraphDTO::new(number_of_nodes, arrows), taxa)
+}
+
+#[test]
+fn test_write_is_deterministic() {
+    let first = build_dto(&[(0, 2), (0, 1)], &[(2, "Pan"), (1, "Homo_sapiens")], 3);
+    let second = build_dto(&[(0, 1), (0, 2)], &[(1, "Homo_sapiens"), (2, "Pan")], 3);
+    let expected = "nodes 3\n0 -> 1\n0 -> 2\nleaf 1 = \"Homo_sapiens\"\nleaf 2 = \"Pan\"\n";
+    assert_eq!(first.to_canonical_text(), expected);
+    assert_eq!(second.to_canonical_text(), expected);
+}
+
+#[test]
+fn test_parse_with_comments_and_escapes() {
+    let text = "# fixture\n\nnodes 3\n  0 -> 1\n# arrows\n0->2\n\nleaf 1 = \"a \\\"quoted\\\" name\"\nleaf 2 = \"b\\\\c\"\n";
+    let dto = PhylogeneticNetworkDTO::parse_canonical_text(text).unwrap();
+    assert_eq!(dto.graph().number_of_nodes(), 3);
+    assert_eq!(dto.graph().arrows().len(), 2);
+    assert_eq!(dto.taxa()[&1].as_str(), "a \"quoted\" name");
+    assert_eq!(dto.taxa()[&2].as_str(), "b\\c");
+    let again = PhylogeneticNetworkDTO::parse_canonical_text(&dto.to_canonical_text()).unwrap();
+    assert_eq!(again, dto);
+}
+
+#[rstest]
+#[case("((A,B),C);")]
+#[case("((A, (D)B#1),(B#1, C));")]
+#[case("((X, (b, ((L1,L2), (L3,L4)))), (d, (c, a)));")]
+fn test_newick_round_trip(#[case] newick: &str) {
+    let network = parse_newick_from_str(newick).unwrap().network;
+    let text = newick_to_canonical_text(newick).unwrap();
+    let dto = PhylogeneticNetworkDTO::parse_canonical_text(&text).unwrap();
+    assert_eq!(dto.to_canonical_text(), text);
+    let options = PhylogeneticNetworkOptions::default().with_allow_internal_taxa(true);
+    let parsed = PhylogeneticNetwork::from_dto_with_options(&dto, &options).unwrap();
+    assert_eq!(parsed.to_canonical_dto(), network.to_canonical_dto());
+}
+
+#[rstest]
+#[case("((A,B),C);", "(C,(B,A));")]
+#[case("((A,(D)B#1),(B#1,C));", "((C,B#1),((D)B#1,A));")]
+#[case("((X,(b,((L1,L2),(L3,L4)))),(d,(c,a)));", "(((a,c),d),((((L4,L3),(L2,L1)),b),X));")]
+fn test_newick_order_independent(#[case] first: &str, #[case] second: &str) {
+    let text = newick_to_canonical_text(first).unwrap();
+    assert_eq!(newick_to_canonical_text(second).unwrap().as_bytes(), text.as_bytes());
+}
+
+#[rstest]
+#[case("0 -> 1\n", 1)]
+#[case("nodes 2\nnodes 2\n", 2)]
+#[case("nodes 2\n0 => 1\n", 2)]
+#[case("nodes 2\nleaf 1 = A\n", 2)]
+#[case("nodes 2\nleaf 1 = \"A\"\nleaf 1 = \"B\"\n", 3)]
+fn test_parse_errors(#[case] text: &str, #[case] line: usize) {
+    let err = PhylogeneticNetworkDTO::parse_canonical_text(text).unwrap_err();
+    match err {
+        CanonicalTextParseError::MissingNodesHeader => assert_eq!(line, 1),
+        CanonicalTextParseError::DuplicatedNodesHeader(no)
+        | CanonicalTextParseError::InvalidLine(no, _)
+        | CanonicalTextParseError::DuplicatedTaxon(no, _) => assert_eq!(no, line),
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashSet;
+
+use dagex::{
+    core::Node,
+    phylo::{parse_newick_from_str, PhylogeneticNetwork, Taxon, TaxonBitSet, TaxonIdSet, TaxonRegistry}};
+
+fn parse(text: &str) -> PhylogeneticNetwork {
+    parse_newick_from_str(text).unwrap().network
+}
+
+fn names(cluster: &TaxonBitSet) -> Vec<&str> {
+    cluster.iter().map(|taxon| taxon.value().as_str()).collect()
+}
+
+fn parent_of(network: &PhylogeneticNetwork, taxon: &str) -> Node {
+    let leaf = network.get_single_by_taxon(taxon).unwrap();
+    network.graph().get_predecessors(leaf)[0]
+}
+
+#[test]
+fn test_tree_clusters() {
+    let network = parse("((A,B),(B,C));");
+    let clusters = network.hardwired_clusters();
+    assert_eq!(clusters.len(), 7);
+    assert_eq!(names(&clusters[&network.root()]).len(), 3);
+    let leaf_clusters: HashSet<Vec<&str>> = network.graph().leaves()
+        .iter()
+        .map(|leaf| names(&clusters[leaf]))
+        .collect();
+    assert_eq!(leaf_clusters, HashSet::from([vec!["A"], vec!["B"], vec!["C"]]));
+    let mut inner: Vec<Vec<&str>> = network.graph().get_successors(network.root())
+        .iter()
+        .map(|node| {
+            let mut cluster = names(&clusters[node]);
+            cluster.sort_unstable();
+            cluster
+        })
+        .collect();
+    inner.sort();
+    assert_eq!(inner, vec![vec!["A", "B"], vec!["B", "C"]]);
+}
+
+#[test]
+fn test_reticulation_clusters() {
+    let network = parse("((A,(D)#H1),(#H1,C));");
+    let clusters = network.hardwired_clusters();
+    let reticulation = parent_of(&network, "D");
+    assert!(network.is_reticulation_node(reticulation));
+    assert_eq!(names(&clusters[&reticulation]), vec!["D"]);
+    let with_a = &clusters[&parent_of(&network, "A")];
+    let with_c = &clusters[&parent_of(&network, "C")];
+    let d = Taxon::new("D").unwrap();
+    assert!(with_a.contains(&d) && with_c.contains(&d));
+    assert_eq!(with_a.len(), 2);
+    assert_eq!(with_c.len(), 2);
+    let root = &clusters[&network.root()];
+    assert_eq!(root.len(), 3);
+    assert!(with_a.is_subset_of(root) && !with_a.is_subset_of(with_c));
+
+    let mut common = with_a.clone();
+    common.intersect_with(with_c);
+    assert_eq!(common, clusters[&reticulation]);
+    let mut union = with_a.clone();
+    union.union_with(with_c);
+    assert_eq!(union.len(), 3);
+}
+
+#[test]
+fn test_shared_registry() {
+    let mut registry = TaxonRegistry::new();
+    let first = parse("((A,B),C);");
+    let second = parse("((C,B),A);");
+    let first_clusters: HashSet<TaxonBitSet> = first.hardwired_clusters_with_registry(&mut registry)
+        .into_values()
+        .collect();
+    let second_clusters: HashSet<TaxonBitSet> = second.hardwired_clusters_with_registry(&mut registry)
+        .into_values()
+        .collect();
+    assert_eq!(registry.len(), 3);
+    assert_eq!(first_clusters.intersection(&second_clusters).count(), 4);
+    assert!(!TaxonBitSet::new(&registry).contains(&Taxon::new("X").unwrap()));
+}
+
+#[test]
+fn test_taxon_id_set_operations() {
+    let mut registry = TaxonRegistry::new();
+    let ids: Vec<_> = (0..130)
+        .map(|idx| registry.register(&format!("t{idx}")).unwrap())
+        .collect();
+    let mut small = TaxonIdSet::new();
+    small.insert(ids[3]);
+    let mut large = TaxonIdSet::new();
+    large.insert(ids[3]);
+    large.insert(ids[129]);
+    let mut union = small.clone();
+    union.union_with(&large);
+    assert_eq!(union, large);
+    assert_eq!(union.iter().collect::<Vec<_>>(), vec![ids[3], ids[129]]);
+    let mut intersection = large.clone();
+    intersection.intersect_with(&small);
+    assert_eq!(intersection, small);
+    let mut empty = large.clone();
+    empty.intersect_with(&TaxonIdSet::new());
+    assert_eq!(empty, TaxonIdSet::new());
+}
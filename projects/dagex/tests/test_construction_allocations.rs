@@ -0,0 +1,63 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering}};
+
+use dagex::core::{ArrowDTO, DirectedGraph, DirectedGraphDTO};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// Counts allocations of the whole test binary, hence the single test.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Every node but the first gets up to 3 random predecessors among nodes
+/// with lower ids. Arrows are shuffled.
+fn random_dag(number_of_nodes: i32) -> DirectedGraphDTO {
+    let mut rng = StdRng::seed_from_u64(564);
+    let mut arrows = Vec::new();
+    for target in 1..number_of_nodes {
+        let mut sources: Vec<i32> = (0..3).map(|_| rng.gen_range(0..target)).collect();
+        sources.sort_unstable();
+        sources.dedup();
+        arrows.extend(sources.into_iter().map(|source| ArrowDTO::new(source, target)));
+    }
+    arrows.shuffle(&mut rng);
+    DirectedGraphDTO::new(number_of_nodes, arrows)
+}
+
+#[test]
+fn test_from_dto_allocations() {
+    let dto = random_dag(1_000_000);
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::SeqCst) - before;
+    assert!(allocations < 100, "Too many allocations: {allocations}");
+    assert_eq!(graph.number_of_arrows(), dto.arrows().len());
+    assert!(graph.basic_properties().acyclic);
+    assert_eq!(graph.root(), Some(0.into()));
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    let cloned = graph.clone();
+    let allocations = ALLOCATIONS.load(Ordering::SeqCst) - before;
+    assert!(allocations < 10, "Too many allocations: {allocations}");
+    assert_eq!(cloned, graph);
+}
@@ -1,4 +1,20 @@
-use dagex::core::{ArrowDTO, DirectedGraph, DirectedGraphDTO, DirectedGraphFromError, Node};
+#![allow(clippy::clone_on_copy, clippy::into_iter_on_ref, clippy::map_clone)]
+
+use std::error::Error;
+
+use dagex::core::{
+    ArrowDTO,
+    ArrowList,
+    ArrowStorage,
+    DirectedGraph,
+    DirectedGraphDTO,
+    DirectedGraphFromError,
+    LevelOrderError,
+    Node,
+    NodeIter,
+    NotAcyclicError,
+    TraversalOrder,
+    DEFAULT_EDGE_LIST_LIMIT};
 use rstest::rstest;
 
 #[test]
@@ -220,6 +236,49 @@ fn test_equality_2() {
     assert_eq!(graph1, graph2);
 }
 
+#[test]
+fn test_arrow_storage() {
+    let graph = build_graph(&[(0, 2), (0, 1), (1, 3), (2, 3)], 4);
+    let graph = graph.with_arrow_added(Node::from(3), Node::from(0)).unwrap();
+    let graph = graph.with_arrow_removed(Node::from(3), Node::from(0)).unwrap();
+    let (graph, _) = graph.with_node_added();
+    assert_eq!(graph.get_successors(Node::from(0)), &[Node::from(1), Node::from(2)]);
+    assert_eq!(graph.get_predecessors(Node::from(3)), &[Node::from(1), Node::from(2)]);
+    assert!(graph.get_successors(Node::from(4)).is_empty());
+    assert!(graph.get_successors(Node::from(5)).is_empty());
+
+    let successors = ArrowStorage::from_raw_parts(
+        vec![0, 2, 3, 4, 4],
+        [1, 2, 3, 3].into_iter().map(Node::from).collect());
+    assert_eq!(successors.len(), 4);
+    assert_eq!(successors.number_of_arrows(), 4);
+    assert_eq!(successors.get(Node::from(2)), &[Node::from(3)]);
+    assert_eq!(format!("{successors:?}"), format!("{:?}", [&[Node::from(1), Node::from(2)][..], &[Node::from(3)], &[Node::from(3)], &[]]));
+    let lists: Vec<ArrowList> = successors.iter().map(ArrowList::from_slice).collect();
+    assert_eq!(ArrowStorage::from_lists(&lists), successors);
+
+    let original = build_graph(&[(0, 1), (0, 2), (1, 3), (2, 3)], 4);
+    let predecessors: Vec<ArrowList> = original.iter_nodes()
+        .map(|node| ArrowList::from_slice(original.get_predecessors(node)))
+        .collect();
+    let compat = unsafe {
+        DirectedGraph::from_arrow_maps_unchecked(
+            4,
+            &lists,
+            &predecessors,
+            original.basic_properties().clone(),
+            original.root(),
+            original.leaves().clone())
+    };
+    assert_eq!(compat, original);
+}
+
+#[test]
+#[should_panic(expected = "Offsets have to end with the number of nodes.")]
+fn test_arrow_storage_invalid_offsets() {
+    let _ = ArrowStorage::from_raw_parts(vec![0, 1, 3], vec![Node::from(1)]);
+}
+
 #[test]
 fn test_unequal() {
     let dto1 = build_dto(&[(0, 1), (1, 2), (1, 3), (2, 4), (3, 5)]);
@@ -231,3 +290,545 @@ fn test_unequal() {
     let graph2 = result2.unwrap();
     assert_ne!(graph1, graph2);
 }
+
+#[test]
+fn test_named_iterators() {
+    let arrows = vec![ArrowDTO::new(0, 2), ArrowDTO::new(0, 1), ArrowDTO::new(1, 3), ArrowDTO::new(2, 3)];
+    let dto = DirectedGraphDTO::new(5, arrows);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+
+    let nodes: NodeIter = graph.iter_nodes();
+    assert_eq!(nodes.len(), 5);
+    let reversed: Vec<i32> = graph.iter_nodes().rev().map(|n| n.id()).collect();
+    assert_eq!(reversed, vec![4, 3, 2, 1, 0]);
+
+    let mut arrows: Vec<(i32, i32)> = graph.iter_arrows()
+        .map(|(s, t)| (s.id(), t.id()))
+        .collect();
+    arrows.sort_unstable();
+    assert_eq!(arrows, vec![(0, 1), (0, 2), (1, 3), (2, 3)]);
+
+    let bfs: Vec<i32> = graph.iter_bfs(Node::from(0)).map(|n| n.id()).collect();
+    assert_eq!(bfs.len(), 4);
+    assert_eq!(bfs[0], 0);
+    assert_eq!(bfs[3], 3);
+    assert_eq!(graph.iter_bfs(Node::from(4)).count(), 1);
+    assert_eq!(graph.iter_bfs(Node::from(10)).count(), 0);
+}
+
+fn build_graph(arrows: &[(i32, i32)], number_of_nodes: i32) -> DirectedGraph {
+    let arrows: Vec<ArrowDTO> = arrows.iter()
+        .map(|p| ArrowDTO::new(p.0, p.1))
+        .collect();
+    DirectedGraph::from_dto(&DirectedGraphDTO::new(number_of_nodes, arrows)).unwrap()
+}
+
+fn topological_ids(graph: &DirectedGraph) -> Vec<i32> {
+    graph.iter_topological().unwrap().map(|n| n.id()).collect()
+}
+
+#[test]
+fn test_topological_diamond() {
+    let graph = build_graph(&[(4, 2), (4, 1), (2, 0), (1, 0), (0, 3)], 5);
+    assert_eq!(topological_ids(&graph), vec![4, 1, 2, 0, 3]);
+}
+
+#[test]
+fn test_topological_forest() {
+    let graph = build_graph(&[(3, 0), (1, 4), (1, 2), (5, 2)], 6);
+    let order = topological_ids(&graph);
+    assert_eq!(order, vec![1, 3, 0, 4, 5, 2]);
+    for (source, target) in graph.iter_arrows() {
+        let s = order.iter().position(|x| *x == source.id()).unwrap();
+        let t = order.iter().position(|x| *x == target.id()).unwrap();
+        assert!(s < t);
+    }
+}
+
+#[test]
+fn test_topological_rejects_cycle() {
+    let graph = build_graph(&[(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)], 5);
+    let error = graph.iter_topological().err().unwrap();
+    let (source, target) = error.cycle_arrow.unwrap();
+    let cycle = [(1, 2), (2, 3), (3, 1)];
+    assert!(cycle.contains(&(source.id(), target.id())), "Invalid arrow: {source:?} -> {target:?}");
+}
+
+fn level_ids(graph: &DirectedGraph) -> Vec<Vec<i32>> {
+    graph.iter_levels().unwrap()
+        .map(|level| level.iter().map(|n| n.id()).collect())
+        .collect()
+}
+
+#[test]
+fn test_levels_binary_tree() {
+    let graph = build_graph(&[(0, 5), (0, 2), (5, 1), (5, 6), (2, 3), (2, 4), (6, 7), (6, 8)], 9);
+    assert_eq!(level_ids(&graph), vec![vec![0], vec![2, 5], vec![1, 3, 4, 6], vec![7, 8]]);
+}
+
+#[test]
+fn test_levels_reticulation() {
+    // Node 4 is a child of 1 (level 1) and of 3 (level 2).
+    let graph = build_graph(&[(0, 1), (0, 2), (2, 3), (1, 4), (3, 4), (4, 5), (3, 6)], 7);
+    assert_eq!(level_ids(&graph), vec![vec![0], vec![1, 2], vec![3], vec![4, 6], vec![5]]);
+    assert_eq!(level_ids(&build_graph(&[], 1)), vec![vec![0]]);
+}
+
+#[test]
+fn test_levels_rejected() {
+    let forest = build_graph(&[(0, 1), (2, 3)], 4);
+    assert!(matches!(forest.iter_levels(), Err(LevelOrderError::NotRooted)));
+    let cyclic = build_graph(&[(0, 1), (1, 2), (2, 1)], 3);
+    let Err(LevelOrderError::NotAcyclic(error)) = cyclic.iter_levels() else {
+        panic!("Expected NotAcyclic error.");
+    };
+    assert!(error.cycle_arrow.is_some());
+
+    // Cycle 2 -> 3 -> 4 -> 2 below node 1, in a graph without a root.
+    let cyclic_forest = build_graph(&[(0, 1), (1, 2), (2, 3), (3, 4), (4, 2), (4, 5), (6, 5)], 7);
+    let Err(LevelOrderError::NotAcyclic(error)) = cyclic_forest.iter_levels() else {
+        panic!("Expected NotAcyclic error.");
+    };
+    let (source, target) = error.cycle_arrow.unwrap();
+    assert!([(2, 3), (3, 4), (4, 2)].contains(&(source.id(), target.id())));
+}
+
+#[test]
+fn test_long_path() {
+    let number_of_nodes = 200_000;
+    let arrows: Vec<ArrowDTO> = (1..number_of_nodes)
+        .map(|idx| ArrowDTO::new(idx - 1, idx))
+        .collect();
+    let graph = DirectedGraph::from_dto(&DirectedGraphDTO::new(number_of_nodes, arrows)).unwrap();
+    let props = graph.basic_properties();
+    assert!(props.acyclic);
+    assert!(props.rooted);
+    assert!(props.connected);
+}
+
+#[test]
+fn test_long_cycle_and_disconnected_path() {
+    let number_of_nodes = 200_000;
+    let mut arrows: Vec<ArrowDTO> = (1..number_of_nodes - 1)
+        .map(|idx| ArrowDTO::new(idx - 1, idx))
+        .collect();
+    arrows.push(ArrowDTO::new(number_of_nodes - 3, 0));
+    let graph = DirectedGraph::from_dto(&DirectedGraphDTO::new(number_of_nodes, arrows)).unwrap();
+    let props = graph.basic_properties();
+    assert!(!props.acyclic);
+    assert!(!props.connected);
+}
+
+fn sorted_ids(nodes: impl Iterator<Item=Node>) -> Vec<i32> {
+    let mut ids: Vec<i32> = nodes.map(|node| node.id()).collect();
+    ids.sort_unstable();
+    ids
+}
+
+#[test]
+fn test_reachability_with_reticulation() {
+    let dto = build_dto(&[(0, 1), (1, 2), (1, 3), (2, 4), (3, 5), (2, 5)]);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    let descendants: Vec<i32> = graph.iter_descendants(Node::from(1)).map(|n| n.id()).collect();
+    assert_eq!(descendants, vec![2, 3, 4, 5]);
+    assert_eq!(sorted_ids(graph.iter_ancestors(Node::from(5))), vec![0, 1, 2, 3]);
+    assert_eq!(graph.iter_descendants(Node::from(4)).count(), 0);
+    assert_eq!(graph.iter_ancestors(Node::from(0)).count(), 0);
+
+    assert!(graph.is_reachable(Node::from(0), Node::from(5)));
+    assert!(graph.is_reachable(Node::from(3), Node::from(3)));
+    assert!(!graph.is_reachable(Node::from(3), Node::from(4)));
+    assert!(!graph.is_reachable(Node::from(5), Node::from(1)));
+}
+
+#[test]
+fn test_reachability_on_cycle() {
+    let dto = build_dto(&[(0, 1), (1, 2), (2, 0), (2, 3)]);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    assert_eq!(sorted_ids(graph.iter_descendants(Node::from(1))), vec![0, 1, 2, 3]);
+    assert_eq!(sorted_ids(graph.iter_ancestors(Node::from(3))), vec![0, 1, 2]);
+    assert!(graph.is_reachable(Node::from(2), Node::from(1)));
+    assert!(!graph.is_reachable(Node::from(3), Node::from(0)));
+}
+
+#[test]
+fn test_reachability_out_of_range() {
+    let dto = build_dto(&[(0, 1)]);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    assert_eq!(graph.iter_descendants(Node::from(-1)).count(), 0);
+    assert_eq!(graph.iter_ancestors(Node::from(2)).count(), 0);
+    assert!(graph.get_successors(Node::from(2)).is_empty());
+    assert!(!graph.is_reachable(Node::from(0), Node::from(2)));
+    assert!(!graph.is_reachable(Node::from(7), Node::from(7)));
+}
+
+fn ids(path: &[Node]) -> Vec<i32> {
+    path.iter().map(|node| node.id()).collect()
+}
+
+#[test]
+fn test_paths_with_reticulation() {
+    let dto = build_dto(&[(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (1, 4)]);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    let paths: Vec<Vec<i32>> = graph.paths_between(Node::from(0), Node::from(4))
+        .map(|path| ids(&path))
+        .collect();
+    assert_eq!(paths, vec![vec![0, 1, 3, 4], vec![0, 1, 4], vec![0, 2, 3, 4]]);
+    assert_eq!(graph.count_paths(Node::from(0), Node::from(4)), 3);
+    assert_eq!(graph.count_paths(Node::from(0), Node::from(3)), 2);
+    assert_eq!(graph.count_paths(Node::from(2), Node::from(1)), 0);
+    assert_eq!(graph.count_paths(Node::from(3), Node::from(3)), 1);
+    assert_eq!(ids(&graph.paths_between(Node::from(3), Node::from(3)).next().unwrap()), vec![3]);
+    assert_eq!(graph.paths_between(Node::from(4), Node::from(0)).count(), 0);
+
+    assert_eq!(graph.shortest_path(Node::from(0), Node::from(4)).map(|path| ids(&path)), Some(vec![0, 1, 4]));
+    assert_eq!(graph.shortest_path(Node::from(2), Node::from(4)).map(|path| path.len()), Some(3));
+    assert_eq!(graph.shortest_path(Node::from(1), Node::from(1)).map(|path| ids(&path)), Some(vec![1]));
+    assert_eq!(graph.shortest_path(Node::from(4), Node::from(0)), None);
+    assert_eq!(graph.shortest_path(Node::from(0), Node::from(5)), None);
+    assert_eq!(graph.count_paths(Node::from(0), Node::from(5)), 0);
+}
+
+#[test]
+fn test_paths_exponential_count() {
+    // Chain of 40 diamonds, 2^40 paths from the first to the last node.
+    let mut arrows = Vec::new();
+    for idx in 0..40 {
+        let base = 3 * idx;
+        arrows.extend([(base, base + 1), (base, base + 2), (base + 1, base + 3), (base + 2, base + 3)]);
+    }
+    let graph = DirectedGraph::from_dto(&build_dto(&arrows)).unwrap();
+    assert_eq!(graph.count_paths(Node::from(0), Node::from(120)), 1 << 40);
+    assert_eq!(graph.paths_between(Node::from(0), Node::from(120)).take(5).count(), 5);
+    assert_eq!(graph.shortest_path(Node::from(0), Node::from(120)).unwrap().len(), 81);
+}
+
+#[test]
+fn test_paths_on_cycle() {
+    let dto = build_dto(&[(0, 1), (1, 2), (2, 0), (2, 3), (1, 3)]);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    let paths: Vec<Vec<i32>> = graph.paths_between(Node::from(0), Node::from(3))
+        .map(|path| ids(&path))
+        .collect();
+    assert_eq!(paths, vec![vec![0, 1, 2, 3], vec![0, 1, 3]]);
+    assert_eq!(graph.count_paths(Node::from(0), Node::from(3)), 2);
+    assert_eq!(graph.count_paths(Node::from(2), Node::from(1)), 1);
+    assert_eq!(graph.shortest_path(Node::from(2), Node::from(1)).map(|path| ids(&path)), Some(vec![2, 0, 1]));
+    assert_eq!(graph.paths_between(Node::from(3), Node::from(0)).count(), 0);
+}
+
+fn reference_depths(graph: &DirectedGraph, start: Node) -> Vec<(i32, u32)> {
+    let mut result: Vec<(i32, u32)> = graph.iter_bfs(start)
+        .map(|node| {
+            let path = graph.shortest_path(start, node).unwrap();
+            (node.id(), u32::try_from(path.len() - 1).unwrap())
+        })
+        .collect();
+    result.sort_unstable();
+    result
+}
+
+fn traversal_ids(iter: impl Iterator<Item=(Node, u32)>) -> Vec<(i32, u32)> {
+    iter.map(|(node, depth)| (node.id(), depth)).collect()
+}
+
+#[test]
+fn test_traversals_with_reticulation() {
+    let dto = build_dto(&[(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (1, 5)]);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    let start = Node::from(0);
+
+    let bfs = traversal_ids(graph.bfs_from(start));
+    assert_eq!(bfs, vec![(0, 0), (1, 1), (2, 1), (3, 2), (5, 2), (4, 3)]);
+    let mut sorted_bfs = bfs.clone();
+    sorted_bfs.sort_unstable();
+    assert_eq!(sorted_bfs, reference_depths(&graph, start));
+    let bfs_nodes: Vec<i32> = graph.iter_bfs(start).map(|node| node.id()).collect();
+    assert_eq!(bfs_nodes, bfs.iter().map(|(id, _)| *id).collect::<Vec<_>>());
+
+    let dfs = traversal_ids(graph.dfs_from(start));
+    assert_eq!(dfs, vec![(0, 0), (1, 1), (3, 2), (4, 3), (5, 2), (2, 1)]);
+    assert_eq!(graph.dfs_from(start).order(), TraversalOrder::DepthFirst);
+
+    let unfolded = traversal_ids(graph.dfs_from(start).unique_visits(false));
+    assert_eq!(unfolded, vec![(0, 0), (1, 1), (3, 2), (4, 3), (5, 2), (2, 1), (3, 2), (4, 3)]);
+
+    assert_eq!(traversal_ids(graph.bfs_from(start).max_depth(1)), vec![(0, 0), (1, 1), (2, 1)]);
+    assert_eq!(traversal_ids(graph.bfs_from(start).max_depth(0)), vec![(0, 0)]);
+    assert_eq!(graph.bfs_from(Node::from(7)).count(), 0);
+}
+
+#[test]
+fn test_traversal_pruning_at_reticulation() {
+    let dto = build_dto(&[(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (1, 5)]);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    let reticulation = |node: Node| graph.get_predecessors(node).len() > 1;
+    let pruned = traversal_ids(graph.bfs_from(Node::from(0)).prune_when(reticulation));
+    assert_eq!(pruned, vec![(0, 0), (1, 1), (2, 1), (3, 2), (5, 2)]);
+    let pruned = traversal_ids(graph.dfs_from(Node::from(0)).unique_visits(false).prune_when(reticulation));
+    assert_eq!(pruned, vec![(0, 0), (1, 1), (3, 2), (5, 2), (2, 1), (3, 2)]);
+}
+
+#[test]
+fn test_traversal_on_cycle() {
+    let dto = build_dto(&[(0, 1), (1, 2), (2, 0), (2, 3)]);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    assert_eq!(traversal_ids(graph.dfs_from(Node::from(1))), vec![(1, 0), (2, 1), (0, 2), (3, 2)]);
+    assert_eq!(graph.bfs_from(Node::from(1)).unique_visits(false).max_depth(6).count(), 9);
+}
+
+#[test]
+fn test_induced_subgraph_from() {
+    let dto = build_dto(&[(0, 1), (1, 2), (1, 3), (2, 4), (3, 5), (2, 5)]);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+
+    let (subgraph, mapping) = graph.induced_subgraph_from(Node::from(2));
+    assert_eq!(mapping, vec![Node::from(2), Node::from(4), Node::from(5)]);
+    assert_eq!(subgraph.number_of_nodes(), 3);
+    assert_eq!(subgraph.root(), Some(Node::from(0)));
+    assert!(subgraph.basic_properties().tree);
+    assert_eq!(subgraph.get_successors(Node::from(0)), &[Node::from(1), Node::from(2)]);
+
+    let (leaf, mapping) = graph.induced_subgraph_from(Node::from(4));
+    assert_eq!(leaf.number_of_nodes(), 1);
+    assert_eq!(mapping, vec![Node::from(4)]);
+
+    let (whole, _) = graph.induced_subgraph_from(Node::from(0));
+    assert_eq!(whole, graph);
+}
+
+#[test]
+fn test_with_arrow_removed_creates_second_root() {
+    let graph = DirectedGraph::from_dto(&build_dto(&[(0, 1), (1, 2), (1, 3), (2, 4), (3, 5), (2, 5)])).unwrap();
+    let modified = graph.with_arrow_removed(Node::from(1), Node::from(3)).unwrap();
+    let props = modified.basic_properties();
+    assert!(!props.rooted);
+    assert!(props.acyclic);
+    assert!(props.connected);
+    assert_eq!(modified.root(), None);
+    assert!(modified.leaves().contains(&Node::from(4)));
+    assert_eq!(modified, DirectedGraph::from_dto(&build_dto(&[(0, 1), (1, 2), (2, 4), (3, 5), (2, 5)])).unwrap());
+
+    let disconnected = modified.with_arrow_removed(Node::from(3), Node::from(5)).unwrap();
+    assert!(!disconnected.basic_properties().connected);
+    assert!(disconnected.basic_properties().tree);
+
+    let result = graph.with_arrow_removed(Node::from(0), Node::from(5));
+    assert!(matches!(result, Err(DirectedGraphFromError::ArrowNotFound(_))), "Invalid result: {result:?}");
+}
+
+#[test]
+fn test_with_arrow_added() {
+    let graph = DirectedGraph::from_dto(&build_dto(&[(0, 1), (0, 2), (1, 3)])).unwrap();
+    let modified = graph.with_arrow_added(Node::from(2), Node::from(3)).unwrap();
+    let props = modified.basic_properties();
+    assert!(props.acyclic);
+    assert!(props.rooted);
+    assert!(!props.tree);
+    assert!(!modified.leaves().contains(&Node::from(2)));
+    assert_eq!(modified.get_predecessors(Node::from(3)), &[Node::from(1), Node::from(2)]);
+    assert_eq!(modified, DirectedGraph::from_dto(&build_dto(&[(0, 1), (0, 2), (1, 3), (2, 3)])).unwrap());
+
+    let cyclic = modified.with_arrow_added(Node::from(3), Node::from(0)).unwrap();
+    assert!(!cyclic.basic_properties().acyclic);
+    assert!(!cyclic.basic_properties().rooted);
+    assert!(cyclic.basic_properties().connected);
+
+    let result = graph.with_arrow_added(Node::from(0), Node::from(1));
+    assert!(matches!(result, Err(DirectedGraphFromError::MultipleParallelArrows(_))), "Invalid result: {result:?}");
+    let result = graph.with_arrow_added(Node::from(0), Node::from(4));
+    assert!(matches!(result, Err(DirectedGraphFromError::ArrowOutsideOfNodesRange(_))), "Invalid result: {result:?}");
+}
+
+#[test]
+fn test_with_node_added() {
+    let graph = DirectedGraph::from_dto(&build_dto(&[(0, 1), (0, 2)])).unwrap();
+    let (modified, node) = graph.with_node_added();
+    assert_eq!(node, Node::from(3));
+    assert_eq!(modified.number_of_nodes(), 4);
+    let props = modified.basic_properties();
+    assert!(!props.rooted);
+    assert!(!props.connected);
+    assert!(props.acyclic);
+    assert!(modified.is_leaf(node));
+
+    let reconnected = modified.with_arrow_added(Node::from(2), node).unwrap();
+    assert!(reconnected.basic_properties().rooted);
+    assert!(reconnected.basic_properties().connected);
+}
+
+#[rstest]
+#[case(&[(0, 1), (1, 2), (1, 3), (2, 4)], 4, (0, 1), (0, 2), 0)]
+#[case(&[(0, 1), (1, 2), (1, 3), (2, 4), (3, 5), (2, 5)], 6, (0, 2), (0, 2), 0)]
+#[case(&[(0, 1), (1, 0), (2, 3), (3, 2)], 4, (1, 1), (1, 1), 0)]
+#[case(&[(0, 1), (4, 4)], 2, (0, 1), (0, 1), 2)]
+fn test_degree_statistics(
+    #[case] arrows: &[(i32, i32)],
+    #[case] number_of_arrows: usize,
+    #[case] in_degrees: (usize, usize),
+    #[case] out_degrees: (usize, usize),
+    #[case] isolated_nodes: usize)
+{
+    let graph = DirectedGraph::from_dto(&build_dto(arrows)).unwrap();
+    assert_eq!(graph.number_of_arrows(), number_of_arrows);
+    assert_eq!(graph.into_dto().arrows().len(), number_of_arrows);
+    let stats = graph.degree_statistics();
+    assert_eq!((stats.min_in_degree, stats.max_in_degree), in_degrees);
+    assert_eq!((stats.min_out_degree, stats.max_out_degree), out_degrees);
+    assert_eq!(stats.isolated_nodes, isolated_nodes);
+    #[allow(clippy::cast_precision_loss)]
+    let mean = number_of_arrows as f64 / f64::from(graph.number_of_nodes());
+    assert!((stats.mean_in_degree - mean).abs() < 1e-9);
+    assert!((stats.mean_out_degree - mean).abs() < 1e-9);
+    assert_eq!(stats, graph.report().degree_statistics);
+
+    let in_sum: usize = graph.iter_nodes().map(|node| graph.in_degree(node)).sum();
+    let out_sum: usize = graph.iter_nodes().map(|node| graph.out_degree(node)).sum();
+    assert_eq!((in_sum, out_sum), (number_of_arrows, number_of_arrows));
+}
+
+#[test]
+fn test_degrees() {
+    let graph = DirectedGraph::from_dto(&build_dto(&[(0, 1), (1, 2), (1, 3), (2, 4), (3, 5), (2, 5)])).unwrap();
+    assert_eq!(graph.out_degree(Node::from(1)), 2);
+    assert_eq!(graph.in_degree(Node::from(5)), 2);
+    assert_eq!(graph.in_degree(Node::from(0)), 0);
+    assert_eq!(graph.in_degree(Node::from(-1)), 0);
+    assert_eq!(graph.out_degree(Node::from(6)), 0);
+
+    let added = graph.with_arrow_added(Node::from(0), Node::from(4)).unwrap();
+    assert_eq!(added.number_of_arrows(), 7);
+    let removed = added.with_arrow_removed(Node::from(1), Node::from(2)).unwrap();
+    assert_eq!(removed.number_of_arrows(), 6);
+    assert_eq!(removed.clone().number_of_arrows(), 6);
+}
+
+#[test]
+fn test_error_messages() {
+    let dto = build_dto(&[(0, 1), (1, 2), (1, 2)]);
+    let error = DirectedGraph::from_dto(&dto).unwrap_err();
+    assert_eq!(error.to_string(), "multiple arrows 1 -> 2");
+    let dto = DirectedGraphDTO::new(2, vec![ArrowDTO::new(0, 5)]);
+    let error = DirectedGraph::from_dto(&dto).unwrap_err();
+    assert_eq!(error.to_string(), "arrow 0 -> 5 outside of nodes range");
+    assert!(error.source().is_none());
+
+    let cyclic = build_graph(&[(0, 1), (1, 2), (2, 1)], 3);
+    let Err(error) = cyclic.iter_levels() else {
+        panic!("Expected NotAcyclic error.");
+    };
+    let source = error.source().unwrap().downcast_ref::<NotAcyclicError>().unwrap();
+    assert!(source.to_string().contains("lies on a cycle"), "Invalid message: {source}");
+    assert_eq!(LevelOrderError::NotRooted.to_string(), "graph is not rooted");
+}
+
+#[test]
+fn test_display() {
+    let dto = build_dto(&[(2, 5), (0, 1), (1, 3), (1, 2), (3, 5), (2, 4)]);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    assert_eq!(graph.to_string(), "DG(n=6): 0->1, 1->2, 1->3, 2->4, 2->5, 3->5");
+    assert_eq!(dto.to_string(), graph.to_string());
+    let single = DirectedGraph::from_dto(&DirectedGraphDTO::new(1, Vec::new())).unwrap();
+    assert_eq!(single.to_string(), "DG(n=1): no arrows");
+}
+
+#[rstest]
+#[case(0, "DG(n=4): ... (3 arrows)")]
+#[case(1, "DG(n=4): 0->1, ... (3 arrows)")]
+#[case(2, "DG(n=4): 0->1, 0->2, ... (3 arrows)")]
+#[case(3, "DG(n=4): 0->1, 0->2, 1->3")]
+#[case(4, "DG(n=4): 0->1, 0->2, 1->3")]
+fn test_display_truncation(#[case] limit: usize, #[case] expected: &str) {
+    let dto = build_dto(&[(0, 1), (0, 2), (1, 3)]);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    assert_eq!(graph.to_edge_list_string(limit), expected);
+    assert_eq!(format!("{graph:.limit$}"), expected);
+    assert_eq!(format!("{dto:.limit$}"), expected);
+}
+
+#[test]
+fn test_display_default_limit() {
+    let size = i32::try_from(DEFAULT_EDGE_LIST_LIMIT).unwrap() + 2;
+    let arrows: Vec<(i32, i32)> = (1..size).map(|target| (0, target)).collect();
+    let graph = build_graph(&arrows, size);
+    let text = graph.to_string();
+    assert!(text.ends_with(&format!(", ... ({} arrows)", arrows.len())), "Invalid text: {text}");
+    assert_eq!(text.matches("->").count(), DEFAULT_EDGE_LIST_LIMIT);
+}
+
+#[rstest]
+#[case(&[(0, 1), (0, 2), (1, 3), (2, 3)], 4)]
+#[case(&[(0, 1), (1, 2), (2, 0)], 3)]
+#[case(&[(0, 1), (2, 3)], 4)]
+fn test_from_dto_with_properties(#[case] arrows: &[(i32, i32)], #[case] number_of_nodes: i32) {
+    let arrows = arrows.iter().map(|p| ArrowDTO::new(p.0, p.1)).collect();
+    let dto = DirectedGraphDTO::new(number_of_nodes, arrows);
+    let before = DirectedGraph::property_verifications();
+    let slow = DirectedGraph::from_dto(&dto).unwrap();
+    let after_slow = DirectedGraph::property_verifications();
+    assert!(after_slow > before);
+
+    let fast = DirectedGraph::from_dto_with_properties(&slow.into_dto(), slow.basic_properties().clone()).unwrap();
+    assert_eq!(DirectedGraph::property_verifications(), after_slow);
+    assert_eq!(fast, slow);
+    assert_eq!(fast.basic_properties(), slow.basic_properties());
+    assert_eq!(fast.root(), slow.root());
+    assert_eq!(fast.leaves(), slow.leaves());
+}
+
+#[test]
+fn test_from_dto_with_properties_checks_structure() {
+    let graph = DirectedGraph::from_dto(&DirectedGraphDTO::new(2, vec![ArrowDTO::new(0, 1)])).unwrap();
+    let dto = DirectedGraphDTO::new(2, vec![ArrowDTO::new(0, 2)]);
+    let result = DirectedGraph::from_dto_with_properties(&dto, graph.basic_properties().clone());
+    assert!(matches!(result, Err(DirectedGraphFromError::ArrowOutsideOfNodesRange(_))), "Invalid result: {result:?}");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "Passed properties don't match the graph.")]
+fn test_from_dto_with_wrong_properties() {
+    let dto = DirectedGraphDTO::new(2, vec![ArrowDTO::new(0, 1), ArrowDTO::new(1, 0)]);
+    let tree = DirectedGraph::from_dto(&DirectedGraphDTO::new(2, vec![ArrowDTO::new(0, 1)])).unwrap();
+    let _ = DirectedGraph::from_dto_with_properties(&dto, tree.basic_properties().clone());
+}
+
+#[test]
+fn test_arrow_iterators() {
+    let graph = build_graph(&[(3, 0), (1, 4), (1, 2), (5, 2), (1, 0), (0, 2)], 6);
+    let pairs = |iter: &mut dyn Iterator<Item=(Node, Node)>| -> Vec<(i32, i32)> {
+        iter.map(|(s, t)| (s.id(), t.id())).collect()
+    };
+    let arrows = pairs(&mut graph.iter_arrows());
+    assert_eq!(arrows, vec![(0, 2), (1, 0), (1, 2), (1, 4), (3, 0), (5, 2)]);
+    assert_eq!(graph.iter_arrows().len(), 6);
+    let mut iter = graph.iter_arrows();
+    iter.next();
+    assert_eq!(iter.len(), 5);
+
+    let dto_arrows: Vec<(i32, i32)> = graph.into_dto().arrows()
+        .iter()
+        .map(|arrow| (arrow.source(), arrow.target()))
+        .collect();
+    assert_eq!(dto_arrows, arrows);
+
+    assert_eq!(pairs(&mut graph.arrows_from(Node::from(1))), vec![(1, 0), (1, 2), (1, 4)]);
+    assert_eq!(pairs(&mut graph.arrows_into(Node::from(2))), vec![(0, 2), (1, 2), (5, 2)]);
+    assert_eq!(graph.arrows_from(Node::from(4)).len(), 0);
+    assert_eq!(graph.arrows_into(Node::from(10)).len(), 0);
+}
+
+#[test]
+fn test_contains_arrow() {
+    let graph = build_graph(&[(3, 0), (1, 4), (1, 2), (5, 2), (1, 0), (0, 2), (2, 3)], 6);
+    let arrows: Vec<(Node, Node)> = graph.iter_arrows().collect();
+    for source in -1..8 {
+        for target in -1..8 {
+            let (source, target) = (Node::from(source), Node::from(target));
+            assert_eq!(
+                graph.contains_arrow(source, target),
+                arrows.contains(&(source, target)),
+                "Invalid result for {source:?} -> {target:?}");
+        }
+    }
+}
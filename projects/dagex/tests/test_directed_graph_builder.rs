@@ -0,0 +1,69 @@
+use dagex::core::{
+    ArrowDTO,
+    DirectedGraph,
+    DirectedGraphBuilder,
+    DirectedGraphDTO,
+    DirectedGraphFromError};
+
+#[test]
+fn test_reticulation_example() {
+    let mut builder = DirectedGraphBuilder::new();
+    let root = builder.add_node();
+    let left = builder.add_node();
+    let right = builder.add_node();
+    let hybrid = builder.add_node();
+    let leaf = builder.add_node();
+    builder
+        .add_arrow(root, left)
+        .add_arrow(root, right)
+        .add_arrow(left, hybrid)
+        .add_arrow(right, hybrid)
+        .add_arrow(hybrid, leaf);
+    let graph = builder.build().unwrap();
+
+    assert_eq!(graph.number_of_nodes(), 5);
+    assert_eq!(graph.root(), Some(root));
+    assert_eq!(graph.get_predecessors(hybrid), &[left, right]);
+    let props = graph.basic_properties();
+    assert!(props.acyclic);
+    assert!(props.rooted);
+    assert!(!props.tree);
+}
+
+#[test]
+fn test_auto_grow_and_deduplicate() {
+    let mut builder = DirectedGraphBuilder::new();
+    builder
+        .add_arrow_ids(0, 3)
+        .add_arrow_ids(0, 3)
+        .add_arrow_ids(3, 1);
+    assert_eq!(builder.number_of_nodes(), 4);
+    let dto = builder.build_dto();
+    let expected = DirectedGraphDTO::new(4, vec![ArrowDTO::new(0, 3), ArrowDTO::new(3, 1)]);
+    assert_eq!(dto, expected);
+}
+
+#[test]
+fn test_report_parallel_arrows() {
+    let mut builder = DirectedGraphBuilder::new().with_parallel_arrows_reported();
+    builder.add_arrow_ids(0, 1).add_arrow_ids(0, 1);
+    let result = builder.build();
+    assert!(matches!(result, Err(DirectedGraphFromError::MultipleParallelArrows(_))), "Invalid result: {result:?}");
+}
+
+#[test]
+fn test_invalid_input() {
+    let result = DirectedGraphBuilder::new().build();
+    assert!(matches!(result, Err(DirectedGraphFromError::EmptyGraph)), "Invalid result: {result:?}");
+
+    let mut builder = DirectedGraphBuilder::new();
+    builder.add_arrow_ids(0, 1).add_arrow_ids(1, DirectedGraph::max_size());
+    assert_eq!(builder.number_of_nodes(), 2);
+    let result = builder.build();
+    assert!(matches!(result, Err(DirectedGraphFromError::ArrowOutsideOfNodesRange(_))), "Invalid result: {result:?}");
+
+    let mut builder = DirectedGraphBuilder::new();
+    builder.add_arrow_ids(0, 1).add_arrow_ids(-1, 0);
+    let result = builder.build();
+    assert!(matches!(result, Err(DirectedGraphFromError::ArrowOutsideOfNodesRange(_))), "Invalid result: {result:?}");
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use dagex::{
+    core::{ArrowDTO, DirectedGraph, DirectedGraphDTO, DirectedGraphFromError, DtoValidationError},
+    generators::{generate_random_dag, generate_random_phylo_network},
+    phylo::{NamedDtoError, PhylogeneticNetwork, PhylogeneticNetworkDTO},
+    raf_array::immutable_string::ImmutableString};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use rstest::rstest;
+
+fn dto(number_of_nodes: i32, arrows: &[(i32, i32)]) -> DirectedGraphDTO {
+    let arrows = arrows.iter()
+        .map(|(source, target)| ArrowDTO::new(*source, *target))
+        .collect();
+    DirectedGraphDTO::new(number_of_nodes, arrows)
+}
+
+fn same_error(validation: &DtoValidationError, construction: &DirectedGraphFromError) -> bool {
+    match (validation, construction) {
+        (DtoValidationError::EmptyGraph, DirectedGraphFromError::EmptyGraph)
+            | (DtoValidationError::TooBigGraph, DirectedGraphFromError::TooBigGraph) => true,
+        (DtoValidationError::MultipleParallelArrows(first), DirectedGraphFromError::MultipleParallelArrows(second))
+            | (DtoValidationError::ArrowOutsideOfNodesRange(first), DirectedGraphFromError::ArrowOutsideOfNodesRange(second))
+            => first == second,
+        _ => false,
+    }
+}
+
+#[rstest]
+#[case(dto(0, &[]))]
+#[case(dto(-3, &[]))]
+#[case(dto(DirectedGraph::max_size() + 1, &[]))]
+#[case(dto(2, &[(0, 1), (1, 0), (0, 1)]))]
+#[case(dto(6, &[(-1, 5)]))]
+#[case(dto(1, &[(0, 5)]))]
+#[case(dto(3, &[(0, 7), (0, 7)]))]
+#[case(dto(3, &[(0, 1), (1, 3), (1, 3)]))]
+fn test_validation_parity(#[case] dto: DirectedGraphDTO) {
+    let validation = dto.validate().unwrap_err();
+    let construction = DirectedGraph::from_dto(&dto).unwrap_err();
+    assert!(same_error(&validation, &construction), "{validation:?} differs from {construction:?}");
+}
+
+#[test]
+fn test_valid_dtos() {
+    let mut rng = StdRng::seed_from_u64(13);
+    for _ in 0..100 {
+        let dto = generate_random_dag(&mut rng, 10, 0.3);
+        assert_eq!(dto.validate(), Ok(()), "Invalid DTO: {dto}");
+        assert!(DirectedGraph::from_dto(&dto).is_ok(), "Invalid DTO: {dto}");
+    }
+    assert_eq!(dto(2, &[(0, 1), (1, 0)]).validate(), Ok(()));
+}
+
+#[test]
+fn test_normalization() {
+    let mut rng = StdRng::seed_from_u64(21);
+    for _ in 0..100 {
+        let dto = generate_random_dag(&mut rng, 12, 0.4);
+        let normalized = dto.normalized();
+        assert_eq!(normalized.normalized(), normalized);
+        let mut arrows = dto.arrows().clone();
+        arrows.shuffle(&mut rng);
+        assert_eq!(DirectedGraphDTO::new(dto.number_of_nodes(), arrows).normalized(), normalized);
+        assert_eq!(DirectedGraph::from_dto(&normalized).unwrap(), DirectedGraph::from_dto(&dto).unwrap());
+        let sorted = normalized.arrows()
+            .windows(2)
+            .all(|pair| (pair[0].source(), pair[0].target()) < (pair[1].source(), pair[1].target()));
+        assert!(sorted, "Not sorted: {normalized}");
+    }
+
+    let with_duplicates = dto(3, &[(1, 2), (0, 1), (1, 2), (0, 2), (0, 1)]);
+    assert_eq!(with_duplicates.normalized(), dto(3, &[(0, 1), (0, 2), (1, 2)]));
+    assert!(with_duplicates.validate().is_err());
+    assert_eq!(with_duplicates.normalized().validate(), Ok(()));
+}
+
+#[test]
+fn test_normalization_of_weighted_duplicates() {
+    let weighted = DirectedGraphDTO::new(3, vec![
+        ArrowDTO::new(0, 1).with_weight(Some(2.5)),
+        ArrowDTO::new(1, 2),
+        ArrowDTO::new(0, 1),
+        ArrowDTO::new(1, 2).with_weight(Some(-1.0)),
+        ArrowDTO::new(0, 1).with_weight(Some(0.5)),
+        ArrowDTO::new(1, 2).with_weight(Some(3.0)),
+        ArrowDTO::new(0, 2),
+        ArrowDTO::new(0, 2),
+    ]);
+    let expected = DirectedGraphDTO::new(3, vec![
+        ArrowDTO::new(0, 1).with_weight(Some(0.5)),
+        ArrowDTO::new(0, 2),
+        ArrowDTO::new(1, 2).with_weight(Some(-1.0)),
+    ]);
+    assert_eq!(weighted.normalized(), expected);
+    assert_eq!(expected.normalized(), expected);
+    assert!(weighted.validate().is_err());
+    assert_eq!(weighted.normalized().validate(), Ok(()));
+}
+
+#[test]
+fn test_phylogenetic_network_dto() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let network_dto = generate_random_phylo_network(&mut rng, 6, 2);
+    assert_eq!(network_dto.validate(), Ok(()));
+    let normalized = network_dto.normalized();
+    assert_eq!(normalized.taxa(), network_dto.taxa());
+    assert_eq!(
+        PhylogeneticNetwork::from_dto(&normalized).unwrap(),
+        PhylogeneticNetwork::from_dto(&network_dto).unwrap());
+
+    let taxon = ImmutableString::new("X").unwrap();
+    let taxa = HashMap::from([(0, taxon.clone()), (9, taxon.clone()), (-2, taxon)]);
+    let invalid = PhylogeneticNetworkDTO::new(dto(3, &[(0, 1), (0, 2)]), taxa);
+    assert_eq!(invalid.validate(), Err(DtoValidationError::TaxonOutsideOfNodesRange(-2)));
+
+    let invalid_graph = PhylogeneticNetworkDTO::new(dto(0, &[]), HashMap::new());
+    assert_eq!(invalid_graph.validate(), Err(DtoValidationError::EmptyGraph));
+}
+
+#[test]
+fn test_from_named_arrows() {
+    let arrows = [("root", "x"), ("root", "y"), ("x", "a"), ("x", "r"), ("y", "r"), ("y", "c"), ("r", "d")];
+    let taxa = [("a", "A"), ("c", "C"), ("d", "D")];
+    let (network_dto, ids) = PhylogeneticNetworkDTO::from_named_arrows(&arrows, &taxa).unwrap();
+    let id = |name: &str| ids[&ImmutableString::new(name).unwrap()];
+    let order = ["root", "x", "y", "a", "r", "c", "d"];
+    for (expected, name) in order.iter().enumerate() {
+        assert_eq!(id(name), i32::try_from(expected).unwrap());
+    }
+    assert_eq!(ids.len(), order.len());
+    assert_eq!(network_dto.graph(), &dto(7, &[(0, 1), (0, 2), (1, 3), (1, 4), (2, 4), (2, 5), (4, 6)]));
+    assert_eq!(network_dto.taxa()[&id("d")].as_str(), "D");
+    assert_eq!(network_dto.taxa().len(), 3);
+
+    let network = PhylogeneticNetwork::from_dto(&network_dto).unwrap();
+    assert_eq!(network.reticulation_count(), 1);
+
+    let (again, again_ids) = PhylogeneticNetworkDTO::from_named_arrows(&arrows, &taxa).unwrap();
+    assert_eq!(again, network_dto);
+    assert_eq!(again_ids, ids);
+}
+
+#[rstest]
+#[case(&[("a", "b"), ("b", "b")], &[], NamedDtoError::SelfLoop(name("b")), "arrow b -> b is a self loop")]
+#[case(&[("a", "b"), ("a", "c"), ("a", "b")], &[], NamedDtoError::MultipleParallelArrows(name("a"), name("b")), "multiple arrows a -> b")]
+#[case(&[("a", "b")], &[("b", "B"), ("z", "Z")], NamedDtoError::UnknownNode(name("z")), "taxon assigned to unknown node z")]
+#[case(&[("a", "b")], &[("b", "B"), ("b", "C")], NamedDtoError::MultipleTaxa(name("b")), "multiple taxa assigned to node b")]
+fn test_from_named_arrows_errors(
+    #[case] arrows: &[(&str, &str)],
+    #[case] taxa: &[(&str, &str)],
+    #[case] expected: NamedDtoError,
+    #[case] message: &str)
+{
+    let error = PhylogeneticNetworkDTO::from_named_arrows(arrows, taxa).unwrap_err();
+    assert_eq!(error, expected);
+    assert_eq!(error.to_string(), message);
+}
+
+fn name(text: &str) -> ImmutableString {
+    ImmutableString::new(text).unwrap()
+}
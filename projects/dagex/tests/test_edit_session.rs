@@ -0,0 +1,112 @@
+use std::cell::RefCell;
+
+use dagex::core::{
+    ArrowDTO,
+    DirectedGraph,
+    DirectedGraphDTO,
+    EditError,
+    GraphEvent,
+    Node};
+
+fn build_graph(arrows: &[(i32, i32)], number_of_nodes: i32) -> DirectedGraph {
+    let arrows: Vec<ArrowDTO> = arrows.iter()
+        .map(|p| ArrowDTO::new(p.0, p.1))
+        .collect();
+    let dto = DirectedGraphDTO::new(number_of_nodes, arrows);
+    DirectedGraph::from_dto(&dto).unwrap()
+}
+
+#[test]
+fn test_events_in_order() {
+    let graph = build_graph(&[(0, 1), (0, 2)], 3);
+    let mut session = graph.edit();
+    let node = session.add_node();
+    session.add_arrow(Node::from(1), node).unwrap();
+    session.remove_arrow(Node::from(0), Node::from(2)).unwrap();
+    let (result, events) = session.commit().unwrap();
+    assert_eq!(result.number_of_nodes(), 4);
+    assert_eq!(events[..3], [
+        GraphEvent::NodeAdded(Node::from(3)),
+        GraphEvent::ArrowAdded(Node::from(1), Node::from(3)),
+        GraphEvent::ArrowRemoved(Node::from(0), Node::from(2)),
+    ]);
+    assert!(matches!(events[3], GraphEvent::PropertiesChanged(ref old, ref new) if old.rooted && !new.rooted));
+}
+
+#[test]
+fn test_remove_node_remaps_last() {
+    let graph = build_graph(&[(0, 1), (0, 2), (2, 3)], 4);
+    let mut session = graph.edit();
+    let moved = session.remove_node(Node::from(1)).unwrap();
+    assert_eq!(moved, Some(Node::from(3)));
+    let (result, events) = session.commit().unwrap();
+    assert_eq!(events, vec![
+        GraphEvent::ArrowRemoved(Node::from(0), Node::from(1)),
+        GraphEvent::NodeRemoved(Node::from(1), Some(Node::from(3))),
+    ]);
+    assert_eq!(result, build_graph(&[(0, 2), (2, 1)], 3));
+}
+
+#[test]
+fn test_weights_kept() {
+    let arrows = vec![
+        ArrowDTO::new(0, 1).with_weight(Some(0.5)),
+        ArrowDTO::new(0, 2),
+        ArrowDTO::new(2, 3).with_weight(Some(1.5)),
+    ];
+    let graph = DirectedGraph::from_dto(&DirectedGraphDTO::new(4, arrows)).unwrap();
+    let mut session = graph.edit();
+    let node = session.add_node();
+    session.add_arrow(Node::from(3), node).unwrap();
+    session.remove_node(Node::from(1)).unwrap();
+    let (result, _) = session.commit().unwrap();
+    // Node 4 got removed id 1, node 3 kept its id.
+    assert_eq!(result.number_of_nodes(), 4);
+    assert_eq!(result.arrow_weight(Node::from(2), Node::from(3)), Some(1.5));
+    assert_eq!(result.arrow_weight(Node::from(0), Node::from(2)), None);
+    assert_eq!(result.arrow_weight(Node::from(3), Node::from(1)), None);
+    assert!(result.contains_arrow(Node::from(3), Node::from(1)));
+
+    let mut session = result.edit();
+    session.remove_node(Node::from(0)).unwrap();
+    let (moved, _) = session.commit().unwrap();
+    // Node 3 moved to 0, its arrow to node 1 is unweighted.
+    assert_eq!(moved.arrow_weight(Node::from(2), Node::from(0)), Some(1.5));
+    assert!(moved.is_weighted());
+}
+
+#[test]
+fn test_errors() {
+    let graph = build_graph(&[(0, 1)], 2);
+    let mut session = graph.edit();
+    assert!(matches!(session.add_arrow(Node::from(0), Node::from(1)), Err(EditError::ArrowAlreadyExists(_, _))));
+    assert!(matches!(session.remove_arrow(Node::from(1), Node::from(0)), Err(EditError::ArrowNotFound(_, _))));
+    assert!(matches!(session.add_arrow(Node::from(0), Node::from(5)), Err(EditError::NodeNotFound(_))));
+    assert!(session.events().is_empty());
+}
+
+#[test]
+fn test_replay_and_subscribe() {
+    let graph = build_graph(&[(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)], 5);
+    let notified = RefCell::new(Vec::new());
+    let (committed, events) = {
+        let mut session = graph.edit();
+        session.subscribe(|events| notified.borrow_mut().extend_from_slice(events));
+        let extra = session.add_node();
+        session.add_arrow(Node::from(4), extra).unwrap();
+        session.remove_node(Node::from(2)).unwrap();
+        session.add_arrow(Node::from(1), Node::from(3)).unwrap_err();
+        session.remove_arrow(Node::from(1), Node::from(3)).unwrap();
+        session.add_arrow(Node::from(0), Node::from(3)).unwrap();
+        session.commit().unwrap()
+    };
+    assert_eq!(*notified.borrow(), events);
+
+    let copy = graph.clone();
+    let mut replay = copy.edit();
+    for event in &events {
+        replay.apply(event).unwrap();
+    }
+    let (replayed, _) = replay.commit().unwrap();
+    assert_eq!(replayed, committed);
+}
@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use dagex::{
+    core::DirectedGraph,
+    generators::{generate_random_dag, generate_random_phylo_network, generate_random_tree},
+    phylo::PhylogeneticNetwork};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rstest::rstest;
+
+#[rstest]
+#[case(1, 0.0)]
+#[case(1, 1.0)]
+#[case(10, 0.0)]
+#[case(10, 1.0)]
+#[case(50, 0.2)]
+fn test_random_dag(#[case] nodes: i32, #[case] density: f64) {
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..20 {
+        let dto = generate_random_dag(&mut rng, nodes, density);
+        let graph = DirectedGraph::from_dto(&dto).unwrap();
+        assert_eq!(graph.number_of_nodes(), nodes);
+        assert!(graph.basic_properties().acyclic);
+        if density == 1.0 {
+            let size = usize::try_from(nodes).unwrap();
+            assert_eq!(graph.number_of_arrows(), size * (size - 1) / 2);
+        }
+        if density == 0.0 {
+            assert_eq!(graph.number_of_arrows(), 0);
+        }
+    }
+}
+
+#[rstest]
+#[case(1)]
+#[case(2)]
+#[case(17)]
+fn test_random_tree(#[case] leaves: i32) {
+    let mut rng = StdRng::seed_from_u64(11);
+    let dto = generate_random_tree(&mut rng, leaves);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    let props = graph.basic_properties();
+    assert!(props.tree && props.binary && props.rooted);
+    assert_eq!(graph.number_of_nodes(), 2 * leaves - 1);
+    assert_eq!(graph.leaves().len(), usize::try_from(leaves).unwrap());
+}
+
+#[test]
+fn test_random_phylo_networks() {
+    let mut rng = StdRng::seed_from_u64(2024);
+    for _ in 0..1000 {
+        let leaves = rng.gen_range(1..30);
+        let reticulations = if leaves > 1 { rng.gen_range(0..10) } else { 0 };
+        let dto = generate_random_phylo_network(&mut rng, leaves, reticulations);
+        let network = PhylogeneticNetwork::from_dto(&dto).unwrap();
+        let graph = network.graph();
+        let actual_reticulations = graph.iter_nodes()
+            .filter(|node| network.is_reticulation_node(*node))
+            .count();
+        assert_eq!(actual_reticulations, usize::try_from(reticulations).unwrap());
+        assert_eq!(graph.leaves().len(), usize::try_from(leaves).unwrap());
+        let taxa: HashSet<_> = graph.leaves().iter()
+            .map(|leaf| network.taxa().get(leaf).unwrap().clone())
+            .collect();
+        assert_eq!(taxa.len(), graph.leaves().len());
+        assert_eq!(network.taxa().len(), taxa.len());
+    }
+}
+
+#[test]
+fn test_determinism() {
+    let generate = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (
+            generate_random_dag(&mut rng, 20, 0.3),
+            generate_random_tree(&mut rng, 20),
+            generate_random_phylo_network(&mut rng, 20, 5),
+        )
+    };
+    assert_eq!(generate(5), generate(5));
+    assert_ne!(generate(5), generate(6));
+}
+
+#[test]
+#[should_panic(expected = "Single leaf network can't have reticulations.")]
+fn test_single_leaf_with_reticulations() {
+    let mut rng = StdRng::seed_from_u64(0);
+    generate_random_phylo_network(&mut rng, 1, 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialization_round_trip() {
+    use dagex::phylo::PhylogeneticNetworkDTO;
+
+    let mut rng = StdRng::seed_from_u64(99);
+    for _ in 0..1000 {
+        let dto = generate_random_phylo_network(&mut rng, 12, 4);
+        let network = PhylogeneticNetwork::from_dto(&dto).unwrap();
+        let json = serde_json::to_string(&network.into_dto()).unwrap();
+        let deserialized: PhylogeneticNetworkDTO = serde_json::from_str(&json).unwrap();
+        let result = PhylogeneticNetwork::try_from(deserialized).unwrap();
+        assert_eq!(result, network);
+    }
+}
@@ -1,21 +1,27 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use dagex::{
     raf_array::immutable_string::ImmutableString,
     core::{ArrowDTO, DirectedGraphDTO},
     phylo::{
+        AddGeneError,
         GenesOverSpecies,
         GenesOverSpeciesNewError,
         PhylogeneticNetwork,
-        PhylogeneticNetworkDTO}};
+        PhylogeneticNetworkDTO,
+        RemoveGeneError,
+        Taxon,
+        TaxonIdSet,
+        TaxonRegistry}};
+use rstest::rstest;
 
 
 fn build_network(arrows: &[(i32, i32)], taxa: &[(i32, &'static str)]) -> PhylogeneticNetwork {
     let mut max = 0;
     let mut target_arrows = Vec::<ArrowDTO>::with_capacity(arrows.len());
     for (source, target) in arrows {
-        let s = source.clone();
-        let t = target.clone();
+        let s = *source;
+        let t = *target;
         max = core::cmp::max(s, core::cmp::max(t, max));
         target_arrows.push(ArrowDTO::new(s, t));
     }
@@ -94,3 +100,216 @@ fn test_taxa_duplicates() {
     let genes_over_species = GenesOverSpecies::new_single_gene(genes, species);
     assert!(matches!(genes_over_species, Err(GenesOverSpeciesNewError::SpeciesContainsTaxaDuplicates)));
 }
+
+fn outcome(result: &Result<GenesOverSpecies, GenesOverSpeciesNewError>) -> String {
+    match result {
+        Ok(_) => "ok".to_owned(),
+        Err(err) => format!("{err:?}"),
+    }
+}
+
+type Labeled = (i32, &'static str);
+
+#[test]
+fn test_registry_path_matches_plain_path() {
+    let species_arrows: &[(i32, i32)] = &[(0, 1), (0, 2), (2, 3), (2, 4)];
+    let cases: Vec<(Vec<Labeled>, Vec<Labeled>)> = vec![
+        (vec![(1, "A"), (3, "B"), (4, "C")], vec![(1, "A"), (2, "C")]),
+        (vec![(1, "A"), (3, "B"), (4, "C")], vec![(1, "A"), (2, "D")]),
+        (vec![(1, "A"), (3, "B"), (4, "A")], vec![(1, "A"), (2, "B")]),
+        (vec![(1, "A"), (3, "B")], vec![(1, "B")]),
+    ];
+    for (species_taxa, gene_taxa) in cases {
+        let gene_arrows: &[(i32, i32)] = if gene_taxa.len() == 2 { &[(0, 1), (0, 2)] } else { &[(0, 1)] };
+        let plain = GenesOverSpecies::new(
+            vec![build_network(gene_arrows, &gene_taxa)],
+            build_network(species_arrows, &species_taxa));
+        let with_registry = GenesOverSpecies::new_with_registry(
+            vec![build_network(gene_arrows, &gene_taxa)],
+            build_network(species_arrows, &species_taxa));
+        assert_eq!(outcome(&plain), outcome(&with_registry));
+        if let Ok(genes_over_species) = with_registry {
+            assert!(plain.unwrap().taxon_registry().is_none());
+            let registry = genes_over_species.taxon_registry().unwrap();
+            assert_eq!(registry.len(), species_taxa.len());
+            for (_, name) in &species_taxa {
+                let taxon = Taxon::new(name).unwrap();
+                assert_eq!(registry.get(registry.id_of(&taxon).unwrap()), &taxon);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_taxon_registry() {
+    let mut registry = TaxonRegistry::new();
+    let a = registry.register("A").unwrap();
+    let b = registry.register("B").unwrap();
+    assert_eq!(registry.register("A").unwrap(), a);
+    assert_eq!((a.value(), b.value()), (0, 1));
+
+    let snapshot = registry.clone();
+    let c = registry.register("C").unwrap();
+    assert_eq!(c.value(), 2);
+    assert_eq!(snapshot.len(), 2);
+    assert_eq!(snapshot.id_of(&Taxon::new("C").unwrap()), None);
+    assert_eq!(registry.get(b).value().as_str(), "B");
+
+    let mut other = TaxonRegistry::new();
+    for name in ["A", "B", "C"] {
+        other.register(name).unwrap();
+    }
+    assert_eq!(other, registry);
+
+    let mut small = TaxonIdSet::new();
+    small.insert(a);
+    small.insert(c);
+    let mut big = small.clone();
+    big.insert(b);
+    assert!(small.is_subset(&big));
+    assert!(!big.is_subset(&small));
+    assert!(TaxonIdSet::new().is_subset(&small));
+    assert_eq!(big.len(), 3);
+}
+
+fn synthetic_gene_networks(count: usize, bad_index: Option<usize>) -> Vec<PhylogeneticNetwork> {
+    const NAMES: [&str; 4] = ["A", "B", "C", "D"];
+    (0..count)
+        .map(|idx| {
+            let second = if Some(idx) == bad_index { "X" } else { NAMES[(idx + 1) % 4] };
+            build_network(&[(0, 1), (0, 2)], &[(1, NAMES[idx % 4]), (2, second)])
+        })
+        .collect()
+}
+
+fn synthetic_species_network() -> PhylogeneticNetwork {
+    build_network(
+        &[(0, 1), (0, 2), (1, 3), (1, 4), (2, 5), (2, 6)],
+        &[(3, "A"), (4, "B"), (5, "C"), (6, "D")])
+}
+
+#[rstest]
+#[case(Some(0))]
+#[case(Some(637))]
+#[case(Some(999))]
+#[case(None)]
+fn test_parallel_validation(#[case] bad_index: Option<usize>) {
+    let serial = GenesOverSpecies::new(
+        synthetic_gene_networks(1000, bad_index),
+        synthetic_species_network());
+    let parallel = GenesOverSpecies::new_parallel(
+        synthetic_gene_networks(1000, bad_index),
+        synthetic_species_network());
+    assert_eq!(outcome(&serial), outcome(&parallel));
+    if bad_index.is_some() {
+        assert!(matches!(parallel, Err(GenesOverSpeciesNewError::IncorrectTaxa)));
+    }
+    else
+    {
+        assert_eq!(parallel.unwrap().gene_networks().len(), 1000);
+    }
+}
+
+#[test]
+fn test_parallel_validation_errors() {
+    let result = GenesOverSpecies::new_parallel(Vec::new(), synthetic_species_network());
+    assert!(matches!(result, Err(GenesOverSpeciesNewError::EmptyGeneNetworks)));
+
+    let species = build_network(&[(0, 1), (0, 2)], &[(1, "A"), (2, "A")]);
+    let result = GenesOverSpecies::new_parallel(synthetic_gene_networks(1, None), species);
+    assert!(matches!(result, Err(GenesOverSpeciesNewError::SpeciesContainsTaxaDuplicates)));
+
+    let mut networks: Vec<Arc<PhylogeneticNetwork>> = synthetic_gene_networks(500, None)
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+    networks.push(networks[10].clone());
+    let result = GenesOverSpecies::from_shared_networks_parallel(networks, synthetic_species_network()).unwrap();
+    assert_eq!(result.gene_networks().len(), 501);
+}
+
+fn assert_index_consistent(genes_over_species: &GenesOverSpecies) {
+    for gene_network in genes_over_species.gene_networks() {
+        let found = genes_over_species.get_gene_network_by_id(gene_network.id()).unwrap();
+        assert!(std::ptr::eq(found, gene_network.as_ref()));
+    }
+}
+
+#[test]
+fn test_add_and_remove_gene_networks() {
+    let mut networks = synthetic_gene_networks(4, None);
+    let extra = networks.split_off(2);
+    let mut genes_over_species = GenesOverSpecies::new(networks, synthetic_species_network()).unwrap();
+    let first_id = genes_over_species.gene_networks()[0].id();
+    let kept = genes_over_species.gene_networks()[1].clone();
+
+    let mut added_ids = Vec::new();
+    for network in extra {
+        added_ids.push(genes_over_species.add_gene_network(network).unwrap());
+    }
+    assert_eq!(genes_over_species.gene_networks().len(), 4);
+    assert_eq!(genes_over_species.gene_networks()[3].id(), added_ids[1]);
+    assert_index_consistent(&genes_over_species);
+
+    let removed = genes_over_species.remove_gene_network(first_id).unwrap();
+    assert_eq!(removed.id(), first_id);
+    assert_eq!(genes_over_species.gene_networks().len(), 3);
+    assert_eq!(genes_over_species.gene_networks()[0].id(), added_ids[1]);
+    assert!(genes_over_species.get_gene_network_by_id(first_id).is_none());
+    assert_eq!(genes_over_species.remove_gene_network(first_id), Err(RemoveGeneError::NotFound));
+    assert_index_consistent(&genes_over_species);
+
+    let readded = genes_over_species.add_shared_gene_network(removed).unwrap();
+    assert_eq!(readded, first_id);
+    assert_eq!(genes_over_species.gene_networks()[3].id(), first_id);
+    assert_index_consistent(&genes_over_species);
+    assert_eq!(genes_over_species.gene_networks()[1], kept);
+}
+
+#[test]
+fn test_add_gene_network_errors() {
+    let mut genes_over_species = GenesOverSpecies::new(
+        synthetic_gene_networks(2, None),
+        synthetic_species_network()).unwrap();
+    let present = genes_over_species.gene_networks()[1].clone();
+    assert_eq!(genes_over_species.add_shared_gene_network(present), Err(AddGeneError::DuplicatedId));
+
+    let bad = synthetic_gene_networks(1, Some(0)).pop().unwrap();
+    assert_eq!(genes_over_species.add_gene_network(bad), Err(AddGeneError::IncorrectTaxa));
+    assert_eq!(genes_over_species.gene_networks().len(), 2);
+    assert_index_consistent(&genes_over_species);
+}
+
+#[test]
+fn test_remove_shared_duplicates() {
+    let mut networks: Vec<Arc<PhylogeneticNetwork>> = synthetic_gene_networks(5, None)
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+    networks.push(networks[1].clone());
+    networks.push(networks[4].clone());
+    networks.push(networks[1].clone());
+    let duplicated_id = networks[1].id();
+    let mut genes_over_species = GenesOverSpecies::from_shared_networks(
+        networks,
+        synthetic_species_network()).unwrap();
+
+    let removed = genes_over_species.remove_gene_network(duplicated_id).unwrap();
+    assert_eq!(removed.id(), duplicated_id);
+    assert_eq!(genes_over_species.gene_networks().len(), 5);
+    assert!(genes_over_species.gene_networks().iter().all(|network| network.id() != duplicated_id));
+    assert_index_consistent(&genes_over_species);
+}
+
+#[test]
+fn test_remove_last_gene_network() {
+    let network = Arc::new(synthetic_gene_networks(1, None).pop().unwrap());
+    let id = network.id();
+    let mut genes_over_species = GenesOverSpecies::from_shared_networks(
+        vec![network.clone(), network],
+        synthetic_species_network()).unwrap();
+    assert_eq!(genes_over_species.remove_gene_network(id), Err(RemoveGeneError::EmptyGeneNetworks));
+    assert_eq!(genes_over_species.gene_networks().len(), 2);
+    assert_index_consistent(&genes_over_species);
+    assert_eq!(RemoveGeneError::EmptyGeneNetworks.to_string(), "cannot remove the last gene network");
+}
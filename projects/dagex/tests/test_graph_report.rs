@@ -0,0 +1,128 @@
+use dagex::{
+    const_parse_newick,
+    core::{ArrowDTO, DirectedGraph, DirectedGraphDTO, GraphAnomaly, Node}};
+use raf_structural_logging::models::{SLDict, SLObject};
+
+fn build_graph(arrows: &[(i32, i32)], number_of_nodes: i32) -> DirectedGraph {
+    let arrows: Vec<ArrowDTO> = arrows.iter()
+        .map(|p| ArrowDTO::new(p.0, p.1))
+        .collect();
+    let dto = DirectedGraphDTO::new(number_of_nodes, arrows);
+    DirectedGraph::from_dto(&dto).unwrap()
+}
+
+#[test]
+fn test_report_reticulation() {
+    let graph = build_graph(&[(0, 1), (1, 2), (1, 3), (2, 4), (3, 5), (2, 5)], 6);
+    let report = graph.report();
+    assert_eq!(report.number_of_nodes, 6);
+    assert_eq!(report.number_of_arrows, 6);
+    assert_eq!(report.number_of_components, 1);
+    assert_eq!(report.height, Some(3));
+    assert_eq!(report.reticulation_count, 1);
+    assert_eq!(report.degree_statistics.max_in_degree, 2);
+    assert_eq!(report.degree_statistics.max_out_degree, 2);
+    assert_eq!(report.degree_statistics.min_out_degree, 0);
+    assert_eq!(report.degree_statistics.isolated_nodes, 0);
+    assert!(report.anomalies.is_empty(), "Invalid anomalies: {:?}", report.anomalies);
+}
+
+#[test]
+fn test_report_anomalies() {
+    let graph = build_graph(&[(0, 1), (2, 3), (3, 2)], 5);
+    let report = graph.report();
+    assert_eq!(report.number_of_components, 3);
+    assert_eq!(report.height, None);
+    assert_eq!(report.degree_statistics.isolated_nodes, 1);
+    assert_eq!(report.anomalies, vec![
+        GraphAnomaly::IsolatedNode(Node::from(4)),
+        GraphAnomaly::MultipleRoots(vec![Node::from(0), Node::from(4)]),
+    ]);
+}
+
+#[test]
+fn test_report_to_dict() {
+    let dict = SLDict::from(&build_graph(&[(0, 1), (1, 2), (1, 3), (2, 4), (3, 5), (2, 5)], 6).report());
+    assert_eq!(dict.get("number_of_nodes"), Some(&SLObject::from(6)));
+    assert_eq!(dict.get("rooted"), Some(&SLObject::from(true)));
+    assert_eq!(dict.get("height"), Some(&SLObject::from(3usize)));
+    assert_eq!(dict.get("mean_in_degree"), Some(&SLObject::from("1.000")));
+    assert_eq!(dict.get("anomalies"), Some(&SLObject::from("")));
+
+    let dict = SLDict::from(&build_graph(&[(0, 1), (2, 3), (3, 2)], 5).report());
+    assert_eq!(dict.get("height"), None);
+    assert_eq!(dict.get("anomalies"), Some(&SLObject::from("isolated node 4; multiple roots [0, 4]")));
+}
+
+#[test]
+fn test_report_network() {
+    let network = const_parse_newick!("((A, (D)B#1),(B#1, ));");
+    let report = network.report();
+    assert_eq!(report.reticulation_count, 1);
+    assert_eq!(report.number_of_components, 1);
+    let unlabeled: Vec<&GraphAnomaly> = report.anomalies.iter()
+        .filter(|a| matches!(a, GraphAnomaly::UnlabeledLeaf(_)))
+        .collect();
+    assert_eq!(unlabeled.len(), 1);
+}
+
+#[test]
+fn test_summary_is_lazy() {
+    let graph = build_graph(&[(0, 1), (1, 2), (1, 3), (2, 4), (3, 5), (2, 5)], 6);
+    let summary = graph.summary();
+    assert_eq!(summary.number_of_nodes(), 6);
+    assert_eq!(summary.number_of_arrows(), 6);
+    assert_eq!(summary.root(), Some(Node::from(0)));
+    assert_eq!(summary.leaf_count(), 2);
+    assert_eq!(summary.reticulation_count(), 1);
+    assert!(!summary.is_depth_computed());
+
+    assert_eq!(summary.max_depth(), Some(3));
+    assert!(summary.is_depth_computed());
+    assert!(graph.summary().with_depth().is_depth_computed());
+}
+
+#[test]
+fn test_summary_display() {
+    let graph = build_graph(&[(0, 1), (2, 3), (3, 2)], 5);
+    let summary = graph.summary();
+    assert_eq!(summary.to_string(), "\
+nodes: 5, arrows: 3, root: none
+acyclic: false, connected: false, rooted: false, binary: true, tree: true
+leaves: 2, reticulations: 0
+max depth: n/a");
+    assert!(summary.is_depth_computed());
+}
+
+#[test]
+fn test_network_summary() {
+    let network = const_parse_newick!("(((A, (D)B#1),(B#1, (C, (E)F#2))), F#2);");
+    let summary = network.summary();
+    assert_eq!(summary.taxa_count(), 6);
+    assert_eq!(summary.reticulation_node_count(), 2);
+    assert_eq!(summary.cross_node_count(), 0);
+    assert!(!summary.graph_summary().is_depth_computed());
+    assert_eq!(summary.to_string(), "\
+nodes: 11, arrows: 12, root: 10
+acyclic: true, connected: true, rooted: true, binary: true, tree: false
+leaves: 4, reticulations: 2
+max depth: 5
+kind: Binary, tree nodes: 5, reticulation nodes: 2, cross nodes: 0
+taxa: 6 [A, B, C, ...]");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_report_display_and_serde() {
+    let graph = build_graph(&[(0, 1), (0, 2)], 4);
+    let report = graph.report();
+    let text = report.to_string();
+    assert!(text.contains("nodes: 4, arrows: 2, components: 2"), "Invalid text: {text}");
+    assert!(text.contains("isolated node 3"), "Invalid text: {text}");
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["number_of_arrows"], 2);
+    assert_eq!(json["height"], 1);
+    assert_eq!(json["anomalies"][0]["IsolatedNode"], 3);
+    assert_eq!(json["anomalies"][1]["MultipleRoots"], serde_json::json!([0, 3]));
+}
@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+
+use dagex::{
+    core::{ArrowDTO, DirectedGraph, DirectedGraphDTO, GraphRead, Node},
+    generators::generate_random_dag};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn build_graph(arrows: &[(i32, i32)], number_of_nodes: i32) -> DirectedGraph {
+    let arrows = arrows.iter()
+        .map(|(source, target)| ArrowDTO::new(*source, *target))
+        .collect();
+    DirectedGraph::from_dto(&DirectedGraphDTO::new(number_of_nodes, arrows)).unwrap()
+}
+
+fn reversed(graph: &DirectedGraph) -> DirectedGraph {
+    let arrows = graph.iter_arrows()
+        .map(|(source, target)| ArrowDTO::new(target.id(), source.id()))
+        .collect();
+    DirectedGraph::from_dto(&DirectedGraphDTO::new(graph.number_of_nodes(), arrows)).unwrap()
+}
+
+/// Preorder of a DFS from `start`, written once for every [`GraphRead`].
+fn dfs(graph: &impl GraphRead, start: Node) -> Vec<Node> {
+    let mut seen = HashSet::from([start]);
+    let mut stack = vec![start];
+    let mut result = Vec::new();
+    while let Some(node) = stack.pop() {
+        result.push(node);
+        for successor in graph.get_successors(node).iter().rev() {
+            if seen.insert(*successor) {
+                stack.push(*successor);
+            }
+        }
+    }
+    result
+}
+
+fn components(graph: &DirectedGraph) -> usize {
+    let view = graph.undirected_view();
+    let mut seen = HashSet::new();
+    let mut count = 0;
+    for start in view.iter_nodes() {
+        if !seen.insert(start) {
+            continue;
+        }
+        count += 1;
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            stack.extend(view.get_neighbours(node).filter(|neighbour| seen.insert(*neighbour)));
+        }
+    }
+    count
+}
+
+#[test]
+fn test_reversed_view_matches_materialized() {
+    let mut rng = StdRng::seed_from_u64(3);
+    for _ in 0..200 {
+        let graph = DirectedGraph::from_dto(&generate_random_dag(&mut rng, 15, 0.2)).unwrap();
+        let materialized = reversed(&graph);
+        let view = graph.reversed_view();
+        assert_eq!(GraphRead::number_of_nodes(&view), materialized.number_of_nodes());
+        for node in view.iter_nodes() {
+            assert_eq!(view.get_successors(node), materialized.get_successors(node));
+            assert_eq!(view.get_predecessors(node), materialized.get_predecessors(node));
+            assert_eq!(dfs(&view, node), dfs(&materialized, node));
+        }
+    }
+}
+
+#[test]
+fn test_reversed_view_of_path() {
+    let graph = build_graph(&[(0, 1), (1, 2), (2, 3)], 4);
+    let view = graph.reversed_view();
+    let ids = |nodes: Vec<Node>| nodes.iter().map(Node::id).collect::<Vec<_>>();
+    assert_eq!(ids(dfs(&view, Node::from(3))), vec![3, 2, 1, 0]);
+    assert_eq!(ids(dfs(&graph, Node::from(3))), vec![3]);
+    assert!(view.get_successors(Node::from(7)).is_empty());
+    assert!(std::ptr::eq(view.graph(), &graph));
+}
+
+#[test]
+fn test_undirected_view() {
+    let graph = build_graph(&[(0, 1), (2, 1), (1, 3), (3, 1)], 5);
+    let view = graph.undirected_view();
+    let neighbours = |id| view.get_neighbours(Node::from(id)).map(|node| node.id()).collect::<Vec<_>>();
+    assert_eq!(neighbours(1), vec![0, 2, 3, 3]);
+    assert_eq!(neighbours(0), vec![1]);
+    assert!(neighbours(4).is_empty());
+    assert_eq!(components(&graph), 2);
+}
+
+#[test]
+fn test_undirected_view_matches_connected_property() {
+    let mut rng = StdRng::seed_from_u64(17);
+    for _ in 0..200 {
+        let graph = DirectedGraph::from_dto(&generate_random_dag(&mut rng, 12, 0.15)).unwrap();
+        assert_eq!(components(&graph) == 1, graph.basic_properties().connected);
+        assert_eq!(components(&graph), components(&reversed(&graph)));
+    }
+}
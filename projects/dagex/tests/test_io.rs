@@ -0,0 +1,86 @@
+use std::{error::Error, fs, path::PathBuf};
+
+use dagex::{
+    generators::generate_random_phylo_network,
+    io::{load_network, load_network_as, save_network, Format, LoadError},
+    phylo::{parse_newick_from_str, PhylogeneticNetwork}};
+use rand::{rngs::StdRng, SeedableRng};
+use rstest::rstest;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("dagex-test-io-{}-{name}", std::process::id()))
+}
+
+#[rstest]
+#[case("((A,B),(C,D));")]
+#[case("((A,(D)#H1),(#H1,C));")]
+#[case("(('Homo sapiens',B),C);")]
+fn test_newick_round_trip(#[case] text: &str, #[values(Format::Newick, Format::Auto)] format: Format) {
+    let network = parse_newick_from_str(text).unwrap().network;
+    let path = temp_path(&format!("newick-{:?}-{}.nwk", format, i32::from(network.id())));
+    save_network(&path, &network, format).unwrap();
+    assert_eq!(load_network(&path).unwrap(), network);
+    assert_eq!(load_network_as(&path, Format::Newick).unwrap(), network);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_canonical_text_round_trip() {
+    let mut rng = StdRng::seed_from_u64(8);
+    let path = temp_path("canonical.txt");
+    for _ in 0..50 {
+        let network = PhylogeneticNetwork::from_dto(&generate_random_phylo_network(&mut rng, 8, 3)).unwrap();
+        save_network(&path, &network, Format::CanonicalText).unwrap();
+        assert_eq!(Format::detect(&fs::read_to_string(&path).unwrap()), Format::CanonicalText);
+        assert_eq!(load_network(&path).unwrap(), network);
+    }
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_detect() {
+    assert_eq!(Format::detect("# comment\n\nnodes 1\n"), Format::CanonicalText);
+    assert_eq!(Format::detect("((A,B),C);"), Format::Newick);
+    assert_eq!(Format::detect("nodesA;"), Format::Newick);
+    assert_eq!(Format::detect(""), Format::Newick);
+}
+
+#[test]
+fn test_load_errors() {
+    let missing = temp_path("missing.nwk");
+    let error = load_network(&missing).unwrap_err();
+    assert!(matches!(error, LoadError::Io(_)), "Invalid result: {error:?}");
+
+    let path = temp_path("invalid.nwk");
+    fs::write(&path, "((A,B),C").unwrap();
+    let error = load_network(&path).unwrap_err();
+    assert!(matches!(error, LoadError::Newick(_)), "Invalid result: {error:?}");
+    assert!(error.to_string().contains("Newick"));
+    assert!(error.source().is_some());
+
+    fs::write(&path, "nodes 2\n0 -> 1\n1 -> 0\n").unwrap();
+    let error = load_network(&path).unwrap_err();
+    assert!(matches!(error, LoadError::CanonicalTextNetwork(_)), "Invalid result: {error:?}");
+
+    fs::write(&path, "nodes 2\n0 => 1\n").unwrap();
+    let error = load_network(&path).unwrap_err();
+    assert!(matches!(error, LoadError::CanonicalText(_)), "Invalid result: {error:?}");
+    assert!(error.to_string().contains("canonical text"));
+    fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "gzip")]
+#[rstest]
+#[case("newick.nwk.gz", Format::Newick)]
+#[case("canonical.txt.gz", Format::CanonicalText)]
+fn test_gzip_round_trip(#[case] name: &str, #[case] format: Format) {
+    let network = parse_newick_from_str("((A,(D)#H1),(#H1,C));").unwrap().network;
+    let path = temp_path(name);
+    save_network(&path, &network, format).unwrap();
+    let bytes = fs::read(&path).unwrap();
+    assert_eq!(bytes[..2], [0x1f, 0x8b]);
+    assert!(fs::read_to_string(&path).is_err());
+    assert_eq!(load_network(&path).unwrap(), network);
+    assert_eq!(load_network_as(&path, format).unwrap(), network);
+    fs::remove_file(&path).unwrap();
+}
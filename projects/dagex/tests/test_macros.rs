@@ -1,6 +1,11 @@
 use std::collections::HashSet;
 
-use dagex::{const_parse_newick, core::Node};
+use dagex::{
+    const_parse_newick,
+    const_parse_newick_file,
+    const_parse_newick_forest_file,
+    core::Node,
+    phylo::{parse_newick_from_str, PhylogeneticNetwork}};
 
 
 #[test]
@@ -138,4 +143,62 @@ fn test_episode_feasibility_network() {
     assert!(network.is_leaf(x_node));
     assert_eq!(graph.get_predecessors(x_node), [w_node]);
     assert_eq!(graph.get_predecessors(w_node), [root]);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_parse_file() {
+    let network = const_parse_newick_file!("tests/data/species.nwk");
+    let expected = parse_newick_from_str(include_str!("data/species.nwk")).unwrap().network;
+    assert_eq!(network, expected);
+    assert_eq!(network.graph().leaves().len(), 4);
+}
+
+#[test]
+fn test_parse_forest_file() {
+    let networks = const_parse_newick_forest_file!("tests/data/forest.nwk");
+    let expected: Vec<_> = include_str!("data/forest.nwk")
+        .lines()
+        .map(|line| parse_newick_from_str(line).unwrap().network)
+        .collect();
+    assert_eq!(networks, expected);
+    let reticulations = networks[2].graph()
+        .iter_nodes()
+        .filter(|n| networks[2].is_reticulation_node(*n))
+        .count();
+    assert_eq!(reticulations, 1);
+    assert_eq!(const_parse_newick_forest_file!("tests/data/two_networks.nwk").len(), 2);
+}
+
+#[test]
+fn test_parse_with_comments() {
+    let network = const_parse_newick!("((A[&&NHX:S=HUMAN],B)[nested [comment]],('C[x]',D));");
+    let expected = parse_newick_from_str("((A,B),('C[x]',D));").unwrap().network;
+    assert_eq!(network, expected);
+    let taxa: HashSet<&str> = network.taxa().values().map(|t| t.value().as_str()).collect();
+    assert_eq!(taxa, HashSet::from(["A", "B", "C[x]", "D"]));
+}
+
+fn reticulated_fixture() -> PhylogeneticNetwork {
+    const_parse_newick!("((A,(B)#H1),((#H1,C),A));")
+}
+
+#[test]
+fn test_repeated_evaluation() {
+    let first = reticulated_fixture();
+    let second = reticulated_fixture();
+    assert_eq!(first, second);
+    assert_ne!(first.id(), second.id());
+    assert_ne!(first.graph().id(), second.graph().id());
+
+    let expected = parse_newick_from_str("((A,(B)#H1),((#H1,C),A));").unwrap().network;
+    assert_eq!(first, expected);
+    let graph = first.graph();
+    let expected_graph = expected.graph();
+    assert_eq!(graph.root(), expected_graph.root());
+    assert_eq!(graph.leaves(), expected_graph.leaves());
+    assert_eq!(graph.basic_properties(), expected_graph.basic_properties());
+    assert_eq!(first.taxa(), expected.taxa());
+    for node in graph.iter_nodes() {
+        assert_eq!(graph.get_predecessors(node), expected_graph.get_predecessors(node));
+    }
+}
@@ -0,0 +1,151 @@
+use std::{collections::HashMap, sync::Arc};
+
+use dagex::{
+    core::{ArrowDTO, DirectedGraphDTO},
+    phylo::{
+        parse_newick_from_str,
+        GenesOverSpecies,
+        NetworkInterner,
+        PhylogeneticNetwork,
+        PhylogeneticNetworkDTO},
+    raf_array::immutable_string::ImmutableString};
+
+fn parse(text: &str) -> PhylogeneticNetwork {
+    parse_newick_from_str(text).unwrap().network
+}
+
+fn build(arrows: &[(i32, i32)], taxa: &[(i32, &str)], number_of_nodes: i32) -> PhylogeneticNetwork {
+    let arrows: Vec<ArrowDTO> = arrows.iter()
+        .map(|p| ArrowDTO::new(p.0, p.1))
+        .collect();
+    let taxa: HashMap<i32, ImmutableString> = taxa.iter()
+        .map(|p| (p.0, ImmutableString::new(p.1).unwrap()))
+        .collect();
+    let dto = PhylogeneticNetworkDTO::new(DirectedGraphDTO::new(number_of_nodes, arrows), taxa);
+    PhylogeneticNetwork::from_dto(&dto).unwrap()
+}
+
+#[test]
+fn test_intern_duplicates() {
+    let interner = NetworkInterner::new(4);
+    let first = interner.intern(parse("((A,B),C);"));
+    let second = interner.intern(parse("((A,B),C);"));
+    let third = interner.intern(parse("(A,(B,C));"));
+    assert!(Arc::ptr_eq(&first, &second));
+    assert!(!Arc::ptr_eq(&first, &third));
+    assert_eq!(interner.len(), 2);
+    let stats = interner.statistics();
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 2);
+    assert_eq!(stats.evictions, 0);
+}
+
+#[test]
+fn test_intern_relabeled() {
+    let interner = NetworkInterner::new(4);
+    let first = interner.intern(build(
+        &[(0, 1), (1, 2), (1, 3), (0, 4)],
+        &[(2, "A"), (3, "B"), (4, "C")],
+        5));
+    // Same network with nodes numbered bottom-up and arrows shuffled.
+    let relabeled = build(
+        &[(2, 1), (4, 3), (2, 0), (4, 2)],
+        &[(0, "A"), (1, "B"), (3, "C")],
+        5);
+    assert_ne!(&relabeled, first.as_ref());
+    let second = interner.intern(relabeled);
+    assert!(Arc::ptr_eq(&first, &second));
+
+    let other = interner.intern(build(
+        &[(4, 2), (2, 0), (2, 1), (4, 3)],
+        &[(0, "A"), (1, "C"), (3, "B")],
+        5));
+    assert!(!Arc::ptr_eq(&first, &other));
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.statistics().hits, 1);
+}
+
+#[test]
+fn test_intern_weighted() {
+    let build_weighted = |weights: [f64; 2]| {
+        let arrows = vec![
+            ArrowDTO::new(0, 1).with_weight(Some(weights[0])),
+            ArrowDTO::new(0, 2).with_weight(Some(weights[1])),
+        ];
+        let taxa: HashMap<i32, ImmutableString> = [(1, "A"), (2, "B")].iter()
+            .map(|p| (p.0, ImmutableString::new(p.1).unwrap()))
+            .collect();
+        let dto = PhylogeneticNetworkDTO::new(DirectedGraphDTO::new(3, arrows), taxa);
+        PhylogeneticNetwork::from_dto(&dto).unwrap()
+    };
+    let interner = NetworkInterner::new(4);
+    let first = interner.intern(build_weighted([1.0, 2.0]));
+    let second = interner.intern(build_weighted([1.0, 3.0]));
+    assert!(!Arc::ptr_eq(&first, &second));
+    let arrows = first.to_canonical_dto().graph().arrows().clone();
+    let weights: Vec<Option<f64>> = arrows.iter().map(ArrowDTO::weight).collect();
+    assert_eq!(weights, vec![Some(1.0), Some(2.0)]);
+
+    let third = interner.intern(build_weighted([1.0, 2.0]));
+    assert!(Arc::ptr_eq(&first, &third));
+    assert_eq!(interner.len(), 2);
+}
+
+#[test]
+fn test_lru_eviction() {
+    let interner = NetworkInterner::new(2);
+    let a = interner.intern(parse("(A,B);"));
+    let _b = interner.intern(parse("(B,C);"));
+    // Touch "a" so that "b" becomes least recently used.
+    let a_again = interner.intern(parse("(A,B);"));
+    assert!(Arc::ptr_eq(&a, &a_again));
+    let _c = interner.intern(parse("(C,D);"));
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.statistics().evictions, 1);
+
+    let a_third = interner.intern(parse("(A,B);"));
+    assert!(Arc::ptr_eq(&a, &a_third));
+    let b_again = interner.intern(parse("(B,C);"));
+    assert_eq!(interner.statistics().misses, 4);
+    assert_eq!(b_again.taxa().len(), 2);
+}
+
+#[test]
+fn test_thread_safety() {
+    let interner = Arc::new(NetworkInterner::new(16));
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let interner = interner.clone();
+            std::thread::spawn(move || {
+                (0..25)
+                    .map(|_| interner.intern(parse("((A,B),(C,D));")))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let results: Vec<Arc<PhylogeneticNetwork>> = handles.into_iter()
+        .flat_map(|h| h.join().unwrap())
+        .collect();
+    assert!(results.iter().all(|n| Arc::ptr_eq(n, &results[0])));
+    let stats = interner.statistics();
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.hits, 99);
+}
+
+#[test]
+fn test_genes_over_species_shared() {
+    let interner = NetworkInterner::new(8);
+    let genes: Vec<Arc<PhylogeneticNetwork>> = ["(A,B);", "(A,B);", "(B,C);"]
+        .iter()
+        .map(|text| interner.intern(parse(text)))
+        .collect();
+    let species = parse("((A,B),C);");
+    let genes_over_species
+        = GenesOverSpecies::from_shared_networks(genes.clone(), species).unwrap();
+    let stored = genes_over_species.gene_networks();
+    assert_eq!(stored.len(), 3);
+    assert!(Arc::ptr_eq(&stored[0], &genes[0]));
+    assert!(Arc::ptr_eq(&stored[1], &stored[0]));
+    let by_id = genes_over_species.get_gene_network_by_id(genes[2].id()).unwrap();
+    assert_eq!(by_id, genes[2].as_ref());
+}
@@ -0,0 +1,57 @@
+use dagex::phylo::{
+    parse_newick_from_str,
+    to_newick_string,
+    to_newick_string_annotated,
+    write_newick,
+    PhylogeneticNetwork};
+use rstest::rstest;
+
+fn parse(text: &str) -> PhylogeneticNetwork {
+    parse_newick_from_str(text).unwrap().network
+}
+
+#[rstest]
+#[case(";")]
+#[case("(,());")]
+#[case("((A, B),(B, C));")]
+#[case("((A, (D)B#1),(B#1, C));")]
+#[case("((X, (b, ((L1,L2), (L3,L4)))), (d, (c, a)));")]
+#[case("(('Homo sapiens', 'a,b'), 'it''s');")]
+fn test_round_trip(#[case] text: &str) {
+    let network = parse(text);
+    let written = to_newick_string(&network);
+    let parsed = parse(&written);
+    assert_eq!(parsed, network, "Invalid round trip: {text} -> {written}");
+}
+
+#[test]
+fn test_reticulation_output() {
+    let ok = parse_newick_from_str("((A, (D)B#1),(B#1, C));").unwrap();
+    assert_eq!(to_newick_string(&ok.network), "((A,(D)B#H1),(C,B#H1));");
+    assert_eq!(to_newick_string_annotated(&ok.network, ok.newick_annotations()), "((A,(D)B#H1),(C,B#H1));");
+}
+
+#[rstest]
+#[case("((A:0.1,B:2)x:1e-5,(C:0.30000000000000004,D))root:7;")]
+#[case("((A:1,(D:2.5)B#1:0.25),(B#1,C:1E+20)):0;")]
+#[case("(('Homo sapiens':1,(b,c)'inner node':3),d);")]
+fn test_annotated_round_trip(#[case] text: &str) {
+    let ok = parse_newick_from_str(text).unwrap();
+    let written = to_newick_string_annotated(&ok.network, ok.newick_annotations());
+    let parsed = parse_newick_from_str(&written).unwrap();
+    assert_eq!(parsed.network, ok.network, "Invalid round trip: {text} -> {written}");
+    let lengths: Vec<_> = parsed.branch_lengths.iter().collect();
+    assert_eq!(lengths, ok.branch_lengths.iter().collect::<Vec<_>>());
+    let labels: Vec<_> = parsed.internal_labels.iter().collect();
+    assert_eq!(labels, ok.internal_labels.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_quoting_and_written_bytes() {
+    let network = parse("(('Homo sapiens', B), 'it''s');");
+    let mut buffer = Vec::new();
+    let result = write_newick(&network, &mut buffer).unwrap();
+    let text = String::from_utf8(buffer).unwrap();
+    assert_eq!(text, "(('Homo sapiens',B),'it''s');");
+    assert_eq!(result.written_bytes, text.len());
+}
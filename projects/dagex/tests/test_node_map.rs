@@ -0,0 +1,101 @@
+use dagex::core::{ArrowDTO, DirectedGraph, DirectedGraphDTO, Node};
+
+fn build_graph(arrows: &[(i32, i32)], number_of_nodes: i32) -> DirectedGraph {
+    let arrows = arrows.iter().map(|p| ArrowDTO::new(p.0, p.1)).collect();
+    DirectedGraph::from_dto(&DirectedGraphDTO::new(number_of_nodes, arrows)).unwrap()
+}
+
+#[test]
+fn test_node_map() {
+    let graph = build_graph(&[(0, 1), (0, 2), (1, 3), (2, 3)], 4);
+    let mut map = graph.new_node_map::<f64>();
+    assert_eq!(map.capacity(), 4);
+    assert!(map.is_empty());
+    assert_eq!(map.graph_id(), graph.id());
+
+    assert_eq!(map.set(&graph, Node::from(1), 0.5), None);
+    assert_eq!(map.set(&graph, Node::from(3), 1.5), None);
+    assert_eq!(map.set(&graph, Node::from(1), 0.25), Some(0.5));
+    *map.get_mut(&graph, Node::from(3)).unwrap() += 1.0;
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&graph, Node::from(1)), Some(&0.25));
+    assert_eq!(map.get(&graph, Node::from(0)), None);
+    assert_eq!(map.get(&graph, Node::from(-1)), None);
+    assert_eq!(map.get(&graph, Node::from(4)), None);
+    let entries: Vec<(i32, f64)> = map.iter().map(|(node, value)| (node.id(), *value)).collect();
+    assert_eq!(entries, vec![(1, 0.25), (3, 2.5)]);
+
+    assert_eq!(map.remove(&graph, Node::from(1)), Some(0.25));
+    assert_eq!(map.len(), 1);
+    map.debug_check_graph(&graph);
+}
+
+#[test]
+#[should_panic(expected = "Node outside of NodeMap capacity.")]
+fn test_node_map_out_of_range() {
+    let graph = build_graph(&[(0, 1)], 2);
+    let mut map = graph.new_node_map();
+    map.set(&graph, Node::from(2), ());
+}
+
+#[test]
+fn test_arrow_map_data() {
+    let graph = build_graph(&[(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)], 5);
+    let mut map = graph.new_arrow_map_data::<&str>();
+    assert_eq!(map.capacity(), 5);
+    assert!(map.is_empty());
+
+    assert_eq!(map.set(Node::from(0), Node::from(2), "a"), None);
+    assert_eq!(map.set(Node::from(2), Node::from(3), "b"), None);
+    assert_eq!(map.set(Node::from(3), Node::from(4), "c"), None);
+    assert_eq!(map.set(Node::from(0), Node::from(2), "d"), Some("a"));
+    assert_eq!(map.get(Node::from(0), Node::from(2)), Some(&"d"));
+    assert_eq!(map.get(Node::from(0), Node::from(1)), None);
+    assert_eq!(map.get(Node::from(2), Node::from(0)), None);
+    assert_eq!(map.get(Node::from(7), Node::from(0)), None);
+    assert_eq!(map.remove(Node::from(3), Node::from(4)), Some("c"));
+    let entries: Vec<((i32, i32), &str)> = map.iter()
+        .map(|((source, target), value)| ((source.id(), target.id()), *value))
+        .collect();
+    assert_eq!(entries, vec![((0, 2), "d"), ((2, 3), "b")]);
+    map.debug_check_graph(&graph);
+}
+
+#[test]
+#[should_panic(expected = "Arrow not present in ArrowMapData graph.")]
+fn test_arrow_map_data_missing_arrow() {
+    let graph = build_graph(&[(0, 1)], 2);
+    let mut map = graph.new_arrow_map_data();
+    map.set(Node::from(1), Node::from(0), ());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "NodeMap used with a different graph")]
+fn test_node_map_other_graph() {
+    let graph = build_graph(&[(0, 1)], 2);
+    let map = graph.new_node_map::<i32>();
+    let clone = graph.clone();
+    map.debug_check_graph(&clone);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "NodeMap used with a different graph")]
+fn test_node_map_accessor_other_graph() {
+    let graph = build_graph(&[(0, 1)], 2);
+    let other = build_graph(&[(0, 1)], 2);
+    let mut map = graph.new_node_map::<i32>();
+    map.set(&graph, Node::from(1), 5);
+    let _ = map.get(&other, Node::from(1));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "ArrowMapData used with a different graph")]
+fn test_arrow_map_data_other_graph() {
+    let graph = build_graph(&[(0, 1)], 2);
+    let other = build_graph(&[(0, 1)], 2);
+    let map = graph.new_arrow_map_data::<i32>();
+    map.debug_check_graph(&other);
+}
@@ -0,0 +1,105 @@
+use std::collections::HashSet;
+
+use dagex::core::{ArrowDTO, DirectedGraph, DirectedGraphDTO, Node, NodeBitSet, NodeSetQuery};
+use rstest::rstest;
+
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: i32) -> i32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % (bound as u64)) as i32
+    }
+}
+
+fn random_dag(number_of_nodes: i32, number_of_arrows: usize, seed: u64) -> DirectedGraph {
+    let mut rng = Lcg(seed);
+    let mut arrows = HashSet::new();
+    while arrows.len() < number_of_arrows {
+        let first = rng.next(number_of_nodes);
+        let second = rng.next(number_of_nodes);
+        if first != second {
+            arrows.insert((first.min(second), first.max(second)));
+        }
+    }
+    let arrows = arrows.into_iter().map(|(s, t)| ArrowDTO::new(s, t)).collect();
+    DirectedGraph::from_dto(&DirectedGraphDTO::new(number_of_nodes, arrows)).unwrap()
+}
+
+fn brute_force_reachable(graph: &DirectedGraph, start: Node, forward: bool) -> HashSet<Node> {
+    let mut result = HashSet::from([start]);
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (source, target) in graph.iter_arrows() {
+            let (from, to) = if forward { (source, target) } else { (target, source) };
+            if result.contains(&from) && result.insert(to) {
+                changed = true;
+            }
+        }
+    }
+    result
+}
+
+fn to_hash_set(set: &NodeBitSet) -> HashSet<Node> {
+    set.iter().collect()
+}
+
+#[test]
+fn test_bitset_operations() {
+    let mut left = NodeBitSet::new(130);
+    let mut right = NodeBitSet::new(130);
+    for id in [0, 5, 64, 129] {
+        assert!(left.insert(Node::from(id)));
+    }
+    assert!(!left.insert(Node::from(5)));
+    for id in [5, 65, 129] {
+        right.insert(Node::from(id));
+    }
+    assert_eq!(left.len(), 4);
+    assert!(left.contains(Node::from(64)));
+    assert!(!left.contains(Node::from(200)));
+    let ids = |set: &NodeBitSet| set.iter().map(|n| n.id()).collect::<Vec<_>>();
+    assert_eq!(ids(&left.union(&right)), vec![0, 5, 64, 65, 129]);
+    assert_eq!(ids(&left.intersection(&right)), vec![5, 129]);
+    assert_eq!(ids(&left.difference(&right)), vec![0, 64]);
+    assert!(left.remove(Node::from(0)));
+    assert!(!left.remove(Node::from(0)));
+    assert!(NodeBitSet::new(10).is_empty());
+}
+
+#[rstest]
+#[case(20, 30, 1, false)]
+#[case(50, 120, 2, true)]
+#[case(100, 150, 3, false)]
+#[case(100, 400, 4, true)]
+fn test_queries_against_brute_force(
+    #[case] number_of_nodes: i32,
+    #[case] number_of_arrows: usize,
+    #[case] seed: u64,
+    #[case] cached: bool)
+{
+    let graph = random_dag(number_of_nodes, number_of_arrows, seed);
+    let mut query = if cached { NodeSetQuery::with_cache(&graph) } else { NodeSetQuery::new(&graph) };
+    for _ in 0..2 {
+        for node in graph.iter_nodes() {
+            assert_eq!(to_hash_set(&query.descendant_set(node)), brute_force_reachable(&graph, node, true));
+            assert_eq!(to_hash_set(&query.ancestor_set(node)), brute_force_reachable(&graph, node, false));
+        }
+    }
+
+    let mut rng = Lcg(seed);
+    for _ in 0..50 {
+        let source = Node::from(rng.next(number_of_nodes));
+        let target = Node::from(rng.next(number_of_nodes));
+        let descendants = brute_force_reachable(&graph, source, true);
+        let expected: HashSet<Node> = if descendants.contains(&target) {
+            descendants.intersection(&brute_force_reachable(&graph, target, false)).copied().collect()
+        }
+        else
+        {
+            HashSet::new()
+        };
+        assert_eq!(to_hash_set(&query.nodes_between(source, target)), expected);
+    }
+}
@@ -0,0 +1,49 @@
+use std::collections::HashSet;
+
+use dagex::core::{DirectedGraph, Node, PackedOptionNode};
+use rstest::rstest;
+
+#[test]
+fn test_size() {
+    assert_eq!(core::mem::size_of::<PackedOptionNode>(), 4);
+    assert_eq!(core::mem::size_of::<Option<Node>>(), 8);
+}
+
+#[rstest]
+#[case(None)]
+#[case(Some(0))]
+#[case(Some(1))]
+#[case(Some(DirectedGraph::max_size() - 1))]
+#[case(Some(-1))]
+#[case(Some(i32::MAX))]
+fn test_conversions(#[case] id: Option<i32>) {
+    let value = id.map(Node::from);
+    let packed = PackedOptionNode::from_option(value);
+    assert_eq!(packed.to_option(), value);
+    assert_eq!(packed.is_none(), value.is_none());
+    assert_eq!(packed.is_some(), value.is_some());
+    assert_eq!(Option::<Node>::from(packed), value);
+    assert_eq!(PackedOptionNode::from(value), packed);
+    assert_eq!(format!("{packed:?}"), format!("{value:?}"));
+}
+
+#[test]
+fn test_sentinel() {
+    assert_eq!(PackedOptionNode::NONE, PackedOptionNode::default());
+    assert_eq!(PackedOptionNode::NONE.to_option(), None);
+    assert!(!(0..DirectedGraph::max_size()).contains(&PackedOptionNode::SENTINEL));
+
+    let mut packed = PackedOptionNode::from(Node::from(5));
+    assert_eq!(packed.take(), Some(Node::from(5)));
+    assert!(packed.is_none());
+    assert_eq!(packed.take(), None);
+
+    let set = HashSet::from([PackedOptionNode::NONE, PackedOptionNode::from(Node::from(0)), PackedOptionNode::default()]);
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "is reserved")]
+fn test_sentinel_node_panics() {
+    let _ = PackedOptionNode::from(Node::from(PackedOptionNode::SENTINEL));
+}
@@ -1,6 +1,16 @@
-use std::collections::HashSet;
+use std::{collections::{HashMap, HashSet}, error::Error};
 
-use dagex::phylo::parse_newick_from_str;
+use dagex::phylo::{
+    parse_newick_forest,
+    parse_newick_forest_from_str,
+    parse_newick_from_str,
+    parse_newick_from_str_with_options,
+    NewickContentError,
+    NewickParseError,
+    NewickParseOptions,
+    PhylogeneticNetworkFromError,
+    PhylogeneticNetworkKind};
+use rstest::rstest;
 
 
 #[test]
@@ -76,3 +86,303 @@ fn test_parser_4() {
         .count();
     assert_eq!(reticulations, 1);
 }
+
+const FOREST: &str = "(A,B);\n[second tree] (C,D));\n  ((A, 'B;C'),C);\n";
+
+#[test]
+fn test_forest_continue_on_error() {
+    let results = parse_newick_forest_from_str(FOREST);
+    assert_eq!(results.len(), 3);
+    let first = results[0].as_ref().unwrap();
+    assert_eq!(first.read_bytes, 6);
+    assert_eq!(first.network.taxa().len(), 2);
+    assert!(matches!(results[1], Err(NewickParseError::ContentError(_))));
+    let third = results[2].as_ref().unwrap();
+    assert_eq!(third.read_bytes, FOREST.len() - 1);
+    let taxa: HashSet<&str> = third.network.taxa().values().map(|t| t.value().as_str()).collect();
+    assert_eq!(taxa, HashSet::from(["A", "B;C", "C"]));
+}
+
+#[test]
+fn test_forest_stop_on_error() {
+    let mut stream = FOREST.as_bytes();
+    let results: Vec<_> = parse_newick_forest(&mut stream)
+        .stop_on_error(true)
+        .collect();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+}
+
+#[test]
+fn test_forest_unterminated_entry() {
+    let results = parse_newick_forest_from_str("(A,B); (C,D)");
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(matches!(results[1], Err(NewickParseError::ContentError(_))));
+    assert!(parse_newick_forest_from_str("  [only comment]\n").is_empty());
+}
+
+fn node_of(ok: &dagex::phylo::NewickParseOk, taxon: &str) -> dagex::core::Node {
+    ok.network.taxa().iter()
+        .find(|kvp| kvp.1.value().as_str() == taxon)
+        .map(|kvp| *kvp.0)
+        .unwrap()
+}
+
+fn length_of(ok: &dagex::phylo::NewickParseOk, taxon: &str) -> Option<f64> {
+    ok.branch_lengths.get(ok.network.graph(), node_of(ok, taxon)).copied()
+}
+
+#[test]
+fn test_branch_lengths() {
+    let ok = parse_newick_from_str("((A:0.1,B:2)'x y':1e-5,( C :3.25 ,D))root:7;").unwrap();
+    ok.branch_lengths.debug_check_graph(ok.network.graph());
+    assert_eq!(length_of(&ok, "A"), Some(0.1));
+    assert_eq!(length_of(&ok, "B"), Some(2.0));
+    assert_eq!(length_of(&ok, "C"), Some(3.25));
+    assert_eq!(length_of(&ok, "D"), None);
+    let graph = ok.network.graph();
+    let root = ok.network.root();
+    assert_eq!(ok.branch_lengths.get(ok.network.graph(), root), Some(&7.0));
+    let inner = graph.get_predecessors(node_of(&ok, "A"))[0];
+    assert_eq!(ok.branch_lengths.get(ok.network.graph(), inner), Some(&1e-5));
+    assert_eq!(ok.branch_lengths.len(), 5);
+}
+
+#[test]
+fn test_branch_lengths_reticulation() {
+    let ok = parse_newick_from_str("((A,(B)#H1:0.5),(#H1:0.75,C:1));").unwrap();
+    let reticulation = ok.network.graph().iter_nodes()
+        .find(|node| ok.network.graph().get_predecessors(*node).len() == 2)
+        .unwrap();
+    assert_eq!(ok.branch_lengths.get(ok.network.graph(), reticulation), Some(&0.5));
+    assert_eq!(length_of(&ok, "C"), Some(1.0));
+    assert_eq!(length_of(&ok, "A"), None);
+}
+
+#[test]
+fn test_invalid_branch_length() {
+    let Err(NewickParseError::ContentError(err)) = parse_newick_from_str("(A:abc,B);") else {
+        panic!("Expected content error.");
+    };
+    assert!(err.message().contains("abc"), "Unexpected message: {err}");
+    assert_eq!(err.position(), 3);
+    assert_eq!(err.excerpt(), "(A:abc,B);");
+}
+
+#[test]
+fn test_forest_branch_lengths() {
+    let results = parse_newick_forest_from_str("(A:1,B);\n(A,B:2);");
+    assert_eq!(length_of(results[0].as_ref().unwrap(), "A"), Some(1.0));
+    assert_eq!(length_of(results[1].as_ref().unwrap(), "B"), Some(2.0));
+}
+
+#[test]
+fn test_internal_labels() {
+    let ok = parse_newick_from_str("((A,B)ab:1,((C,(D,E):2)'c d e',F))root;").unwrap();
+    let taxa: HashSet<&str> = ok.network.taxa().values().map(|t| t.value().as_str()).collect();
+    assert_eq!(taxa, HashSet::from(["A", "B", "C", "D", "E", "F", "ab", "c d e", "root"]));
+    let graph = ok.network.graph();
+    let label_of = |node| ok.internal_labels.get(ok.network.graph(), node).map(|label| label.as_str());
+    assert_eq!(label_of(ok.network.root()), Some("root"));
+    assert_eq!(label_of(graph.get_predecessors(node_of(&ok, "A"))[0]), Some("ab"));
+    assert_eq!(label_of(graph.get_predecessors(node_of(&ok, "C"))[0]), Some("c d e"));
+    let unlabeled = graph.get_predecessors(node_of(&ok, "D"))[0];
+    assert_eq!(label_of(unlabeled), None);
+    assert_eq!(ok.branch_lengths.get(ok.network.graph(), unlabeled), Some(&2.0));
+    assert_eq!(ok.internal_labels.len(), 3);
+}
+
+#[test]
+fn test_branch_lengths_as_weights() {
+    let ok = parse_newick_from_str("((A:1,(D)B#1:1.5)X:3,(B#1:2.5,C));").unwrap();
+    let network = &ok.network;
+    let graph = network.graph();
+    let reticulation = network.get_single_by_taxon("B").unwrap();
+    let weights: HashSet<String> = graph.get_predecessors(reticulation)
+        .iter()
+        .map(|parent| format!("{:?}", graph.arrow_weight(*parent, reticulation)))
+        .collect();
+    assert_eq!(weights, HashSet::from(["Some(1.5)".to_owned(), "Some(2.5)".to_owned()]));
+    let leaf = node_of(&ok, "A");
+    let parent = graph.get_predecessors(leaf)[0];
+    assert_eq!(graph.arrow_weight(parent, leaf), Some(1.0));
+    assert_eq!(graph.arrow_weight(network.root(), parent), Some(3.0));
+    let leaf = node_of(&ok, "C");
+    assert_eq!(graph.arrow_weight(graph.get_predecessors(leaf)[0], leaf), None);
+}
+
+#[test]
+fn test_error_source_chain() {
+    let error = parse_newick_from_str("(A,B,C);").unwrap_err();
+    assert!(matches!(error, NewickParseError::PhylogeneticNetworkError(_)), "Invalid result: {error:?}");
+    let source = error.source().unwrap();
+    assert!(matches!(
+        source.downcast_ref::<PhylogeneticNetworkFromError>(),
+        Some(PhylogeneticNetworkFromError::NotBinary)));
+    assert_eq!(source.to_string(), "phylogenetic network is not binary");
+    assert!(source.source().is_none());
+
+    let error = parse_newick_from_str("(A:abc,B);").unwrap_err();
+    assert!(error.to_string().starts_with("invalid newick content: "));
+    assert!(error.source().is_none());
+}
+
+fn content_error(input: &str, options: &NewickParseOptions) -> NewickContentError {
+    match parse_newick_from_str_with_options(input, options) {
+        Err(NewickParseError::ContentError(err)) => err,
+        other => panic!("Expected content error, got: {other:?}"),
+    }
+}
+
+#[rstest]
+#[case("((A,B),A);", NewickParseOptions::default().with_allow_duplicate_taxa(false), 7, "Duplicate taxon 'A'.")]
+#[case("((A,B),(C,A));", NewickParseOptions::default().with_allow_duplicate_taxa(false), 10, "Duplicate taxon 'A'.")]
+#[case("((A,),B);", NewickParseOptions::default().with_allow_unlabeled_leaves(false), 4, "Leaf without a name.")]
+#[case("((A,#H1),((B)#H1,(C,#H1)));", NewickParseOptions::default().with_allow_repeated_hybrids(false), 20, "Reticulation 1 occurs more than twice.")]
+fn test_parse_options_rejections(
+    #[case] input: &str,
+    #[case] options: NewickParseOptions,
+    #[case] position: usize,
+    #[case] message: &str)
+{
+    let err = content_error(input, &options);
+    assert_eq!(err.position(), position, "Invalid position: {err}");
+    assert_eq!(err.message(), message);
+}
+
+#[test]
+fn test_parse_options_defaults() {
+    assert!(parse_newick_from_str("((A,B),A);").is_ok());
+    assert!(parse_newick_from_str("((A,),B);").is_ok());
+    let options = NewickParseOptions::default()
+        .with_allow_duplicate_taxa(false)
+        .with_allow_unlabeled_leaves(false);
+    assert!(parse_newick_from_str_with_options("((A,B),(C,D));", &options).is_ok());
+    let reticulation = "((A,#H1),(B)#H1);";
+    let options = options.with_allow_repeated_hybrids(false);
+    assert!(parse_newick_from_str_with_options(reticulation, &options).is_ok());
+}
+
+#[test]
+fn test_parse_options_require_binary() {
+    let result = parse_newick_from_str("(A,B,C);");
+    assert!(matches!(result, Err(NewickParseError::PhylogeneticNetworkError(PhylogeneticNetworkFromError::NotBinary))));
+
+    let options = NewickParseOptions::default().with_require_binary(false);
+    let network = parse_newick_from_str_with_options("(A,B,C);", &options).unwrap().network;
+    assert_eq!(network.taxa().len(), 3);
+    assert_eq!(network.graph().out_degree(network.root()), 3);
+    assert_eq!(network.kind(), PhylogeneticNetworkKind::Multifurcating);
+    let binary = parse_newick_from_str_with_options("((A,B),C);", &options).unwrap().network;
+    assert_eq!(binary.kind(), PhylogeneticNetworkKind::Binary);
+
+    let repeated = "((A,#H1),((B)#H1,(C,#H1)));";
+    let result = parse_newick_from_str(repeated);
+    assert!(matches!(result, Err(NewickParseError::PhylogeneticNetworkError(PhylogeneticNetworkFromError::NotBinary))));
+    let network = parse_newick_from_str_with_options(repeated, &options).unwrap().network;
+    assert_eq!(network.reticulation_count(), 1);
+    assert_eq!(network.taxa().len(), 3);
+}
+
+#[test]
+fn test_forest_error_positions() {
+    let results = parse_newick_forest_from_str("(A,B);\n(C:x,D);\n(E,F)");
+    assert_eq!(results.len(), 3);
+    let Err(NewickParseError::ContentError(err)) = &results[1] else {
+        panic!("Expected content error.");
+    };
+    assert_eq!(err.position(), 10);
+    assert_eq!(err.excerpt(), "(C:x,D);");
+    let Err(NewickParseError::ContentError(err)) = &results[2] else {
+        panic!("Expected content error.");
+    };
+    assert_eq!(err.position(), 21);
+    assert!(err.to_string().contains("at byte 21"), "Unexpected message: {err}");
+
+    let mut stream = "((A,B),(C,A));".as_bytes();
+    let options = NewickParseOptions::default().with_allow_duplicate_taxa(false);
+    let results: Vec<_> = parse_newick_forest(&mut stream).with_options(options).collect();
+    assert!(matches!(&results[0], Err(NewickParseError::ContentError(err)) if err.position() == 10));
+}
+
+#[test]
+fn test_comments_skipped() {
+    let input = "((A[x],B[nested [comment]; here]):1[c],('C[not a comment]',D)[&&NHX:S=HUMAN]);";
+    let ok = parse_newick_from_str(input).unwrap();
+    let taxa: HashSet<&str> = ok.network.taxa().values().map(|t| t.value().as_str()).collect();
+    assert_eq!(taxa, HashSet::from(["A", "B", "C[not a comment]", "D"]));
+    let inner = ok.network.graph().get_predecessors(node_of(&ok, "A"))[0];
+    assert_eq!(ok.branch_lengths.get(ok.network.graph(), inner), Some(&1.0));
+    assert!(ok.annotations.is_empty());
+    assert_eq!(ok.read_bytes, input.len());
+}
+
+#[test]
+fn test_forest_nested_comments() {
+    let results = parse_newick_forest_from_str("[a [b]; c] (A,B);\n(C[d [e]],D);");
+    assert_eq!(results.len(), 2);
+    let taxa: HashSet<&str> = results[1].as_ref().unwrap().network.taxa()
+        .values()
+        .map(|t| t.value().as_str())
+        .collect();
+    assert_eq!(taxa, HashSet::from(["C", "D"]));
+}
+
+fn nhx_of(ok: &dagex::phylo::NewickParseOk, node: dagex::core::Node) -> HashMap<&str, &str> {
+    ok.annotations.get(&node)
+        .map(|pairs| pairs.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_nhx_annotations() {
+    let input = "((HUMAN_g1:0.1[&&NHX:S=HUMAN:D=N],MOUSE_g1:0.2[&&NHX:S=MOUSE])\
+        :0.3[&&NHX:S=Euarchontoglires:D=Y],RAT_g1[&&NHX:S=RAT][other comment])root;";
+    let options = NewickParseOptions::default().with_parse_nhx(true);
+    let ok = parse_newick_from_str_with_options(input, &options).unwrap();
+    let human = node_of(&ok, "HUMAN_g1");
+    assert_eq!(nhx_of(&ok, human), HashMap::from([("S", "HUMAN"), ("D", "N")]));
+    assert_eq!(nhx_of(&ok, node_of(&ok, "MOUSE_g1")), HashMap::from([("S", "MOUSE")]));
+    assert_eq!(nhx_of(&ok, node_of(&ok, "RAT_g1")), HashMap::from([("S", "RAT")]));
+    let inner = ok.network.graph().get_predecessors(human)[0];
+    assert_eq!(nhx_of(&ok, inner), HashMap::from([("S", "Euarchontoglires"), ("D", "Y")]));
+    assert_eq!(ok.annotations.len(), 4);
+    assert_eq!(length_of(&ok, "HUMAN_g1"), Some(0.1));
+    assert_eq!(ok.branch_lengths.get(ok.network.graph(), inner), Some(&0.3));
+    assert_eq!(ok.internal_labels.get(ok.network.graph(), ok.network.root()).map(|label| label.as_str()), Some("root"));
+}
+
+#[test]
+fn test_nhx_annotations_reticulation() {
+    let input = "((A,(B)#H1[&&NHX:D=N]),(#H1[&&NHX:D=Y:S=X],C));";
+    let options = NewickParseOptions::default().with_parse_nhx(true);
+    let ok = parse_newick_from_str_with_options(input, &options).unwrap();
+    let reticulation = ok.network.graph().get_predecessors(node_of(&ok, "B"))[0];
+    assert_eq!(nhx_of(&ok, reticulation), HashMap::from([("D", "N"), ("S", "X")]));
+    assert_eq!(ok.annotations.len(), 1);
+}
+
+#[rstest]
+#[case("(A[&&NHX:S=HUMAN:oops],B);", 17, "Malformed NHX annotation 'oops', expected key=value.")]
+#[case("((A,B)[&&NHX:=Y],C);", 13, "Malformed NHX annotation '=Y', expected key=value.")]
+fn test_nhx_malformed(#[case] input: &str, #[case] position: usize, #[case] message: &str) {
+    let err = content_error(input, &NewickParseOptions::default().with_parse_nhx(true));
+    assert_eq!(err.position(), position, "Invalid position: {err}");
+    assert_eq!(err.message(), message);
+    assert!(parse_newick_from_str(input).is_ok());
+}
+
+#[test]
+fn test_comment_rejections() {
+    let options = NewickParseOptions::default().with_allow_comments(false);
+    let err = content_error("((A,B)[x],C);", &options);
+    assert_eq!(err.position(), 6);
+    assert_eq!(err.message(), "Comments are not allowed.");
+    assert!(parse_newick_from_str_with_options("('A[1]',B);", &options).is_ok());
+
+    let err = content_error("(A,B)[oops;", &NewickParseOptions::default());
+    assert_eq!(err.position(), 5);
+    assert_eq!(err.message(), "Unexpected end of input, unterminated comment.");
+}
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+
+use dagex::phylo::{
+    parse_newick_from_str,
+    PhylogeneticForest,
+    PhylogeneticForestNewError,
+    PhylogeneticForestRestrictionError,
+    PhylogeneticNetwork,
+    Taxon};
+
+fn parse(text: &str) -> PhylogeneticNetwork {
+    parse_newick_from_str(text).unwrap().network
+}
+
+fn forest(texts: &[&str]) -> PhylogeneticForest {
+    PhylogeneticForest::new(texts.iter().map(|t| parse(t)).collect()).unwrap()
+}
+
+fn taxa(names: &[&str]) -> HashSet<Taxon> {
+    names.iter().map(|name| Taxon::new(name).unwrap()).collect()
+}
+
+#[test]
+fn test_empty_forest() {
+    let result = PhylogeneticForest::new(Vec::new());
+    assert!(matches!(result, Err(PhylogeneticForestNewError::EmptyForest)));
+}
+
+#[test]
+fn test_incompatible_taxa() {
+    let networks = vec![parse("(A,B);"), parse("(A,X);")];
+    let result = PhylogeneticForest::new_with_taxa(networks, &taxa(&["A", "B"]));
+    assert!(matches!(result, Err(PhylogeneticForestNewError::IncompatibleTaxa(1))));
+}
+
+#[test]
+fn test_taxa_index() {
+    let forest = forest(&["((A,B),C);", "((A,C),D);", "(A,(C,E));"]);
+    assert_eq!(forest.len(), 3);
+    assert_eq!(forest.taxa().count(), 5);
+    assert_eq!(forest.common_taxa(), taxa(&["A", "C"]));
+    let with_b: Vec<&PhylogeneticNetwork> = forest
+        .networks_with_taxon(&Taxon::new("B").unwrap())
+        .collect();
+    assert_eq!(with_b, vec![&forest.networks()[0]]);
+    assert_eq!(forest.networks_with_taxon(&Taxon::new("Z").unwrap()).count(), 0);
+}
+
+#[test]
+fn test_restrict_all_to_common_taxa() {
+    let forest = forest(&["((A,B),C);", "((A,C),D);", "(A,(C,E));"]);
+    let restricted = forest.restrict_all_to_common_taxa().unwrap();
+    for network in restricted.networks() {
+        assert_eq!(network.graph().number_of_nodes(), 3);
+        assert_eq!(network.taxa().len(), 2);
+    }
+
+    let disjoint = self::forest(&["(A,B);", "(C,D);"]);
+    let result = disjoint.restrict_all_to_common_taxa();
+    assert!(matches!(result, Err(PhylogeneticForestRestrictionError::EmptyCommonTaxa)));
+}
+
+#[test]
+fn test_restrict_keeps_reticulation() {
+    let forest = forest(&["((A, (D)B#1),(B#1, C));", "(A,D);"]);
+    let restricted = forest.restrict_all_to_common_taxa().unwrap();
+    let network = &restricted.networks()[0];
+    assert_eq!(network.graph().number_of_nodes(), 5);
+    assert_eq!(network.statistics().number_of_reticulation_arrows, 2);
+    assert_eq!(network.taxa().len(), 2);
+    assert_eq!(network.graph().leaves().len(), 2);
+}
+
+#[test]
+fn test_into_genes_over_species() {
+    let forest = forest(&["(A,B);", "(B,C);"]);
+    let species = parse("((A,B),C);");
+    let genes_over_species = forest.into_genes_over_species(species).unwrap();
+    assert_eq!(genes_over_species.gene_networks().len(), 2);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_dto_round_trip() {
+    let forest = forest(&["((A,B),C);", "((A,C),D);"]);
+    let dto = forest.into_dto();
+    let json = serde_json::to_string(&dto).unwrap();
+    let deserialized: dagex::phylo::PhylogeneticForestDTO = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, dto);
+    let restored = PhylogeneticForest::from_dto(&deserialized).unwrap();
+    assert_eq!(restored.len(), 2);
+    assert_eq!(restored.common_taxa(), taxa(&["A", "C"]));
+    assert_eq!(restored.networks()[0], forest.networks()[0]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_dto_round_trip_keeps_universe() {
+    let universe = taxa(&["A", "B", "C", "Unused"]);
+    let forest = PhylogeneticForest::new_with_taxa(vec![parse("((A,B),C);"), parse("(A,C);")], &universe).unwrap();
+    assert_eq!(forest.taxa_universe(), &universe);
+    assert_eq!(forest.taxa().count(), 3);
+    let dto = forest.into_dto();
+    let names: Vec<&str> = dto.taxa().iter().map(|taxon| taxon.as_str()).collect();
+    assert_eq!(names, vec!["A", "B", "C", "Unused"]);
+    let json = serde_json::to_string(&dto).unwrap();
+    let deserialized: dagex::phylo::PhylogeneticForestDTO = serde_json::from_str(&json).unwrap();
+    let restored = PhylogeneticForest::from_dto(&deserialized).unwrap();
+    assert_eq!(restored.taxa_universe(), &universe);
+    assert_eq!(restored, forest);
+    assert_eq!(restored.into_dto(), dto);
+}
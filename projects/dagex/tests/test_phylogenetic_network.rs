@@ -1,6 +1,9 @@
+#![allow(clippy::clone_on_copy)]
+
 use std::collections::{HashMap, HashSet};
 
 use dagex::{
+    const_parse_newick,
     raf_array::immutable_string::ImmutableString,
     core::{
         ArrowDTO,
@@ -9,11 +12,20 @@ use dagex::{
         Node
     },
     phylo::{
+        parse_newick_from_str,
+        ArrowKind,
+        NetworkEditError,
         PhylogeneticNetwork,
         PhylogeneticNetworkDTO,
-        PhylogeneticNetworkFromError
+        PhylogeneticNetworkFromError,
+        PhylogeneticNetworkKind,
+        PhylogeneticNetworkOptions,
+        PhylogeneticNetworkProperties,
+        RestrictionError,
+        Taxon
     }
 };
+use rstest::rstest;
 
 fn imm(text: &str) -> ImmutableString { ImmutableString::new(text).unwrap() }
 
@@ -145,4 +157,397 @@ fn test_tree_child() {
     assert_eq!(cross_nodes(&network), HashSet::new());
 
     assert!(!network.graph().basic_properties().tree);
+    assert!(network.is_tree_child());
+    assert!(network.is_tree_sibling());
+    assert!(network.is_time_consistent());
+}
+
+#[test]
+fn test_not_tree_child() {
+    let dto = PhylogeneticNetworkDTO::new(
+        dg_dto(&[(0, 1), (0, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 5), (4, 6)]),
+        HashMap::new());
+    let network = PhylogeneticNetwork::from_dto(&dto).unwrap();
+    assert_eq!(reticulation_nodes(&network), HashSet::from([Node::from(3), Node::from(4)]));
+    assert!(!network.is_tree_child());
+    assert!(!network.is_tree_sibling());
+    assert!(network.is_time_consistent());
+}
+
+#[test]
+fn test_time_inconsistent() {
+    let dto = PhylogeneticNetworkDTO::new(
+        dg_dto(&[(0, 1), (0, 2), (1, 3), (1, 4), (3, 4), (3, 5), (4, 6)]),
+        HashMap::new());
+    let network = PhylogeneticNetwork::from_dto(&dto).unwrap();
+    assert_eq!(reticulation_nodes(&network), HashSet::from([Node::from(4)]));
+    assert_eq!(
+        *network.properties(),
+        PhylogeneticNetworkProperties { tree_child: true, tree_sibling: true, time_consistent: false });
+}
+
+#[test]
+fn test_tree_properties() {
+    let network = const_parse_newick!("((A, B),(C, D));");
+    assert!(network.is_tree_child() && network.is_tree_sibling() && network.is_time_consistent());
+    assert_eq!(network.clone().properties(), network.properties());
+}
+
+#[test]
+fn test_classify_arrows_tree() {
+    let network = const_parse_newick!("((A, B),(B, C));");
+    let statistics = network.statistics();
+    assert_eq!(statistics.number_of_tree_arrows, 6);
+    assert_eq!(statistics.number_of_reticulation_arrows, 0);
+    assert_eq!(network.iter_reticulation_arrows().count(), 0);
+    let root = network.root();
+    let child = network.graph().get_successors(root)[0];
+    assert_eq!(network.classify_arrow(root, child), Some(ArrowKind::Tree));
+    assert_eq!(network.classify_arrow(child, root), None);
+}
+
+#[test]
+fn test_classify_arrows_reticulated() {
+    let network = const_parse_newick!("((A, (D)B#1),(B#1, C));");
+    let statistics = network.statistics();
+    assert_eq!(statistics.number_of_tree_arrows, 5);
+    assert_eq!(statistics.number_of_reticulation_arrows, 2);
+    let reticulation_arrows: Vec<(Node, Node)> = network.iter_reticulation_arrows().collect();
+    assert_eq!(reticulation_arrows.len(), 2);
+    let target = reticulation_arrows[0].1;
+    assert!(network.is_reticulation_node(target));
+    for (source, target) in reticulation_arrows {
+        assert_eq!(network.classify_arrow(source, target), Some(ArrowKind::Reticulation));
+    }
+    let leaf = network.graph().get_successors(target)[0];
+    assert_eq!(network.classify_arrow(target, leaf), Some(ArrowKind::Tree));
+}
+
+#[test]
+fn test_taxa_index_with_duplicates() {
+    let network = const_parse_newick!("((A, B),(B, (C, B)));");
+    let nodes: Vec<Node> = network.iter_by_taxon("B").collect();
+    assert_eq!(nodes.len(), 3);
+    assert!(nodes.windows(2).all(|w| w[0].id() < w[1].id()));
+    for node in &nodes {
+        assert_eq!(network.taxa()[node].value().as_str(), "B");
+    }
+    assert_eq!(network.get_single_by_taxon("B"), None);
+    assert_eq!(network.get_single_by_taxon("X"), None);
+    let a = network.get_single_by_taxon("A").unwrap();
+    assert_eq!(network.taxa()[&a].value().as_str(), "A");
+
+    let cloned = network.clone();
+    assert_eq!(cloned.iter_by_taxon("B").collect::<Vec<_>>(), nodes);
+    let taxon = cloned.taxa()[&a].clone();
+    assert_eq!(cloned.get_nodes_by_taxon(&taxon), &[a]);
+}
+
+#[test]
+fn test_subnetwork_from() {
+    let network = const_parse_newick!("((A, (D)B#1),(B#1, C));");
+    let (whole, mapping) = network.subnetwork_from(network.root());
+    assert_eq!(whole, network);
+    assert_eq!(mapping, network.graph().iter_nodes().collect::<Vec<_>>());
+
+    let a = network.get_single_by_taxon("A").unwrap();
+    let (leaf, mapping) = network.subnetwork_from(a);
+    assert_eq!(leaf.graph().number_of_nodes(), 1);
+    assert_eq!(mapping, vec![a]);
+    assert_eq!(leaf.taxa()[&leaf.root()].value().as_str(), "A");
+
+    let parent = network.graph().get_predecessors(a)[0];
+    let (clade, mapping) = network.subnetwork_from(parent);
+    assert_eq!(clade.graph().number_of_nodes(), 4);
+    assert!(clade.graph().basic_properties().rooted);
+    assert!(clade.graph().basic_properties().tree);
+    assert_eq!(mapping[clade.root().id() as usize], parent);
+    for (node, taxon) in clade.taxa() {
+        assert_eq!(network.taxa()[&mapping[node.id() as usize]], *taxon);
+    }
+    assert!(clade.get_single_by_taxon("D").is_some());
+    assert!(clade.get_single_by_taxon("C").is_none());
+}
+
+/// Sorted leaf taxa below each node, in sorted order. Describes restrictions up to
+/// isomorphism in the cases below.
+fn leaf_clusters(network: &PhylogeneticNetwork) -> Vec<Vec<String>> {
+    let graph = network.graph();
+    let mut result: Vec<Vec<String>> = graph.iter_nodes()
+        .map(|node| {
+            let mut cluster: Vec<String> = graph.iter_descendants(node)
+                .chain(core::iter::once(node))
+                .filter(|descendant| graph.is_leaf(*descendant))
+                .map(|leaf| network.taxa()[&leaf].value().as_str().to_owned())
+                .collect();
+            cluster.sort();
+            cluster
+        })
+        .collect();
+    result.sort();
+    result
+}
+
+fn taxa_set(names: &[&str]) -> HashSet<Taxon> {
+    names.iter()
+        .map(|name| Taxon::from(ImmutableString::new(name).unwrap()))
+        .collect()
+}
+
+#[rstest]
+#[case(&["A", "C", "D"], "((A, (D)B#1),(B#1, C));")]
+#[case(&["A", "D"], "((A, (D)B#1), B#1);")]
+#[case(&["C", "D", "X"], "((D)B#1, (B#1, C));")]
+#[case(&["A", "C"], "(A, C);")]
+#[case(&["D"], "D;")]
+#[case(&["A", "X"], "A;")]
+fn test_restrict_to_taxa(#[case] taxa: &[&str], #[case] expected: &str) {
+    let network = const_parse_newick!("((A, (D)B#1),(B#1, C));");
+    let restricted = network.restrict_to_taxa(&taxa_set(taxa)).unwrap();
+    let expected = parse_newick_from_str(expected).unwrap().network;
+    assert_eq!(leaf_clusters(&restricted), leaf_clusters(&expected));
+    assert_eq!(restricted.graph().number_of_nodes(), expected.graph().number_of_nodes());
+    assert_eq!(restricted.reticulation_count(), expected.reticulation_count());
+    assert_eq!(restricted.taxa().len(), restricted.graph().leaves().len());
+    assert!(restricted.graph().basic_properties().binary);
+}
+
+#[test]
+fn test_restrict_to_all_taxa() {
+    let network = const_parse_newick!("((A, (D)B#1),(B#1, C));");
+    let restricted = network.restrict_to_taxa(&taxa_set(&["A", "B", "C", "D"])).unwrap();
+    assert_eq!(restricted, network);
+    assert_ne!(restricted.id(), network.id());
+}
+
+#[test]
+fn test_restrict_to_missing_taxa() {
+    let network = const_parse_newick!("((A, (D)B#1),(B#1, C));");
+    assert_eq!(network.restrict_to_taxa(&taxa_set(&["X", "Y"])), Err(RestrictionError::EmptyIntersection));
+    assert_eq!(network.restrict_to_taxa(&HashSet::new()), Err(RestrictionError::EmptyIntersection));
+}
+
+#[rstest]
+#[case(None, r#"DG(n=5): 0->1, 0->2, 2->3, 2->4, taxa: {1: "A", 3: "B", 4: "C"}"#)]
+#[case(Some(3), r#"DG(n=5): 0->1, 0->2, 2->3, ... (4 arrows), taxa: {1: "A", 3: "B", 4: "C"}"#)]
+#[case(Some(2), r#"DG(n=5): 0->1, 0->2, ... (4 arrows), taxa: {1: "A", 3: "B", ... (3 taxa)}"#)]
+#[case(Some(0), r#"DG(n=5): ... (4 arrows), taxa: {... (3 taxa)}"#)]
+fn test_display(#[case] limit: Option<usize>, #[case] expected: &str) {
+    let dto = PhylogeneticNetworkDTO::new(
+        dg_dto(&[(2, 4), (0, 2), (2, 3), (0, 1)]),
+        HashMap::from_iter([(4, imm("C")), (1, imm("A")), (3, imm("B"))]));
+    let network = PhylogeneticNetwork::from_dto(&dto).unwrap();
+    let (network_text, dto_text) = match limit {
+        Some(limit) => (format!("{network:.limit$}"), format!("{dto:.limit$}")),
+        None => (network.to_string(), dto.to_string()),
+    };
+    assert_eq!(network_text, expected);
+    assert_eq!(dto_text, expected);
+}
+
+#[test]
+fn test_multifurcations_opt_in() {
+    let taxa = HashMap::from([(1, imm("A")), (2, imm("B")), (3, imm("C"))]);
+    let dto = PhylogeneticNetworkDTO::new(dg_dto(&[(0, 1), (0, 2), (0, 3)]), taxa);
+    let result = PhylogeneticNetwork::from_dto(&dto);
+    assert!(matches!(result, Err(PhylogeneticNetworkFromError::NotBinary)), "Invalid result: {result:?}");
+
+    let options = PhylogeneticNetworkOptions::default().with_allow_multifurcations(true);
+    let network = PhylogeneticNetwork::from_dto_with_options(&dto, &options).unwrap();
+    assert_eq!(network.kind(), PhylogeneticNetworkKind::Multifurcating);
+    let network = PhylogeneticNetwork::from_graph_and_taxa_with_options(
+        network.graph().clone(),
+        network.taxa().clone(),
+        &PhylogeneticNetworkOptions::default());
+    assert!(matches!(network, Err(PhylogeneticNetworkFromError::NotBinary)));
+
+    let binary = const_parse_newick!("((A,B),C);");
+    assert_eq!(binary.kind(), PhylogeneticNetworkKind::Binary);
+
+    let cyclic = PhylogeneticNetworkDTO::new(dg_dto(&[(0, 1), (1, 2), (2, 1), (0, 3), (0, 4)]), HashMap::new());
+    let result = PhylogeneticNetwork::from_dto_with_options(&cyclic, &options);
+    assert!(matches!(result, Err(PhylogeneticNetworkFromError::NotAcyclic)), "Invalid result: {result:?}");
+}
+
+#[rstest]
+#[case(PhylogeneticNetworkOptions::default())]
+#[case(PhylogeneticNetworkOptions::default().with_allow_multifurcations(true))]
+fn test_taxon_not_on_leaf(#[case] options: PhylogeneticNetworkOptions) {
+    let taxa = HashMap::from([(1, imm("A")), (2, imm("B")), (0, imm("R"))]);
+    let dto = PhylogeneticNetworkDTO::new(dg_dto(&[(0, 1), (0, 2)]), taxa);
+    let result = PhylogeneticNetwork::from_dto_with_options(&dto, &options);
+    assert!(matches!(result, Err(PhylogeneticNetworkFromError::TaxonNotOnLeaf(node)) if node == Node::from(0)), "Invalid result: {result:?}");
+    assert_eq!(result.unwrap_err().to_string(), "taxon assigned to node 0, which is not a leaf");
+}
+
+fn taxa_values(network: &PhylogeneticNetwork) -> Vec<String> {
+    let mut result: Vec<String> = network.taxa()
+        .values()
+        .map(|taxon| taxon.value().as_str().to_owned())
+        .collect();
+    result.sort();
+    result
+}
+
+#[test]
+fn test_displayed_trees_of_reticulated_network() {
+    let network = const_parse_newick!("((A, (D)B#1),(B#1, C));");
+    let iter = network.iter_displayed_trees();
+    assert_eq!(iter.combinations(), Some(2));
+    assert_eq!(iter.size_hint(), (2, Some(2)));
+    let trees: Vec<PhylogeneticNetwork> = iter.collect();
+    assert_eq!(trees.len(), 2);
+
+    // Labeled reticulation node stays, as a node of in- and out-degree 1.
+    let expected = ["((A, (D)B), C);", "(A, ((D)B, C));"];
+    for (tree, expected) in trees.iter().zip(expected) {
+        let expected = parse_newick_from_str(expected).unwrap().network;
+        assert!(tree.graph().basic_properties().tree);
+        assert_eq!(tree.reticulation_count(), 0);
+        assert_eq!(leaf_clusters(tree), leaf_clusters(&expected));
+        assert_eq!(tree.graph().number_of_nodes(), expected.graph().number_of_nodes());
+        assert_eq!(taxa_values(tree), taxa_values(&network));
+    }
+}
+
+#[test]
+fn test_displayed_trees_of_tree() {
+    let network = const_parse_newick!("((A, B),(C, D));");
+    let mut iter = network.iter_displayed_trees();
+    assert_eq!(iter.size_hint(), (1, Some(1)));
+    assert_eq!(iter.next(), Some(network.clone()));
+    assert_eq!(iter.size_hint(), (0, Some(0)));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn test_displayed_trees_limit() {
+    let network = const_parse_newick!("(((A, (D)B#1),(B#1, (C, (E)F#2))), F#2);");
+    let iter = network.iter_displayed_trees();
+    assert_eq!(iter.combinations(), Some(4));
+    assert_eq!(network.iter_displayed_trees().count(), 4);
+
+    let mut iter = network.iter_displayed_trees().with_limit(3);
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    assert!(iter.next().is_some());
+    assert_eq!(iter.size_hint(), (2, Some(2)));
+    assert_eq!(iter.count(), 2);
+    assert_eq!(network.iter_displayed_trees().with_limit(0).count(), 0);
+    assert!(network.iter_displayed_trees().all(|tree| tree.graph().basic_properties().tree));
+}
+
+#[test]
+fn test_paths_to_taxon() {
+    let network = const_parse_newick!("((A, (D)B#1),(B#1, C));");
+    let paths: Vec<Vec<Node>> = network.paths_to_taxon("D").collect();
+    assert_eq!(paths.len(), 2);
+    let leaf = network.get_single_by_taxon("D").unwrap();
+    for path in &paths {
+        assert_eq!(path.len(), 4);
+        assert_eq!(path.first(), Some(&network.root()));
+        assert_eq!(path.last(), Some(&leaf));
+    }
+    assert_ne!(paths[0], paths[1]);
+    assert_eq!(network.graph().count_paths(network.root(), leaf), 2);
+    assert_eq!(network.paths_to_taxon("A").count(), 1);
+    assert_eq!(network.paths_to_taxon("X").count(), 0);
+}
+
+#[test]
+fn test_iter_leaves_below() {
+    let network = const_parse_newick!("((A, (D)B#1),(B#1, C));");
+    let taxa_below = |node: Node| {
+        let mut result: Vec<&str> = network.iter_leaves_below(node)
+            .map(|leaf| network.taxa()[&leaf].value().as_str())
+            .collect();
+        result.sort_unstable();
+        result
+    };
+    assert_eq!(taxa_below(network.root()), vec!["A", "C", "D"]);
+    let [left, right] = network.graph().get_successors(network.root()) else { panic!() };
+    let mut sides = [taxa_below(*left), taxa_below(*right)];
+    sides.sort();
+    assert_eq!(sides, [vec!["A", "D"], vec!["C", "D"]]);
+    let leaf = network.get_single_by_taxon("D").unwrap();
+    assert_eq!(taxa_below(leaf), vec!["D"]);
+}
+
+#[test]
+fn test_with_leaf_relabeled() {
+    let network = const_parse_newick!("((A, B), C);");
+    let leaf = network.get_single_by_taxon("A").unwrap();
+    let relabeled = network.with_leaf_relabeled(leaf, Taxon::new("X").unwrap()).unwrap();
+    assert_ne!(relabeled.id(), network.id());
+    assert_eq!(relabeled.graph(), network.graph());
+    assert_eq!(relabeled.get_single_by_taxon("X"), Some(leaf));
+    assert_eq!(relabeled.get_single_by_taxon("A"), None);
+    assert_eq!(taxa_values(&relabeled), vec!["B", "C", "X"]);
+    assert_eq!(relabeled, parse_newick_from_str("((X, B), C);").unwrap().network);
+
+    let result = network.with_leaf_relabeled(network.root(), Taxon::new("X").unwrap());
+    assert!(matches!(result, Err(NetworkEditError::NotLeaf(node)) if node == network.root()), "Invalid result: {result:?}");
+    let result = network.with_leaf_relabeled(Node::from(5), Taxon::new("X").unwrap());
+    assert!(matches!(result, Err(NetworkEditError::NodeNotFound(_))), "Invalid result: {result:?}");
+}
+
+fn parent_arrow(network: &PhylogeneticNetwork, taxon: &str) -> (Node, Node) {
+    let leaf = network.get_single_by_taxon(taxon).unwrap();
+    (network.graph().get_predecessors(leaf)[0], leaf)
+}
+
+#[test]
+fn test_with_reticulation_added() {
+    let network = const_parse_newick!("((A, B), (C, D));");
+    let edited = network
+        .with_reticulation_added(parent_arrow(&network, "A"), parent_arrow(&network, "C"))
+        .unwrap();
+    assert_ne!(edited.id(), network.id());
+    assert_eq!(network.reticulation_count(), 0);
+    assert_eq!(edited.reticulation_count(), 1);
+    assert_eq!(edited.graph().number_of_nodes(), network.graph().number_of_nodes() + 2);
+    assert!(edited.graph().basic_properties().binary);
+    assert_eq!(taxa_values(&edited), taxa_values(&network));
+    let reticulation = Node::from(network.graph().number_of_nodes() + 1);
+    assert!(edited.is_reticulation_node(reticulation));
+    assert_eq!(edited.graph().get_successors(reticulation), &[edited.get_single_by_taxon("C").unwrap()]);
+    assert_eq!(edited.iter_displayed_trees().count(), 2);
+
+    let twice = edited
+        .with_reticulation_added(parent_arrow(&edited, "B"), parent_arrow(&edited, "D"))
+        .unwrap();
+    assert_eq!(twice.reticulation_count(), 2);
+}
+
+#[test]
+fn test_with_reticulation_added_rejected() {
+    let network = const_parse_newick!("((A, B), (C, D));");
+    let (parent, leaf) = parent_arrow(&network, "A");
+    let result = network.with_reticulation_added((parent, leaf), (parent, leaf));
+    assert!(matches!(result, Err(NetworkEditError::SameArrow(..))), "Invalid result: {result:?}");
+    let result = network.with_reticulation_added((parent, leaf), (leaf, parent));
+    assert!(matches!(result, Err(NetworkEditError::ArrowNotFound(s, t)) if s == leaf && t == parent), "Invalid result: {result:?}");
+    let result = network.with_reticulation_added((parent, leaf), (network.root(), parent));
+    assert!(
+        matches!(result, Err(NetworkEditError::NetworkError(PhylogeneticNetworkFromError::NotAcyclic))),
+        "Invalid result: {result:?}");
+}
+
+#[test]
+fn test_with_node_suppressed() {
+    let network = const_parse_newick!("(((A)X, B), C);");
+    let (middle, _) = parent_arrow(&network, "A");
+    let suppressed = network.with_node_suppressed(middle).unwrap();
+    assert_ne!(suppressed.id(), network.id());
+    assert_eq!(suppressed.graph().number_of_nodes(), network.graph().number_of_nodes() - 1);
+    // Name of the suppressed node is its taxon, and goes away with it.
+    assert_eq!(taxa_values(&suppressed), ["A", "B", "C"]);
+    assert_eq!(leaf_clusters(&suppressed), leaf_clusters(&parse_newick_from_str("((A, B), C);").unwrap().network));
+
+    for node in [network.root(), network.get_single_by_taxon("B").unwrap(), network.graph().get_predecessors(middle)[0]] {
+        let result = network.with_node_suppressed(node);
+        assert!(matches!(result, Err(NetworkEditError::NotSuppressible(n)) if n == node), "Invalid result: {result:?}");
+    }
+    let result = network.with_node_suppressed(Node::from(-1));
+    assert!(matches!(result, Err(NetworkEditError::NodeNotFound(_))), "Invalid result: {result:?}");
+    assert_eq!(result.unwrap_err().to_string(), "node -1 is not in the network");
 }
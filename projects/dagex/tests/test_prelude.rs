@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use dagex::prelude::*;
+
+#[test]
+fn test_prelude_network_from_newick() {
+    let network = const_parse_newick!("((A, (D)B#1),(B#1, C));");
+    let parsed = parse_newick_from_str("((A, (D)B#1),(B#1, C));").unwrap().network;
+    assert_eq!(network.graph(), parsed.graph());
+    let taxon = Taxon::new("D").unwrap();
+    assert_eq!(network.get_nodes_by_taxon(&taxon).len(), 1);
+
+    let mut text = Vec::new();
+    write_newick(&network, &mut text).unwrap();
+    let reparsed = parse_newick(&mut text.as_slice()).unwrap().network;
+    assert_eq!(reparsed.taxa().len(), network.taxa().len());
+}
+
+#[test]
+fn test_prelude_network_from_dto() {
+    let graph = DirectedGraphDTO::new(3, vec![ArrowDTO::new(0, 1), ArrowDTO::new(0, 2)]);
+    assert_eq!(DirectedGraph::from_dto(&graph).unwrap().root(), Some(Node::from(0)));
+    let taxa = HashMap::from([
+        (1, ImmutableString::new("A").unwrap()),
+        (2, ImmutableString::new("B").unwrap())]);
+    let network = PhylogeneticNetwork::from_dto(&PhylogeneticNetworkDTO::new(graph, taxa)).unwrap();
+    assert_eq!(network.iter_leaves_below(network.root()).count(), 2);
+}
+
+#[test]
+fn test_prelude_forests() {
+    let networks = const_parse_newick_forest_file!("tests/data/species.nwk");
+    assert_eq!(networks.len(), 1);
+    assert_eq!(parse_newick_forest_from_str("(A,B);(C,D);").len(), 2);
+    assert_eq!(parse_newick_forest(&mut "(A,B);".as_bytes()).count(), 1);
+    let species = const_parse_newick_file!("tests/data/species.nwk");
+    assert_eq!(species.taxa().len(), 4);
+    let _ = core::mem::size_of::<GenesOverSpecies>();
+}
@@ -1,5 +1,21 @@
-use dagex::{core::{ArrowDTO, DirectedGraphDTO}, phylo::PhylogeneticNetworkDTO};
+use std::sync::Arc;
+
+use dagex::{
+    core::{ArrowDTO, DirectedGraph, DirectedGraphDTO},
+    phylo::{
+        parse_newick_from_str,
+        GenesOverSpecies,
+        GenesOverSpeciesDTO,
+        GenesOverSpeciesFromError,
+        GenesOverSpeciesNewError,
+        PhylogeneticNetwork,
+        PhylogeneticNetworkDTO,
+        PhylogeneticNetworkFromError,
+        PhylogeneticNetworkId,
+        Taxon}};
+use dagex::generators::{generate_random_dag, generate_random_phylo_network};
 use dagex::raf_array::immutable_string::ImmutableString;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use rstest::rstest;
 
 #[rstest]
@@ -40,6 +56,57 @@ fn test_graph_dto(
 }
 
 
+#[rstest]
+#[case(0, 1, Some(2.5), r#"[0,1,2.5]"#)]
+#[case(3, -2, Some(-0.125), r#"[3,-2,-0.125]"#)]
+#[case(7, 4, None, r#"[7,4]"#)]
+fn test_weighted_arrow_dto(
+    #[case] source: i32,
+    #[case] target: i32,
+    #[case] weight: Option<f64>,
+    #[case] expected: &str)
+{
+    let arrow = ArrowDTO::new(source, target).with_weight(weight);
+    assert_eq!(serde_json::to_string(&arrow).unwrap(), expected);
+    let darrow: ArrowDTO = serde_json::from_str(expected).unwrap();
+    assert_eq!(darrow, arrow);
+    assert_eq!(darrow.weight(), weight);
+}
+
+#[test]
+fn test_weighted_graph_round_trip() {
+    let json = r#"{"number_of_nodes":4,"arrows":[[0,1,0.5],[0,2],[2,3,1.75]]}"#;
+    let dto: DirectedGraphDTO = serde_json::from_str(json).unwrap();
+    assert!(dto.is_weighted());
+    assert_eq!(serde_json::to_string(&dto).unwrap(), json);
+    let unweighted = DirectedGraphDTO::new(
+        4,
+        dto.arrows().iter().map(|arrow| ArrowDTO::new(arrow.source(), arrow.target())).collect());
+    assert!(!unweighted.is_weighted());
+    assert_ne!(unweighted, dto);
+
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    assert!(graph.is_weighted());
+    assert_eq!(graph.arrow_weight(0.into(), 1.into()), Some(0.5));
+    assert_eq!(graph.arrow_weight(0.into(), 2.into()), None);
+    assert_eq!(graph.arrow_weight(1.into(), 3.into()), None);
+    assert_eq!(graph.into_dto(), dto);
+    assert_eq!(graph.clone().arrow_weight(2.into(), 3.into()), Some(1.75));
+    assert_eq!(graph, DirectedGraph::from_dto(&unweighted).unwrap());
+
+    let without = graph.with_arrow_removed(0.into(), 1.into()).unwrap();
+    assert_eq!(without.arrow_weight(0.into(), 1.into()), None);
+    assert_eq!(without.arrow_weight(2.into(), 3.into()), Some(1.75));
+
+    let parallel = DirectedGraphDTO::new(2, vec![
+        ArrowDTO::new(0, 1).with_weight(Some(1.0)),
+        ArrowDTO::new(0, 1).with_weight(Some(2.0))]);
+    assert!(parallel.validate().is_err());
+    assert!(DirectedGraph::from_dto(&parallel).is_err());
+    assert_eq!(parallel.normalized().arrows(), &vec![ArrowDTO::new(0, 1).with_weight(Some(1.0))]);
+    assert!(DirectedGraph::from_dto(&parallel.normalized()).is_ok());
+}
+
 #[rstest]
 #[case(0, &[(0, 0), (1, -1)], &[(0, "A")], r#"{"number_of_nodes":0,"arrows":[[0,0],[1,-1]],"taxa":[[0,"A"]]}"#)]
 #[case(-1, &[(0, 0), (1, -1)], &[], r#"{"number_of_nodes":-1,"arrows":[[0,0],[1,-1]],"taxa":[]}"#)]
@@ -63,3 +130,310 @@ fn test_phylogenetic_dto(
     let dpn: PhylogeneticNetworkDTO = serde_json::from_str(expected).unwrap();
     assert_eq!(dpn, pn);
 }
+
+
+#[test]
+fn test_reticulated_network_round_trip() {
+    let arrows = [(0, 1), (0, 2), (1, 3), (2, 3), (1, 4), (3, 5), (2, 6)];
+    let arrows: Vec<ArrowDTO> = arrows.iter().map(|p| ArrowDTO::new(p.0, p.1)).collect();
+    let taxa = [(6, "C"), (4, "A"), (5, "B")];
+    let taxa = taxa.iter().map(|p| (p.0, ImmutableString::new(p.1).unwrap())).collect();
+    let dto = PhylogeneticNetworkDTO::new(DirectedGraphDTO::new(7, arrows), taxa);
+
+    let json = serde_json::to_string(&dto).unwrap();
+    assert!(json.ends_with(r#""taxa":[[4,"A"],[5,"B"],[6,"C"]]}"#));
+    let deserialized: PhylogeneticNetworkDTO = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, dto);
+
+    let network = PhylogeneticNetwork::try_from(deserialized).unwrap();
+    assert_eq!(network.reticulation_count(), 1);
+    let json = serde_json::to_string(&network.into_dto()).unwrap();
+    let restored: PhylogeneticNetworkDTO = serde_json::from_str(&json).unwrap();
+    assert_eq!(PhylogeneticNetwork::try_from(restored).unwrap(), network);
+}
+
+#[test]
+fn test_graph_without_arrows_round_trip() {
+    let graph = DirectedGraph::from_dto(&DirectedGraphDTO::new(1, Vec::new())).unwrap();
+    let json = serde_json::to_string(&graph.into_dto()).unwrap();
+    assert_eq!(json, r#"{"number_of_nodes":1,"arrows":[]}"#);
+    let dto: DirectedGraphDTO = serde_json::from_str(&json).unwrap();
+    assert_eq!(DirectedGraph::from_dto(&dto).unwrap(), graph);
+
+    let dto = PhylogeneticNetworkDTO::new(dto, [(0, ImmutableString::new("A").unwrap())].into());
+    let json = serde_json::to_string(&dto).unwrap();
+    let network = PhylogeneticNetwork::try_from(serde_json::from_str::<PhylogeneticNetworkDTO>(&json).unwrap()).unwrap();
+    assert_eq!(network.into_dto(), dto);
+}
+
+#[test]
+fn test_invalid_dto_fails_validation() {
+    let arrows = vec![ArrowDTO::new(0, 1), ArrowDTO::new(1, 0)];
+    let dto = PhylogeneticNetworkDTO::new(DirectedGraphDTO::new(2, arrows), Default::default());
+    assert!(PhylogeneticNetwork::try_from(dto).is_err());
+    assert!(serde_json::from_str::<PhylogeneticNetworkDTO>(
+        r#"{"number_of_nodes":1,"arrows":[],"taxa":[[0,"A"],[0,"B"]]}"#).is_err());
+}
+
+#[rstest]
+#[case("A")]
+#[case("")]
+#[case("Homo sapiens")]
+fn test_taxon(#[case] text: &str) {
+    let taxon = Taxon::new(text).unwrap();
+    let json = serde_json::to_string(&taxon).unwrap();
+    assert_eq!(json, serde_json::to_string(text).unwrap());
+    let deserialized: Taxon = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, taxon);
+}
+
+#[test]
+fn test_network_id() {
+    let network = PhylogeneticNetwork::from_dto(&PhylogeneticNetworkDTO::new(
+        DirectedGraphDTO::new(1, Vec::new()), Default::default())).unwrap();
+    let json = serde_json::to_string(&network.id()).unwrap();
+    assert_eq!(json, i32::from(network.id()).to_string());
+}
+
+fn parse(text: &str) -> PhylogeneticNetwork {
+    parse_newick_from_str(text).unwrap().network
+}
+
+fn genes_over_species() -> GenesOverSpecies {
+    let genes = vec![
+        parse("((A,B),C);"),
+        parse("((A,(D)#H1),(#H1,C));"),
+        parse("(B,D);"),
+    ];
+    GenesOverSpecies::new(genes, parse("(((A,B),C),D);")).unwrap()
+}
+
+#[test]
+fn test_genes_over_species_round_trip() {
+    let original = genes_over_species();
+    let json = serde_json::to_string(&original.into_dto()).unwrap();
+    assert!(json.starts_with(r#"{"species_network":"#), "Unexpected json: {json}");
+    let dto: GenesOverSpeciesDTO = serde_json::from_str(&json).unwrap();
+    assert_eq!(dto.gene_networks().len(), 3);
+    let deserialized = GenesOverSpecies::try_from(dto).unwrap();
+    assert_eq!(deserialized, original);
+    for (gene, original_gene) in deserialized.gene_networks().iter().zip(original.gene_networks()) {
+        assert_eq!(gene.id(), original_gene.id());
+        assert_eq!(deserialized.get_gene_network_by_id(gene.id()), Some(gene.as_ref()));
+    }
+}
+
+#[test]
+fn test_genes_over_species_shared_ids_round_trip() {
+    let original = genes_over_species();
+    let mut genes: Vec<Arc<PhylogeneticNetwork>> = original.gene_networks().to_vec();
+    genes.push(genes[0].clone());
+    let original = GenesOverSpecies::from_shared_networks(genes, original.species_network().clone()).unwrap();
+    let json = serde_json::to_string(&original.into_dto()).unwrap();
+    let dto: GenesOverSpeciesDTO = serde_json::from_str(&json).unwrap();
+    let deserialized = GenesOverSpecies::from_dto(&dto).unwrap();
+    assert_eq!(deserialized.gene_networks().len(), 4);
+    assert!(Arc::ptr_eq(&deserialized.gene_networks()[0], &deserialized.gene_networks()[3]));
+
+    let mut conflicting = dto.gene_networks().clone();
+    let id = conflicting[0].id().unwrap();
+    conflicting[3] = conflicting[1].clone().with_id(Some(id));
+    let dto = GenesOverSpeciesDTO::new(dto.species_network().clone(), conflicting);
+    assert!(matches!(
+        GenesOverSpecies::from_dto(&dto),
+        Err(GenesOverSpeciesFromError::GeneNetworkError(3, PhylogeneticNetworkFromError::InvalidId(invalid))) if invalid == id));
+}
+
+#[test]
+fn test_network_dto_id_round_trip() {
+    let network = parse("((A,B),C);");
+    let json = serde_json::to_string(&network.into_dto()).unwrap();
+    assert!(!json.contains("\"id\""), "Unexpected json: {json}");
+
+    let json = serde_json::to_string(&network.into_dto_with_id()).unwrap();
+    assert!(json.ends_with(&format!(r#","id":{}}}"#, i32::from(network.id()))), "Unexpected json: {json}");
+    let dto: PhylogeneticNetworkDTO = serde_json::from_str(&json).unwrap();
+    assert_eq!(dto, network.into_dto_with_id());
+    let first = PhylogeneticNetwork::from_dto(&dto).unwrap();
+    let second = PhylogeneticNetwork::from_dto(&dto).unwrap();
+    assert_eq!(first.id(), network.id());
+    assert_eq!(second.id(), network.id());
+    assert_eq!(first, second);
+    assert_eq!(first, network);
+    assert_ne!(PhylogeneticNetwork::from_dto(&network.into_dto()).unwrap().id(), network.id());
+}
+
+#[test]
+fn test_explicit_ids_are_not_generated() {
+    let explicit = i32::from(PhylogeneticNetworkId::generate_next()) + 1000;
+    let explicit_id = PhylogeneticNetworkId::from_explicit(explicit).unwrap();
+    assert_eq!(i32::from(explicit_id), explicit);
+    for _ in 0..10 {
+        assert!(i32::from(PhylogeneticNetworkId::generate_next()) > explicit);
+    }
+    let dto = parse("(A,B);").into_dto().with_id(Some(explicit));
+    let network = PhylogeneticNetwork::from_dto(&dto).unwrap();
+    assert_eq!(network.id(), explicit_id);
+    assert!(i32::from(parse("(A,B);").id()) > explicit);
+}
+
+#[test]
+fn test_explicit_id_below_counter() {
+    // Ids saved by another process are usually below the current counter.
+    let saved = i32::from(PhylogeneticNetworkId::generate_next());
+    for _ in 0..10 {
+        parse("(A,B);");
+    }
+    let dto = parse("((A,B),C);").into_dto().with_id(Some(saved));
+    let network = PhylogeneticNetwork::from_dto(&dto).unwrap();
+    assert_eq!(i32::from(network.id()), saved);
+    assert_eq!(PhylogeneticNetworkId::from_explicit(saved), Some(network.id()));
+}
+
+#[test]
+fn test_explicit_id_collisions() {
+    let explicit = i32::from(PhylogeneticNetworkId::generate_next()) + 1000;
+    let dto = parse("(A,B);").into_dto().with_id(Some(explicit));
+    let other = parse("((A,B),C);").into_dto().with_id(Some(explicit));
+    let first = PhylogeneticNetwork::from_dto(&dto).unwrap();
+    let second = PhylogeneticNetwork::from_dto(&dto).unwrap();
+    assert!(matches!(PhylogeneticNetwork::from_dto(&other), Err(PhylogeneticNetworkFromError::InvalidId(invalid)) if invalid == explicit));
+    drop(first);
+    let cloned = second.clone();
+    assert_eq!(cloned.id(), second.id());
+    drop(second);
+    assert!(matches!(PhylogeneticNetwork::from_dto(&other), Err(PhylogeneticNetworkFromError::InvalidId(_))));
+    drop(cloned);
+    let replaced = PhylogeneticNetwork::from_dto(&other).unwrap();
+    assert_eq!(i32::from(replaced.id()), explicit);
+
+    let exported = parse("(A,B);");
+    let dto = exported.into_dto_with_id();
+    let other = parse("((A,B),C);").into_dto().with_id(dto.id());
+    assert!(matches!(PhylogeneticNetwork::from_dto(&other), Err(PhylogeneticNetworkFromError::InvalidId(_))));
+    assert_eq!(PhylogeneticNetwork::from_dto(&dto).unwrap(), exported);
+    drop(exported);
+    assert!(PhylogeneticNetwork::from_dto(&other).is_ok());
+}
+
+#[test]
+fn test_equality_ignores_ids() {
+    let generated = parse("(A,B);");
+    let dto = parse("((A,B),C);").into_dto().with_id(Some(i32::from(generated.id())));
+    let loaded = PhylogeneticNetwork::from_dto(&dto).unwrap();
+    assert_eq!(loaded.id(), generated.id());
+    assert_ne!(loaded, generated);
+    let cloned = generated.clone();
+    assert_eq!(cloned.id(), generated.id());
+    assert_eq!(cloned, generated);
+}
+
+#[test]
+fn test_explicit_id_range() {
+    for id in [i32::MAX, i32::MAX - 1, -1, i32::MIN] {
+        assert!(PhylogeneticNetworkId::from_explicit(id).is_none(), "Accepted id {id}");
+        let dto = parse("(A,B);").into_dto().with_id(Some(id));
+        assert!(matches!(PhylogeneticNetwork::from_dto(&dto), Err(PhylogeneticNetworkFromError::InvalidId(_))));
+    }
+    let first = i32::from(PhylogeneticNetworkId::generate_next());
+    let second = i32::from(PhylogeneticNetworkId::generate_next());
+    assert!(first >= 0 && second > first);
+}
+
+#[test]
+fn test_genes_over_species_corrupted() {
+    let original = genes_over_species().into_dto();
+    let mut genes = original.gene_networks().clone();
+    let graph = genes[2].graph().clone();
+    genes[2] = PhylogeneticNetworkDTO::new(graph, [(1, ImmutableString::new("X").unwrap())].into());
+    let dto = GenesOverSpeciesDTO::new(original.species_network().clone(), genes.clone());
+    let result = GenesOverSpecies::from_dto(&dto);
+    assert!(matches!(result, Err(GenesOverSpeciesFromError::NewError(GenesOverSpeciesNewError::IncorrectTaxa))));
+
+    genes[1] = PhylogeneticNetworkDTO::new(DirectedGraphDTO::new(2, vec![ArrowDTO::new(0, 1), ArrowDTO::new(1, 0)]), Default::default());
+    let dto = GenesOverSpeciesDTO::new(original.species_network().clone(), genes);
+    let result = GenesOverSpecies::from_dto(&dto);
+    assert!(matches!(result, Err(GenesOverSpeciesFromError::GeneNetworkError(1, _))));
+    let empty = GenesOverSpeciesDTO::new(original.species_network().clone(), Vec::new());
+    assert!(matches!(
+        GenesOverSpecies::from_dto(&empty),
+        Err(GenesOverSpeciesFromError::NewError(GenesOverSpeciesNewError::EmptyGeneNetworks))));
+}
+
+#[test]
+fn test_canonical_dto() {
+    let mut rng = StdRng::seed_from_u64(31);
+    for _ in 0..100 {
+        let dto = generate_random_dag(&mut rng, 12, 0.3);
+        let graph = DirectedGraph::from_dto(&dto).unwrap();
+        let canonical = graph.to_canonical_dto();
+        assert_eq!(canonical.number_of_nodes(), dto.number_of_nodes());
+        assert_eq!(canonical.arrows().len(), dto.arrows().len());
+        assert_eq!(canonical.normalized(), canonical);
+        let bytes = serde_json::to_vec(&canonical).unwrap();
+        for _ in 0..5 {
+            let mut arrows = dto.arrows().clone();
+            arrows.shuffle(&mut rng);
+            let shuffled = DirectedGraph::from_dto(&DirectedGraphDTO::new(dto.number_of_nodes(), arrows)).unwrap();
+            assert_eq!(serde_json::to_vec(&shuffled.to_canonical_dto()).unwrap(), bytes);
+        }
+    }
+}
+
+#[test]
+fn test_canonical_dto_relabeling() {
+    let mut rng = StdRng::seed_from_u64(47);
+    for _ in 0..100 {
+        let dto = generate_random_dag(&mut rng, 12, 0.3);
+        let canonical = DirectedGraph::from_dto(&dto).unwrap().to_canonical_dto();
+        let mut new_ids: Vec<i32> = (0..dto.number_of_nodes()).collect();
+        new_ids.shuffle(&mut rng);
+        let arrows = dto.arrows()
+            .iter()
+            .map(|arrow| ArrowDTO::new(new_ids[arrow.source() as usize], new_ids[arrow.target() as usize]))
+            .collect();
+        let relabeled = DirectedGraph::from_dto(&DirectedGraphDTO::new(dto.number_of_nodes(), arrows)).unwrap();
+        assert_eq!(relabeled.to_canonical_dto(), canonical);
+    }
+}
+
+#[test]
+fn test_canonical_dto_renumbering() {
+    let arrows = [(3, 1), (3, 0), (1, 2), (4, 5)]
+        .into_iter()
+        .map(|(source, target)| ArrowDTO::new(source, target))
+        .collect();
+    let graph = DirectedGraph::from_dto(&DirectedGraphDTO::new(6, arrows)).unwrap();
+    let expected = [(1, 0), (3, 2), (4, 3), (4, 5)]
+        .into_iter()
+        .map(|(source, target)| ArrowDTO::new(source, target))
+        .collect();
+    assert_eq!(graph.to_canonical_dto(), DirectedGraphDTO::new(6, expected));
+}
+
+#[test]
+fn test_phylogenetic_network_canonical_dto() {
+    let mut rng = StdRng::seed_from_u64(8);
+    for _ in 0..50 {
+        let dto = generate_random_phylo_network(&mut rng, 7, 2);
+        let network = PhylogeneticNetwork::from_dto(&dto).unwrap();
+        let canonical = network.to_canonical_dto();
+        let bytes = serde_json::to_vec(&canonical).unwrap();
+        let mut arrows = dto.graph().arrows().clone();
+        arrows.shuffle(&mut rng);
+        let graph = DirectedGraphDTO::new(dto.graph().number_of_nodes(), arrows);
+        let shuffled = PhylogeneticNetwork::from_dto(&PhylogeneticNetworkDTO::new(graph, dto.taxa().clone())).unwrap();
+        assert_eq!(serde_json::to_vec(&shuffled.to_canonical_dto()).unwrap(), bytes);
+
+        let restored = PhylogeneticNetwork::from_dto(&canonical).unwrap();
+        let mut taxa: Vec<_> = restored.taxa().values().map(|taxon| taxon.value().as_str().to_owned()).collect();
+        let mut original: Vec<_> = network.taxa().values().map(|taxon| taxon.value().as_str().to_owned()).collect();
+        taxa.sort();
+        original.sort();
+        assert_eq!(taxa, original);
+        assert_eq!(restored.root(), dagex::core::Node::from(0));
+        for (node, taxon) in restored.taxa() {
+            assert!(restored.is_leaf(*node), "Taxon {taxon:?} on non-leaf node");
+        }
+    }
+}
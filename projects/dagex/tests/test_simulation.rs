@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+
+use dagex::phylo::{
+    simulation::{simulate_gene_trees, simulate_species_tree, GeneSimulationParams},
+    parse_newick_from_str,
+    PhylogeneticNetworkKind,
+    Taxon};
+use rand::{rngs::StdRng, SeedableRng};
+use rstest::rstest;
+
+fn leaf_taxa(network: &dagex::phylo::PhylogeneticNetwork) -> Vec<String> {
+    let mut taxa: Vec<String> = network.taxa()
+        .values()
+        .map(|taxon| taxon.value().as_str().to_owned())
+        .collect();
+    taxa.sort_unstable();
+    taxa
+}
+
+#[rstest]
+#[case(1)]
+#[case(2)]
+#[case(17)]
+fn test_simulate_species_tree(#[case] leaves: i32) {
+    let mut rng = StdRng::seed_from_u64(7);
+    let species = simulate_species_tree(&mut rng, leaves);
+    assert_eq!(species.graph().number_of_nodes(), 2 * leaves - 1);
+    assert_eq!(species.root().id(), 0);
+    assert!(species.graph().basic_properties().tree);
+    assert_eq!(species.kind(), PhylogeneticNetworkKind::Binary);
+    assert_eq!(species.taxa().len(), leaves as usize);
+
+    let again = simulate_species_tree(&mut StdRng::seed_from_u64(7), leaves);
+    assert_eq!(again.into_dto().graph(), species.into_dto().graph());
+}
+
+#[test]
+fn test_default_params_copy_species_tree() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let species = simulate_species_tree(&mut rng, 10);
+    let simulation = simulate_gene_trees(&mut rng, &species, 4, &GeneSimulationParams::default());
+    assert_eq!(simulation.duplications(), 0);
+    assert_eq!(simulation.losses(), 0);
+    let genes_over_species = simulation.genes_over_species();
+    assert_eq!(genes_over_species.gene_networks().len(), 4);
+    for gene in genes_over_species.gene_networks() {
+        assert_eq!(gene.graph().number_of_nodes(), species.graph().number_of_nodes());
+        assert_eq!(leaf_taxa(gene), leaf_taxa(&species));
+    }
+}
+
+#[test]
+fn test_simulation_params_effects() {
+    let species = simulate_species_tree(&mut StdRng::seed_from_u64(11), 12);
+    let simulate = |params: GeneSimulationParams| {
+        simulate_gene_trees(&mut StdRng::seed_from_u64(5), &species, 20, &params)
+    };
+    let low = simulate(GeneSimulationParams::default().with_duplication_rate(0.05));
+    let high = simulate(GeneSimulationParams::default().with_duplication_rate(0.4));
+    assert!(low.duplications() < high.duplications(), "{} vs {}", low.duplications(), high.duplications());
+    assert_eq!(low.losses(), 0);
+
+    let lossy = simulate(GeneSimulationParams::default().with_loss_rate(0.3));
+    assert!(lossy.losses() > 0);
+    let species_taxa: HashSet<Taxon> = species.taxa().values().cloned().collect();
+    for gene in lossy.genes_over_species().gene_networks() {
+        assert!(gene.taxa().values().all(|taxon| species_taxa.contains(taxon)));
+        assert!(gene.taxa().len() <= species_taxa.len());
+    }
+
+    let again = simulate(GeneSimulationParams::default().with_duplication_rate(0.4));
+    assert_eq!(again.duplications(), high.duplications());
+    let dtos = |simulation: &dagex::phylo::simulation::GeneSimulation| -> Vec<_> {
+        simulation.genes_over_species().gene_networks()
+            .iter()
+            .map(|gene| gene.into_dto())
+            .collect()
+    };
+    assert_eq!(dtos(&again), dtos(&high));
+}
+
+#[test]
+fn test_simulation_within_network() {
+    let species = parse_newick_from_str("((a,(b)#H1),((#H1,c),d));").unwrap().network;
+    let mut rng = StdRng::seed_from_u64(1);
+    let params = GeneSimulationParams::default().with_duplication_rate(0.2).with_loss_rate(0.1);
+    let simulation = simulate_gene_trees(&mut rng, &species, 10, &params);
+    for gene in simulation.genes_over_species().gene_networks() {
+        assert!(gene.graph().basic_properties().tree);
+        assert_eq!(gene.kind(), PhylogeneticNetworkKind::Binary);
+    }
+}
@@ -0,0 +1,59 @@
+use dagex::{
+    const_parse_newick,
+    core::{ArrowDTO, DirectedGraph, DirectedGraphDTO, Node}};
+
+fn build_graph(arrows: &[(i32, i32)], number_of_nodes: i32) -> DirectedGraph {
+    let arrows: Vec<ArrowDTO> = arrows.iter()
+        .map(|p| ArrowDTO::new(p.0, p.1))
+        .collect();
+    let dto = DirectedGraphDTO::new(number_of_nodes, arrows);
+    DirectedGraph::from_dto(&dto).unwrap()
+}
+
+#[test]
+fn test_cherry_occurrences() {
+    let host = build_graph(&[(0, 1), (0, 2), (1, 3), (1, 4)], 5);
+    let cherry = build_graph(&[(0, 1), (0, 2)], 3);
+    let occurrences = host.find_subgraph_occurrences(&cherry, 100);
+    assert_eq!(occurrences.len(), 4);
+    for occurrence in &occurrences {
+        assert_eq!(occurrence.len(), 3);
+        let parent = occurrence[&Node::from(0)];
+        for child in [1, 2] {
+            let mapped = occurrence[&Node::from(child)];
+            assert!(host.get_successors(parent).contains(&mapped));
+        }
+    }
+    assert_eq!(host.find_subgraph_occurrences(&cherry, 1).len(), 1);
+    assert!(host.find_subgraph_occurrences(&cherry, 0).is_empty());
+}
+
+#[test]
+fn test_reticulation_motif() {
+    let network = const_parse_newick!("((A, (D)B#1),(B#1, C));");
+    let motif = build_graph(&[(0, 2), (1, 2)], 3);
+    let occurrences = network.graph().find_subgraph_occurrences(&motif, 100);
+    assert_eq!(occurrences.len(), 2);
+    let reticulation = occurrences[0][&Node::from(2)];
+    assert!(network.is_reticulation_node(reticulation));
+
+    let double = build_graph(&[(0, 2), (1, 2), (0, 3), (1, 3)], 4);
+    assert!(network.graph().find_subgraph_occurrences(&double, 100).is_empty());
+}
+
+#[test]
+fn test_taxon_compatibility() {
+    let network = const_parse_newick!("((A,B),(C,D));");
+    let pattern = const_parse_newick!("(A,B);");
+    let occurrences = network.find_subnetwork_occurrences(&pattern, 100);
+    assert_eq!(occurrences.len(), 1);
+    let missing = const_parse_newick!("(A,C);");
+    assert!(network.find_subnetwork_occurrences(&missing, 100).is_empty());
+}
+
+#[test]
+fn test_cancellation() {
+    let host = build_graph(&[(0, 1), (0, 2), (1, 3), (1, 4)], 5);
+    let cherry = build_graph(&[(0, 1), (0, 2)], 3);
+    assert!(host.find_subgraph_occurrences_cancellable(&cherry, 100, || true).is_empty());
+}
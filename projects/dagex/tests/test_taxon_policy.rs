@@ -0,0 +1,134 @@
+use dagex::{
+    core::Node,
+    phylo::{
+        parse_newick_from_str,
+        write_newick,
+        PhylogeneticNetwork,
+        PhylogeneticNetworkFromError,
+        PhylogeneticNetworkOptions,
+        Taxon,
+        TaxonCharacters,
+        TaxonError,
+        TaxonPolicy}};
+use rstest::rstest;
+
+fn validated(text: &str, policy: &TaxonPolicy) -> String {
+    Taxon::new_validated(text, policy).unwrap().value().as_str().to_owned()
+}
+
+#[test]
+fn test_default_policy_is_permissive() {
+    let policy = TaxonPolicy::default();
+    for text in ["", "  Homo  sapiens ", "a\tb", "Ünïcödé", "x(y)"] {
+        assert_eq!(validated(text, &policy), text);
+        let taxon = Taxon::new(text).unwrap();
+        assert_eq!(taxon.normalized(&policy), taxon);
+    }
+}
+
+#[rstest]
+#[case(TaxonPolicy::default().with_trim_whitespace(true), "  Homo  sapiens \n", "Homo  sapiens")]
+#[case(TaxonPolicy::default().with_collapse_whitespace(true), "Homo \t sapiens", "Homo_sapiens")]
+#[case(TaxonPolicy::default().with_collapse_whitespace(true), " a b ", "_a_b_")]
+#[case(TaxonPolicy::default().with_trim_whitespace(true).with_collapse_whitespace(true), " a b ", "a_b")]
+#[case(TaxonPolicy::default().with_fold_case(true), "Homo SAPIENS", "homo sapiens")]
+fn test_normalization_rules(#[case] policy: TaxonPolicy, #[case] text: &str, #[case] expected: &str) {
+    assert_eq!(validated(text, &policy), expected);
+    assert_eq!(Taxon::new(text).unwrap().normalized(&policy), Taxon::new(expected).unwrap());
+}
+
+#[test]
+fn test_max_length() {
+    let policy = TaxonPolicy::default()
+        .with_trim_whitespace(true)
+        .with_max_length(Some(3));
+    assert_eq!(validated("  abc  ", &policy), "abc");
+    assert_eq!(validated("żół", &policy), "żół");
+    let result = Taxon::new_validated("abcd", &policy);
+    assert!(matches!(result, Err(TaxonError::TooLong { length: 4, limit: 3 })), "Invalid result: {result:?}");
+    assert_eq!(result.unwrap_err().to_string(), "taxon has 4 characters, exceeding the limit of 3");
+}
+
+#[rstest]
+#[case(TaxonCharacters::Any, "a b\u{7}", None)]
+#[case(TaxonCharacters::Printable, "a b(c)", None)]
+#[case(TaxonCharacters::Printable, "a\u{7}b", Some('\u{7}'))]
+#[case(TaxonCharacters::Alphanumeric, "Homo_sapiens-1.2", None)]
+#[case(TaxonCharacters::Alphanumeric, "Homo sapiens", Some(' '))]
+#[case(TaxonCharacters::Alphanumeric, "a(b)", Some('('))]
+fn test_allowed_characters(#[case] characters: TaxonCharacters, #[case] text: &str, #[case] invalid: Option<char>) {
+    let policy = TaxonPolicy::default().with_allowed_characters(characters);
+    let result = Taxon::new_validated(text, &policy);
+    match invalid {
+        None => assert!(result.is_ok(), "Invalid result: {result:?}"),
+        Some(c) => assert!(matches!(result, Err(TaxonError::InvalidCharacter(x)) if x == c), "Invalid result: {result:?}"),
+    }
+}
+
+#[test]
+fn test_characters_checked_after_normalization() {
+    let policy = TaxonPolicy::default()
+        .with_collapse_whitespace(true)
+        .with_allowed_characters(TaxonCharacters::Alphanumeric);
+    assert_eq!(validated("Homo sapiens", &policy), "Homo_sapiens");
+}
+
+fn sloppy_policy() -> TaxonPolicy {
+    TaxonPolicy::default()
+        .with_trim_whitespace(true)
+        .with_collapse_whitespace(true)
+        .with_fold_case(true)
+}
+
+fn reload(network: &PhylogeneticNetwork, options: &PhylogeneticNetworkOptions)
+    -> Result<PhylogeneticNetwork, PhylogeneticNetworkFromError>
+{
+    PhylogeneticNetwork::from_dto_with_options(&network.into_dto(), options)
+}
+
+#[test]
+fn test_network_with_policy() {
+    let sloppy = parse_newick_from_str("((' Homo  Sapiens ', Pan), GORILLA);").unwrap().network;
+    let clean = parse_newick_from_str("((homo_sapiens, pan), gorilla);").unwrap().network;
+    assert_ne!(sloppy, clean);
+
+    let options = PhylogeneticNetworkOptions::default().with_taxon_policy(Some(sloppy_policy()));
+    assert_eq!(options.taxon_policy(), Some(&sloppy_policy()));
+    assert_eq!(reload(&sloppy, &options).unwrap(), reload(&clean, &options).unwrap());
+    assert_eq!(reload(&sloppy, &PhylogeneticNetworkOptions::default()).unwrap(), sloppy);
+}
+
+#[test]
+fn test_network_with_rejected_taxon() {
+    let network = parse_newick_from_str("((A, 'B C'), 'D E');").unwrap().network;
+    let policy = TaxonPolicy::default().with_allowed_characters(TaxonCharacters::Alphanumeric);
+    let options = PhylogeneticNetworkOptions::default().with_taxon_policy(Some(policy));
+    let result = reload(&network, &options);
+    let Err(PhylogeneticNetworkFromError::InvalidTaxon(node, TaxonError::InvalidCharacter(' '))) = result else {
+        panic!("Invalid result: {result:?}");
+    };
+    let expected = ["B C", "D E"].iter()
+        .map(|taxon| network.get_single_by_taxon(taxon).unwrap())
+        .min_by_key(Node::id)
+        .unwrap();
+    assert_eq!(node, expected);
+}
+
+#[rstest]
+#[case(TaxonPolicy::default(), "x 'y'", "x 'y'")]
+#[case(TaxonPolicy::default().with_trim_whitespace(true), " a:b ", "a:b")]
+#[case(sloppy_policy(), " Homo  Sapiens ", "homo_sapiens")]
+fn test_newick_round_trip(#[case] policy: TaxonPolicy, #[case] text: &str, #[case] expected: &str) {
+    let taxon = Taxon::new_validated(text, &policy).unwrap();
+    assert_eq!(taxon.value().as_str(), expected);
+    let quoted = format!("'{}'", text.replace('\'', "''"));
+    let network = parse_newick_from_str(&format!("({quoted}, B);")).unwrap().network;
+    let options = PhylogeneticNetworkOptions::default().with_taxon_policy(Some(policy));
+    let network = reload(&network, &options).unwrap();
+
+    let mut output = Vec::new();
+    write_newick(&network, &mut output).unwrap();
+    let parsed = parse_newick_from_str(core::str::from_utf8(&output).unwrap()).unwrap().network;
+    assert_eq!(reload(&parsed, &options).unwrap(), network);
+    assert_eq!(parsed.get_nodes_by_taxon(&taxon).len(), 1);
+}
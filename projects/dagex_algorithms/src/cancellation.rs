@@ -0,0 +1,299 @@
+use core::cmp::Ordering as CmpOrdering;
+use std::{
+    collections::BinaryHeap,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc,
+        Condvar,
+        Mutex,
+        OnceLock,
+        Weak},
+    thread,
+    time::{Duration, Instant}};
+
+/// State of a [`CancellationTokenSource`] and its tokens. Transitions only
+/// go forward, in the order of declaration.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+#[repr(u8)]
+pub enum TokenState {
+    NotCancelled = 0,
+
+    /// Cancellation requested, registered callbacks are being invoked.
+    /// Tokens already report [`CancellationToken::is_cancelled`].
+    Cancelling = 1,
+
+    /// Cancellation requested and all registered callbacks finished.
+    Cancelled = 2,
+}
+
+impl TokenState {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::NotCancelled,
+            1 => Self::Cancelling,
+            _ => Self::Cancelled,
+        }
+    }
+}
+
+type Callback = Box<dyn FnOnce() + Send>;
+
+#[derive(Default)]
+struct Callbacks {
+    next_id: u64,
+    entries: Vec<(u64, Callback)>,
+}
+
+struct TokenInner {
+    state: AtomicU8,
+    callbacks: Mutex<Callbacks>,
+    cancelled: Condvar,
+}
+
+impl TokenInner {
+    #[inline(always)]
+    fn state(&self) -> TokenState {
+        TokenState::from_u8(self.state.load(Ordering::Acquire))
+    }
+
+    #[inline(always)]
+    fn is_cancelled(&self) -> bool {
+        self.state() != TokenState::NotCancelled
+    }
+
+    /// Only the first call moves the state forward and invokes callbacks,
+    /// no matter how many threads race here.
+    fn cancel(&self) {
+        let won = self.state.compare_exchange(
+            TokenState::NotCancelled as u8,
+            TokenState::Cancelling as u8,
+            Ordering::AcqRel,
+            Ordering::Acquire).is_ok();
+        if !won {
+            return;
+        }
+
+        let entries = {
+            let mut callbacks = self.callbacks.lock().unwrap();
+            self.cancelled.notify_all();
+            core::mem::take(&mut callbacks.entries)
+        };
+        for (_, callback) in entries {
+            callback();
+        }
+        self.state.store(TokenState::Cancelled as u8, Ordering::Release);
+    }
+}
+
+/// Owned by [`CancellationTokenSource`] and weakly referenced by scheduled
+/// cancellations, so that dropping the source disarms them.
+struct TimerTarget {
+    inner: Arc<TokenInner>,
+}
+
+/// Owner side of cancellation. Creates [`CancellationToken`]s observing
+/// its state, and cancels all of them at once.
+pub struct CancellationTokenSource {
+    inner: Arc<TokenInner>,
+    timer_target: Arc<TimerTarget>,
+}
+
+impl CancellationTokenSource {
+    pub fn new() -> Self {
+        let inner = Arc::new(TokenInner {
+            state: AtomicU8::new(TokenState::NotCancelled as u8),
+            callbacks: Mutex::default(),
+            cancelled: Condvar::new(),
+        });
+        let timer_target = Arc::new(TimerTarget { inner: inner.clone() });
+        Self { inner, timer_target }
+    }
+
+    /// Creates new [`CancellationToken`] bound to this source.
+    pub fn token(&self) -> CancellationToken {
+        CancellationToken { inner: Some(self.inner.clone()) }
+    }
+
+    /// Cancels all tokens bound to this source and invokes registered
+    /// callbacks on the current thread. Idempotent.
+    pub fn cancel(&self) {
+        self.inner.cancel();
+    }
+
+    /// Schedules [`CancellationTokenSource::cancel`] after `duration` on a
+    /// timer thread shared by all sources, spawned on first use. Dropping
+    /// the source before the deadline disarms the timer.
+    ///
+    /// # Panics
+    /// When the timer thread cannot be spawned.
+    pub fn cancel_after(&self, duration: Duration) {
+        let entry = TimerEntry {
+            deadline: Instant::now() + duration,
+            target: Arc::downgrade(&self.timer_target),
+        };
+        // The timer thread never exits, so sending cannot fail.
+        let _ = timer_sender().lock().unwrap().send(entry);
+    }
+
+    #[inline(always)]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+
+    #[inline(always)]
+    pub fn state(&self) -> TokenState {
+        self.inner.state()
+    }
+}
+
+impl Default for CancellationTokenSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Consumer side of cancellation, cheap to clone and to send between
+/// threads. Long running operations are expected to check
+/// [`CancellationToken::is_cancelled`] periodically.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Option<Arc<TokenInner>>,
+}
+
+impl CancellationToken {
+    /// Creates token which is never cancelled.
+    pub fn none() -> Self {
+        Self { inner: None }
+    }
+
+    #[inline(always)]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner
+            .as_ref()
+            .is_some_and(|inner| inner.is_cancelled())
+    }
+
+    #[inline(always)]
+    pub fn state(&self) -> TokenState {
+        self.inner
+            .as_ref()
+            .map_or(TokenState::NotCancelled, |inner| inner.state())
+    }
+
+    /// Blocks the current thread until the token gets cancelled or
+    /// `timeout` passes. Returns [`CancellationToken::is_cancelled`].
+    ///
+    /// # Panics
+    /// When internal lock got poisoned.
+    pub fn wait_cancelled(&self, timeout: Duration) -> bool {
+        let Some(inner) = &self.inner else {
+            thread::sleep(timeout);
+            return false;
+        };
+        let callbacks = inner.callbacks.lock().unwrap();
+        let _guard = inner.cancelled
+            .wait_timeout_while(callbacks, timeout, |_| !inner.is_cancelled())
+            .unwrap();
+        inner.is_cancelled()
+    }
+
+    /// Registers `callback` to be invoked exactly once on cancellation, on
+    /// the cancelling thread. If the token is already cancelled `callback`
+    /// is invoked immediately on the current thread. Dropping the returned
+    /// registration unregisters a not yet invoked callback. Tokens which
+    /// are never cancelled drop `callback` right away.
+    ///
+    /// # Panics
+    /// When internal lock got poisoned.
+    pub fn register<F>(&self, callback: F) -> CancellationTokenRegistration
+        where F: FnOnce() + Send + 'static
+    {
+        let Some(inner) = &self.inner else {
+            return CancellationTokenRegistration { inner: Weak::new(), id: 0 };
+        };
+        let mut callbacks = inner.callbacks.lock().unwrap();
+        if inner.is_cancelled() {
+            drop(callbacks);
+            callback();
+            return CancellationTokenRegistration { inner: Weak::new(), id: 0 };
+        }
+        let id = callbacks.next_id;
+        callbacks.next_id += 1;
+        callbacks.entries.push((id, Box::new(callback)));
+        CancellationTokenRegistration { inner: Arc::downgrade(inner), id }
+    }
+}
+
+/// Handle of a callback registered with [`CancellationToken::register`].
+/// Unregisters the callback on drop.
+pub struct CancellationTokenRegistration {
+    inner: Weak<TokenInner>,
+    id: u64,
+}
+
+impl Drop for CancellationTokenRegistration {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.upgrade() {
+            let mut callbacks = inner.callbacks.lock().unwrap();
+            callbacks.entries.retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+struct TimerEntry {
+    deadline: Instant,
+    target: Weak<TimerTarget>,
+}
+
+impl PartialEq for TimerEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl Eq for TimerEntry { }
+
+impl PartialOrd for TimerEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Reversed, so that [`BinaryHeap`] pops the earliest deadline first.
+impl Ord for TimerEntry {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        other.deadline.cmp(&self.deadline)
+    }
+}
+
+fn timer_sender() -> &'static Mutex<Sender<TimerEntry>> {
+    static SENDER: OnceLock<Mutex<Sender<TimerEntry>>> = OnceLock::new();
+    SENDER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<TimerEntry>();
+        thread::Builder::new()
+            .name("dagex-cancellation-timer".to_owned())
+            .spawn(move || {
+                let mut entries = BinaryHeap::<TimerEntry>::new();
+                loop {
+                    let now = Instant::now();
+                    while entries.peek().is_some_and(|entry| entry.deadline <= now) {
+                        if let Some(target) = entries.pop().and_then(|entry| entry.target.upgrade()) {
+                            target.inner.cancel();
+                        }
+                    }
+                    let message = match entries.peek() {
+                        Some(next) => receiver.recv_timeout(next.deadline - now),
+                        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                    };
+                    match message {
+                        Ok(new_entry) => entries.push(new_entry),
+                        Err(RecvTimeoutError::Timeout) => { },
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+            })
+            .expect("Failed to spawn cancellation timer thread.");
+        Mutex::new(sender)
+    })
+}
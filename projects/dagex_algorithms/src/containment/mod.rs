@@ -0,0 +1,482 @@
+use core::fmt::{Display, Formatter};
+use std::{collections::{HashMap, HashSet}, marker::PhantomData, sync::Arc};
+
+use raf_structural_logging::{core::CoreLoggerFactory, models::SLDict};
+use dagex::{
+    core::Node,
+    phylo::{PhylogeneticNetwork, PhylogeneticNetworkKind, Taxon, TaxonBitSet, TaxonRegistry},
+    raf_array::immutable_string::ImmutableString};
+
+use crate::cancellation::CancellationToken;
+use crate::logger::{build_default_logger_factory, build_logger_name, AlgorithmLogger};
+use crate::traits::{Algorithm, AlgorithmError, AlgorithmFactory, AlgorithmFactoryBuilder};
+
+/// Checks whether a cluster, i.e. a set of taxa, is a softwired cluster of
+/// a network: whether some tree displayed by the network, see
+/// [`PhylogeneticNetwork::iter_displayed_trees`], has a node with exactly
+/// these taxa below it.
+///
+/// # Notes
+/// For each candidate node, parents of reticulations below it are chosen
+/// in topological order. The outcome depends only on which of the already
+/// processed nodes with unprocessed successors are reachable from the
+/// candidate, so failed states are memoized by that frontier. For networks
+/// of low level frontiers stay small, and the check is fast regardless of
+/// the total number of reticulations.
+pub struct ContainmentAlgorithm<'a> {
+    network: &'a PhylogeneticNetwork,
+    cluster: &'a HashSet<Taxon>,
+    logger_factory: Arc<CoreLoggerFactory>,
+    logger_name: ImmutableString,
+}
+
+/// Parent choices displaying a cluster, see [`ContainmentResult::witness`].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ContainmentWitness {
+    node: Node,
+    parents: Vec<(Node, Node)>,
+}
+
+impl ContainmentWitness {
+    /// Node of the network with exactly the cluster's taxa below it, once
+    /// [`ContainmentWitness::parents`] are chosen.
+    pub fn node(&self) -> Node { self.node }
+
+    /// `(reticulation, parent)` pairs, one for every reticulation node of
+    /// the network, ordered by reticulation id. Keeping only arrows from
+    /// these parents displays a tree with the cluster.
+    pub fn parents(&self) -> &[(Node, Node)] { &self.parents }
+}
+
+pub struct ContainmentResult {
+    witness: Option<ContainmentWitness>,
+}
+
+impl ContainmentResult {
+    /// Whether the network has the cluster as a softwired cluster.
+    pub fn is_contained(&self) -> bool { self.witness.is_some() }
+
+    /// Parent choices achieving the cluster, if it is contained.
+    pub fn witness(&self) -> Option<&ContainmentWitness> { self.witness.as_ref() }
+}
+
+/// Search for a single candidate node.
+struct CandidateSearch<'a> {
+    network: &'a PhylogeneticNetwork,
+    cluster: &'a HashSet<Taxon>,
+    /// Candidate followed by its descendants, in topological order.
+    order: Vec<Node>,
+    /// Nodes of `order` processed before given reticulation, which have
+    /// successors processed at it or later. Empty at other positions.
+    frontiers: Vec<Vec<Node>>,
+    reachable: Vec<bool>,
+    failed: HashSet<(usize, Vec<u64>)>,
+}
+
+#[allow(clippy::cast_sign_loss)]
+#[inline(always)]
+fn index(node: Node) -> usize {
+    node.id() as usize
+}
+
+impl<'a> CandidateSearch<'a> {
+    fn new(
+        network: &'a PhylogeneticNetwork,
+        cluster: &'a HashSet<Taxon>,
+        candidate: Node,
+        topological: &[Node]) -> Self
+    {
+        let graph = network.graph();
+        let size = topological.len();
+        let mut is_descendant = vec![false; size];
+        is_descendant[index(candidate)] = true;
+        for node in graph.iter_descendants(candidate) {
+            is_descendant[index(node)] = true;
+        }
+        let order: Vec<Node> = topological.iter()
+            .copied()
+            .filter(|node| is_descendant[index(*node)])
+            .collect();
+
+        let mut positions = vec![0; size];
+        for (position, node) in order.iter().enumerate() {
+            positions[index(*node)] = position;
+        }
+        let mut last_use = vec![0; order.len()];
+        for (position, node) in order.iter().enumerate() {
+            last_use[position] = graph.get_successors(*node)
+                .iter()
+                .map(|successor| positions[index(*successor)])
+                .max()
+                .unwrap_or(position);
+        }
+        // Frontiers are only needed at reticulations, where the search
+        // branches.
+        let mut frontiers = vec![Vec::new(); order.len()];
+        let mut frontier: Vec<Node> = Vec::new();
+        for position in 1..order.len() {
+            frontier.retain(|node| last_use[positions[index(*node)]] >= position);
+            if last_use[position - 1] >= position {
+                frontier.push(order[position - 1]);
+            }
+            if graph.get_predecessors(order[position]).len() > 1 {
+                frontiers[position].clone_from(&frontier);
+            }
+        }
+
+        Self {
+            network: network,
+            cluster: cluster,
+            order: order,
+            frontiers: frontiers,
+            reachable: vec![false; size],
+            failed: HashSet::new(),
+        }
+    }
+
+    fn frontier_key(&self, position: usize) -> (usize, Vec<u64>) {
+        let frontier = &self.frontiers[position];
+        let mut bits = vec![0u64; frontier.len().div_ceil(64)];
+        for (idx, node) in frontier.iter().enumerate() {
+            if self.reachable[index(*node)] {
+                bits[idx / 64] |= 1 << (idx % 64);
+            }
+        }
+        (position, bits)
+    }
+
+    /// Decides reachability of nodes from `position` on. Returns `false` if
+    /// no choice of reticulation parents gives exactly the cluster.
+    fn search(&mut self, mut position: usize, ct: &mut CancellationToken) -> bool {
+        let graph = self.network.graph();
+        while position < self.order.len() {
+            let node = self.order[position];
+            let reachable = if position == 0 {
+                true
+            }
+            else
+            {
+                let predecessors = graph.get_predecessors(node);
+                let any_in = predecessors.iter().any(|parent| self.reachable[index(*parent)]);
+                let any_out = predecessors.iter().any(|parent| !self.reachable[index(*parent)]);
+                if any_in && any_out {
+                    return self.branch(position, ct);
+                }
+                any_in
+            };
+            if !self.is_consistent(node, reachable) {
+                return false;
+            }
+            self.reachable[index(node)] = reachable;
+            position += 1;
+        }
+        true
+    }
+
+    /// Tries both choices for the reticulation at `position`.
+    fn branch(&mut self, position: usize, ct: &mut CancellationToken) -> bool {
+        if ct.is_cancelled() {
+            return false;
+        }
+        let key = self.frontier_key(position);
+        if self.failed.contains(&key) {
+            return false;
+        }
+        let node = self.order[position];
+        for reachable in [true, false] {
+            if self.is_consistent(node, reachable) {
+                self.reachable[index(node)] = reachable;
+                if self.search(position + 1, ct) {
+                    return true;
+                }
+            }
+        }
+        self.failed.insert(key);
+        false
+    }
+
+    /// Leaf is reachable if and only if its taxon is in the cluster.
+    fn is_consistent(&self, node: Node, reachable: bool) -> bool {
+        match self.network.taxa().get(&node) {
+            Some(taxon) => self.cluster.contains(taxon) == reachable,
+            None => true,
+        }
+    }
+
+    /// Picks for every reticulation a parent of the same reachability.
+    fn build_witness(&self) -> ContainmentWitness {
+        let graph = self.network.graph();
+        let parents = graph.iter_nodes()
+            .filter(|node| graph.get_predecessors(*node).len() > 1)
+            .map(|node| {
+                let reachable = self.reachable[index(node)];
+                let predecessors = graph.get_predecessors(node);
+                let parent = predecessors.iter()
+                    .find(|parent| self.reachable[index(**parent)] == reachable)
+                    .unwrap_or(&predecessors[0]);
+                (node, *parent)
+            })
+            .collect();
+        ContainmentWitness {
+            node: self.order[0],
+            parents: parents,
+        }
+    }
+}
+
+impl ContainmentAlgorithm<'_> {
+    fn calculate(self, ct: &mut CancellationToken)
+        -> Result<ContainmentResult, AlgorithmError<()>>
+    {
+        let network = self.network;
+        let mut registry = TaxonRegistry::new();
+        let clusters = network.hardwired_clusters_with_registry(&mut registry);
+        let mut cluster = TaxonBitSet::new(&registry);
+        for taxon in self.cluster {
+            cluster.insert(taxon);
+        }
+
+        // Network is acyclic, so the topological order always exists.
+        let topological: Vec<Node> = network.graph()
+            .iter_topological()
+            .map(Iterator::collect)
+            .unwrap_or_default();
+        for candidate in network.graph().iter_nodes() {
+            if ct.is_cancelled() {
+                return Err(AlgorithmError::Cancelled);
+            }
+            if !cluster.is_subset_of(&clusters[&candidate]) {
+                continue;
+            }
+            let mut search = CandidateSearch::new(network, self.cluster, candidate, &topological);
+            if search.search(0, ct) {
+                return Ok(ContainmentResult { witness: Some(search.build_witness()) });
+            }
+        }
+        if ct.is_cancelled() {
+            return Err(AlgorithmError::Cancelled);
+        }
+        Ok(ContainmentResult { witness: None })
+    }
+}
+
+impl<'a> Algorithm<'a> for ContainmentAlgorithm<'a> {
+    type Input<'b> = (&'b PhylogeneticNetwork, &'b HashSet<Taxon>);
+
+    type Output<'b> = ContainmentResult;
+
+    type Error = ();
+
+    fn run_with_cancellation(self, ct: &mut CancellationToken)
+        -> Result<Self::Output<'a>, AlgorithmError<Self::Error>>
+    {
+        let logger = AlgorithmLogger::new(&self.logger_factory, &self.logger_name);
+        logger.log_run(|| self.calculate(ct))
+    }
+}
+
+#[derive(Debug)]
+pub enum ContainmentInputValidationError {
+    /// Network is [`PhylogeneticNetworkKind::Multifurcating`], while the
+    /// algorithm supports binary networks only.
+    NotBinary,
+
+    /// Cluster has no taxa.
+    EmptyCluster,
+
+    /// Cluster taxon missing in the network. Holds the first one in
+    /// alphabetical order.
+    UnknownTaxon(Taxon),
+
+    /// Taxon assigned to more than one leaf of the network. Holds the
+    /// first one in alphabetical order.
+    DuplicateTaxon(Taxon),
+
+    /// Network passed to [`contains_tree`] as tree has reticulations.
+    NotTree,
+}
+
+pub struct ContainmentAlgorithmFactory {
+    logger_factory: Arc<CoreLoggerFactory>,
+    _priv: PhantomData<()>,
+}
+
+impl ContainmentAlgorithmFactory {
+    fn build<'a>(
+        &self,
+        network: &'a PhylogeneticNetwork,
+        cluster: &'a HashSet<Taxon>,
+        logger_name: ImmutableString) -> Result<ContainmentAlgorithm<'a>, ContainmentInputValidationError>
+    {
+        if network.kind() != PhylogeneticNetworkKind::Binary {
+            return Err(ContainmentInputValidationError::NotBinary);
+        }
+        if cluster.is_empty() {
+            return Err(ContainmentInputValidationError::EmptyCluster);
+        }
+
+        let mut counts = HashMap::<&Taxon, usize>::new();
+        for taxon in network.taxa().values() {
+            *counts.entry(taxon).or_default() += 1;
+        }
+        let duplicate = first_alphabetically(counts.iter()
+            .filter(|(_, count)| **count > 1)
+            .map(|(taxon, _)| *taxon));
+        if let Some(taxon) = duplicate {
+            return Err(ContainmentInputValidationError::DuplicateTaxon(taxon));
+        }
+        let unknown = first_alphabetically(cluster.iter()
+            .filter(|taxon| !counts.contains_key(taxon)));
+        if let Some(taxon) = unknown {
+            return Err(ContainmentInputValidationError::UnknownTaxon(taxon));
+        }
+
+        Ok(ContainmentAlgorithm {
+            network: network,
+            cluster: cluster,
+            logger_factory: self.logger_factory.clone(),
+            logger_name: logger_name,
+        })
+    }
+}
+
+fn first_alphabetically<'a>(taxa: impl Iterator<Item=&'a Taxon>) -> Option<Taxon> {
+    taxa.min_by(|left, right| left.value().as_str().cmp(right.value().as_str()))
+        .cloned()
+}
+
+impl AlgorithmFactory for ContainmentAlgorithmFactory {
+    type Input<'a> = (&'a PhylogeneticNetwork, &'a HashSet<Taxon>);
+
+    type Algo<'a> = ContainmentAlgorithm<'a>;
+
+    type Error = ContainmentInputValidationError;
+
+    /// [`PhylogeneticNetwork`]s are always rooted and acyclic, so only
+    /// being binary and taxa are validated.
+    fn create<'a>(&mut self, input: Self::Input<'a>)
+        -> Result<Self::Algo<'a>, Self::Error>
+    {
+        let (network, cluster) = input;
+        let logger_name = build_logger_name("ContainmentAlgorithm", network);
+        let result = self.build(network, cluster, logger_name.clone());
+        AlgorithmLogger::new(&self.logger_factory, &logger_name)
+            .log_validation(&result, || {
+                let mut params = SLDict::new();
+                params.insert("number_of_nodes", network.graph().number_of_nodes());
+                params.insert("cluster_size", cluster.len());
+                params
+            });
+        result
+    }
+}
+
+#[derive(Default)]
+pub struct ContainmentAlgorithmFactoryBuilder {
+    logger_factory: Option<Arc<CoreLoggerFactory>>,
+}
+
+impl AlgorithmFactoryBuilder for ContainmentAlgorithmFactoryBuilder {
+    type LoggerFactory = CoreLoggerFactory;
+
+    type AlgoFactory = ContainmentAlgorithmFactory;
+
+    type Error = ();
+
+    fn set_logger_factory(
+        &mut self,
+        logger_factory: &Arc<Self::LoggerFactory>)
+    {
+        self.logger_factory = Some(logger_factory.clone());
+    }
+
+    fn create(self) -> Result<Self::AlgoFactory, Self::Error> {
+        let factory = ContainmentAlgorithmFactory {
+            logger_factory: self.logger_factory.unwrap_or_else(build_default_logger_factory),
+            _priv: PhantomData,
+        };
+        Ok(factory)
+    }
+}
+
+/// Checks whether every cluster of `tree` is a softwired cluster of
+/// `network`, see [`ContainmentAlgorithm`]. This is necessary for
+/// `network` to display `tree`, but in general not sufficient, since each
+/// cluster may be achieved with different parent choices.
+///
+/// # Errors
+/// [`ContainmentInputValidationError::NotTree`] if `tree` has
+/// reticulations, and errors of [`ContainmentAlgorithmFactory`] for each
+/// cluster otherwise.
+pub fn contains_tree(network: &PhylogeneticNetwork, tree: &PhylogeneticNetwork)
+    -> Result<bool, ContainmentInputValidationError>
+{
+    if !tree.graph().basic_properties().tree {
+        return Err(ContainmentInputValidationError::NotTree);
+    }
+    let mut factory = ContainmentAlgorithmFactory {
+        logger_factory: build_default_logger_factory(),
+        _priv: PhantomData,
+    };
+    let mut clusters: Vec<HashSet<Taxon>> = tree.hardwired_clusters()
+        .into_values()
+        .filter(|cluster| !cluster.is_empty())
+        .collect::<HashSet<TaxonBitSet>>()
+        .into_iter()
+        .map(|cluster| cluster.iter().cloned().collect())
+        .collect();
+    // Small clusters are the cheapest to check.
+    clusters.sort_by_key(HashSet::len);
+    for cluster in &clusters {
+        let algorithm = factory.create((network, cluster))?;
+        if !algorithm.run().is_ok_and(|result| result.is_contained()) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+impl From<&ContainmentInputValidationError> for SLDict {
+    fn from(value: &ContainmentInputValidationError) -> Self {
+        let mut dict = SLDict::new();
+        match value {
+            ContainmentInputValidationError::NotBinary => {
+                dict.insert("error", "NotBinary");
+            },
+            ContainmentInputValidationError::EmptyCluster => {
+                dict.insert("error", "EmptyCluster");
+            },
+            ContainmentInputValidationError::UnknownTaxon(taxon) => {
+                dict.insert("error", "UnknownTaxon");
+                dict.insert("taxon", taxon.value().as_str());
+            },
+            ContainmentInputValidationError::DuplicateTaxon(taxon) => {
+                dict.insert("error", "DuplicateTaxon");
+                dict.insert("taxon", taxon.value().as_str());
+            },
+            ContainmentInputValidationError::NotTree => {
+                dict.insert("error", "NotTree");
+            },
+        }
+        dict
+    }
+}
+
+impl Display for ContainmentInputValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ContainmentInputValidationError::NotBinary
+                => f.write_str("input network is not binary"),
+            ContainmentInputValidationError::EmptyCluster
+                => f.write_str("cluster is empty"),
+            ContainmentInputValidationError::UnknownTaxon(taxon)
+                => write!(f, "cluster taxon {} is missing in the network", taxon.value().as_str()),
+            ContainmentInputValidationError::DuplicateTaxon(taxon)
+                => write!(f, "taxon {} is assigned to multiple leaves", taxon.value().as_str()),
+            ContainmentInputValidationError::NotTree
+                => f.write_str("tree has reticulations"),
+        }
+    }
+}
+
+impl std::error::Error for ContainmentInputValidationError { }
@@ -1,50 +1,122 @@
-use std::{marker::PhantomData, sync::Arc};
+use core::fmt::{Display, Formatter};
+use std::{marker::PhantomData, mem::size_of, sync::Arc, time::Instant};
 
-use dagex::raf_array::array::Array;
-use raf_structural_logging::core::CoreLoggerFactory;
-use dagex::core::{DirectedGraph, Node};
+use dagex::raf_array::{array::Array, immutable_string::ImmutableString};
+use raf_structural_logging::{core::CoreLoggerFactory, models::SLDict};
+use dagex::core::{DirectedGraph, Node, NodeMap};
 
-use crate::traits::{Algorithm, AlgorithmFactory, AlgorithmFactoryBuilder};
+use crate::cancellation::CancellationToken;
+use crate::logger::{build_default_logger_factory, build_logger_name, graph_params, AlgorithmLogger};
+use crate::traits::{Algorithm, AlgorithmError, AlgorithmFactory, AlgorithmFactoryBuilder, AlgorithmMetrics};
+use crate::validated_dag::{DagValidationError, ValidatedRootedDag};
 
+/// Computes depth of every node of a rooted, acyclic graph, i.e. the length
+/// of the longest path from the root to the node.
 pub struct DepthAlgorithm<'a> {
     graph: &'a DirectedGraph,
+    /// Source of cached depths, if created with
+    /// [`DepthAlgorithmFactory::create_validated`].
+    dag: Option<&'a ValidatedRootedDag<'a>>,
     scanned_nodes: Array<i32>,
+    logger_factory: Arc<CoreLoggerFactory>,
+    logger_name: ImmutableString,
 }
 
 pub struct DepthResult<'a> {
+    graph: &'a DirectedGraph,
     max_depth: i32,
-    phantom: PhantomData<&'a()>,
+    depths: NodeMap<i32>,
+    processed_nodes: usize,
 }
 
 impl<'a> DepthResult<'a> {
-    fn new(max_depth: i32) -> Self {
-        Self { max_depth, phantom: PhantomData }
+    fn new(graph: &'a DirectedGraph, max_depth: i32, depths: NodeMap<i32>, processed_nodes: usize) -> Self {
+        Self { graph, max_depth, depths, processed_nodes }
     }
 
     pub fn max_depth(&self) -> i32 { self.max_depth }
+
+    /// Length of the longest path from the root to `node`. `None` if `node`
+    /// is not reachable from the root or is outside of the graph.
+    pub fn depth_of(&self, node: Node) -> Option<i32> { self.depths.get(self.graph, node).copied() }
+
+    /// Depths of all nodes, see [`DepthResult::depth_of`].
+    pub fn depths(&self) -> &NodeMap<i32> { &self.depths }
+
+    /// Number of nodes whose depth got calculated. Each node reachable from
+    /// the root is processed exactly once.
+    pub fn processed_nodes(&self) -> usize { self.processed_nodes }
 }
 
+/// Number of processed nodes between consecutive cancellation checks.
+const CANCELLATION_CHECK_INTERVAL: usize = 1024;
+
 impl<'a> DepthAlgorithm<'a> {
+    fn calculate(mut self, ct: &CancellationToken) -> Result<DepthResult<'a>, AlgorithmError<()>> {
+        if ct.is_cancelled() {
+            return Err(AlgorithmError::Cancelled);
+        }
+        if let Some(dag) = self.dag {
+            let depths = dag.depths();
+            return Ok(DepthResult::new(self.graph, dag.max_depth(), depths.clone(), depths.len()));
+        }
+        let root = self.graph.root().unwrap();
+        let postorder = self.scan(root, ct).ok_or(AlgorithmError::Cancelled)?;
+        #[allow(clippy::cast_sign_loss)]
+        let max_depth = self.scanned_nodes.as_slice()[root.id() as usize];
+        let depths = self.calculate_depths(&postorder);
+        Ok(DepthResult::new(self.graph, max_depth, depths, postorder.len()))
+    }
+
+    /// Post-order traversal with an explicit stack of `(node, position of
+    /// the next successor)` pairs, filling `scanned_nodes` with lengths of
+    /// the longest paths down to leaves. Returns the post-order, or `None`
+    /// if `ct` got cancelled.
     #[allow(clippy::cast_sign_loss)]
-    fn scan(&mut self, node: Node) -> i32 {
-        let idx = node.id() as usize;
-
-        {
-            let scanned_nodes = self.scanned_nodes.as_slice_mut();
-            let scanned_value = scanned_nodes[idx];
-            if scanned_value != -1 {
-                return scanned_value;
+    fn scan(&mut self, root: Node, ct: &CancellationToken) -> Option<Vec<Node>> {
+        let graph = self.graph;
+        let scanned_nodes = self.scanned_nodes.as_slice_mut();
+        let mut postorder = Vec::with_capacity(graph.number_of_nodes() as usize);
+        let mut stack = vec![(root, 0)];
+        while let Some((node, position)) = stack.last_mut() {
+            let successors = graph.get_successors(*node);
+            if let Some(child) = successors.get(*position) {
+                *position += 1;
+                if scanned_nodes[child.id() as usize] == -1 {
+                    stack.push((*child, 0));
+                }
+                continue;
+            }
+
+            let final_depth = successors.iter()
+                .map(|child| scanned_nodes[child.id() as usize])
+                .max()
+                .unwrap_or(-1);
+            scanned_nodes[node.id() as usize] = final_depth + 1;
+            postorder.push(*node);
+            stack.pop();
+
+            if postorder.len() % CANCELLATION_CHECK_INTERVAL == 0 && ct.is_cancelled() {
+                return None;
             }
         }
+        Some(postorder)
+    }
 
-        let mut final_depth = -1;
-        for child in self.graph.get_successors(node) {
-            let child_depth = self.scan(*child);
-            final_depth = core::cmp::max(final_depth, child_depth);
+    /// Reversed post-order is a topological order of nodes reachable from
+    /// the root, so a single relaxation pass gives longest paths.
+    fn calculate_depths(&self, postorder: &[Node]) -> NodeMap<i32> {
+        let mut depths = self.graph.new_node_map();
+        for node in postorder.iter().rev() {
+            let depth = *depths.get(self.graph, *node).unwrap_or(&0);
+            depths.set(self.graph, *node, depth);
+            for child in self.graph.get_successors(*node) {
+                if depths.get(self.graph, *child).map_or(true, |value| *value < depth + 1) {
+                    depths.set(self.graph, *child, depth + 1);
+                }
+            }
         }
-        let final_value = final_depth + 1;
-        self.scanned_nodes.as_slice_mut()[idx] = final_value;
-        final_value
+        depths
     }
 }
 
@@ -56,14 +128,35 @@ impl<'a> Algorithm<'a> for DepthAlgorithm<'a> {
 
     type Error = ();
 
-    fn run(mut self) -> Result<Self::Output<'a>, Self::Error> {
-        let root = self.graph.root().unwrap();
-        let level = self.scan(root);
-        Ok(DepthResult::new(level))
+    fn run_with_cancellation(self, ct: &mut CancellationToken)
+        -> Result<Self::Output<'a>, AlgorithmError<Self::Error>>
+    {
+        let logger = AlgorithmLogger::new(&self.logger_factory, &self.logger_name);
+        logger.log_run(|| self.calculate(ct))
+    }
+
+    /// Visited nodes are [`DepthResult::processed_nodes`].
+    fn run_instrumented(self) -> Result<(Self::Output<'a>, AlgorithmMetrics), Self::Error> {
+        let start = Instant::now();
+        let allocations = estimated_memory_bytes(self.graph) as u64;
+        let logger = AlgorithmLogger::new(&self.logger_factory, &self.logger_name);
+        let output = self.run()?;
+        let metrics = AlgorithmMetrics::new(start.elapsed(), output.processed_nodes() as u64, allocations);
+        logger.log_metrics(&metrics);
+        Ok((output, metrics))
     }
 }
 
-#[derive(Debug)]
+#[allow(clippy::cast_sign_loss)]
+fn estimated_memory_bytes(input: &DirectedGraph) -> usize {
+    let per_node = size_of::<i32>()
+        + size_of::<Option<i32>>()
+        + size_of::<Node>()
+        + size_of::<(Node, usize)>();
+    (input.number_of_nodes() as usize).saturating_mul(per_node)
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub enum DepthInputValidationError {
     /// Input is not rooted.
     InputNotRooted,
@@ -71,19 +164,91 @@ pub enum DepthInputValidationError {
     /// Input is not acyclic.
     InputNotAcyclic,
 
-    /// Graph is too big. This algorithm allocates memory equal to
-    /// `size_of::<i32>() * number_of_nodes` to achieve linear performance. We
-    /// don't allow too big graphs thus. For max limit see
-    /// [`DepthAlgorithmFactory::max_size`].
-    GraphTooBig,
+    /// Graph is too big. This algorithm allocates memory linear in
+    /// `number_of_nodes` to achieve linear performance. We
+    /// don't allow too big graphs thus. Holds the limit, see
+    /// [`DepthAlgorithmFactoryBuilder::set_max_nodes`], and the actual
+    /// number of nodes.
+    GraphTooBig { limit: usize, size: usize },
 }
 
 pub struct DepthAlgorithmFactory {
+    max_nodes: usize,
+    logger_factory: Arc<CoreLoggerFactory>,
     _priv: PhantomData<()>,
 }
 
 impl DepthAlgorithmFactory {
+    /// Default, and the biggest allowed, limit of nodes.
     pub const fn max_size() -> usize { 1 << 30 }
+
+    /// Limit of nodes of accepted graphs.
+    pub fn max_nodes(&self) -> usize { self.max_nodes }
+
+    /// Upper bound of memory allocated by a run on `input`, in bytes:
+    /// per node depth buffers, the post-order and the traversal stack.
+    #[inline(always)]
+    pub fn estimated_memory_bytes(&self, input: &DirectedGraph) -> usize {
+        estimated_memory_bytes(input)
+    }
+
+    /// Same as [`AlgorithmFactory::create`], but skips validation of
+    /// already validated `input`. Created algorithm reuses depths cached by
+    /// `input`, computing them on the first run only.
+    ///
+    /// # Errors
+    /// [`DepthInputValidationError::GraphTooBig`] only.
+    pub fn create_validated<'a>(&mut self, input: &'a ValidatedRootedDag<'a>)
+        -> Result<DepthAlgorithm<'a>, DepthInputValidationError>
+    {
+        let graph = input.graph();
+        let logger_name = build_logger_name("DepthAlgorithm", graph);
+        let result = self.verify_size(graph)
+            .map(|_| DepthAlgorithm {
+                graph: graph,
+                dag: Some(input),
+                scanned_nodes: Array::new_with_fill(0, &mut || -1),
+                logger_factory: self.logger_factory.clone(),
+                logger_name: logger_name.clone(),
+            });
+        AlgorithmLogger::new(&self.logger_factory, &logger_name)
+            .log_validation(&result, || graph_params(graph));
+        result
+    }
+
+    fn build<'a>(&self, input: &'a DirectedGraph, logger_name: ImmutableString)
+        -> Result<DepthAlgorithm<'a>, DepthInputValidationError>
+    {
+        ValidatedRootedDag::validate(input)?;
+        let no = self.verify_size(input)?;
+        let scanned_nodes = Array::new_with_fill(no, &mut || -1);
+
+        Ok(DepthAlgorithm {
+            graph: input,
+            dag: None,
+            scanned_nodes: scanned_nodes,
+            logger_factory: self.logger_factory.clone(),
+            logger_name: logger_name,
+        })
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn verify_size(&self, input: &DirectedGraph) -> Result<usize, DepthInputValidationError> {
+        let no = input.number_of_nodes() as usize;
+        if no > self.max_nodes {
+            return Err(DepthInputValidationError::GraphTooBig { limit: self.max_nodes, size: no });
+        }
+        Ok(no)
+    }
+}
+
+impl From<DagValidationError> for DepthInputValidationError {
+    fn from(value: DagValidationError) -> Self {
+        match value {
+            DagValidationError::InputNotRooted => Self::InputNotRooted,
+            DagValidationError::InputNotAcyclic => Self::InputNotAcyclic,
+        }
+    }
 }
 
 impl AlgorithmFactory for DepthAlgorithmFactory {
@@ -93,36 +258,30 @@ impl AlgorithmFactory for DepthAlgorithmFactory {
 
     type Error = DepthInputValidationError;
 
-    #[allow(clippy::cast_sign_loss)]
     fn create<'a>(&mut self, input: Self::Input<'a>)
         -> Result<Self::Algo<'a>, Self::Error>
     {
-        let props = input.basic_properties();
-
-        if !props.rooted {
-            return Err(DepthInputValidationError::InputNotRooted);
-        }
-
-        if !props.acyclic {
-            return Err(DepthInputValidationError::InputNotAcyclic);
-        }
-        
-        let no = input.number_of_nodes() as usize;
-        if no > Self::max_size() {
-            return Err(DepthInputValidationError::GraphTooBig);
-        }
-
-        let scanned_nodes = Array::new_with_fill(no, &mut || -1);
-
-        Ok(DepthAlgorithm {
-            graph: input,
-            scanned_nodes: scanned_nodes,
-        })
+        let logger_name = build_logger_name("DepthAlgorithm", input);
+        let result = self.build(input, logger_name.clone());
+        AlgorithmLogger::new(&self.logger_factory, &logger_name)
+            .log_validation(&result, || graph_params(input));
+        result
     }
 }
 
 #[derive(Default)]
-pub struct DepthAlgorithmFactoryBuilder;
+pub struct DepthAlgorithmFactoryBuilder {
+    max_nodes: Option<usize>,
+    logger_factory: Option<Arc<CoreLoggerFactory>>,
+}
+
+impl DepthAlgorithmFactoryBuilder {
+    /// Limits number of nodes of graphs accepted by created factories.
+    /// Defaults to, and is capped at, [`DepthAlgorithmFactory::max_size`].
+    pub fn set_max_nodes(&mut self, max_nodes: usize) {
+        self.max_nodes = Some(max_nodes.min(DepthAlgorithmFactory::max_size()));
+    }
+}
 
 impl AlgorithmFactoryBuilder for DepthAlgorithmFactoryBuilder {
     type LoggerFactory = CoreLoggerFactory;
@@ -133,12 +292,52 @@ impl AlgorithmFactoryBuilder for DepthAlgorithmFactoryBuilder {
 
     fn set_logger_factory(
         &mut self,
-        _logger_factory: &Arc<Self::LoggerFactory>)
+        logger_factory: &Arc<Self::LoggerFactory>)
     {
+        self.logger_factory = Some(logger_factory.clone());
     }
 
     fn create(self) -> Result<Self::AlgoFactory, Self::Error> {
-        let factory = DepthAlgorithmFactory { _priv: PhantomData };
+        let factory = DepthAlgorithmFactory {
+            max_nodes: self.max_nodes.unwrap_or(DepthAlgorithmFactory::max_size()),
+            logger_factory: self.logger_factory.unwrap_or_else(build_default_logger_factory),
+            _priv: PhantomData,
+        };
         Ok(factory)
     }
 }
+
+impl From<&DepthInputValidationError> for SLDict {
+    fn from(value: &DepthInputValidationError) -> Self {
+        let mut dict = SLDict::new();
+        match value {
+            DepthInputValidationError::InputNotRooted => {
+                dict.insert("error", "InputNotRooted");
+            },
+            DepthInputValidationError::InputNotAcyclic => {
+                dict.insert("error", "InputNotAcyclic");
+            },
+            DepthInputValidationError::GraphTooBig { limit, size } => {
+                dict.insert("error", "GraphTooBig");
+                dict.insert("limit", *limit);
+                dict.insert("size", *size);
+            },
+        }
+        dict
+    }
+}
+
+impl Display for DepthInputValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DepthInputValidationError::InputNotRooted
+                => f.write_str("input graph is not rooted"),
+            DepthInputValidationError::InputNotAcyclic
+                => f.write_str("input graph is not acyclic"),
+            DepthInputValidationError::GraphTooBig { limit, size }
+                => write!(f, "input graph has {size} nodes, exceeding the maximum of {limit}"),
+        }
+    }
+}
+
+impl std::error::Error for DepthInputValidationError { }
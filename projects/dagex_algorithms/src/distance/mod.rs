@@ -0,0 +1,249 @@
+use core::fmt::{Display, Formatter};
+use std::{collections::HashSet, marker::PhantomData, sync::Arc};
+
+use raf_structural_logging::{core::CoreLoggerFactory, models::SLDict};
+use dagex::{
+    phylo::{PhylogeneticNetwork, Taxon, TaxonBitSet, TaxonRegistry},
+    raf_array::immutable_string::ImmutableString};
+
+use crate::cancellation::CancellationToken;
+use crate::logger::{build_default_logger_factory, build_logger_name, AlgorithmLogger};
+use crate::traits::{Algorithm, AlgorithmError, AlgorithmFactory, AlgorithmFactoryBuilder};
+
+/// Computes generalized Robinson-Foulds distance between two networks over
+/// the same taxa, i.e. the size of the symmetric difference of their sets
+/// of non-trivial hardwired clusters. Trivial clusters (empty, single
+/// taxon and all taxa) are shared by all such networks and are skipped.
+pub struct DistanceAlgorithm<'a> {
+    first: &'a PhylogeneticNetwork,
+    second: &'a PhylogeneticNetwork,
+    logger_factory: Arc<CoreLoggerFactory>,
+    logger_name: ImmutableString,
+}
+
+pub struct DistanceResult {
+    raw: usize,
+    normalized: f64,
+    only_in_first: Vec<TaxonBitSet>,
+    only_in_second: Vec<TaxonBitSet>,
+}
+
+impl DistanceResult {
+    /// Number of non-trivial clusters present in exactly one network.
+    pub fn raw(&self) -> usize { self.raw }
+
+    /// [`DistanceResult::raw`] divided by the total number of non-trivial
+    /// clusters of both networks, in `[0, 1]`. Zero if there are none.
+    pub fn normalized(&self) -> f64 { self.normalized }
+
+    /// Clusters of the first network missing in the second one, ordered by
+    /// taxon ids.
+    pub fn only_in_first(&self) -> &[TaxonBitSet] { &self.only_in_first }
+
+    /// Clusters of the second network missing in the first one, ordered by
+    /// taxon ids.
+    pub fn only_in_second(&self) -> &[TaxonBitSet] { &self.only_in_second }
+}
+
+fn nontrivial_clusters(
+    network: &PhylogeneticNetwork,
+    registry: &mut TaxonRegistry) -> HashSet<TaxonBitSet>
+{
+    let clusters = network.hardwired_clusters_with_registry(registry);
+    let all_taxa = clusters[&network.root()].len();
+    clusters.into_values()
+        .filter(|cluster| cluster.len() > 1 && cluster.len() < all_taxa)
+        .collect()
+}
+
+fn sorted_difference(
+    first: &HashSet<TaxonBitSet>,
+    second: &HashSet<TaxonBitSet>) -> Vec<TaxonBitSet>
+{
+    let mut result: Vec<TaxonBitSet> = first.difference(second).cloned().collect();
+    result.sort_by_cached_key(|cluster| cluster.ids().iter().collect::<Vec<_>>());
+    result
+}
+
+impl DistanceAlgorithm<'_> {
+    #[allow(clippy::cast_precision_loss)]
+    fn calculate(self, ct: &mut CancellationToken)
+        -> Result<DistanceResult, AlgorithmError<()>>
+    {
+        if ct.is_cancelled() {
+            return Err(AlgorithmError::Cancelled);
+        }
+        let mut registry = TaxonRegistry::new();
+        let first = nontrivial_clusters(self.first, &mut registry);
+        if ct.is_cancelled() {
+            return Err(AlgorithmError::Cancelled);
+        }
+        let second = nontrivial_clusters(self.second, &mut registry);
+        let only_in_first = sorted_difference(&first, &second);
+        let only_in_second = sorted_difference(&second, &first);
+        let raw = only_in_first.len() + only_in_second.len();
+        let total = first.len() + second.len();
+        let normalized = if total == 0 { 0.0 } else { raw as f64 / total as f64 };
+        Ok(DistanceResult {
+            raw: raw,
+            normalized: normalized,
+            only_in_first: only_in_first,
+            only_in_second: only_in_second,
+        })
+    }
+}
+
+impl<'a> Algorithm<'a> for DistanceAlgorithm<'a> {
+    type Input<'b> = (&'b PhylogeneticNetwork, &'b PhylogeneticNetwork);
+
+    type Output<'b> = DistanceResult;
+
+    type Error = ();
+
+    fn run_with_cancellation(self, ct: &mut CancellationToken)
+        -> Result<Self::Output<'a>, AlgorithmError<Self::Error>>
+    {
+        let logger = AlgorithmLogger::new(&self.logger_factory, &self.logger_name);
+        logger.log_run(|| self.calculate(ct))
+    }
+}
+
+#[derive(Debug)]
+pub enum DistanceInputValidationError {
+    /// Networks have different taxa. Holds taxa of the other network
+    /// missing in the given one, each list sorted.
+    DifferentTaxa {
+        missing_in_first: Vec<Taxon>,
+        missing_in_second: Vec<Taxon>,
+    },
+}
+
+pub struct DistanceAlgorithmFactory {
+    logger_factory: Arc<CoreLoggerFactory>,
+    _priv: PhantomData<()>,
+}
+
+fn missing_taxa(network: &PhylogeneticNetwork, other: &PhylogeneticNetwork) -> Vec<Taxon> {
+    let taxa: HashSet<&Taxon> = network.taxa().values().collect();
+    let mut missing: Vec<Taxon> = other.taxa().values()
+        .filter(|taxon| !taxa.contains(taxon))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .cloned()
+        .collect();
+    missing.sort_by(|left, right| left.value().as_str().cmp(right.value().as_str()));
+    missing
+}
+
+impl AlgorithmFactory for DistanceAlgorithmFactory {
+    type Input<'a> = (&'a PhylogeneticNetwork, &'a PhylogeneticNetwork);
+
+    type Algo<'a> = DistanceAlgorithm<'a>;
+
+    type Error = DistanceInputValidationError;
+
+    /// [`PhylogeneticNetwork`]s are always rooted and acyclic, so only taxa
+    /// are validated.
+    fn create<'a>(&mut self, input: Self::Input<'a>)
+        -> Result<Self::Algo<'a>, Self::Error>
+    {
+        let (first, second) = input;
+        let logger_name = build_logger_name("DistanceAlgorithm", &input);
+        let missing_in_first = missing_taxa(first, second);
+        let missing_in_second = missing_taxa(second, first);
+        let result = if missing_in_first.is_empty() && missing_in_second.is_empty() {
+            Ok(DistanceAlgorithm {
+                first: first,
+                second: second,
+                logger_factory: self.logger_factory.clone(),
+                logger_name: logger_name.clone(),
+            })
+        }
+        else
+        {
+            Err(DistanceInputValidationError::DifferentTaxa {
+                missing_in_first: missing_in_first,
+                missing_in_second: missing_in_second,
+            })
+        };
+        AlgorithmLogger::new(&self.logger_factory, &logger_name)
+            .log_validation(&result, || {
+                let mut params = SLDict::new();
+                params.insert("first_nodes", first.graph().number_of_nodes());
+                params.insert("second_nodes", second.graph().number_of_nodes());
+                params.insert("number_of_taxa", first.taxa().len());
+                params
+            });
+        result
+    }
+}
+
+#[derive(Default)]
+pub struct DistanceAlgorithmFactoryBuilder {
+    logger_factory: Option<Arc<CoreLoggerFactory>>,
+}
+
+impl AlgorithmFactoryBuilder for DistanceAlgorithmFactoryBuilder {
+    type LoggerFactory = CoreLoggerFactory;
+
+    type AlgoFactory = DistanceAlgorithmFactory;
+
+    type Error = ();
+
+    fn set_logger_factory(
+        &mut self,
+        logger_factory: &Arc<Self::LoggerFactory>)
+    {
+        self.logger_factory = Some(logger_factory.clone());
+    }
+
+    fn create(self) -> Result<Self::AlgoFactory, Self::Error> {
+        let factory = DistanceAlgorithmFactory {
+            logger_factory: self.logger_factory.unwrap_or_else(build_default_logger_factory),
+            _priv: PhantomData,
+        };
+        Ok(factory)
+    }
+}
+
+fn taxa_names(taxa: &[Taxon]) -> Vec<&str> {
+    taxa.iter()
+        .map(|taxon| taxon.value().as_str())
+        .collect()
+}
+
+fn join_taxa(taxa: &[Taxon]) -> String {
+    taxa.iter()
+        .map(|taxon| taxon.value().as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl From<&DistanceInputValidationError> for SLDict {
+    fn from(value: &DistanceInputValidationError) -> Self {
+        let mut dict = SLDict::new();
+        match value {
+            DistanceInputValidationError::DifferentTaxa { missing_in_first, missing_in_second } => {
+                dict.insert("error", "DifferentTaxa");
+                dict.insert("missing_in_first", taxa_names(missing_in_first));
+                dict.insert("missing_in_second", taxa_names(missing_in_second));
+            },
+        }
+        dict
+    }
+}
+
+impl Display for DistanceInputValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DistanceInputValidationError::DifferentTaxa { missing_in_first, missing_in_second }
+                => write!(
+                    f,
+                    "networks have different taxa, missing in first: [{}], missing in second: [{}]",
+                    join_taxa(missing_in_first),
+                    join_taxa(missing_in_second)),
+        }
+    }
+}
+
+impl std::error::Error for DistanceInputValidationError { }
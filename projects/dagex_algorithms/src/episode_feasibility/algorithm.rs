@@ -1,45 +1,156 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc, time::Instant};
 
+use dagex::{
+    core::Node,
+    phylo::{PhylogeneticNetwork, PhylogeneticNetworkId},
+    raf_array::immutable_string::ImmutableString};
 use raf_multi_valued_logic::tribool::TriBool;
+use raf_structural_logging::core::CoreLoggerFactory;
 
-use crate::traits::Algorithm;
+use crate::cancellation::CancellationToken;
+use crate::logger::AlgorithmLogger;
+use crate::traits::{Algorithm, AlgorithmError, AlgorithmMetrics};
 
-use super::{EpisodeFeasabilityInput, EpisodeFeasabilityOutput, FormulaData};
+use super::{
+    estimated_memory_bytes,
+    EpisodeFeasabilityInput,
+    EpisodeFeasabilityOutput,
+    FeasibilityViolation,
+    FormulaData,
+    FormulaTrace,
+    InfeasibilityWitness,
+    SpeciesData};
 
 
 pub struct EpisodeFeasabilityAlgorithm<'a> {
     input: EpisodeFeasabilityInput<'a>,
+    trace: bool,
+    logger_factory: Arc<CoreLoggerFactory>,
+    logger_name: ImmutableString,
 }
 
 impl<'a> EpisodeFeasabilityAlgorithm<'a> {
     pub(super) fn new(
-        input: EpisodeFeasabilityInput<'a>) -> Self
+        input: EpisodeFeasabilityInput<'a>,
+        trace: bool,
+        logger_factory: Arc<CoreLoggerFactory>,
+        logger_name: ImmutableString) -> Self
     {
-        Self { input }
+        Self { input, trace, logger_factory, logger_name }
     }
-}
-
-impl<'a> Algorithm<'a> for EpisodeFeasabilityAlgorithm<'a> {
-    type Input<'b> = EpisodeFeasabilityInput<'b>;
 
-    type Output<'b> = EpisodeFeasabilityOutput<'b>;
-
-    type Error = ();
+    fn logger(&self) -> AlgorithmLogger {
+        AlgorithmLogger::new(&self.logger_factory, &self.logger_name)
+    }
 
-    fn run(self) -> Result<Self::Output<'a>, Self::Error> {
-        let episode_candidates = self.input.episode_candidates();
+    /// Runs the algorithm, returning also the number of visited species
+    /// nodes, summed over all gene networks.
+    fn run_counted(self, ct: &CancellationToken)
+        -> Result<(EpisodeFeasabilityOutput<'a>, u64), AlgorithmError<()>>
+    {
         let genes_over_species = self.input.genes_over_species();
         let species = genes_over_species.species_network();
+        let species_data = SpeciesData::new(species, self.input.episode_candidates());
         let species_root = species.root();
         let genes = genes_over_species.gene_networks();
         let mut result = HashMap::with_capacity(genes.len());
+        let mut traces = HashMap::new();
+        let mut witness = None;
+        let mut visited_nodes = 0;
         for gene_network in genes {
-            let formula_data = FormulaData::new(gene_network, species, episode_candidates);
+            if ct.is_cancelled() {
+                return Err(AlgorithmError::Cancelled);
+            }
+            let formula_data = FormulaData::new(gene_network, &species_data);
             let gene_root = gene_network.root();
-            let calc_result = formula_data.delta_down(gene_root, species_root);
+            let calc_result = if self.trace {
+                let mut trace = HashMap::new();
+                let calc_result = formula_data.delta_down_traced(gene_root, species_root, &mut trace);
+                if calc_result != TriBool::TRUE && witness.is_none() {
+                    witness = Some(find_witness(gene_network.id(), species, &trace));
+                }
+                traces.insert(gene_network.id(), trace);
+                calc_result
+            }
+            else
+            {
+                formula_data.delta_down(gene_root, species_root)
+            };
+            visited_nodes += formula_data.visited_nodes();
             result.insert(gene_network.id(), calc_result == TriBool::TRUE);
         }
-        
-        Ok(Self::Output::new(result))
+
+        let output = EpisodeFeasabilityOutput::new(result);
+        if self.trace {
+            Ok((output.with_trace(traces, witness), visited_nodes))
+        }
+        else
+        {
+            Ok((output, visited_nodes))
+        }
+    }
+}
+
+/// Looks for the first, in preorder, non candidate species node of
+/// `UNKNOWN` value, and for the reason of that value.
+fn find_witness(
+    gene_network: PhylogeneticNetworkId,
+    species: &PhylogeneticNetwork,
+    trace: &HashMap<Node, FormulaTrace>) -> InfeasibilityWitness
+{
+    let mut stack = vec![species.root()];
+    let mut violation = FeasibilityViolation::NoPlacement;
+    while let Some(node) = stack.pop() {
+        let successors = species.graph().get_successors(node);
+        let node_trace = &trace[&node];
+        if node_trace.delta_down == TriBool::UNKNOWN && !node_trace.episode_candidate {
+            let blocked = successors.iter()
+                .find(|successor| trace[successor].delta_down == TriBool::UNKNOWN);
+            violation = match blocked {
+                Some(blocked) if node_trace.epsilon != TriBool::UNKNOWN
+                    => FeasibilityViolation::BlockedArrow(node, *blocked),
+                _ => FeasibilityViolation::NotCandidateNode(node),
+            };
+            break;
+        }
+        stack.extend(successors.iter().rev());
+    }
+
+    InfeasibilityWitness {
+        gene_network: gene_network,
+        violation: violation,
+    }
+}
+
+impl<'a> Algorithm<'a> for EpisodeFeasabilityAlgorithm<'a> {
+    type Input<'b> = EpisodeFeasabilityInput<'b>;
+
+    type Output<'b> = EpisodeFeasabilityOutput<'b>;
+
+    type Error = ();
+
+    fn run_with_cancellation(self, ct: &mut CancellationToken)
+        -> Result<Self::Output<'a>, AlgorithmError<Self::Error>>
+    {
+        let logger = self.logger();
+        logger.log_run(|| self.run_counted(ct))
+            .map(|(output, _)| output)
+    }
+
+    /// Visited nodes are species nodes for which formulas got evaluated,
+    /// with repetitions, summed over all gene networks.
+    fn run_instrumented(self) -> Result<(Self::Output<'a>, AlgorithmMetrics), Self::Error> {
+        let start = Instant::now();
+        let allocations = estimated_memory_bytes(&self.input, self.trace) as u64;
+        let logger = self.logger();
+        match logger.log_run(|| self.run_counted(&CancellationToken::none())) {
+            Ok((output, visited_nodes)) => {
+                let metrics = AlgorithmMetrics::new(start.elapsed(), visited_nodes, allocations);
+                logger.log_metrics(&metrics);
+                Ok((output, metrics))
+            },
+            Err(AlgorithmError::Error(err)) => Err(err),
+            Err(AlgorithmError::Cancelled) => unreachable!("Algorithm cancelled without cancellation."),
+        }
     }
 }
@@ -1,20 +1,88 @@
-use std::{marker::PhantomData, sync::Arc};
+use core::fmt::{Display, Formatter};
+use std::{marker::PhantomData, mem::size_of, sync::Arc};
 
-use raf_structural_logging::core::CoreLoggerFactory;
+use dagex::{core::Node, phylo::{PhylogeneticNetworkId, PhylogeneticNetworkKind}};
+use raf_structural_logging::{core::CoreLoggerFactory, models::SLDict};
 
+use crate::logger::{build_default_logger_factory, build_logger_name, AlgorithmLogger};
 use crate::traits::{AlgorithmFactory, AlgorithmFactoryBuilder};
 
-use super::{EpisodeFeasabilityAlgorithm, EpisodeFeasabilityInput};
+use super::{EpisodeFeasabilityAlgorithm, EpisodeFeasabilityInput, FormulaTrace};
 
 type EFLoggerFactory = CoreLoggerFactory;
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum EpisodeFeasabilityInputValidationError {
+    /// Species network or one of gene networks is too big. Holds the limit,
+    /// see [`EpisodeFeasabilityAlgorithmFactoryBuilder::set_max_nodes`],
+    /// and the number of nodes of the biggest network.
+    GraphTooBig { limit: usize, size: usize },
+
+    /// Species network or one of gene networks is
+    /// [`PhylogeneticNetworkKind::Multifurcating`], while formulas work on
+    /// binary networks only. Holds id of the first such network, species
+    /// network checked first.
+    NotBinary(PhylogeneticNetworkId),
+}
+
 pub struct EpisodeFeasabilityAlgorithmFactory {
+    trace: bool,
+    max_nodes: usize,
+    logger_factory: Arc<EFLoggerFactory>,
     _phantom: PhantomData<()>
 }
 
 impl EpisodeFeasabilityAlgorithmFactory {
-    pub(super) fn new() -> Self {
-        Self { _phantom: PhantomData }
+    pub(super) fn new(max_nodes: usize, logger_factory: Arc<EFLoggerFactory>) -> Self {
+        Self { trace: false, max_nodes, logger_factory, _phantom: PhantomData }
+    }
+
+    /// Default, and the biggest allowed, limit of nodes.
+    pub const fn max_size() -> usize { 1 << 30 }
+
+    /// Limit of nodes of accepted species and gene networks.
+    pub fn max_nodes(&self) -> usize { self.max_nodes }
+
+    /// Upper bound of memory allocated by a run on `input`, in bytes,
+    /// excluding recursion: per species node candidate flags, per gene
+    /// network results and, with trace enabled, per gene network and
+    /// species node formula values.
+    #[inline(always)]
+    pub fn estimated_memory_bytes(&self, input: &EpisodeFeasabilityInput) -> usize {
+        estimated_memory_bytes(input, self.trace)
+    }
+
+    /// Makes created algorithms record per node formula values, see
+    /// [`EpisodeFeasabilityOutput::trace`](super::EpisodeFeasabilityOutput::trace).
+    /// Disabled by default.
+    #[must_use]
+    pub fn with_trace(mut self, value: bool) -> Self {
+        self.trace = value;
+        self
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn validate(&self, input: &EpisodeFeasabilityInput)
+        -> Result<(), EpisodeFeasabilityInputValidationError>
+    {
+        let genes_over_species = input.genes_over_species();
+        let size = genes_over_species.gene_networks()
+            .iter()
+            .map(|network| network.graph().number_of_nodes())
+            .chain([genes_over_species.species_network().graph().number_of_nodes()])
+            .max()
+            .unwrap_or_default() as usize;
+        if size > self.max_nodes {
+            return Err(EpisodeFeasabilityInputValidationError::GraphTooBig { limit: self.max_nodes, size: size });
+        }
+
+        let not_binary = [genes_over_species.species_network()].into_iter()
+            .chain(genes_over_species.gene_networks().iter().map(AsRef::as_ref))
+            .find(|network| network.kind() != PhylogeneticNetworkKind::Binary);
+        if let Some(network) = not_binary {
+            return Err(EpisodeFeasabilityInputValidationError::NotBinary(network.id()));
+        }
+        Ok(())
     }
 }
 
@@ -23,18 +91,56 @@ impl AlgorithmFactory for EpisodeFeasabilityAlgorithmFactory {
 
     type Algo<'a> = EpisodeFeasabilityAlgorithm<'a>;
 
-    type Error = ();
+    type Error = EpisodeFeasabilityInputValidationError;
 
     fn create<'a>(&mut self, input: Self::Input<'a>)
         -> Result<Self::Algo<'a>, Self::Error>
     {
-        Ok(Self::Algo::new(input))
+        let logger_name = build_logger_name("EpisodeFeasabilityAlgorithm", &input);
+        let result = self.validate(&input);
+        AlgorithmLogger::new(&self.logger_factory, &logger_name)
+            .log_validation(&result, || input_params(&input, self.trace));
+        result.map(|()| Self::Algo::new(input, self.trace, self.logger_factory.clone(), logger_name))
     }
 }
 
+fn input_params(input: &EpisodeFeasabilityInput, trace: bool) -> SLDict {
+    let genes_over_species = input.genes_over_species();
+    let mut params = SLDict::new();
+    params.insert("species_nodes", genes_over_species.species_network().graph().number_of_nodes());
+    params.insert("gene_networks", genes_over_species.gene_networks().len());
+    params.insert("episode_candidates", input.episode_candidates().len());
+    params.insert("trace", trace);
+    params
+}
+
+#[allow(clippy::cast_sign_loss)]
+pub(super) fn estimated_memory_bytes(input: &EpisodeFeasabilityInput, trace: bool) -> usize {
+    let genes_over_species = input.genes_over_species();
+    let species_nodes = genes_over_species.species_network().graph().number_of_nodes() as usize;
+    let genes = genes_over_species.gene_networks().len();
+    let mut result = species_nodes.saturating_mul(size_of::<bool>())
+        .saturating_add(genes.saturating_mul(size_of::<(PhylogeneticNetworkId, bool)>()));
+    if trace {
+        let per_gene = species_nodes.saturating_mul(size_of::<(Node, FormulaTrace)>());
+        result = result.saturating_add(genes.saturating_mul(per_gene));
+    }
+    result
+}
+
 #[derive(Default)]
 pub struct EpisodeFeasabilityAlgorithmFactoryBuilder {
-    _phantom: PhantomData<()>,
+    max_nodes: Option<usize>,
+    logger_factory: Option<Arc<EFLoggerFactory>>,
+}
+
+impl EpisodeFeasabilityAlgorithmFactoryBuilder {
+    /// Limits number of nodes of species and gene networks accepted by
+    /// created factories. Defaults to, and is capped at,
+    /// [`EpisodeFeasabilityAlgorithmFactory::max_size`].
+    pub fn set_max_nodes(&mut self, max_nodes: usize) {
+        self.max_nodes = Some(max_nodes.min(EpisodeFeasabilityAlgorithmFactory::max_size()));
+    }
 }
 
 impl AlgorithmFactoryBuilder for EpisodeFeasabilityAlgorithmFactoryBuilder {
@@ -46,11 +152,45 @@ impl AlgorithmFactoryBuilder for EpisodeFeasabilityAlgorithmFactoryBuilder {
 
     fn set_logger_factory(
         &mut self,
-        _logger_factory: &Arc<Self::LoggerFactory>)
+        logger_factory: &Arc<Self::LoggerFactory>)
     {
+        self.logger_factory = Some(logger_factory.clone());
     }
 
     fn create(self) -> Result<Self::AlgoFactory, Self::Error> {
-        Ok(Self::AlgoFactory::new())
+        let max_nodes = self.max_nodes.unwrap_or(EpisodeFeasabilityAlgorithmFactory::max_size());
+        let logger_factory = self.logger_factory.unwrap_or_else(build_default_logger_factory);
+        Ok(Self::AlgoFactory::new(max_nodes, logger_factory))
     }
 }
+
+impl From<&EpisodeFeasabilityInputValidationError> for SLDict {
+    fn from(value: &EpisodeFeasabilityInputValidationError) -> Self {
+        let mut dict = SLDict::new();
+        match value {
+            EpisodeFeasabilityInputValidationError::GraphTooBig { limit, size } => {
+                dict.insert("error", "GraphTooBig");
+                dict.insert("limit", *limit);
+                dict.insert("size", *size);
+            },
+            EpisodeFeasabilityInputValidationError::NotBinary(id) => {
+                dict.insert("error", "NotBinary");
+                dict.insert("network", i32::from(*id));
+            },
+        }
+        dict
+    }
+}
+
+impl Display for EpisodeFeasabilityInputValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EpisodeFeasabilityInputValidationError::GraphTooBig { limit, size }
+                => write!(f, "input network has {size} nodes, exceeding the maximum of {limit}"),
+            EpisodeFeasabilityInputValidationError::NotBinary(id)
+                => write!(f, "input network {} is not binary", i32::from(*id)),
+        }
+    }
+}
+
+impl std::error::Error for EpisodeFeasabilityInputValidationError { }
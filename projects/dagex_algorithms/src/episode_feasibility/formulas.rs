@@ -1,28 +1,80 @@
-use std::collections::HashSet;
+use std::{cell::Cell, collections::{HashMap, HashSet}};
 
 use dagex::{core::Node, phylo::PhylogeneticNetwork};
 use raf_multi_valued_logic::tribool::TriBool;
 
+use super::FormulaTrace;
+
+/// Species side of formulas, independent of gene networks. Built once per
+/// run and shared by [`FormulaData`] of all gene networks.
+#[derive(Debug, Clone)]
+pub struct SpeciesData<'a> {
+    species: &'a PhylogeneticNetwork,
+    episode_candidates: Vec<bool>,
+}
+
+impl<'a> SpeciesData<'a> {
+    #[allow(clippy::cast_sign_loss)]
+    pub fn new(
+        species: &'a PhylogeneticNetwork,
+        episode_candidates: &HashSet<Node>,
+    ) -> Self {
+        let mut candidates = vec![false; species.graph().number_of_nodes() as usize];
+        for node in episode_candidates {
+            if let Some(value) = candidates.get_mut(node.id() as usize) {
+                *value = true;
+            }
+        }
+        #[cfg(feature = "test-utils")]
+        SPECIES_PRECOMPUTATIONS.with(|count| count.set(count.get() + 1));
+        Self { species, episode_candidates: candidates }
+    }
+
+    /// Number of [`SpeciesData`] constructed so far on the current thread.
+    #[cfg(feature = "test-utils")]
+    pub fn constructed() -> usize {
+        SPECIES_PRECOMPUTATIONS.with(Cell::get)
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    #[inline(always)]
+    fn is_candidate(&self, node: Node) -> bool {
+        self.episode_candidates[node.id() as usize]
+    }
+}
+
+#[cfg(feature = "test-utils")]
+thread_local! {
+    /// See [`SpeciesData::constructed`].
+    static SPECIES_PRECOMPUTATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
 #[derive(Debug, Clone)]
 pub struct FormulaData<'a> {
     genes: &'a PhylogeneticNetwork,
     species: &'a PhylogeneticNetwork,
-    episode_candidates: &'a HashSet<Node>,
+    species_data: &'a SpeciesData<'a>,
+    visited_nodes: Cell<u64>,
 }
 
 impl<'a> FormulaData<'a> {
     pub fn new(
         genes: &'a PhylogeneticNetwork,
-        species: &'a PhylogeneticNetwork,
-        episode_candidates: &'a HashSet<Node>,
+        species_data: &'a SpeciesData<'a>,
     ) -> Self {
-        Self { genes, species, episode_candidates }
+        Self { genes, species: species_data.species, species_data, visited_nodes: Cell::new(0) }
+    }
+
+    /// Number of species nodes visited so far, i.e. of evaluations of
+    /// [`FormulaData::delta_down`] and [`FormulaData::delta_down_traced`].
+    pub fn visited_nodes(&self) -> u64 {
+        self.visited_nodes.get()
     }
 
     pub fn delta(&self, gene_node: Node, species_node: Node) -> TriBool {
         if self.genes.is_tree_node(gene_node) {
             let result = self.delta_star(gene_node, species_node);
-            if self.episode_candidates.contains(&species_node) {
+            if self.species_data.is_candidate(species_node) {
                 return result;
             }
             return result.and(TriBool::UNKNOWN);
@@ -32,12 +84,13 @@ impl<'a> FormulaData<'a> {
     }
 
     pub fn delta_down(&self, gene_node: Node, species_node: Node) -> TriBool {
+        self.visited_nodes.set(self.visited_nodes.get() + 1);
         let mut epsilon_result = self.epsilon(gene_node, species_node);
         if self.species.is_leaf(species_node) {
             return epsilon_result;
         }
 
-        let is_candidate = self.episode_candidates.contains(&species_node);
+        let is_candidate = self.species_data.is_candidate(species_node);
 
         let apply_is_possible = if is_candidate {
             |tri: TriBool| { tri.is_possible() }
@@ -54,6 +107,34 @@ impl<'a> FormulaData<'a> {
         epsilon_result
     }
 
+    /// Works like [`FormulaData::delta_down`], but additionally records
+    /// values calculated for each visited species node into `trace`.
+    pub fn delta_down_traced(
+        &self,
+        gene_node: Node,
+        species_node: Node,
+        trace: &mut HashMap<Node, FormulaTrace>) -> TriBool
+    {
+        self.visited_nodes.set(self.visited_nodes.get() + 1);
+        let sigma = self.sigma(gene_node, species_node);
+        let epsilon = self.epsilon_with_sigma(gene_node, species_node, sigma);
+        let is_candidate = self.species_data.is_candidate(species_node);
+        let mut delta_down = epsilon;
+        for successor in self.species.graph().get_successors(species_node) {
+            let successor_result = self.delta_down_traced(gene_node, *successor, trace);
+            let modified = if is_candidate { successor_result.is_possible() } else { successor_result };
+            delta_down = delta_down.or(modified);
+        }
+
+        trace.insert(species_node, FormulaTrace {
+            sigma: sigma,
+            epsilon: epsilon,
+            delta_down: delta_down,
+            episode_candidate: is_candidate,
+        });
+        delta_down
+    }
+
     pub fn sigma(&self, gene_node: Node, species_node: Node) -> TriBool {
         let genes = &self.genes;
         let species = &self.species;
@@ -89,7 +170,11 @@ impl<'a> FormulaData<'a> {
     }
 
     fn epsilon(&self, gene_node: Node, species_node: Node) -> TriBool {
-        let sigma_result = self.sigma(gene_node, species_node);
+        self.epsilon_with_sigma(gene_node, species_node, self.sigma(gene_node, species_node))
+    }
+
+    /// [`FormulaData::epsilon`] with already calculated `sigma_result`.
+    fn epsilon_with_sigma(&self, gene_node: Node, species_node: Node, sigma_result: TriBool) -> TriBool {
         if sigma_result == TriBool::TRUE {
             return TriBool::TRUE;
         }
@@ -2,6 +2,9 @@ use std::{collections::HashSet, hash::Hasher};
 
 use dagex::{core::Node, phylo::GenesOverSpecies};
 
+/// All gene networks of `genes_over_species` are checked against its
+/// species network in a single run. A single gene network is the
+/// degenerate case, see [`GenesOverSpecies::new_single_gene`].
 #[derive(Debug, PartialEq, Eq)]
 pub struct EpisodeFeasabilityInput<'a> {
     genes_over_species: &'a GenesOverSpecies,
@@ -27,7 +30,7 @@ impl<'a> EpisodeFeasabilityInput<'a> {
     }
 }
 
-impl<'a> core::hash::Hash for EpisodeFeasabilityInput<'a> {
+impl core::hash::Hash for EpisodeFeasabilityInput<'_> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.genes_over_species.hash(state);
 
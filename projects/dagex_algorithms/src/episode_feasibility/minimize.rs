@@ -0,0 +1,286 @@
+use core::fmt::{Display, Formatter};
+use std::{collections::HashSet, sync::Arc};
+
+use dagex::{
+    core::Node,
+    phylo::{GenesOverSpecies, PhylogeneticNetwork, PhylogeneticNetworkId, Taxon}};
+
+use crate::traits::{Algorithm, AlgorithmFactory};
+
+use super::{
+    EpisodeFeasabilityAlgorithmFactory,
+    EpisodeFeasabilityInput,
+    EpisodeFeasabilityInputValidationError};
+
+/// Error of [`minimize_infeasible`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum MinimizeInfeasibleError {
+    /// All gene networks of the input are feasible, there is nothing to
+    /// minimize.
+    Feasible,
+
+    /// Forwarded error of [`EpisodeFeasabilityAlgorithmFactory`] on the
+    /// original input.
+    InvalidInput(EpisodeFeasabilityInputValidationError),
+}
+
+/// Infeasible sub-instance found by [`minimize_infeasible`].
+#[derive(Debug)]
+pub struct MinimizedInstance {
+    genes_over_species: GenesOverSpecies,
+    episode_candidates: HashSet<Node>,
+    gene_networks: Vec<PhylogeneticNetworkId>,
+    taxa: Vec<Taxon>,
+    iterations: usize,
+    budget_exhausted: bool,
+}
+
+impl MinimizedInstance {
+    /// The minimized instance itself. Its networks get fresh ids once taxa
+    /// are pruned.
+    #[inline(always)]
+    pub fn genes_over_species(&self) -> &GenesOverSpecies {
+        &self.genes_over_species
+    }
+
+    /// Episode candidates of [`MinimizedInstance::genes_over_species`].
+    #[inline(always)]
+    pub fn episode_candidates(&self) -> &HashSet<Node> {
+        &self.episode_candidates
+    }
+
+    /// Ids of surviving gene networks in the original input, in input
+    /// order.
+    #[inline(always)]
+    pub fn gene_networks(&self) -> &[PhylogeneticNetworkId] {
+        &self.gene_networks
+    }
+
+    /// Surviving taxa of the species network, ordered alphabetically.
+    #[inline(always)]
+    pub fn taxa(&self) -> &[Taxon] {
+        &self.taxa
+    }
+
+    /// Number of algorithm runs on candidate sub-instances, excluding the
+    /// initial run on the original input.
+    #[inline(always)]
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Whether minimization stopped due to the budget, so that the result
+    /// might not be minimal.
+    #[inline(always)]
+    pub fn budget_exhausted(&self) -> bool {
+        self.budget_exhausted
+    }
+}
+
+/// Greedily shrinks infeasible `input` to a smaller sub-instance that is
+/// still infeasible. Gene networks are tried for removal in input order,
+/// then species taxa in alphabetical order, restricting species and gene
+/// networks via [`PhylogeneticNetwork::restrict_to_taxa`]. Each removal is
+/// kept only if the algorithm still reports some gene network infeasible.
+/// Passes repeat until nothing can be removed or `max_iterations`
+/// algorithm runs were made. The result is deterministic given the same
+/// input ordering.
+///
+/// # Notes
+/// Restriction renumbers species nodes. A restricted node is an episode
+/// candidate if its cluster, i.e. taxa of its descendant leaves, equals
+/// the restricted cluster of some original candidate. Since every kept
+/// removal is verified by a run, the result is infeasible regardless.
+///
+/// # Errors
+/// For concrete errors see [`MinimizeInfeasibleError`] docs.
+///
+/// # Panics
+/// Only when rebuilding an already verified sub-instance fails, which
+/// indicates a bug.
+pub fn minimize_infeasible(
+    input: &EpisodeFeasabilityInput,
+    factory: &mut EpisodeFeasabilityAlgorithmFactory,
+    max_iterations: usize) -> Result<MinimizedInstance, MinimizeInfeasibleError>
+{
+    match is_infeasible(factory, input.genes_over_species(), input.episode_candidates()) {
+        Ok(true) => { },
+        Ok(false) => return Err(MinimizeInfeasibleError::Feasible),
+        Err(err) => return Err(MinimizeInfeasibleError::InvalidInput(err)),
+    }
+
+    let minimizer = Minimizer::new(input);
+    let mut genes: Vec<usize> = (0..minimizer.genes.len()).collect();
+    let mut taxa = minimizer.all_taxa.clone();
+    let mut iterations = 0;
+    let mut budget_exhausted = false;
+    let mut changed = true;
+    'passes: while changed {
+        changed = false;
+
+        let mut idx = 0;
+        while idx < genes.len() && genes.len() > 1 {
+            if iterations == max_iterations {
+                budget_exhausted = true;
+                break 'passes;
+            }
+            iterations += 1;
+            let mut candidate = genes.clone();
+            candidate.remove(idx);
+            if minimizer.is_infeasible(factory, &candidate, &taxa) {
+                genes = candidate;
+                changed = true;
+            }
+            else
+            {
+                idx += 1;
+            }
+        }
+
+        let mut idx = 0;
+        while idx < taxa.len() && taxa.len() > 1 {
+            if iterations == max_iterations {
+                budget_exhausted = true;
+                break 'passes;
+            }
+            iterations += 1;
+            let mut candidate = taxa.clone();
+            candidate.remove(idx);
+            let kept_genes = minimizer.build(&genes, &candidate)
+                .filter(|instance| is_infeasible(factory, &instance.0, &instance.1) == Ok(true))
+                .map(|instance| instance.2);
+            if let Some(kept_genes) = kept_genes {
+                genes = kept_genes;
+                taxa = candidate;
+                changed = true;
+            }
+            else
+            {
+                idx += 1;
+            }
+        }
+    }
+
+    let (genes_over_species, episode_candidates, genes) = minimizer.build(&genes, &taxa)
+        .expect("Accepted instance has to be valid.");
+    Ok(MinimizedInstance {
+        genes_over_species: genes_over_species,
+        episode_candidates: episode_candidates,
+        gene_networks: genes.iter().map(|idx| minimizer.genes[*idx].id()).collect(),
+        taxa: taxa,
+        iterations: iterations,
+        budget_exhausted: budget_exhausted,
+    })
+}
+
+fn is_infeasible(
+    factory: &mut EpisodeFeasabilityAlgorithmFactory,
+    genes_over_species: &GenesOverSpecies,
+    episode_candidates: &HashSet<Node>) -> Result<bool, EpisodeFeasabilityInputValidationError>
+{
+    let input = EpisodeFeasabilityInput::new(genes_over_species, episode_candidates);
+    let output = factory.create(input)?
+        .run()
+        .expect("Episode feasibility has no domain errors.");
+    Ok(!output.all_feasible())
+}
+
+/// Sub-instance: gene networks, episode candidates and indices of
+/// non-empty gene networks.
+type Instance = (GenesOverSpecies, HashSet<Node>, Vec<usize>);
+
+struct Minimizer<'a> {
+    input: &'a EpisodeFeasabilityInput<'a>,
+    genes: Vec<Arc<PhylogeneticNetwork>>,
+    all_taxa: Vec<Taxon>,
+    candidate_clusters: Vec<HashSet<Taxon>>,
+}
+
+impl<'a> Minimizer<'a> {
+    fn new(input: &'a EpisodeFeasabilityInput<'a>) -> Self {
+        let species = input.genes_over_species().species_network();
+        let mut all_taxa: Vec<Taxon> = species.taxa().values().cloned().collect();
+        all_taxa.sort_by(|left, right| left.value().as_str().cmp(right.value().as_str()));
+        let mut candidates: Vec<Node> = input.episode_candidates().iter().copied().collect();
+        candidates.sort_unstable_by_key(Node::id);
+        let candidate_clusters = candidates.into_iter()
+            .map(|node| cluster(species, node))
+            .collect();
+        Self {
+            input: input,
+            genes: input.genes_over_species().gene_networks().to_vec(),
+            all_taxa: all_taxa,
+            candidate_clusters: candidate_clusters,
+        }
+    }
+
+    fn is_infeasible(
+        &self,
+        factory: &mut EpisodeFeasabilityAlgorithmFactory,
+        genes: &[usize],
+        taxa: &[Taxon]) -> bool
+    {
+        self.build(genes, taxa)
+            .is_some_and(|instance| is_infeasible(factory, &instance.0, &instance.1) == Ok(true))
+    }
+
+    /// Builds sub-instance with `genes` restricted to `taxa`. Gene networks
+    /// without any of `taxa` are dropped. `None` if no gene network is left.
+    fn build(&self, genes: &[usize], taxa: &[Taxon]) -> Option<Instance> {
+        let species = self.input.genes_over_species().species_network();
+        if taxa.len() == self.all_taxa.len() {
+            let species = PhylogeneticNetwork::from_dto(&species.into_dto()).ok()?;
+            let gene_networks = genes.iter().map(|idx| self.genes[*idx].clone()).collect();
+            let genes_over_species = GenesOverSpecies::from_shared_networks(gene_networks, species).ok()?;
+            return Some((genes_over_species, self.input.episode_candidates().clone(), genes.to_vec()));
+        }
+
+        let taxa: HashSet<Taxon> = taxa.iter().cloned().collect();
+        let species = species.restrict_to_taxa(&taxa).ok()?;
+        let mut kept = Vec::with_capacity(genes.len());
+        let mut gene_networks = Vec::with_capacity(genes.len());
+        for idx in genes {
+            if let Ok(network) = self.genes[*idx].restrict_to_taxa(&taxa) {
+                kept.push(*idx);
+                gene_networks.push(Arc::new(network));
+            }
+        }
+        if gene_networks.is_empty() {
+            return None;
+        }
+
+        let restricted_clusters: Vec<HashSet<Taxon>> = self.candidate_clusters.iter()
+            .map(|cluster| cluster.intersection(&taxa).cloned().collect())
+            .collect();
+        let episode_candidates = species.graph().iter_nodes()
+            .filter(|node| {
+                let node_cluster = cluster(&species, *node);
+                !node_cluster.is_empty() && restricted_clusters.contains(&node_cluster)
+            })
+            .collect();
+        let genes_over_species = GenesOverSpecies::from_shared_networks(gene_networks, species).ok()?;
+        Some((genes_over_species, episode_candidates, kept))
+    }
+}
+
+/// Taxa of `node` and its descendants.
+fn cluster(network: &PhylogeneticNetwork, node: Node) -> HashSet<Taxon> {
+    let taxa = network.taxa();
+    [node].into_iter()
+        .chain(network.graph().iter_descendants(node))
+        .filter_map(|node| taxa.get(&node).cloned())
+        .collect()
+}
+
+impl Display for MinimizeInfeasibleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MinimizeInfeasibleError::Feasible
+                => f.write_str("all gene networks are feasible"),
+            MinimizeInfeasibleError::InvalidInput(err)
+                => write!(f, "invalid input: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for MinimizeInfeasibleError { }
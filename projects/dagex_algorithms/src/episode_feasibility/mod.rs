@@ -3,9 +3,19 @@ mod output;
 mod algorithm;
 mod factory;
 mod formulas;
+mod minimize;
 
-use formulas::FormulaData;
+use formulas::{FormulaData, SpeciesData};
 pub use input::*;
 pub use output::*;
 pub use algorithm::*;
 pub use factory::*;
+pub use minimize::*;
+
+/// Number of times the species side of formulas was precomputed on the
+/// current thread. Exposed for tests only.
+#[cfg(feature = "test-utils")]
+#[doc(hidden)]
+pub fn species_precomputations() -> usize {
+    SpeciesData::constructed()
+}
@@ -1,29 +1,117 @@
 use std::{collections::HashMap, hash::Hasher, marker::PhantomData};
 
-use dagex::phylo::PhylogeneticNetworkId;
+use dagex::{core::Node, phylo::PhylogeneticNetworkId};
+use raf_multi_valued_logic::tribool::TriBool;
+
+/// Formula values calculated for the root of a gene network placed at
+/// a single species node.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FormulaTrace {
+    pub sigma: TriBool,
+
+    /// Combined `sigma` and `delta` value of the node itself.
+    pub epsilon: TriBool,
+
+    /// Value for the whole subtree rooted at the node. The gene network is
+    /// feasible if it is `TRUE` at the species root.
+    pub delta_down: TriBool,
+
+    /// Whether the node is an episode candidate.
+    pub episode_candidate: bool,
+}
+
+/// Constraint preventing a gene network from being feasible.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FeasibilityViolation {
+    /// Gene network fits at the species node only as an episode, but the
+    /// node is not an episode candidate.
+    NotCandidateNode(Node),
+
+    /// Gene network fits below the arrow's target only as an episode. The
+    /// arrow's source is not an episode candidate, so the arrow blocks it.
+    BlockedArrow(Node, Node),
+
+    /// Gene network cannot be placed at any species node.
+    NoPlacement,
+}
+
+/// First violated constraint of an infeasible instance.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct InfeasibilityWitness {
+    /// First infeasible gene network, in input order.
+    pub gene_network: PhylogeneticNetworkId,
+
+    /// Violation at the first, in preorder, species node which is not an
+    /// episode candidate and has `UNKNOWN` value.
+    pub violation: FeasibilityViolation,
+}
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct EpisodeFeasabilityOutput<'a> {
     result: HashMap<PhylogeneticNetworkId, bool>,
+    trace: Option<HashMap<PhylogeneticNetworkId, HashMap<Node, FormulaTrace>>>,
+    witness: Option<InfeasibilityWitness>,
     phantom: PhantomData<&'a ()>,
 }
 
-impl<'a> EpisodeFeasabilityOutput<'a> {
+impl EpisodeFeasabilityOutput<'_> {
     pub fn new(
         result: HashMap<PhylogeneticNetworkId, bool>) -> Self
     {
         Self {
             result,
+            trace: None,
+            witness: None,
             phantom: PhantomData,
         }
     }
-    
+
+    pub(super) fn with_trace(
+        mut self,
+        trace: HashMap<PhylogeneticNetworkId, HashMap<Node, FormulaTrace>>,
+        witness: Option<InfeasibilityWitness>) -> Self
+    {
+        self.trace = Some(trace);
+        self.witness = witness;
+        self
+    }
+
+    /// Feasibility of each gene network, keyed by its id.
     pub fn result(&self) -> &HashMap<PhylogeneticNetworkId, bool> {
         &self.result
     }
+
+    /// Feasibility of gene network `id`. `None` if there was no such
+    /// gene network in the input.
+    pub fn is_feasible(&self, id: PhylogeneticNetworkId) -> Option<bool> {
+        self.result.get(&id).copied()
+    }
+
+    /// Aggregate verdict, i.e. whether all gene networks are feasible.
+    pub fn all_feasible(&self) -> bool {
+        self.result.values().all(|value| *value)
+    }
+
+    /// Number of infeasible gene networks.
+    pub fn infeasible_count(&self) -> usize {
+        self.result.values().filter(|value| !**value).count()
+    }
+
+    /// Per species node formula values of gene network `id`. Available
+    /// only if tracing was enabled, see
+    /// [`EpisodeFeasabilityAlgorithmFactory::with_trace`](super::EpisodeFeasabilityAlgorithmFactory::with_trace).
+    pub fn trace(&self, id: PhylogeneticNetworkId) -> Option<&HashMap<Node, FormulaTrace>> {
+        self.trace.as_ref().and_then(|trace| trace.get(&id))
+    }
+
+    /// Points to the first violated constraint. `None` if all gene
+    /// networks are feasible or tracing was disabled.
+    pub fn explain_infeasibility(&self) -> Option<InfeasibilityWitness> {
+        self.witness.clone()
+    }
 }
 
-impl<'a> core::hash::Hash for EpisodeFeasabilityOutput<'a> {
+impl core::hash::Hash for EpisodeFeasabilityOutput<'_> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.result.len().hash(state);
 
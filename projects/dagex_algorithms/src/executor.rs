@@ -0,0 +1,233 @@
+//! Runs a single algorithm over many inputs on a pool of worker threads.
+use core::fmt::{Debug, Display, Formatter};
+use std::{
+    any::Any,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Mutex,
+        PoisonError},
+    thread};
+
+use crate::{
+    cancellation::CancellationToken,
+    traits::{Algorithm, AlgorithmError, AlgorithmFactory, AlgorithmFactoryBuilder}};
+
+/// Output of algorithm created by factory `F` for inputs of lifetime `'a`.
+pub type BatchOutput<'a, F> = <<F as AlgorithmFactory>::Algo<'a> as Algorithm<'a>>::Output<'a>;
+
+/// Result of a single item of [`BatchExecutor::run`].
+pub type BatchItemResult<'a, F> = Result<BatchOutput<'a, F>, BatchItemError<<F as AlgorithmFactory>::Error>>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BatchItemError<E> {
+    /// Factory rejected the input.
+    Validation(E),
+
+    /// Algorithm failed. Holds [`Debug`] representation of its error.
+    Algorithm(String),
+
+    /// Executor got cancelled before, or while, the item was processed.
+    Cancelled,
+
+    /// Factory or algorithm panicked. Holds the panic message, if it was
+    /// a string.
+    Panicked(String),
+}
+
+/// Runs `create` and `run` of a single factory for each input of a batch.
+/// Factory is shared between workers, but held only while creating
+/// algorithms, so runs are concurrent.
+pub struct BatchExecutor<F: AlgorithmFactory> {
+    factory: Mutex<F>,
+    threads: usize,
+    cancellation: CancellationToken,
+}
+
+impl<F: AlgorithmFactory> BatchExecutor<F> {
+    /// Creates new [`BatchExecutor`] with factory created by `builder`,
+    /// running items on `threads` worker threads. Zero means items run on
+    /// the calling thread.
+    ///
+    /// # Errors
+    /// Forwarded [`AlgorithmFactoryBuilder::Error`].
+    pub fn new<B>(builder: B, threads: usize) -> Result<Self, B::Error>
+        where B: AlgorithmFactoryBuilder<AlgoFactory=F>
+    {
+        Ok(Self {
+            factory: Mutex::new(builder.create()?),
+            threads: threads,
+            cancellation: CancellationToken::none(),
+        })
+    }
+
+    /// Sets cancellation token. Once it is cancelled no more items are
+    /// issued to workers and they are reported as
+    /// [`BatchItemError::Cancelled`]. Items in progress are cancelled
+    /// through [`Algorithm::run_with_cancellation`].
+    #[must_use]
+    pub fn with_cancellation(mut self, ct: CancellationToken) -> Self {
+        self.cancellation = ct;
+        self
+    }
+
+    #[inline(always)]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Runs the algorithm on each of `inputs`. Failures of single items
+    /// don't abort the batch. Results are in the order of `inputs`.
+    #[inline(always)]
+    pub fn run<'a>(&self, inputs: Vec<F::Input<'a>>) -> Vec<BatchItemResult<'a, F>>
+        where F: Send,
+              F::Input<'a>: Send,
+              F::Error: Send,
+              BatchOutput<'a, F>: Send
+    {
+        self.run_with_progress(inputs, |_, _| { })
+    }
+
+    /// Same as [`BatchExecutor::run`], additionally calling
+    /// `progress(completed, total)` after each finished item. It is always
+    /// called on the calling thread.
+    pub fn run_with_progress<'a, P>(&self, inputs: Vec<F::Input<'a>>, mut progress: P)
+        -> Vec<BatchItemResult<'a, F>>
+        where F: Send,
+              F::Input<'a>: Send,
+              F::Error: Send,
+              BatchOutput<'a, F>: Send,
+              P: FnMut(usize, usize)
+    {
+        let total = inputs.len();
+        let mut results: Vec<Option<BatchItemResult<'a, F>>> = Vec::with_capacity(total);
+        results.resize_with(total, || None);
+        let mut completed = 0;
+        let mut complete = |index: usize, result: BatchItemResult<'a, F>| {
+            results[index] = Some(result);
+            completed += 1;
+            progress(completed, total);
+        };
+
+        if self.threads == 0 {
+            for (index, input) in inputs.into_iter().enumerate() {
+                complete(index, self.run_item(input));
+            }
+        }
+        else
+        {
+            let (job_sender, job_receiver) = channel::<(usize, F::Input<'a>)>();
+            let (result_sender, result_receiver) = channel();
+            let job_receiver = Mutex::new(job_receiver);
+            thread::scope(|scope| {
+                for _ in 0..self.threads {
+                    let jobs = &job_receiver;
+                    let results = result_sender.clone();
+                    scope.spawn(move || self.worker_loop(jobs, &results));
+                }
+                drop(result_sender);
+
+                let mut pending = inputs.into_iter().enumerate();
+                let mut in_flight = 0;
+                loop {
+                    // At most one item per worker is issued, so that
+                    // cancellation stops new work quickly.
+                    while in_flight < self.threads && !self.cancellation.is_cancelled() {
+                        let Some(job) = pending.next() else { break; };
+                        if job_sender.send(job).is_err() {
+                            break;
+                        }
+                        in_flight += 1;
+                    }
+                    if in_flight == 0 {
+                        break;
+                    }
+                    let Ok((index, result)) = result_receiver.recv() else { break; };
+                    in_flight -= 1;
+                    complete(index, result);
+                }
+                drop(job_sender);
+                for (index, _) in pending {
+                    complete(index, Err(BatchItemError::Cancelled));
+                }
+            });
+        }
+
+        results.into_iter()
+            .map(|result| result.unwrap_or(Err(BatchItemError::Cancelled)))
+            .collect()
+    }
+
+    fn worker_loop<'a>(
+        &self,
+        jobs: &Mutex<Receiver<(usize, F::Input<'a>)>>,
+        results: &Sender<(usize, BatchItemResult<'a, F>)>)
+    {
+        loop {
+            let job = jobs.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .recv();
+            let Ok((index, input)) = job else { return; };
+            if results.send((index, self.run_item(input))).is_err() {
+                return;
+            }
+        }
+    }
+
+    fn run_item<'a>(&self, input: F::Input<'a>) -> BatchItemResult<'a, F> {
+        if self.cancellation.is_cancelled() {
+            return Err(BatchItemError::Cancelled);
+        }
+        let run = || {
+            let algorithm = {
+                // Panic in another item's `create` doesn't make the factory
+                // unusable, its `create` takes inputs one by one.
+                let mut factory = self.factory.lock().unwrap_or_else(PoisonError::into_inner);
+                factory.create(input).map_err(BatchItemError::Validation)?
+            };
+            let mut ct = self.cancellation.clone();
+            algorithm.run_with_cancellation(&mut ct)
+                .map_err(|err| match err {
+                    AlgorithmError::Error(err) => BatchItemError::Algorithm(format!("{err:?}")),
+                    AlgorithmError::Cancelled => BatchItemError::Cancelled,
+                })
+        };
+        catch_unwind(AssertUnwindSafe(run))
+            .unwrap_or_else(|payload| Err(BatchItemError::Panicked(panic_message(&*payload))))
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    }
+    else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    }
+    else
+    {
+        String::new()
+    }
+}
+
+impl<E: Display> Display for BatchItemError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BatchItemError::Validation(_) => f.write_str("batch item failed validation"),
+            BatchItemError::Algorithm(err) => write!(f, "batch item failed: {err}"),
+            BatchItemError::Cancelled => f.write_str("batch cancelled"),
+            BatchItemError::Panicked(message) => write!(f, "batch item panicked: {message}"),
+        }
+    }
+}
+
+impl<E> std::error::Error for BatchItemError<E>
+    where E: std::error::Error + 'static
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BatchItemError::Validation(err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use core::fmt::{Display, Formatter};
+use std::{marker::PhantomData, sync::Arc};
+
+use dagex::raf_array::immutable_string::ImmutableString;
+use raf_structural_logging::{core::CoreLoggerFactory, models::SLDict};
+use dagex::core::{DirectedGraph, Node, PackedOptionNode};
+
+use crate::cancellation::CancellationToken;
+use crate::logger::{build_default_logger_factory, build_logger_name, graph_params, AlgorithmLogger};
+use crate::traits::{Algorithm, AlgorithmError, AlgorithmFactory, AlgorithmFactoryBuilder};
+use crate::validated_dag::{DagValidationError, ValidatedRootedDag};
+
+/// Computes level of a rooted, acyclic graph, i.e. the maximum number of
+/// reticulation nodes (nodes of in-degree at least 2) in a biconnected
+/// component of the underlying undirected graph. A reticulation node is
+/// counted in the component containing its incoming arrows.
+pub struct LevelAlgorithm<'a> {
+    graph: &'a DirectedGraph,
+    discovery: Vec<i32>,
+    low: Vec<i32>,
+    logger_factory: Arc<CoreLoggerFactory>,
+    logger_name: ImmutableString,
+}
+
+pub struct LevelResult<'a> {
+    level: usize,
+    component_reticulations: Vec<usize>,
+    phantom: PhantomData<&'a()>,
+}
+
+impl LevelResult<'_> {
+    pub fn level(&self) -> usize { self.level }
+
+    /// Number of reticulation nodes of each biconnected component, in order
+    /// of discovery. Bridges form single-arrow components.
+    pub fn component_reticulations(&self) -> &[usize] { &self.component_reticulations }
+}
+
+/// Number of search steps between consecutive cancellation checks.
+const CANCELLATION_CHECK_INTERVAL: usize = 1024;
+
+struct Frame {
+    node: Node,
+    parent: PackedOptionNode,
+    position: usize,
+}
+
+impl LevelAlgorithm<'_> {
+    #[inline(always)]
+    #[allow(clippy::cast_sign_loss)]
+    fn idx(node: Node) -> usize {
+        node.id() as usize
+    }
+
+    /// Returns `position`-th neighbour of `node` in the undirected sense,
+    /// together with the arrow connecting them in its original direction.
+    fn neighbour(&self, node: Node, position: usize) -> Option<(Node, (Node, Node))> {
+        let successors = self.graph.get_successors(node);
+        if let Some(successor) = successors.get(position) {
+            return Some((*successor, (node, *successor)));
+        }
+        let predecessors = self.graph.get_predecessors(node);
+        predecessors.get(position - successors.len())
+            .map(|predecessor| (*predecessor, (*predecessor, node)))
+    }
+
+    fn is_counted_reticulation_arrow(&self, source: Node, target: Node) -> bool {
+        let predecessors = self.graph.get_predecessors(target);
+        predecessors.len() >= 2 && predecessors[0] == source
+    }
+
+    /// Iterative Hopcroft-Tarjan biconnected components search. Returns
+    /// `false` if `ct` got cancelled.
+    fn scan(
+        &mut self,
+        start: Node,
+        component_reticulations: &mut Vec<usize>,
+        ct: &CancellationToken) -> bool
+    {
+        let mut steps = 0;
+        let mut time = 0;
+        let mut arrows = Vec::<(Node, Node)>::new();
+        let mut stack = vec![Frame { node: start, parent: PackedOptionNode::NONE, position: 0 }];
+        self.discovery[Self::idx(start)] = time;
+        self.low[Self::idx(start)] = time;
+
+        while let Some(frame) = stack.last_mut() {
+            steps += 1;
+            if steps % CANCELLATION_CHECK_INTERVAL == 0 && ct.is_cancelled() {
+                return false;
+            }
+            let node = frame.node;
+            let parent = frame.parent.to_option();
+            if let Some((neighbour, arrow)) = self.neighbour(node, frame.position) {
+                frame.position += 1;
+                let neighbour_discovery = self.discovery[Self::idx(neighbour)];
+                if neighbour_discovery == -1 {
+                    time += 1;
+                    self.discovery[Self::idx(neighbour)] = time;
+                    self.low[Self::idx(neighbour)] = time;
+                    arrows.push(arrow);
+                    stack.push(Frame { node: neighbour, parent: PackedOptionNode::from(node), position: 0 });
+                }
+                else if Some(neighbour) != parent
+                    && neighbour_discovery < self.discovery[Self::idx(node)]
+                {
+                    arrows.push(arrow);
+                    let low = &mut self.low[Self::idx(node)];
+                    *low = (*low).min(neighbour_discovery);
+                }
+                continue;
+            }
+
+            stack.pop();
+            let Some(parent) = parent else {
+                continue;
+            };
+            let node_low = self.low[Self::idx(node)];
+            let parent_low = &mut self.low[Self::idx(parent)];
+            *parent_low = (*parent_low).min(node_low);
+            if node_low >= self.discovery[Self::idx(parent)] {
+                let mut reticulations = 0;
+                while let Some((source, target)) = arrows.pop() {
+                    if self.is_counted_reticulation_arrow(source, target) {
+                        reticulations += 1;
+                    }
+                    let tree_arrow = (source == parent && target == node)
+                        || (source == node && target == parent);
+                    if tree_arrow {
+                        break;
+                    }
+                }
+                component_reticulations.push(reticulations);
+            }
+        }
+        true
+    }
+}
+
+impl<'a> LevelAlgorithm<'a> {
+    fn calculate(mut self, ct: &mut CancellationToken)
+        -> Result<LevelResult<'a>, AlgorithmError<()>>
+    {
+        if ct.is_cancelled() {
+            return Err(AlgorithmError::Cancelled);
+        }
+        let root = self.graph.root().unwrap();
+        let mut component_reticulations = Vec::new();
+        if !self.scan(root, &mut component_reticulations, ct) {
+            return Err(AlgorithmError::Cancelled);
+        }
+        let level = component_reticulations.iter().copied().max().unwrap_or(0);
+        Ok(LevelResult {
+            level: level,
+            component_reticulations: component_reticulations,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<'a> Algorithm<'a> for LevelAlgorithm<'a> {
+    type Input<'b> = &'b DirectedGraph;
+
+    type Output<'b> = LevelResult<'b>;
+
+    type Error = ();
+
+    fn run_with_cancellation(self, ct: &mut CancellationToken)
+        -> Result<Self::Output<'a>, AlgorithmError<Self::Error>>
+    {
+        let logger = AlgorithmLogger::new(&self.logger_factory, &self.logger_name);
+        logger.log_run(|| self.calculate(ct))
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelInputValidationError {
+    /// Input is not rooted.
+    InputNotRooted,
+
+    /// Input is not acyclic.
+    InputNotAcyclic,
+
+    /// Graph is too big. This algorithm allocates memory linear in the
+    /// number of nodes. For max limit see [`LevelAlgorithmFactory::max_size`].
+    GraphTooBig,
+}
+
+pub struct LevelAlgorithmFactory {
+    logger_factory: Arc<CoreLoggerFactory>,
+    _priv: PhantomData<()>,
+}
+
+impl LevelAlgorithmFactory {
+    pub const fn max_size() -> usize { 1 << 30 }
+
+    /// Same as [`AlgorithmFactory::create`], but skips validation of
+    /// already validated `input`.
+    ///
+    /// # Errors
+    /// [`LevelInputValidationError::GraphTooBig`] only.
+    pub fn create_validated<'a>(&mut self, input: &ValidatedRootedDag<'a>)
+        -> Result<LevelAlgorithm<'a>, LevelInputValidationError>
+    {
+        let graph = input.graph();
+        self.logged(graph, |logger_name| self.build(graph, logger_name))
+    }
+
+    fn logged<'a>(
+        &self,
+        input: &'a DirectedGraph,
+        create: impl FnOnce(ImmutableString) -> Result<LevelAlgorithm<'a>, LevelInputValidationError>)
+        -> Result<LevelAlgorithm<'a>, LevelInputValidationError>
+    {
+        let logger_name = build_logger_name("LevelAlgorithm", input);
+        let result = create(logger_name.clone());
+        AlgorithmLogger::new(&self.logger_factory, &logger_name)
+            .log_validation(&result, || graph_params(input));
+        result
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn build<'a>(&self, input: &'a DirectedGraph, logger_name: ImmutableString)
+        -> Result<LevelAlgorithm<'a>, LevelInputValidationError>
+    {
+        let no = input.number_of_nodes() as usize;
+        if no > Self::max_size() {
+            return Err(LevelInputValidationError::GraphTooBig);
+        }
+
+        Ok(LevelAlgorithm {
+            graph: input,
+            discovery: vec![-1; no],
+            low: vec![0; no],
+            logger_factory: self.logger_factory.clone(),
+            logger_name: logger_name,
+        })
+    }
+}
+
+impl From<DagValidationError> for LevelInputValidationError {
+    fn from(value: DagValidationError) -> Self {
+        match value {
+            DagValidationError::InputNotRooted => Self::InputNotRooted,
+            DagValidationError::InputNotAcyclic => Self::InputNotAcyclic,
+        }
+    }
+}
+
+impl AlgorithmFactory for LevelAlgorithmFactory {
+    type Input<'a> = &'a DirectedGraph;
+
+    type Algo<'a> = LevelAlgorithm<'a>;
+
+    type Error = LevelInputValidationError;
+
+    fn create<'a>(&mut self, input: Self::Input<'a>)
+        -> Result<Self::Algo<'a>, Self::Error>
+    {
+        self.logged(input, |logger_name| {
+            ValidatedRootedDag::validate(input)?;
+            self.build(input, logger_name)
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct LevelAlgorithmFactoryBuilder {
+    logger_factory: Option<Arc<CoreLoggerFactory>>,
+}
+
+impl AlgorithmFactoryBuilder for LevelAlgorithmFactoryBuilder {
+    type LoggerFactory = CoreLoggerFactory;
+
+    type AlgoFactory = LevelAlgorithmFactory;
+
+    type Error = ();
+
+    fn set_logger_factory(
+        &mut self,
+        logger_factory: &Arc<Self::LoggerFactory>)
+    {
+        self.logger_factory = Some(logger_factory.clone());
+    }
+
+    fn create(self) -> Result<Self::AlgoFactory, Self::Error> {
+        let factory = LevelAlgorithmFactory {
+            logger_factory: self.logger_factory.unwrap_or_else(build_default_logger_factory),
+            _priv: PhantomData,
+        };
+        Ok(factory)
+    }
+}
+
+impl From<&LevelInputValidationError> for SLDict {
+    fn from(value: &LevelInputValidationError) -> Self {
+        let mut dict = SLDict::new();
+        match value {
+            LevelInputValidationError::InputNotRooted => {
+                dict.insert("error", "InputNotRooted");
+            },
+            LevelInputValidationError::InputNotAcyclic => {
+                dict.insert("error", "InputNotAcyclic");
+            },
+            LevelInputValidationError::GraphTooBig => {
+                dict.insert("error", "GraphTooBig");
+                dict.insert("limit", LevelAlgorithmFactory::max_size());
+            },
+        }
+        dict
+    }
+}
+
+impl Display for LevelInputValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LevelInputValidationError::InputNotRooted
+                => f.write_str("input graph is not rooted"),
+            LevelInputValidationError::InputNotAcyclic
+                => f.write_str("input graph is not acyclic"),
+            LevelInputValidationError::GraphTooBig
+                => write!(f, "input graph exceeds the maximum of {} nodes", LevelAlgorithmFactory::max_size()),
+        }
+    }
+}
+
+impl std::error::Error for LevelInputValidationError { }
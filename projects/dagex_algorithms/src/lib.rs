@@ -8,6 +8,15 @@
     clippy::module_name_repetitions,
 )]
 pub mod traits;
+pub mod cancellation;
+pub mod containment;
 pub mod depth;
+pub mod distance;
+pub mod level;
 pub mod episode_feasibility;
+pub mod executor;
 pub mod logger;
+pub mod pipeline;
+pub mod reconciliation;
+pub mod scc;
+pub mod validated_dag;
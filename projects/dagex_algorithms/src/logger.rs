@@ -1,11 +1,15 @@
-use std::{hash::Hasher, sync::{Arc, OnceLock}};
+use core::fmt::Debug;
+use std::{hash::Hasher, sync::{Arc, OnceLock}, time::{Duration, Instant}};
 
-use dagex::raf_array::immutable_string::ImmutableString;
+use dagex::{core::DirectedGraph, raf_array::immutable_string::ImmutableString};
 use raf_structural_logging::{
-    core::{CoreLoggerFactory, CoreLoggerFactoryBuilder},
-    traits::StructuralLoggerFactoryBuilder};
+    core::{CoreLogger, CoreLoggerFactory, CoreLoggerFactoryBuilder},
+    models::{LogDataHolder, LogLevel, SLDict},
+    traits::{StructuralLogger, StructuralLoggerFactory, StructuralLoggerFactoryBuilder}};
 use raf_structural_logging_console::ConsoleHandler;
 
+use crate::traits::{AlgorithmError, AlgorithmMetrics};
+
 static DEFAULT_LOGGER_FACTORY: OnceLock<Arc<CoreLoggerFactory>> = OnceLock::new();
 
 pub fn build_default_logger_factory() -> Arc<CoreLoggerFactory> {
@@ -36,7 +40,7 @@ pub fn build_logger_name<T: core::hash::Hash>(prefix: &str, data: &T) -> Immutab
     let nobuffer = &mut buffer[(prefix_len+1)..INLINE_SIZE];
     let mut offset = 0;
     loop {
-        nobuffer[offset] = (value % 10) as u8;
+        nobuffer[offset] = b'0' + (value % 10) as u8;
         offset += 1;
         value /= 10;
         if value == 0 {
@@ -48,3 +52,85 @@ pub fn build_logger_name<T: core::hash::Hash>(prefix: &str, data: &T) -> Immutab
     let txt = unsafe { core::str::from_utf8_unchecked(complete_slice) };
     ImmutableString::new(txt).unwrap()
 }
+
+/// Writes entries of a single algorithm instance: validation of its input
+/// by the factory, and its runs.
+pub(crate) struct AlgorithmLogger {
+    logger: CoreLogger,
+}
+
+impl AlgorithmLogger {
+    pub(crate) fn new(logger_factory: &CoreLoggerFactory, name: &ImmutableString) -> Self {
+        Self { logger: logger_factory.create(name.as_str()) }
+    }
+
+    /// Logs outcome of input validation. On success `input` describes the
+    /// accepted input, on failure the error is logged instead.
+    pub(crate) fn log_validation<T, E>(&self, result: &Result<T, E>, input: impl FnOnce() -> SLDict)
+        where for<'e> SLDict: From<&'e E>
+    {
+        match result {
+            Ok(_) => {
+                let mut params = SLDict::new();
+                params.insert("input", input());
+                self.log(LogLevel::Info, "Created for {input}", params);
+            },
+            Err(err) => {
+                self.log(LogLevel::Warning, "Input rejected with {error}", SLDict::from(err));
+            },
+        }
+    }
+
+    /// Logs start of `run`, and its end with elapsed time.
+    pub(crate) fn log_run<T, E: Debug>(&self, run: impl FnOnce() -> Result<T, AlgorithmError<E>>)
+        -> Result<T, AlgorithmError<E>>
+    {
+        self.log(LogLevel::Info, "Run started", SLDict::new());
+        let start = Instant::now();
+        let result = run();
+        let mut params = SLDict::new();
+        params.insert("elapsed_us", elapsed_us(start.elapsed()));
+        match &result {
+            Ok(_) => {
+                self.log(LogLevel::Info, "Run finished in {elapsed_us} us", params);
+            },
+            Err(AlgorithmError::Cancelled) => {
+                self.log(LogLevel::Warning, "Run cancelled after {elapsed_us} us", params);
+            },
+            Err(AlgorithmError::Error(err)) => {
+                params.insert("error", format!("{err:?}"));
+                self.log(LogLevel::Error, "Run failed after {elapsed_us} us with {error}", params);
+            },
+        }
+        result
+    }
+
+    pub(crate) fn log_metrics(&self, metrics: &AlgorithmMetrics) {
+        self.log(
+            LogLevel::Info,
+            "Run metrics: {elapsed_us} us, {nodes_visited} nodes visited, {allocations_estimate} bytes allocated",
+            SLDict::from(metrics));
+    }
+
+    fn log(&self, level: LogLevel, template: &str, params: SLDict) {
+        self.logger.log(level, LogDataHolder::new(template, params));
+    }
+}
+
+pub(crate) fn elapsed_us(elapsed: Duration) -> u64 {
+    u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX)
+}
+
+/// Size and basic properties of `graph`, for [`AlgorithmLogger::log_validation`].
+pub(crate) fn graph_params(graph: &DirectedGraph) -> SLDict {
+    let properties = graph.basic_properties();
+    let mut params = SLDict::new();
+    params.insert("number_of_nodes", graph.number_of_nodes());
+    params.insert("number_of_arrows", graph.number_of_arrows());
+    params.insert("acyclic", properties.acyclic);
+    params.insert("connected", properties.connected);
+    params.insert("rooted", properties.rooted);
+    params.insert("binary", properties.binary);
+    params.insert("tree", properties.tree);
+    params
+}
@@ -0,0 +1,288 @@
+//! Pull based pipeline connecting a source of items (e.g. parsed networks),
+//! an optional validation stage and a per-item algorithm stage.
+use core::fmt::{Display, Formatter};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+        Mutex},
+    thread::JoinHandle};
+
+use crate::cancellation::CancellationToken;
+
+type StageFn<TItem, TOutput, TError> = dyn Fn(TItem) -> Result<TOutput, TError> + Send + Sync;
+type ValidationFn<TItem, TError> = dyn Fn(&TItem) -> Result<(), TError> + Send + Sync;
+type ItemResult<TOutput, TError> = Result<TOutput, PipelineError<TError>>;
+
+#[derive(Debug)]
+pub enum PipelineError<TError> {
+    /// Error produced by the source, the validation or the stage.
+    Stage(TError),
+
+    /// Item was pulled from the source, but the pipeline got cancelled
+    /// before the stage run on it.
+    Cancelled,
+}
+
+/// Configuration of a pipeline. Built with [`Pipeline::new`] and `with_*`
+/// methods, started with [`Pipeline::run`].
+pub struct Pipeline<TItem, TOutput, TError> {
+    stage: Arc<StageFn<TItem, TOutput, TError>>,
+    validation: Option<Arc<ValidationFn<TItem, TError>>>,
+    workers: usize,
+    max_in_flight: usize,
+    cancellation: CancellationToken,
+}
+
+impl<TItem, TOutput, TError> Pipeline<TItem, TOutput, TError>
+    where TItem: Send + 'static,
+          TOutput: Send + 'static,
+          TError: Send + 'static
+{
+    /// Creates new [`Pipeline`] running `stage` on each item. By default
+    /// there is no validation, the stage runs on the pulling thread and at
+    /// most 16 items are in flight.
+    pub fn new<F>(stage: F) -> Self
+        where F: Fn(TItem) -> Result<TOutput, TError> + Send + Sync + 'static
+    {
+        Self {
+            stage: Arc::new(stage),
+            validation: None,
+            workers: 0,
+            max_in_flight: 16,
+            cancellation: CancellationToken::none(),
+        }
+    }
+
+    /// Sets validation run on each item, on the pulling thread, before it is
+    /// passed to the stage.
+    #[must_use]
+    pub fn with_validation<F>(mut self, validation: F) -> Self
+        where F: Fn(&TItem) -> Result<(), TError> + Send + Sync + 'static
+    {
+        self.validation = Some(Arc::new(validation));
+        self
+    }
+
+    /// Runs the stage on `workers` background threads. Zero means the stage
+    /// runs on the pulling thread.
+    #[must_use]
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Limits number of items pulled from the source but not yet returned
+    /// from the pipeline.
+    ///
+    /// # Panics
+    /// When `max_in_flight` is 0.
+    #[must_use]
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        assert!(max_in_flight > 0, "max_in_flight has to be positive.");
+        self.max_in_flight = max_in_flight;
+        self
+    }
+
+    /// Sets cancellation token. Once it is cancelled no more items are
+    /// pulled from the source and items already pulled are reported as
+    /// [`PipelineError::Cancelled`] unless the stage has already run on them.
+    #[must_use]
+    pub fn with_cancellation(mut self, ct: CancellationToken) -> Self {
+        self.cancellation = ct;
+        self
+    }
+
+    /// Starts the pipeline over `source`. Returns iterator over
+    /// `(item_index, result)` pairs, in source order.
+    pub fn run<TSource>(self, source: TSource) -> PipelineIterator<TSource, TItem, TOutput, TError>
+        where TSource: Iterator<Item=Result<TItem, TError>>
+    {
+        let (result_sender, result_receiver) = channel();
+        let mut job_sender = None;
+        let mut handles = Vec::with_capacity(self.workers);
+        if self.workers > 0 {
+            let (sender, receiver) = channel::<(usize, TItem)>();
+            let receiver = Arc::new(Mutex::new(receiver));
+            for _ in 0..self.workers {
+                let receiver = receiver.clone();
+                let results = result_sender.clone();
+                let stage = self.stage.clone();
+                let cancellation = self.cancellation.clone();
+                handles.push(std::thread::spawn(move || {
+                    worker_loop(&receiver, &results, &*stage, &cancellation);
+                }));
+            }
+            job_sender = Some(sender);
+        }
+
+        PipelineIterator {
+            source: source,
+            source_index: 0,
+            source_finished: false,
+            next_index: 0,
+            outstanding: 0,
+            ready: BTreeMap::new(),
+            pipeline: self,
+            job_sender: job_sender,
+            result_receiver: result_receiver,
+            handles: handles,
+        }
+    }
+}
+
+fn worker_loop<TItem, TOutput, TError>(
+    jobs: &Mutex<Receiver<(usize, TItem)>>,
+    results: &Sender<(usize, ItemResult<TOutput, TError>)>,
+    stage: &StageFn<TItem, TOutput, TError>,
+    cancellation: &CancellationToken)
+{
+    loop {
+        let job = match jobs.lock() {
+            Ok(guard) => guard.recv(),
+            Err(_) => return,
+        };
+        let Ok((index, item)) = job else { return; };
+        let result = run_stage(stage, cancellation, item);
+        if results.send((index, result)).is_err() {
+            return;
+        }
+    }
+}
+
+fn run_stage<TItem, TOutput, TError>(
+    stage: &StageFn<TItem, TOutput, TError>,
+    cancellation: &CancellationToken,
+    item: TItem) -> ItemResult<TOutput, TError>
+{
+    if cancellation.is_cancelled() {
+        return Err(PipelineError::Cancelled);
+    }
+    stage(item).map_err(PipelineError::Stage)
+}
+
+/// Iterator returned by [`Pipeline::run`].
+pub struct PipelineIterator<TSource, TItem, TOutput, TError> {
+    source: TSource,
+    source_index: usize,
+    source_finished: bool,
+    next_index: usize,
+    outstanding: usize,
+    ready: BTreeMap<usize, ItemResult<TOutput, TError>>,
+    pipeline: Pipeline<TItem, TOutput, TError>,
+    job_sender: Option<Sender<(usize, TItem)>>,
+    result_receiver: Receiver<(usize, ItemResult<TOutput, TError>)>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl<TSource, TItem, TOutput, TError> PipelineIterator<TSource, TItem, TOutput, TError>
+    where TSource: Iterator<Item=Result<TItem, TError>>
+{
+    fn fill(&mut self) {
+        while !self.source_finished
+            && self.outstanding + self.ready.len() < self.pipeline.max_in_flight
+        {
+            if self.pipeline.cancellation.is_cancelled() {
+                self.source_finished = true;
+                break;
+            }
+
+            let Some(next) = self.source.next() else {
+                self.source_finished = true;
+                break;
+            };
+            let index = self.source_index;
+            self.source_index += 1;
+
+            let item = match next {
+                Ok(item) => item,
+                Err(err) => {
+                    self.ready.insert(index, Err(PipelineError::Stage(err)));
+                    continue;
+                },
+            };
+
+            if let Some(validation) = &self.pipeline.validation {
+                if let Err(err) = validation(&item) {
+                    self.ready.insert(index, Err(PipelineError::Stage(err)));
+                    continue;
+                }
+            }
+
+            if let Some(sender) = &self.job_sender {
+                if sender.send((index, item)).is_ok() {
+                    self.outstanding += 1;
+                }
+                else
+                {
+                    self.ready.insert(index, Err(PipelineError::Cancelled));
+                }
+            }
+            else
+            {
+                let result = run_stage(&*self.pipeline.stage, &self.pipeline.cancellation, item);
+                self.ready.insert(index, result);
+            }
+        }
+    }
+}
+
+impl<TSource, TItem, TOutput, TError> Iterator for PipelineIterator<TSource, TItem, TOutput, TError>
+    where TSource: Iterator<Item=Result<TItem, TError>>
+{
+    type Item = (usize, ItemResult<TOutput, TError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.fill();
+
+            if let Some(result) = self.ready.remove(&self.next_index) {
+                let index = self.next_index;
+                self.next_index += 1;
+                return Some((index, result));
+            }
+
+            if self.outstanding == 0 {
+                return None;
+            }
+
+            match self.result_receiver.recv() {
+                Ok((index, result)) => {
+                    self.outstanding -= 1;
+                    self.ready.insert(index, result);
+                },
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+impl<TSource, TItem, TOutput, TError> Drop for PipelineIterator<TSource, TItem, TOutput, TError> {
+    fn drop(&mut self) {
+        self.job_sender = None;
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl<TError: Display> Display for PipelineError<TError> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PipelineError::Stage(_) => f.write_str("pipeline stage failed"),
+            PipelineError::Cancelled => f.write_str("pipeline cancelled"),
+        }
+    }
+}
+
+impl<TError> std::error::Error for PipelineError<TError>
+    where TError: std::error::Error + 'static
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PipelineError::Stage(err) => Some(err),
+            PipelineError::Cancelled => None,
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use core::fmt::{Display, Formatter};
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+
+use raf_structural_logging::{core::CoreLoggerFactory, models::SLDict};
+use dagex::core::Node;
+use dagex::phylo::{GenesOverSpecies, PhylogeneticNetwork, PhylogeneticNetworkId, Taxon};
+use dagex::raf_array::immutable_string::ImmutableString;
+
+use crate::cancellation::CancellationToken;
+use crate::logger::{build_default_logger_factory, build_logger_name, AlgorithmLogger};
+use crate::traits::{Algorithm, AlgorithmError, AlgorithmFactory, AlgorithmFactoryBuilder};
+
+/// Computes LCA reconciliation of each gene network into the species tree:
+/// leaves are mapped to species leaves with the same [`Taxon`], other nodes
+/// to the lowest common ancestor of their children's images.
+pub struct ReconciliationAlgorithm<'a> {
+    genes_over_species: &'a GenesOverSpecies,
+    species_taxa: HashMap<&'a Taxon, Node>,
+    species_parents: Vec<Node>,
+    species_depths: Vec<i32>,
+    logger_factory: Arc<CoreLoggerFactory>,
+    logger_name: ImmutableString,
+}
+
+pub struct ReconciliationResult<'a> {
+    images: HashMap<PhylogeneticNetworkId, Vec<Node>>,
+    number_of_duplications: usize,
+    number_of_speciations: usize,
+    phantom: PhantomData<&'a()>,
+}
+
+impl ReconciliationResult<'_> {
+    /// Returns node of the species tree `node` of the gene network with
+    /// `gene_network_id` is mapped to.
+    ///
+    /// # Panics
+    /// When there is no such gene network or node.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn image(&self, gene_network_id: PhylogeneticNetworkId, node: Node) -> Node {
+        self.images[&gene_network_id][node.id() as usize]
+    }
+
+    /// Number of gene nodes with at least two children, mapped to the same
+    /// species node as one of their children.
+    pub fn number_of_duplications(&self) -> usize { self.number_of_duplications }
+
+    /// Number of gene nodes with at least two children, mapped to a species
+    /// node strictly above images of all their children.
+    pub fn number_of_speciations(&self) -> usize { self.number_of_speciations }
+}
+
+impl ReconciliationAlgorithm<'_> {
+    #[allow(clippy::cast_sign_loss)]
+    fn lca(&self, mut left: Node, mut right: Node) -> Node {
+        let depth = |node: Node| self.species_depths[node.id() as usize];
+        let parent = |node: Node| self.species_parents[node.id() as usize];
+        while depth(left) > depth(right) {
+            left = parent(left);
+        }
+        while depth(right) > depth(left) {
+            right = parent(right);
+        }
+        while left != right {
+            left = parent(left);
+            right = parent(right);
+        }
+        left
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn reconcile(&self, gene_network: &PhylogeneticNetwork, result: &mut ReconciliationResult<'_>) {
+        let graph = gene_network.graph();
+        let order: Vec<Node> = graph.iter_topological()
+            .expect("Phylogenetic network is acyclic.")
+            .collect();
+        let mut images = vec![Node::from(-1); graph.number_of_nodes() as usize];
+        for node in order.into_iter().rev() {
+            let successors = graph.get_successors(node);
+            let image = if let Some((first, rest)) = successors.split_first() {
+                let image = rest.iter().fold(
+                    images[first.id() as usize],
+                    |acc, child| self.lca(acc, images[child.id() as usize]));
+                if successors.len() >= 2 {
+                    if successors.iter().any(|child| images[child.id() as usize] == image) {
+                        result.number_of_duplications += 1;
+                    }
+                    else
+                    {
+                        result.number_of_speciations += 1;
+                    }
+                }
+                image
+            }
+            else
+            {
+                self.species_taxa[&gene_network.taxa()[&node]]
+            };
+            images[node.id() as usize] = image;
+        }
+        result.images.insert(gene_network.id(), images);
+    }
+}
+
+impl<'a> ReconciliationAlgorithm<'a> {
+    fn calculate(self, ct: &mut CancellationToken)
+        -> Result<ReconciliationResult<'a>, AlgorithmError<()>>
+    {
+        let mut result = ReconciliationResult {
+            images: HashMap::new(),
+            number_of_duplications: 0,
+            number_of_speciations: 0,
+            phantom: PhantomData,
+        };
+        for gene_network in self.genes_over_species.gene_networks() {
+            if ct.is_cancelled() {
+                return Err(AlgorithmError::Cancelled);
+            }
+            if !result.images.contains_key(&gene_network.id()) {
+                self.reconcile(gene_network, &mut result);
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl<'a> Algorithm<'a> for ReconciliationAlgorithm<'a> {
+    type Input<'b> = &'b GenesOverSpecies;
+
+    type Output<'b> = ReconciliationResult<'b>;
+
+    type Error = ();
+
+    fn run_with_cancellation(self, ct: &mut CancellationToken)
+        -> Result<Self::Output<'a>, AlgorithmError<Self::Error>>
+    {
+        let logger = AlgorithmLogger::new(&self.logger_factory, &self.logger_name);
+        logger.log_run(|| self.calculate(ct))
+    }
+}
+
+#[derive(Debug)]
+pub enum ReconciliationInputValidationError {
+    /// Species network is not a tree, i.e. it contains reticulations.
+    SpeciesNotTree,
+
+    /// Gene network has a leaf without taxon, which cannot be mapped to the
+    /// species tree.
+    UnlabeledGeneLeaf(PhylogeneticNetworkId, Node),
+}
+
+pub struct ReconciliationAlgorithmFactory {
+    logger_factory: Arc<CoreLoggerFactory>,
+    _priv: PhantomData<()>,
+}
+
+impl AlgorithmFactory for ReconciliationAlgorithmFactory {
+    type Input<'a> = &'a GenesOverSpecies;
+
+    type Algo<'a> = ReconciliationAlgorithm<'a>;
+
+    type Error = ReconciliationInputValidationError;
+
+    fn create<'a>(&mut self, input: Self::Input<'a>)
+        -> Result<Self::Algo<'a>, Self::Error>
+    {
+        let logger_name = build_logger_name("ReconciliationAlgorithm", input);
+        let result = self.build(input, logger_name.clone());
+        AlgorithmLogger::new(&self.logger_factory, &logger_name)
+            .log_validation(&result, || {
+                let mut params = SLDict::new();
+                params.insert("species_nodes", input.species_network().graph().number_of_nodes());
+                params.insert("gene_networks", input.gene_networks().len());
+                params
+            });
+        result
+    }
+}
+
+impl ReconciliationAlgorithmFactory {
+    #[allow(clippy::cast_sign_loss)]
+    fn build<'a>(&self, input: &'a GenesOverSpecies, logger_name: ImmutableString)
+        -> Result<ReconciliationAlgorithm<'a>, ReconciliationInputValidationError>
+    {
+        let species = input.species_network();
+        let species_graph = species.graph();
+        if !species_graph.basic_properties().tree {
+            return Err(ReconciliationInputValidationError::SpeciesNotTree);
+        }
+
+        for gene_network in input.gene_networks() {
+            let unlabeled = gene_network.graph()
+                .leaves()
+                .iter()
+                .filter(|leaf| !gene_network.taxa().contains_key(leaf))
+                .min_by_key(|leaf| leaf.id());
+            if let Some(leaf) = unlabeled {
+                return Err(ReconciliationInputValidationError::UnlabeledGeneLeaf(
+                    gene_network.id(),
+                    *leaf));
+            }
+        }
+
+        let no = species_graph.number_of_nodes() as usize;
+        let mut species_parents = vec![species.root(); no];
+        let mut species_depths = vec![0; no];
+        for node in species_graph.iter_bfs(species.root()) {
+            for child in species_graph.get_successors(node) {
+                species_parents[child.id() as usize] = node;
+                species_depths[child.id() as usize] = species_depths[node.id() as usize] + 1;
+            }
+        }
+        let species_taxa = species.taxa()
+            .iter()
+            .map(|(node, taxon)| (taxon, *node))
+            .collect();
+
+        Ok(ReconciliationAlgorithm {
+            genes_over_species: input,
+            species_taxa: species_taxa,
+            species_parents: species_parents,
+            species_depths: species_depths,
+            logger_factory: self.logger_factory.clone(),
+            logger_name: logger_name,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct ReconciliationAlgorithmFactoryBuilder {
+    logger_factory: Option<Arc<CoreLoggerFactory>>,
+}
+
+impl AlgorithmFactoryBuilder for ReconciliationAlgorithmFactoryBuilder {
+    type LoggerFactory = CoreLoggerFactory;
+
+    type AlgoFactory = ReconciliationAlgorithmFactory;
+
+    type Error = ();
+
+    fn set_logger_factory(
+        &mut self,
+        logger_factory: &Arc<Self::LoggerFactory>)
+    {
+        self.logger_factory = Some(logger_factory.clone());
+    }
+
+    fn create(self) -> Result<Self::AlgoFactory, Self::Error> {
+        let factory = ReconciliationAlgorithmFactory {
+            logger_factory: self.logger_factory.unwrap_or_else(build_default_logger_factory),
+            _priv: PhantomData,
+        };
+        Ok(factory)
+    }
+}
+
+impl From<&ReconciliationInputValidationError> for SLDict {
+    fn from(value: &ReconciliationInputValidationError) -> Self {
+        let mut dict = SLDict::new();
+        match value {
+            ReconciliationInputValidationError::SpeciesNotTree => {
+                dict.insert("error", "SpeciesNotTree");
+            },
+            ReconciliationInputValidationError::UnlabeledGeneLeaf(network, leaf) => {
+                dict.insert("error", "UnlabeledGeneLeaf");
+                dict.insert("network", i32::from(*network));
+                dict.insert("node", leaf.id());
+            },
+        }
+        dict
+    }
+}
+
+impl Display for ReconciliationInputValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReconciliationInputValidationError::SpeciesNotTree
+                => f.write_str("species network is not a tree"),
+            ReconciliationInputValidationError::UnlabeledGeneLeaf(network, leaf)
+                => write!(f, "leaf {} of gene network {} has no taxon", leaf.id(), i32::from(*network)),
+        }
+    }
+}
+
+impl std::error::Error for ReconciliationInputValidationError { }
@@ -0,0 +1,305 @@
+use core::fmt::{Display, Formatter};
+use std::{marker::PhantomData, sync::Arc};
+
+use dagex::raf_array::immutable_string::ImmutableString;
+use raf_structural_logging::{core::CoreLoggerFactory, models::SLDict};
+use dagex::core::{ArrowDTO, DirectedGraph, DirectedGraphDTO, Node};
+
+use crate::cancellation::CancellationToken;
+use crate::logger::{build_default_logger_factory, build_logger_name, graph_params, AlgorithmLogger};
+use crate::traits::{Algorithm, AlgorithmError, AlgorithmFactory, AlgorithmFactoryBuilder};
+
+/// Decomposes a directed graph, not necessarily rooted nor acyclic, into
+/// strongly connected components with iterative Tarjan's algorithm.
+pub struct SccAlgorithm<'a> {
+    graph: &'a DirectedGraph,
+    discovery: Vec<i32>,
+    low: Vec<i32>,
+    on_stack: Vec<bool>,
+    logger_factory: Arc<CoreLoggerFactory>,
+    logger_name: ImmutableString,
+}
+
+pub struct SccResult<'a> {
+    components: Vec<u32>,
+    nodes: Vec<Node>,
+    offsets: Vec<usize>,
+    condensation: DirectedGraphDTO,
+    phantom: PhantomData<&'a()>,
+}
+
+impl SccResult<'_> {
+    /// Component of `node`. Components are numbered in a topological order
+    /// of the condensation, i.e. arrows between different components always
+    /// go from a lower to a higher number.
+    ///
+    /// # Panics
+    /// When `node` is not in the graph.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn component_of(&self, node: Node) -> u32 { self.components[node.id() as usize] }
+
+    pub fn component_count(&self) -> usize { self.offsets.len() - 1 }
+
+    /// Iterates over nodes of each component, in order of component
+    /// numbers. Nodes within a component are sorted by id.
+    pub fn iter_components(&self) -> impl Iterator<Item = &[Node]> + '_ {
+        self.offsets.windows(2).map(|range| &self.nodes[range[0]..range[1]])
+    }
+
+    /// Graph with a node for each component and an arrow between two
+    /// components whenever an arrow joins their nodes. Acyclic by
+    /// construction, arrows are sorted.
+    pub fn condensation(&self) -> &DirectedGraphDTO { &self.condensation }
+}
+
+/// Number of search steps between consecutive cancellation checks.
+const CANCELLATION_CHECK_INTERVAL: usize = 1024;
+
+impl SccAlgorithm<'_> {
+    #[inline(always)]
+    #[allow(clippy::cast_sign_loss)]
+    fn idx(node: Node) -> usize {
+        node.id() as usize
+    }
+
+    /// Runs Tarjan's search from `start` with an explicit stack of `(node,
+    /// position of the next successor)` pairs, pushing found components
+    /// into `found`, sinks first. Returns `false` if `ct` got cancelled.
+    fn scan(
+        &mut self,
+        start: Node,
+        time: &mut i32,
+        found: &mut Vec<Vec<Node>>,
+        ct: &CancellationToken) -> bool
+    {
+        let graph = self.graph;
+        let mut steps = 0;
+        let mut component_stack = Vec::<Node>::new();
+        let mut stack = vec![(start, 0usize)];
+        self.visit(start, time, &mut component_stack);
+
+        while let Some((node, position)) = stack.last_mut() {
+            steps += 1;
+            if steps % CANCELLATION_CHECK_INTERVAL == 0 && ct.is_cancelled() {
+                return false;
+            }
+            let node = *node;
+            if let Some(successor) = graph.get_successors(node).get(*position) {
+                *position += 1;
+                let successor = *successor;
+                if self.discovery[Self::idx(successor)] == -1 {
+                    self.visit(successor, time, &mut component_stack);
+                    stack.push((successor, 0));
+                }
+                else if self.on_stack[Self::idx(successor)] {
+                    let low = &mut self.low[Self::idx(node)];
+                    *low = (*low).min(self.discovery[Self::idx(successor)]);
+                }
+                continue;
+            }
+
+            stack.pop();
+            let node_low = self.low[Self::idx(node)];
+            if node_low == self.discovery[Self::idx(node)] {
+                let mut component = Vec::new();
+                while let Some(member) = component_stack.pop() {
+                    self.on_stack[Self::idx(member)] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort_unstable_by_key(Node::id);
+                found.push(component);
+            }
+            if let Some((parent, _)) = stack.last() {
+                let parent_low = &mut self.low[Self::idx(*parent)];
+                *parent_low = (*parent_low).min(node_low);
+            }
+        }
+        true
+    }
+
+    fn visit(&mut self, node: Node, time: &mut i32, component_stack: &mut Vec<Node>) {
+        self.discovery[Self::idx(node)] = *time;
+        self.low[Self::idx(node)] = *time;
+        self.on_stack[Self::idx(node)] = true;
+        component_stack.push(node);
+        *time += 1;
+    }
+}
+
+impl<'a> SccAlgorithm<'a> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn calculate(mut self, ct: &mut CancellationToken)
+        -> Result<SccResult<'a>, AlgorithmError<()>>
+    {
+        if ct.is_cancelled() {
+            return Err(AlgorithmError::Cancelled);
+        }
+        let graph = self.graph;
+        let mut time = 0;
+        let mut found = Vec::new();
+        for node in graph.iter_nodes() {
+            if self.discovery[Self::idx(node)] == -1 && !self.scan(node, &mut time, &mut found, ct) {
+                return Err(AlgorithmError::Cancelled);
+            }
+        }
+
+        // Tarjan's algorithm finds sinks first, reversing gives topological
+        // order.
+        found.reverse();
+        let mut components = vec![0u32; self.discovery.len()];
+        let mut nodes = Vec::with_capacity(self.discovery.len());
+        let mut offsets = Vec::with_capacity(found.len() + 1);
+        offsets.push(0);
+        for (component, members) in found.iter().enumerate() {
+            for member in members {
+                components[Self::idx(*member)] = component as u32;
+            }
+            nodes.extend_from_slice(members);
+            offsets.push(nodes.len());
+        }
+
+        let mut arrows: Vec<(i32, i32)> = graph.iter_arrows()
+            .map(|(source, target)| (
+                components[Self::idx(source)] as i32,
+                components[Self::idx(target)] as i32))
+            .filter(|(source, target)| source != target)
+            .collect();
+        arrows.sort_unstable();
+        arrows.dedup();
+        let arrows = arrows.into_iter()
+            .map(|(source, target)| ArrowDTO::new(source, target))
+            .collect();
+        let condensation = DirectedGraphDTO::new(found.len() as i32, arrows);
+
+        Ok(SccResult {
+            components: components,
+            nodes: nodes,
+            offsets: offsets,
+            condensation: condensation,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<'a> Algorithm<'a> for SccAlgorithm<'a> {
+    type Input<'b> = &'b DirectedGraph;
+
+    type Output<'b> = SccResult<'b>;
+
+    type Error = ();
+
+    fn run_with_cancellation(self, ct: &mut CancellationToken)
+        -> Result<Self::Output<'a>, AlgorithmError<Self::Error>>
+    {
+        let logger = AlgorithmLogger::new(&self.logger_factory, &self.logger_name);
+        logger.log_run(|| self.calculate(ct))
+    }
+}
+
+#[derive(Debug)]
+pub enum SccInputValidationError {
+    /// Graph is too big. This algorithm allocates memory linear in the
+    /// number of nodes. For max limit see [`SccAlgorithmFactory::max_size`].
+    GraphTooBig,
+}
+
+pub struct SccAlgorithmFactory {
+    logger_factory: Arc<CoreLoggerFactory>,
+    _priv: PhantomData<()>,
+}
+
+impl SccAlgorithmFactory {
+    pub const fn max_size() -> usize { 1 << 30 }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn build<'a>(&self, input: &'a DirectedGraph, logger_name: ImmutableString)
+        -> Result<SccAlgorithm<'a>, SccInputValidationError>
+    {
+        let no = input.number_of_nodes() as usize;
+        if no > Self::max_size() {
+            return Err(SccInputValidationError::GraphTooBig);
+        }
+
+        Ok(SccAlgorithm {
+            graph: input,
+            discovery: vec![-1; no],
+            low: vec![0; no],
+            on_stack: vec![false; no],
+            logger_factory: self.logger_factory.clone(),
+            logger_name: logger_name,
+        })
+    }
+}
+
+impl AlgorithmFactory for SccAlgorithmFactory {
+    type Input<'a> = &'a DirectedGraph;
+
+    type Algo<'a> = SccAlgorithm<'a>;
+
+    type Error = SccInputValidationError;
+
+    fn create<'a>(&mut self, input: Self::Input<'a>)
+        -> Result<Self::Algo<'a>, Self::Error>
+    {
+        let logger_name = build_logger_name("SccAlgorithm", input);
+        let result = self.build(input, logger_name.clone());
+        AlgorithmLogger::new(&self.logger_factory, &logger_name)
+            .log_validation(&result, || graph_params(input));
+        result
+    }
+}
+
+#[derive(Default)]
+pub struct SccAlgorithmFactoryBuilder {
+    logger_factory: Option<Arc<CoreLoggerFactory>>,
+}
+
+impl AlgorithmFactoryBuilder for SccAlgorithmFactoryBuilder {
+    type LoggerFactory = CoreLoggerFactory;
+
+    type AlgoFactory = SccAlgorithmFactory;
+
+    type Error = ();
+
+    fn set_logger_factory(
+        &mut self,
+        logger_factory: &Arc<Self::LoggerFactory>)
+    {
+        self.logger_factory = Some(logger_factory.clone());
+    }
+
+    fn create(self) -> Result<Self::AlgoFactory, Self::Error> {
+        let factory = SccAlgorithmFactory {
+            logger_factory: self.logger_factory.unwrap_or_else(build_default_logger_factory),
+            _priv: PhantomData,
+        };
+        Ok(factory)
+    }
+}
+
+impl From<&SccInputValidationError> for SLDict {
+    fn from(value: &SccInputValidationError) -> Self {
+        let mut dict = SLDict::new();
+        match value {
+            SccInputValidationError::GraphTooBig => {
+                dict.insert("error", "GraphTooBig");
+                dict.insert("limit", SccAlgorithmFactory::max_size());
+            },
+        }
+        dict
+    }
+}
+
+impl Display for SccInputValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SccInputValidationError::GraphTooBig
+                => write!(f, "input graph exceeds the maximum of {} nodes", SccAlgorithmFactory::max_size()),
+        }
+    }
+}
+
+impl std::error::Error for SccInputValidationError { }
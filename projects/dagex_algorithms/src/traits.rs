@@ -1,7 +1,63 @@
-use core::fmt::Debug;
-use std::sync::Arc;
+use core::fmt::{Debug, Display, Formatter};
+use std::{sync::Arc, time::{Duration, Instant}};
 
-use raf_structural_logging::traits::StructuralLoggerFactory;
+use raf_structural_logging::{models::SLDict, traits::StructuralLoggerFactory};
+
+use crate::cancellation::CancellationToken;
+use crate::logger::elapsed_us;
+
+#[derive(Debug)]
+pub enum AlgorithmError<E> {
+    /// Domain error of the algorithm.
+    Error(E),
+
+    /// Algorithm noticed cancellation of the passed [`CancellationToken`]
+    /// and stopped before completion.
+    Cancelled,
+}
+
+impl<E> From<E> for AlgorithmError<E> {
+    fn from(value: E) -> Self { Self::Error(value) }
+}
+
+/// Basic runtime metrics of a single [`Algorithm`] run, see
+/// [`Algorithm::run_instrumented`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct AlgorithmMetrics {
+    elapsed: Duration,
+    nodes_visited: u64,
+    allocations_estimate: u64,
+}
+
+impl AlgorithmMetrics {
+    pub fn new(elapsed: Duration, nodes_visited: u64, allocations_estimate: u64) -> Self {
+        Self {
+            elapsed: elapsed,
+            nodes_visited: nodes_visited,
+            allocations_estimate: allocations_estimate,
+        }
+    }
+
+    /// Wall time of the run.
+    #[inline(always)]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Number of nodes visited by the run, with repetitions. Its exact
+    /// meaning is described by each algorithm, 0 if not counted.
+    #[inline(always)]
+    pub fn nodes_visited(&self) -> u64 {
+        self.nodes_visited
+    }
+
+    /// Upper bound of memory allocated by the run, in bytes. 0 if not
+    /// estimated.
+    #[inline(always)]
+    pub fn allocations_estimate(&self) -> u64 {
+        self.allocations_estimate
+    }
+}
 
 /// Represents given algorithm's temporary data.
 pub trait Algorithm<'a>: Sized {
@@ -10,11 +66,40 @@ pub trait Algorithm<'a>: Sized {
     type Error: Debug;
 
     /// Runs current algorithm on the internal input and consumes
-    /// the [`Algorithm`] instance.
+    /// the [`Algorithm`] instance. Equivalent to
+    /// [`Algorithm::run_with_cancellation`] with a token that is never
+    /// cancelled.
     /// 
     /// # Errors
     /// For errors see [`Algorithm::Error`] description.
-    fn run(self) -> Result<Self::Output<'a>, Self::Error>;
+    fn run(self) -> Result<Self::Output<'a>, Self::Error> {
+        match self.run_with_cancellation(&mut CancellationToken::none()) {
+            Ok(output) => Ok(output),
+            Err(AlgorithmError::Error(err)) => Err(err),
+            Err(AlgorithmError::Cancelled) => unreachable!("Algorithm cancelled without cancellation."),
+        }
+    }
+
+    /// Runs current algorithm like [`Algorithm::run`], periodically
+    /// checking `ct`.
+    /// 
+    /// # Errors
+    /// [`AlgorithmError::Cancelled`] if `ct` got cancelled during the run,
+    /// otherwise [`AlgorithmError::Error`] wrapping [`Algorithm::Error`].
+    fn run_with_cancellation(self, ct: &mut CancellationToken)
+        -> Result<Self::Output<'a>, AlgorithmError<Self::Error>>;
+
+    /// Runs current algorithm like [`Algorithm::run`], additionally
+    /// measuring it. By default only [`AlgorithmMetrics::elapsed`] is
+    /// filled.
+    ///
+    /// # Errors
+    /// For errors see [`Algorithm::Error`] description.
+    fn run_instrumented(self) -> Result<(Self::Output<'a>, AlgorithmMetrics), Self::Error> {
+        let start = Instant::now();
+        let output = self.run()?;
+        Ok((output, AlgorithmMetrics::new(start.elapsed(), 0, 0)))
+    }
 }
 
 pub trait AlgorithmFactory: Sized {
@@ -36,7 +121,9 @@ pub trait AlgorithmFactoryBuilder: Sized + Default {
     type AlgoFactory: AlgorithmFactory;
     type Error: Debug;
 
-    /// Sets `logger_factory` for internal usage of algorithm.
+    /// Sets `logger_factory` for internal usage of algorithm: created
+    /// factories log input validation, algorithms log their runs.
+    /// Defaults to [`build_default_logger_factory`](crate::logger::build_default_logger_factory).
     fn set_logger_factory(
         &mut self,
         logger_factory: &Arc<Self::LoggerFactory>);
@@ -47,3 +134,45 @@ pub trait AlgorithmFactoryBuilder: Sized + Default {
     /// For concrete description see associated [`AlgorithmFactoryBuilder::Error`] docs.
     fn create(self) -> Result<Self::AlgoFactory, Self::Error>;
 }
+
+/// Key-value form meant for log messages, e.g.
+/// `elapsed_us=1250, nodes_visited=17, allocations_estimate=408`.
+impl Display for AlgorithmMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "elapsed_us={}, nodes_visited={}, allocations_estimate={}",
+            self.elapsed.as_micros(),
+            self.nodes_visited,
+            self.allocations_estimate)
+    }
+}
+
+/// Same keys as the [`Display`] form, for structural logs.
+impl From<&AlgorithmMetrics> for SLDict {
+    fn from(value: &AlgorithmMetrics) -> Self {
+        let mut dict = SLDict::new();
+        dict.insert("elapsed_us", elapsed_us(value.elapsed));
+        dict.insert("nodes_visited", value.nodes_visited);
+        dict.insert("allocations_estimate", value.allocations_estimate);
+        dict
+    }
+}
+
+impl<E: Display> Display for AlgorithmError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AlgorithmError::Error(err) => Display::fmt(err, f),
+            AlgorithmError::Cancelled => f.write_str("algorithm cancelled"),
+        }
+    }
+}
+
+impl<E: std::error::Error> std::error::Error for AlgorithmError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AlgorithmError::Error(err) => err.source(),
+            AlgorithmError::Cancelled => None,
+        }
+    }
+}
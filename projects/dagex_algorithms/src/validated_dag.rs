@@ -0,0 +1,161 @@
+//! Input shared by algorithms working on rooted, acyclic graphs. Validation
+//! happens once, and derived data is computed on first use and then reused
+//! by all algorithm runs on the same [`ValidatedRootedDag`], see e.g.
+//! [`DepthAlgorithmFactory::create_validated`](crate::depth::DepthAlgorithmFactory::create_validated).
+use core::{cell::{Cell, OnceCell}, fmt::{Display, Formatter}};
+
+use dagex::core::{DirectedGraph, Node, NodeMap};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DagValidationError {
+    /// Input is not rooted.
+    InputNotRooted,
+
+    /// Input is not acyclic.
+    InputNotAcyclic,
+}
+
+/// Rooted, acyclic [`DirectedGraph`] together with lazily computed and
+/// cached topological order, depths and post-order.
+pub struct ValidatedRootedDag<'a> {
+    graph: &'a DirectedGraph,
+    root: Node,
+    topological_order: OnceCell<Vec<Node>>,
+    depths: OnceCell<(NodeMap<i32>, i32)>,
+    postorder: OnceCell<(Vec<Node>, NodeMap<usize>)>,
+    computations: Cell<usize>,
+}
+
+impl<'a> ValidatedRootedDag<'a> {
+    /// Validates `graph`. Doesn't compute anything else.
+    ///
+    /// # Errors
+    /// For concrete errors see [`DagValidationError`] docs.
+    pub fn new(graph: &'a DirectedGraph) -> Result<Self, DagValidationError> {
+        Self::validate(graph)?;
+        let Some(root) = graph.root() else {
+            return Err(DagValidationError::InputNotRooted);
+        };
+        Ok(Self {
+            graph: graph,
+            root: root,
+            topological_order: OnceCell::new(),
+            depths: OnceCell::new(),
+            postorder: OnceCell::new(),
+            computations: Cell::new(0),
+        })
+    }
+
+    /// Checks whether `graph` is rooted and acyclic, without constructing
+    /// the wrapper.
+    ///
+    /// # Errors
+    /// For concrete errors see [`DagValidationError`] docs.
+    pub fn validate(graph: &DirectedGraph) -> Result<(), DagValidationError> {
+        let props = graph.basic_properties();
+        if !props.rooted {
+            return Err(DagValidationError::InputNotRooted);
+        }
+        if !props.acyclic {
+            return Err(DagValidationError::InputNotAcyclic);
+        }
+        Ok(())
+    }
+
+    pub fn graph(&self) -> &'a DirectedGraph { self.graph }
+
+    pub fn root(&self) -> Node { self.root }
+
+    /// Nodes in topological order, see [`DirectedGraph::iter_topological`].
+    ///
+    /// # Panics
+    /// Only when the graph's properties are inconsistent, since it was
+    /// validated to be acyclic.
+    pub fn topological_order(&self) -> &[Node] {
+        self.topological_order.get_or_init(|| {
+            self.count_computation();
+            self.graph.iter_topological().expect("Validated graph has to be acyclic.").collect()
+        })
+    }
+
+    /// Length of the longest path from the root to each node.
+    pub fn depths(&self) -> &NodeMap<i32> { &self.depth_data().0 }
+
+    /// Length of the longest path in the graph.
+    pub fn max_depth(&self) -> i32 { self.depth_data().1 }
+
+    /// Nodes in post-order of depth-first search from the root, following
+    /// successors in ascending id order.
+    pub fn postorder(&self) -> &[Node] { &self.postorder_data().0 }
+
+    /// Position of each node in [`ValidatedRootedDag::postorder`].
+    pub fn postorder_numbers(&self) -> &NodeMap<usize> { &self.postorder_data().1 }
+
+    /// Number of cached values computed so far. Exposed for tests only.
+    #[doc(hidden)]
+    pub fn computations(&self) -> usize { self.computations.get() }
+
+    fn count_computation(&self) {
+        self.computations.set(self.computations.get() + 1);
+    }
+
+    fn depth_data(&self) -> &(NodeMap<i32>, i32) {
+        self.depths.get_or_init(|| {
+            let order = self.topological_order();
+            self.count_computation();
+            let mut depths = self.graph.new_node_map();
+            let mut max_depth = 0;
+            depths.set(self.graph, self.root, 0);
+            for node in order {
+                let depth = *depths.get(self.graph, *node).unwrap();
+                max_depth = max_depth.max(depth);
+                for child in self.graph.get_successors(*node) {
+                    if depths.get(self.graph, *child).map_or(true, |value| *value < depth + 1) {
+                        depths.set(self.graph, *child, depth + 1);
+                    }
+                }
+            }
+            (depths, max_depth)
+        })
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn postorder_data(&self) -> &(Vec<Node>, NodeMap<usize>) {
+        self.postorder.get_or_init(|| {
+            self.count_computation();
+            let graph = self.graph;
+            let mut visited = vec![false; graph.number_of_nodes() as usize];
+            let mut postorder = Vec::with_capacity(visited.len());
+            let mut numbers = graph.new_node_map();
+            let mut stack = vec![(self.root, 0)];
+            visited[self.root.id() as usize] = true;
+            while let Some((node, position)) = stack.last_mut() {
+                if let Some(child) = graph.get_successors(*node).get(*position) {
+                    *position += 1;
+                    if !visited[child.id() as usize] {
+                        visited[child.id() as usize] = true;
+                        stack.push((*child, 0));
+                    }
+                    continue;
+                }
+                numbers.set(graph, *node, postorder.len());
+                postorder.push(*node);
+                stack.pop();
+            }
+            (postorder, numbers)
+        })
+    }
+}
+
+impl Display for DagValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DagValidationError::InputNotRooted
+                => f.write_str("input graph is not rooted"),
+            DagValidationError::InputNotAcyclic
+                => f.write_str("input graph is not acyclic"),
+        }
+    }
+}
+
+impl std::error::Error for DagValidationError { }
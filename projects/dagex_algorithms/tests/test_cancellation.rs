@@ -0,0 +1,192 @@
+use std::{
+    sync::{atomic::{AtomicUsize, Ordering}, mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant}};
+
+use dagex::{
+    const_parse_newick,
+    core::{ArrowDTO, DirectedGraph, DirectedGraphDTO},
+    phylo::GenesOverSpecies};
+use dagex_algorithms::{
+    cancellation::{CancellationToken, CancellationTokenSource, TokenState},
+    depth::DepthAlgorithmFactoryBuilder,
+    episode_feasibility::{EpisodeFeasabilityAlgorithmFactoryBuilder, EpisodeFeasabilityInput},
+    traits::{Algorithm, AlgorithmError, AlgorithmFactory, AlgorithmFactoryBuilder}};
+
+/// Root with many leaves attached directly to it.
+fn large_dag() -> DirectedGraph {
+    let leaves = 300_000;
+    let arrows = (1..=leaves).map(|leaf| ArrowDTO::new(0, leaf)).collect();
+    let dto = DirectedGraphDTO::new(leaves + 1, arrows);
+    DirectedGraph::from_dto(&dto).unwrap()
+}
+
+/// Single path through `nodes` nodes, so that the depth scan visits far
+/// more nodes than `CANCELLATION_CHECK_INTERVAL` before finishing.
+fn deep_dag(nodes: i32) -> DirectedGraph {
+    let arrows = (1..nodes).map(|node| ArrowDTO::new(node - 1, node)).collect();
+    let dto = DirectedGraphDTO::new(nodes, arrows);
+    DirectedGraph::from_dto(&dto).unwrap()
+}
+
+#[test]
+fn test_token_states() {
+    let source = CancellationTokenSource::new();
+    let token = source.token();
+    let cloned = token.clone();
+    assert!(!token.is_cancelled());
+    source.cancel();
+    source.cancel();
+    assert!(source.is_cancelled());
+    assert!(token.is_cancelled());
+    assert!(cloned.is_cancelled());
+    assert!(!CancellationToken::none().is_cancelled());
+}
+
+#[test]
+fn test_depth_cancelled_from_another_thread() {
+    let nodes = 1 << 18;
+    let graph = deep_dag(nodes);
+    let mut factory = DepthAlgorithmFactoryBuilder::default().create().unwrap();
+    let source = CancellationTokenSource::new();
+    let mut token = source.token();
+    let cancelled_at = Arc::new(Mutex::new(None));
+    let recorder = cancelled_at.clone();
+    let _registration = token.register(move || { *recorder.lock().unwrap() = Some(Instant::now()); });
+
+    let (started_sender, started_receiver) = mpsc::channel();
+    let (result, started, finished) = thread::scope(|scope| {
+        let run = scope.spawn(|| {
+            // Created before signalling, so that the run consists of the
+            // initial check and the scan only.
+            let algorithm = factory.create(&graph).unwrap();
+            assert_eq!(token.state(), TokenState::NotCancelled);
+            let started = Instant::now();
+            started_sender.send(()).unwrap();
+            let result = algorithm.run_with_cancellation(&mut token);
+            (result, started, Instant::now())
+        });
+        started_receiver.recv().unwrap();
+        source.cancel_after(Duration::from_millis(2));
+        run.join().unwrap()
+    });
+
+    assert!(matches!(result, Err(AlgorithmError::Cancelled)));
+    let cancelled_at = cancelled_at.lock().unwrap().expect("Cancellation callback not invoked.");
+    assert!(started < cancelled_at && cancelled_at <= finished);
+
+    let full = factory.create(&graph).unwrap().run().unwrap();
+    assert_eq!(full.max_depth(), nodes - 1);
+}
+
+#[test]
+fn test_depth_not_cancelled() {
+    let graph = large_dag();
+    let source = CancellationTokenSource::new();
+    let mut factory = DepthAlgorithmFactoryBuilder::default().create().unwrap();
+    let result = factory.create(&graph).unwrap()
+        .run_with_cancellation(&mut source.token())
+        .unwrap();
+    assert_eq!(result.max_depth(), 1);
+}
+
+#[test]
+fn test_episode_feasibility_cancelled() {
+    let genes = const_parse_newick!("((a, b), c);");
+    let species = const_parse_newick!("((a, b), c);");
+    let genes_over_species = GenesOverSpecies::new_single_gene(genes, species).unwrap();
+    let candidates = std::collections::HashSet::new();
+    let source = CancellationTokenSource::new();
+    source.cancel();
+    let mut factory = EpisodeFeasabilityAlgorithmFactoryBuilder::default().create().unwrap();
+    let input = EpisodeFeasabilityInput::new(&genes_over_species, &candidates);
+    let result = factory.create(input).unwrap().run_with_cancellation(&mut source.token());
+    assert!(matches!(result, Err(AlgorithmError::Cancelled)));
+}
+
+fn counting_callback(counter: &Arc<AtomicUsize>) -> impl FnOnce() + Send + 'static {
+    let counter = counter.clone();
+    move || { counter.fetch_add(1, Ordering::SeqCst); }
+}
+
+#[test]
+fn test_registrations() {
+    let source = CancellationTokenSource::new();
+    let token = source.token();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let _first = token.register(counting_callback(&counter));
+    let second = token.register(counting_callback(&counter));
+    drop(second);
+    source.cancel();
+    source.cancel();
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+    assert_eq!(token.state(), TokenState::Cancelled);
+    let _late = token.register(counting_callback(&counter));
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+    let _never = CancellationToken::none().register(counting_callback(&counter));
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_cancel_after() {
+    let source = CancellationTokenSource::new();
+    let token = source.token();
+    let start = Instant::now();
+    source.cancel_after(Duration::from_millis(30));
+    assert!(!token.is_cancelled());
+    assert!(token.wait_cancelled(Duration::from_secs(10)));
+    assert!(start.elapsed() >= Duration::from_millis(30));
+    assert!(!CancellationToken::none().wait_cancelled(Duration::from_millis(1)));
+}
+
+#[test]
+fn test_dropped_source_disarms_timer() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let source = CancellationTokenSource::new();
+    let token = source.token();
+    let _registration = token.register(counting_callback(&counter));
+    source.cancel_after(Duration::from_millis(10));
+    drop(source);
+    assert!(!token.wait_cancelled(Duration::from_millis(100)));
+    assert_eq!(counter.load(Ordering::SeqCst), 0);
+    assert_eq!(token.state(), TokenState::NotCancelled);
+}
+
+#[test]
+fn test_cancel_races_with_timer() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let rounds = 200;
+    for _ in 0..rounds {
+        let source = CancellationTokenSource::new();
+        let token = source.token();
+        let _registration = token.register(counting_callback(&counter));
+        source.cancel_after(Duration::from_millis(1));
+        thread::sleep(Duration::from_millis(1));
+        source.cancel();
+        assert!(token.wait_cancelled(Duration::from_secs(10)));
+    }
+    // Give the timer thread time to process all deadlines.
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(counter.load(Ordering::SeqCst), rounds);
+}
+
+#[test]
+fn test_state_is_monotonic() {
+    let source = CancellationTokenSource::new();
+    let token = source.token();
+    let _registration = token.register(|| thread::sleep(Duration::from_millis(20)));
+    let observed = thread::scope(|scope| {
+        let observer = scope.spawn(|| {
+            let mut states = vec![token.state()];
+            while *states.last().unwrap() != TokenState::Cancelled {
+                states.push(token.state());
+            }
+            states
+        });
+        source.cancel_after(Duration::from_millis(5));
+        observer.join().unwrap()
+    });
+    assert!(observed.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(observed.contains(&TokenState::Cancelling));
+    assert_eq!(observed.first(), Some(&TokenState::NotCancelled));
+}
@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+
+use dagex::{
+    const_parse_newick,
+    core::Node,
+    phylo::{
+        parse_newick_from_str_with_options,
+        NewickParseOptions,
+        PhylogeneticNetwork,
+        PhylogeneticNetworkDTO,
+        Taxon}};
+use dagex_algorithms::{
+    cancellation::CancellationTokenSource,
+    containment::{
+        contains_tree,
+        ContainmentAlgorithmFactoryBuilder,
+        ContainmentInputValidationError,
+        ContainmentResult},
+    traits::{Algorithm, AlgorithmError, AlgorithmFactory, AlgorithmFactoryBuilder}};
+use rstest::rstest;
+
+fn taxa(names: &[&str]) -> HashSet<Taxon> {
+    names.iter().map(|name| Taxon::new(name).unwrap()).collect()
+}
+
+fn check(network: &PhylogeneticNetwork, cluster: &[&str]) -> ContainmentResult {
+    let mut factory = ContainmentAlgorithmFactoryBuilder::default().create().unwrap();
+    let cluster = taxa(cluster);
+    factory.create((network, &cluster)).unwrap().run().unwrap()
+}
+
+/// Taxa below witness node, following only arrows into reticulations from
+/// the chosen parents.
+fn displayed_cluster(network: &PhylogeneticNetwork, result: &ContainmentResult) -> HashSet<Taxon> {
+    let witness = result.witness().unwrap();
+    let graph = network.graph();
+    let reticulations: Vec<Node> = graph.iter_nodes()
+        .filter(|node| graph.get_predecessors(*node).len() > 1)
+        .collect();
+    assert_eq!(witness.parents().iter().map(|(node, _)| *node).collect::<Vec<_>>(), reticulations);
+    let mut stack = vec![witness.node()];
+    let mut result = HashSet::new();
+    while let Some(node) = stack.pop() {
+        if let Some(taxon) = network.taxa().get(&node) {
+            result.insert(taxon.clone());
+        }
+        for successor in graph.get_successors(node) {
+            let kept = witness.parents().iter()
+                .find(|(reticulation, _)| reticulation == successor)
+                .map_or(true, |(_, parent)| *parent == node);
+            if kept {
+                stack.push(*successor);
+            }
+        }
+    }
+    result
+}
+
+#[rstest]
+#[case(&["A", "D"], true)]
+#[case(&["C", "D"], true)]
+#[case(&["A", "C", "D"], true)]
+#[case(&["D"], true)]
+#[case(&["A"], true)]
+#[case(&["A", "C"], false)]
+fn test_single_reticulation(#[case] cluster: &[&str], #[case] expected: bool) {
+    let network = const_parse_newick!("((A,(D)#H1),(#H1,C));");
+    let result = check(&network, cluster);
+    assert_eq!(result.is_contained(), expected);
+    if expected {
+        assert_eq!(displayed_cluster(&network, &result), taxa(cluster));
+    }
+    else
+    {
+        assert!(result.witness().is_none());
+    }
+}
+
+#[test]
+fn test_tree() {
+    let network = const_parse_newick!("(((A,B),C),D);");
+    assert!(check(&network, &["A", "B"]).is_contained());
+    assert!(check(&network, &["A", "B", "C"]).is_contained());
+    assert!(!check(&network, &["B", "C"]).is_contained());
+    assert!(check(&network, &["A", "B"]).witness().unwrap().parents().is_empty());
+}
+
+/// Chain of `size` gadgets `u -> a, u -> b, a -> r, b -> r`, with leaves
+/// `A<i>` and `B<i>` below `a` and `b`, and `r` leading to the next gadget.
+/// The last `r` leads to leaf `Z`. Displays `2^size` trees.
+fn gadget_chain(size: usize) -> PhylogeneticNetwork {
+    let mut arrows = Vec::new();
+    let mut leaves = Vec::new();
+    for idx in 0..size {
+        let next = if idx + 1 == size { "Z".to_owned() } else { format!("u{}", idx + 1) };
+        for (source, target) in [
+            (format!("u{idx}"), format!("a{idx}")),
+            (format!("u{idx}"), format!("b{idx}")),
+            (format!("a{idx}"), format!("r{idx}")),
+            (format!("b{idx}"), format!("r{idx}")),
+            (format!("a{idx}"), format!("A{idx}")),
+            (format!("b{idx}"), format!("B{idx}")),
+            (format!("r{idx}"), next)]
+        {
+            arrows.push((source, target));
+        }
+        leaves.push(format!("A{idx}"));
+        leaves.push(format!("B{idx}"));
+    }
+    leaves.push("Z".to_owned());
+    let arrows: Vec<(&str, &str)> = arrows.iter().map(|(s, t)| (s.as_str(), t.as_str())).collect();
+    let taxa: Vec<(&str, &str)> = leaves.iter().map(|leaf| (leaf.as_str(), leaf.as_str())).collect();
+    let (dto, _) = PhylogeneticNetworkDTO::from_named_arrows(&arrows, &taxa).unwrap();
+    PhylogeneticNetwork::from_dto(&dto).unwrap()
+}
+
+#[test]
+fn test_many_reticulations() {
+    let network = gadget_chain(30);
+    assert_eq!(network.reticulation_count(), 30);
+
+    let result = check(&network, &["B29", "Z"]);
+    assert!(result.is_contained());
+    assert_eq!(displayed_cluster(&network, &result), taxa(&["B29", "Z"]));
+
+    let cluster = ["A10", "B11", "A12", "A13", "B13", "A14", "B14"];
+    let result = check(&network, &cluster);
+    assert!(!result.is_contained());
+
+    let mut cluster: Vec<String> = (11..30)
+        .flat_map(|idx| [format!("A{idx}"), format!("B{idx}")])
+        .collect();
+    cluster.extend(["A10".to_owned(), "Z".to_owned()]);
+    let cluster: Vec<&str> = cluster.iter().map(String::as_str).collect();
+    let result = check(&network, &cluster);
+    assert!(result.is_contained());
+    assert_eq!(displayed_cluster(&network, &result), taxa(&cluster));
+
+    assert!(!check(&network, &["A0", "B0"]).is_contained());
+}
+
+#[test]
+fn test_contains_tree() {
+    let network = const_parse_newick!("((A,(D)#H1),(#H1,C));");
+    assert!(contains_tree(&network, &const_parse_newick!("((A,D),C);")).unwrap());
+    assert!(contains_tree(&network, &const_parse_newick!("(A,(D,C));")).unwrap());
+    assert!(!contains_tree(&network, &const_parse_newick!("((A,C),D);")).unwrap());
+    let result = contains_tree(&network, &network);
+    assert!(matches!(result, Err(ContainmentInputValidationError::NotTree)), "Invalid result: {result:?}");
+    let result = contains_tree(&network, &const_parse_newick!("((A,X),C);"));
+    assert!(
+        matches!(&result, Err(ContainmentInputValidationError::UnknownTaxon(taxon)) if taxon.value().as_str() == "X"),
+        "Invalid result: {result:?}");
+}
+
+#[test]
+fn test_validation() {
+    let mut factory = ContainmentAlgorithmFactoryBuilder::default().create().unwrap();
+    let network = const_parse_newick!("((A,B),(A,C));");
+    let cluster = taxa(&["B"]);
+    let result = factory.create((&network, &cluster));
+    assert!(
+        matches!(&result, Err(ContainmentInputValidationError::DuplicateTaxon(taxon)) if taxon.value().as_str() == "A"),
+        "Invalid result: {:?}", result.err());
+
+    let network = const_parse_newick!("((A,B),C);");
+    let empty = HashSet::new();
+    let result = factory.create((&network, &empty));
+    assert!(matches!(result, Err(ContainmentInputValidationError::EmptyCluster)), "Invalid result: {:?}", result.err());
+    let cluster = taxa(&["Y", "X", "A"]);
+    let result = factory.create((&network, &cluster));
+    assert!(
+        matches!(&result, Err(ContainmentInputValidationError::UnknownTaxon(taxon)) if taxon.value().as_str() == "X"),
+        "Invalid result: {:?}", result.err());
+    assert_eq!(result.err().unwrap().to_string(), "cluster taxon X is missing in the network");
+
+    let options = NewickParseOptions::default().with_require_binary(false);
+    let network = parse_newick_from_str_with_options("(A,B,C);", &options).unwrap().network;
+    let cluster = taxa(&["A"]);
+    let result = factory.create((&network, &cluster));
+    assert!(matches!(result, Err(ContainmentInputValidationError::NotBinary)), "Invalid result: {:?}", result.err());
+}
+
+#[test]
+fn test_cancellation() {
+    let network = gadget_chain(5);
+    let mut factory = ContainmentAlgorithmFactoryBuilder::default().create().unwrap();
+    let cluster = taxa(&["A0", "B0"]);
+    let source = CancellationTokenSource::new();
+    source.cancel();
+    let result = factory.create((&network, &cluster)).unwrap().run_with_cancellation(&mut source.token());
+    assert!(matches!(result, Err(AlgorithmError::Cancelled)));
+}
@@ -1,5 +1,11 @@
-use dagex::core::{ArrowDTO, DirectedGraph, DirectedGraphDTO};
-use dagex_algorithms::{depth::DepthAlgorithmFactoryBuilder, traits::{Algorithm, AlgorithmFactory, AlgorithmFactoryBuilder}};
+use std::sync::{Arc, Mutex};
+
+use dagex::core::{ArrowDTO, DirectedGraph, DirectedGraphDTO, Node};
+use dagex_algorithms::{depth::{DepthAlgorithmFactory, DepthAlgorithmFactoryBuilder, DepthInputValidationError}, traits::{Algorithm, AlgorithmFactory, AlgorithmFactoryBuilder, AlgorithmMetrics}};
+use raf_structural_logging::{
+    core::{CoreLoggerFactory, CoreLoggerFactoryBuilder},
+    models::{Log, LogLevel, SLObject},
+    traits::{StructuralLogHandler, StructuralLoggerFactoryBuilder}};
 use rstest::rstest;
 
 fn build_graph(arr: &[(i32, i32)]) -> DirectedGraph {
@@ -30,3 +36,178 @@ fn test_depth_algorithm(#[case] arrows: &[(i32, i32)], #[case] expected: i32) {
     let result = algo.run().unwrap();
     assert_eq!(result.max_depth(), expected);
 }
+
+#[test]
+fn test_depth_of_long_path() {
+    let number_of_nodes = 500_000;
+    let arrows = (1..number_of_nodes)
+        .map(|idx| ArrowDTO::new(idx - 1, idx))
+        .collect();
+    let dto = DirectedGraphDTO::new(number_of_nodes, arrows);
+    let graph = DirectedGraph::from_dto(&dto).unwrap();
+    let mut factory = DepthAlgorithmFactoryBuilder::default().create().unwrap();
+    let result = factory.create(&graph).unwrap().run().unwrap();
+    assert_eq!(result.max_depth(), number_of_nodes - 1);
+    assert_eq!(result.processed_nodes(), 500_000);
+}
+
+#[test]
+fn test_each_node_processed_once() {
+    // Chain of diamonds: every node below the root is reachable through
+    // exponentially many paths.
+    let diamonds = 1_000;
+    let mut arrows = Vec::new();
+    for idx in 0..diamonds {
+        let top = 3 * idx;
+        arrows.extend([(top, top + 1), (top, top + 2), (top + 1, top + 3), (top + 2, top + 3)]);
+    }
+    let graph = build_graph(&arrows);
+    let mut factory = DepthAlgorithmFactoryBuilder::default().create().unwrap();
+    let result = factory.create(&graph).unwrap().run().unwrap();
+    assert_eq!(result.max_depth(), 2 * diamonds);
+    assert_eq!(result.processed_nodes(), graph.number_of_nodes() as usize);
+}
+
+#[rstest]
+#[case(&[(0, 1), (0, 2), (1, 3)], &[0, 1, 1, 2])]
+#[case(&[(0, 1), (1, 2), (2, 3), (0, 4)], &[0, 1, 2, 3, 1])]
+fn test_depth_of(#[case] arrows: &[(i32, i32)], #[case] expected: &[i32]) {
+    let graph = build_graph(arrows);
+    let mut factory = DepthAlgorithmFactoryBuilder::default().create().unwrap();
+    let result = factory.create(&graph).unwrap().run().unwrap();
+    let depths: Vec<i32> = graph.iter_nodes()
+        .map(|node| result.depth_of(node).unwrap())
+        .collect();
+    assert_eq!(depths, expected);
+    assert_eq!(result.depth_of(Node::from(-1)), None);
+    assert_eq!(result.depth_of(Node::from(graph.number_of_nodes())), None);
+}
+
+#[test]
+fn test_depth_of_reticulation() {
+    // Node 4 is reachable both by 0 -> 4 and by 0 -> 1 -> 2 -> 3 -> 4.
+    let graph = build_graph(&[(0, 1), (1, 2), (2, 3), (3, 4), (0, 4), (4, 5), (3, 6)]);
+    let mut factory = DepthAlgorithmFactoryBuilder::default().create().unwrap();
+    let result = factory.create(&graph).unwrap().run().unwrap();
+    assert_eq!(result.depth_of(Node::from(4)), Some(4));
+    assert_eq!(result.depth_of(Node::from(5)), Some(5));
+    assert_eq!(result.depth_of(Node::from(6)), Some(4));
+    assert_eq!(result.max_depth(), 5);
+    assert_eq!(result.depths().len(), 7);
+}
+
+#[test]
+fn test_max_nodes() {
+    let graph = build_graph(&[(0, 1), (0, 2), (1, 3)]);
+    let mut builder = DepthAlgorithmFactoryBuilder::default();
+    builder.set_max_nodes(3);
+    let mut factory = builder.create().unwrap();
+    assert_eq!(factory.max_nodes(), 3);
+    let Err(err) = factory.create(&graph) else {
+        panic!("Expected GraphTooBig error.");
+    };
+    assert_eq!(err, DepthInputValidationError::GraphTooBig { limit: 3, size: 4 });
+    assert_eq!(err.to_string(), "input graph has 4 nodes, exceeding the maximum of 3");
+
+    let mut builder = DepthAlgorithmFactoryBuilder::default();
+    builder.set_max_nodes(4);
+    assert!(builder.create().unwrap().create(&graph).is_ok());
+
+    let mut builder = DepthAlgorithmFactoryBuilder::default();
+    builder.set_max_nodes(usize::MAX);
+    assert_eq!(builder.create().unwrap().max_nodes(), DepthAlgorithmFactory::max_size());
+    assert_eq!(
+        DepthAlgorithmFactoryBuilder::default().create().unwrap().max_nodes(),
+        DepthAlgorithmFactory::max_size());
+}
+
+#[test]
+fn test_estimated_memory_bytes() {
+    let factory = DepthAlgorithmFactoryBuilder::default().create().unwrap();
+    let small = factory.estimated_memory_bytes(&build_graph(&[(0, 1)]));
+    let big = factory.estimated_memory_bytes(&build_graph(&[(0, 1), (1, 2), (2, 3)]));
+    assert!(small > 0);
+    assert_eq!(big, 2 * small);
+    assert!(small >= 2 * core::mem::size_of::<i32>());
+}
+
+#[rstest]
+#[case(&[(0, 1)])]
+#[case(&[(0, 1), (0, 2), (1, 3)])]
+#[case(&[(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)])]
+fn test_run_instrumented(#[case] arrows: &[(i32, i32)]) {
+    let graph = build_graph(arrows);
+    let mut factory = DepthAlgorithmFactoryBuilder::default().create().unwrap();
+    let expected_bytes = factory.estimated_memory_bytes(&graph) as u64;
+    let (result, metrics) = factory.create(&graph).unwrap().run_instrumented().unwrap();
+    assert_eq!(metrics.nodes_visited(), graph.number_of_nodes() as u64);
+    assert_eq!(metrics.allocations_estimate(), expected_bytes);
+    assert_eq!(result.max_depth(), factory.create(&graph).unwrap().run().unwrap().max_depth());
+
+    let text = AlgorithmMetrics::new(metrics.elapsed(), 3, 40).to_string();
+    assert!(text.ends_with(", nodes_visited=3, allocations_estimate=40"), "Unexpected text: {text}");
+}
+
+#[derive(Default)]
+struct MemoryHandler {
+    logs: Mutex<Vec<Log>>,
+}
+
+impl StructuralLogHandler for MemoryHandler {
+    fn handle(&self, log: &Log) {
+        self.logs.lock().unwrap().push(log.clone());
+    }
+}
+
+fn build_memory_logger_factory() -> (Arc<MemoryHandler>, Arc<CoreLoggerFactory>) {
+    let handler = Arc::new(MemoryHandler::default());
+    let mut builder = CoreLoggerFactoryBuilder::default();
+    builder.add_handler(handler.clone());
+    (handler, Arc::new(builder.build()))
+}
+
+fn templates_and_keys(logs: &[Log]) -> Vec<(LogLevel, &str, Vec<&str>)> {
+    logs.iter()
+        .map(|log| (log.level(), log.data().template(), log.data().params().keys().collect()))
+        .collect()
+}
+
+#[test]
+fn test_logging() {
+    let (handler, logger_factory) = build_memory_logger_factory();
+    let mut builder = DepthAlgorithmFactoryBuilder::default();
+    builder.set_logger_factory(&logger_factory);
+    builder.set_max_nodes(3);
+    let mut factory = builder.create().unwrap();
+
+    let graph = build_graph(&[(0, 1), (0, 2)]);
+    factory.create(&graph).unwrap().run_instrumented().unwrap();
+    let too_big = build_graph(&[(0, 1), (0, 2), (1, 3)]);
+    assert!(factory.create(&too_big).is_err());
+
+    let logs = handler.logs.lock().unwrap();
+    assert_eq!(templates_and_keys(&logs), vec![
+        (LogLevel::Info, "Created for {input}", vec!["input"]),
+        (LogLevel::Info, "Run started", vec![]),
+        (LogLevel::Info, "Run finished in {elapsed_us} us", vec!["elapsed_us"]),
+        (
+            LogLevel::Info,
+            "Run metrics: {elapsed_us} us, {nodes_visited} nodes visited, {allocations_estimate} bytes allocated",
+            vec!["allocations_estimate", "elapsed_us", "nodes_visited"],
+        ),
+        (LogLevel::Warning, "Input rejected with {error}", vec!["error", "limit", "size"]),
+    ]);
+    assert!(logs[..4].iter().all(|log| log.logger_name() == logs[0].logger_name()));
+    assert!(logs[0].logger_name().starts_with("DepthAlgorithm-"));
+    assert_ne!(logs[4].logger_name(), logs[0].logger_name());
+
+    let Some(SLObject::Dict(input)) = logs[0].data().params().get("input") else {
+        panic!("Expected input dict.");
+    };
+    assert_eq!(input.get("number_of_nodes"), Some(&SLObject::from(3)));
+    assert_eq!(input.get("rooted"), Some(&SLObject::from(true)));
+    assert_eq!(logs[3].data().params().get("nodes_visited"), Some(&SLObject::from(3u64)));
+    let rejected = logs[4].data().params();
+    assert_eq!(rejected.get("error"), Some(&SLObject::from("GraphTooBig")));
+    assert_eq!(rejected.get("size"), Some(&SLObject::from(4usize)));
+}
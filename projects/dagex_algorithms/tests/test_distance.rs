@@ -0,0 +1,85 @@
+use dagex::{const_parse_newick, phylo::PhylogeneticNetwork};
+use dagex_algorithms::{
+    distance::{DistanceAlgorithmFactoryBuilder, DistanceInputValidationError, DistanceResult},
+    traits::{Algorithm, AlgorithmFactory, AlgorithmFactoryBuilder}};
+use rstest::rstest;
+
+fn distance(first: &PhylogeneticNetwork, second: &PhylogeneticNetwork) -> DistanceResult {
+    let mut factory = DistanceAlgorithmFactoryBuilder::default().create().unwrap();
+    factory.create((first, second)).unwrap().run().unwrap()
+}
+
+fn names(clusters: &[dagex::phylo::TaxonBitSet]) -> Vec<Vec<&str>> {
+    clusters.iter()
+        .map(|cluster| {
+            let mut names: Vec<&str> = cluster.iter().map(|taxon| taxon.value().as_str()).collect();
+            names.sort_unstable();
+            names
+        })
+        .collect()
+}
+
+#[rstest]
+#[case(const_parse_newick!("((A,B),(C,D));"), const_parse_newick!("((B,A),(D,C));"))]
+#[case(const_parse_newick!("(A,B);"), const_parse_newick!("(B,A);"))]
+#[case(const_parse_newick!("((A,(D)#H1),(#H1,C));"), const_parse_newick!("((C,(D)#H1),(#H1,A));"))]
+fn test_identical(#[case] first: PhylogeneticNetwork, #[case] second: PhylogeneticNetwork) {
+    let result = distance(&first, &second);
+    assert_eq!(result.raw(), 0);
+    assert_eq!(result.normalized(), 0.0);
+    assert!(result.only_in_first().is_empty() && result.only_in_second().is_empty());
+}
+
+#[test]
+fn test_four_leaves() {
+    let first = const_parse_newick!("((A,B),(C,D));");
+    let second = const_parse_newick!("((A,C),(B,D));");
+    let result = distance(&first, &second);
+    assert_eq!(result.raw(), 4);
+    assert_eq!(result.normalized(), 1.0);
+    let mut only_in_first = names(result.only_in_first());
+    only_in_first.sort();
+    assert_eq!(only_in_first, vec![vec!["A", "B"], vec!["C", "D"]]);
+
+    let third = const_parse_newick!("(((A,B),C),D);");
+    let result = distance(&first, &third);
+    assert_eq!(result.raw(), 2);
+    assert_eq!(names(result.only_in_first()), vec![vec!["C", "D"]]);
+    assert_eq!(names(result.only_in_second()), vec![vec!["A", "B", "C"]]);
+    assert_eq!(result.normalized(), 0.5);
+}
+
+#[test]
+fn test_network_and_its_tree() {
+    let network = const_parse_newick!("((A,(D)#H1),(#H1,C));");
+    let tree = const_parse_newick!("((A,D),C);");
+    let result = distance(&network, &tree);
+    assert_eq!(result.raw(), 1);
+    assert_eq!(names(result.only_in_first()), vec![vec!["C", "D"]]);
+    assert!(result.only_in_second().is_empty());
+    assert!((result.normalized() - 1.0 / 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_different_taxa() {
+    let first = const_parse_newick!("((A,B),C);");
+    let second = const_parse_newick!("((A,D),(E,C));");
+    let mut factory = DistanceAlgorithmFactoryBuilder::default().create().unwrap();
+    let Err(DistanceInputValidationError::DifferentTaxa { missing_in_first, missing_in_second })
+        = factory.create((&first, &second)) else
+    {
+        panic!("Expected DifferentTaxa error.");
+    };
+    let names = |taxa: &[dagex::phylo::Taxon]| taxa.iter()
+        .map(|taxon| taxon.value().as_str().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(names(&missing_in_first), vec!["D", "E"]);
+    assert_eq!(names(&missing_in_second), vec!["B"]);
+
+    let Err(error) = factory.create((&first, &second)) else {
+        panic!("Expected DifferentTaxa error.");
+    };
+    assert_eq!(
+        error.to_string(),
+        "networks have different taxa, missing in first: [D, E], missing in second: [B]");
+}
@@ -1,14 +1,33 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::Arc};
 
-use dagex::{const_parse_newick, phylo::GenesOverSpecies};
+use dagex::{
+    const_parse_newick,
+    core::Node,
+    phylo::{
+        parse_newick_from_str,
+        parse_newick_from_str_with_options,
+        GenesOverSpecies,
+        NewickParseOptions,
+        PhylogeneticNetwork,
+        simulation::{simulate_gene_trees, simulate_species_tree, GeneSimulationParams}}};
 use dagex_algorithms::{
     episode_feasibility::{
+        EpisodeFeasabilityAlgorithmFactory,
         EpisodeFeasabilityAlgorithmFactoryBuilder,
-        EpisodeFeasabilityInput},
+        EpisodeFeasabilityInputValidationError,
+        EpisodeFeasabilityInput,
+        EpisodeFeasabilityOutput,
+        FeasibilityViolation,
+        minimize_infeasible,
+        species_precomputations,
+        MinimizeInfeasibleError},
     traits::{
         Algorithm,
         AlgorithmFactory,
         AlgorithmFactoryBuilder}};
+use rand::{rngs::StdRng, SeedableRng};
+use raf_multi_valued_logic::tribool::TriBool;
+use rstest::rstest;
 
 
 #[test]
@@ -31,3 +50,307 @@ fn test_episode_feasibility_algorithm() {
     let result = algo.run().unwrap();
     assert!(result.result().get(&genes_id).unwrap());
 }
+
+fn build_instance(
+    genes: &str,
+    species: &str,
+    candidates: impl Fn(&PhylogeneticNetwork) -> HashSet<Node>) -> (GenesOverSpecies, HashSet<Node>)
+{
+    let genes = parse_newick_from_str(genes).unwrap().network;
+    let species = parse_newick_from_str(species).unwrap().network;
+    let episode_candidates = candidates(&species);
+    let genes_over_species = GenesOverSpecies::new_single_gene(genes, species).unwrap();
+    (genes_over_species, episode_candidates)
+}
+
+fn run<'a>(
+    genes_over_species: &'a GenesOverSpecies,
+    episode_candidates: &'a HashSet<Node>,
+    trace: bool) -> EpisodeFeasabilityOutput<'a>
+{
+    let mut factory = EpisodeFeasabilityAlgorithmFactoryBuilder::default()
+        .create()
+        .unwrap()
+        .with_trace(trace);
+    let episode_input = EpisodeFeasabilityInput::new(genes_over_species, episode_candidates);
+    factory.create(episode_input).unwrap().run().unwrap()
+}
+
+fn parent_of_leaf(species: &PhylogeneticNetwork, taxon: &str) -> Node {
+    let leaf = species.taxa().iter()
+        .find(|kvp| kvp.1.value().as_str() == taxon)
+        .map(|kvp| *kvp.0)
+        .unwrap();
+    species.graph().get_predecessors(leaf)[0]
+}
+
+#[test]
+fn test_trace_is_disabled_by_default() {
+    let (genes_over_species, episode_candidates)
+        = build_instance("((a,b),c);", "((a,c),b);", |_| HashSet::new());
+    let id = genes_over_species.gene_networks()[0].id();
+    let result = run(&genes_over_species, &episode_candidates, false);
+    assert!(!result.result()[&id]);
+    assert!(result.trace(id).is_none());
+    assert_eq!(result.explain_infeasibility(), None);
+}
+
+#[rstest]
+#[case("((a,b),c);", "((a,c),b);")]
+#[case("((a,b),c);", "(((a,c),b),d);")]
+#[case("(a,b);", "((a,c),(b,d));")]
+fn test_trace_does_not_change_result(
+    #[case] genes: &str,
+    #[case] species: &str,
+    #[values(false, true)] root_candidate: bool)
+{
+    let (genes_over_species, episode_candidates) = build_instance(genes, species, |species| {
+        if root_candidate { HashSet::from([species.root()]) } else { HashSet::new() }
+    });
+    let id = genes_over_species.gene_networks()[0].id();
+    let traced = run(&genes_over_species, &episode_candidates, true);
+    let untraced = run(&genes_over_species, &episode_candidates, false);
+    assert_eq!(traced.result(), untraced.result());
+    let root_trace = traced.trace(id).unwrap()[&genes_over_species.species_network().root()];
+    assert_eq!(root_trace.delta_down == TriBool::TRUE, traced.result()[&id]);
+    assert_eq!(traced.explain_infeasibility().is_none(), traced.result()[&id]);
+}
+
+#[test]
+fn test_witness_not_candidate_node() {
+    let (genes_over_species, episode_candidates)
+        = build_instance("((a,b),c);", "((a,c),b);", |_| HashSet::new());
+    let species = genes_over_species.species_network();
+    let id = genes_over_species.gene_networks()[0].id();
+    let result = run(&genes_over_species, &episode_candidates, true);
+    let witness = result.explain_infeasibility().unwrap();
+    assert_eq!(witness.gene_network, id);
+    assert_eq!(witness.violation, FeasibilityViolation::NotCandidateNode(species.root()));
+
+    let trace = result.trace(id).unwrap();
+    assert_eq!(trace.len(), species.graph().number_of_nodes() as usize);
+    let root_trace = trace[&species.root()];
+    assert_eq!(root_trace.epsilon, TriBool::UNKNOWN);
+    assert_eq!(root_trace.delta_down, TriBool::UNKNOWN);
+    assert!(!root_trace.episode_candidate);
+}
+
+#[test]
+fn test_witness_blocked_arrow() {
+    let (genes_over_species, episode_candidates)
+        = build_instance("((a,b),c);", "(((a,c),b),d);", |_| HashSet::new());
+    let species = genes_over_species.species_network();
+    let blocked = species.graph().get_predecessors(parent_of_leaf(species, "a"))[0];
+    let id = genes_over_species.gene_networks()[0].id();
+    let result = run(&genes_over_species, &episode_candidates, true);
+    assert_eq!(
+        result.explain_infeasibility().unwrap().violation,
+        FeasibilityViolation::BlockedArrow(species.root(), blocked));
+    let trace = result.trace(id).unwrap();
+    assert_eq!(trace[&blocked].delta_down, TriBool::UNKNOWN);
+    assert_eq!(trace[&species.root()].epsilon, TriBool::FALSE);
+
+    let episode_candidates = HashSet::from([blocked]);
+    let result = run(&genes_over_species, &episode_candidates, true);
+    assert!(result.result()[&id]);
+    assert_eq!(result.explain_infeasibility(), None);
+}
+
+#[rstest]
+fn test_multiple_gene_networks(#[values(false, true)] root_candidate: bool) {
+    const SPECIES: &str = "((a,c),(b,d));";
+    let parse = |text: &str| Arc::new(parse_newick_from_str(text).unwrap().network);
+    let genes: Vec<_> = ["((a,c),b);", "((a,b),c);", "(a,d);", "((a,c),(b,d));", "((a,d),(b,c));"]
+        .into_iter()
+        .map(parse)
+        .collect();
+    let species = parse_newick_from_str(SPECIES).unwrap().network;
+    let episode_candidates = if root_candidate { HashSet::from([species.root()]) } else { HashSet::new() };
+    let genes_over_species = GenesOverSpecies::from_shared_networks(genes.clone(), species).unwrap();
+    let before = species_precomputations();
+    let output = run(&genes_over_species, &episode_candidates, false);
+    assert_eq!(species_precomputations() - before, 1);
+    assert_eq!(output.result().len(), genes.len());
+    let before = species_precomputations();
+    run(&genes_over_species, &episode_candidates, true);
+    assert_eq!(species_precomputations() - before, 1);
+
+    for gene in &genes {
+        let species = parse_newick_from_str(SPECIES).unwrap().network;
+        let single = GenesOverSpecies::from_shared_networks(vec![gene.clone()], species).unwrap();
+        let before = species_precomputations();
+        let single_output = run(&single, &episode_candidates, false);
+        assert_eq!(species_precomputations() - before, 1);
+        assert_eq!(output.is_feasible(gene.id()), single_output.is_feasible(gene.id()));
+    }
+
+    let infeasible = output.result().values().filter(|value| !**value).count();
+    assert_eq!(output.infeasible_count(), infeasible);
+    assert_eq!(output.all_feasible(), infeasible == 0);
+    assert_eq!(output.is_feasible(genes[3].id()), Some(true));
+    assert!(infeasible > 0 || root_candidate);
+}
+
+#[test]
+fn test_max_nodes() {
+    let (genes_over_species, episode_candidates) = build_instance(
+        "((a,c),b);",
+        "((a,c),(b,d));",
+        |_| HashSet::new());
+    let input = || EpisodeFeasabilityInput::new(&genes_over_species, &episode_candidates);
+    let mut builder = EpisodeFeasabilityAlgorithmFactoryBuilder::default();
+    builder.set_max_nodes(6);
+    let mut factory = builder.create().unwrap();
+    let Err(err) = factory.create(input()) else {
+        panic!("Expected GraphTooBig error.");
+    };
+    assert_eq!(err, EpisodeFeasabilityInputValidationError::GraphTooBig { limit: 6, size: 7 });
+    assert_eq!(err.to_string(), "input network has 7 nodes, exceeding the maximum of 6");
+
+    let mut builder = EpisodeFeasabilityAlgorithmFactoryBuilder::default();
+    builder.set_max_nodes(7);
+    assert!(builder.create().unwrap().create(input()).is_ok());
+    assert_eq!(
+        EpisodeFeasabilityAlgorithmFactoryBuilder::default().create().unwrap().max_nodes(),
+        EpisodeFeasabilityAlgorithmFactory::max_size());
+}
+
+#[test]
+fn test_estimated_memory_bytes() {
+    let (genes_over_species, episode_candidates) = build_instance(
+        "((a,c),b);",
+        "((a,c),(b,d));",
+        |_| HashSet::new());
+    let input = EpisodeFeasabilityInput::new(&genes_over_species, &episode_candidates);
+    let factory = EpisodeFeasabilityAlgorithmFactoryBuilder::default().create().unwrap();
+    let plain = factory.estimated_memory_bytes(&input);
+    let traced = factory.with_trace(true).estimated_memory_bytes(&input);
+    assert!(plain >= 7);
+    assert!(traced > plain);
+}
+
+#[rstest]
+#[case("(((a,b),c),d);")]
+#[case("((a,b),(c,d));")]
+#[case("((a,(b)#H1),((#H1,c),d));")]
+fn test_run_instrumented(#[case] species: &str) {
+    let species = parse_newick_from_str(species).unwrap().network;
+    let genes = ["a;", "d;"]
+        .map(|text| parse_newick_from_str(text).unwrap().network)
+        .to_vec();
+    let size = species.graph().number_of_nodes() as u64;
+    let genes_over_species = GenesOverSpecies::new(genes, species).unwrap();
+    let episode_candidates = HashSet::new();
+    for trace in [false, true] {
+        let input = EpisodeFeasabilityInput::new(&genes_over_species, &episode_candidates);
+        let mut factory = EpisodeFeasabilityAlgorithmFactoryBuilder::default()
+            .create()
+            .unwrap()
+            .with_trace(trace);
+        let expected_bytes = factory.estimated_memory_bytes(&input) as u64;
+        let (output, metrics) = factory.create(input).unwrap().run_instrumented().unwrap();
+        assert_eq!(output.result().len(), 2);
+        assert_eq!(metrics.allocations_estimate(), expected_bytes);
+        // Each gene leaf visits every species node once, except for the
+        // reticulation and its leaf, reached along both parents.
+        let extra = if genes_over_species.species_network().reticulation_count() > 0 { 2 * 2 } else { 0 };
+        assert_eq!(metrics.nodes_visited(), 2 * size + extra);
+    }
+}
+
+#[rstest]
+#[case("(a,b,c);", "((a,b),c);", false)]
+#[case("((a,b),c);", "(a,b,c);", true)]
+fn test_multifurcating_input_rejected(#[case] genes: &str, #[case] species: &str, #[case] species_rejected: bool) {
+    let options = NewickParseOptions::default().with_require_binary(false);
+    let genes = parse_newick_from_str_with_options(genes, &options).unwrap().network;
+    let species = parse_newick_from_str_with_options(species, &options).unwrap().network;
+    let expected_id = if species_rejected { species.id() } else { genes.id() };
+    let genes_over_species = GenesOverSpecies::new_single_gene(genes, species).unwrap();
+    let episode_candidates = HashSet::new();
+    let input = EpisodeFeasabilityInput::new(&genes_over_species, &episode_candidates);
+    let mut factory = EpisodeFeasabilityAlgorithmFactoryBuilder::default().create().unwrap();
+    let Err(error) = factory.create(input) else { panic!("Multifurcating input accepted."); };
+    assert_eq!(error, EpisodeFeasabilityInputValidationError::NotBinary(expected_id));
+    assert_eq!(error.to_string(), format!("input network {} is not binary", i32::from(expected_id)));
+}
+
+fn instance_with_single_infeasible_gene() -> (Vec<Arc<PhylogeneticNetwork>>, GenesOverSpecies) {
+    let parse = |text: &str| Arc::new(parse_newick_from_str(text).unwrap().network);
+    let genes: Vec<_> = ["((a,c),b);", "((a,c),(b,d));", "((a,b),(c,d));", "(b,d);", "((a,c),d);"]
+        .into_iter()
+        .map(parse)
+        .collect();
+    let species = parse_newick_from_str("((a,c),(b,d));").unwrap().network;
+    let genes_over_species = GenesOverSpecies::from_shared_networks(genes.clone(), species).unwrap();
+    (genes, genes_over_species)
+}
+
+#[test]
+fn test_minimize_infeasible_isolates_gene_network() {
+    let (genes, genes_over_species) = instance_with_single_infeasible_gene();
+    let episode_candidates = HashSet::new();
+    let output = run(&genes_over_species, &episode_candidates, false);
+    assert_eq!(output.infeasible_count(), 1);
+    assert_eq!(output.is_feasible(genes[2].id()), Some(false));
+
+    let mut factory = EpisodeFeasabilityAlgorithmFactoryBuilder::default().create().unwrap();
+    let input = EpisodeFeasabilityInput::new(&genes_over_species, &episode_candidates);
+    let minimized = minimize_infeasible(&input, &mut factory, 1000).unwrap();
+    assert_eq!(minimized.gene_networks(), &[genes[2].id()]);
+    assert!(!minimized.budget_exhausted());
+    assert!(minimized.taxa().len() < 4);
+    let minimized_output = run(minimized.genes_over_species(), minimized.episode_candidates(), false);
+    assert!(!minimized_output.all_feasible());
+
+    let again = minimize_infeasible(&input, &mut factory, 1000).unwrap();
+    assert_eq!(again.gene_networks(), minimized.gene_networks());
+    assert_eq!(again.taxa(), minimized.taxa());
+    assert_eq!(again.iterations(), minimized.iterations());
+}
+
+#[test]
+fn test_minimize_infeasible_budget() {
+    let (genes, genes_over_species) = instance_with_single_infeasible_gene();
+    let episode_candidates = HashSet::new();
+    let mut factory = EpisodeFeasabilityAlgorithmFactoryBuilder::default().create().unwrap();
+    let input = EpisodeFeasabilityInput::new(&genes_over_species, &episode_candidates);
+    let minimized = minimize_infeasible(&input, &mut factory, 0).unwrap();
+    assert!(minimized.budget_exhausted());
+    assert_eq!(minimized.iterations(), 0);
+    let ids: Vec<_> = genes.iter().map(|gene| gene.id()).collect();
+    assert_eq!(minimized.gene_networks(), ids.as_slice());
+    assert_eq!(minimized.taxa().len(), 4);
+}
+
+#[test]
+fn test_minimize_feasible_input() {
+    let (genes_over_species, episode_candidates)
+        = build_instance("((a,b),c);", "((a,b),c);", |_| HashSet::new());
+    let mut factory = EpisodeFeasabilityAlgorithmFactoryBuilder::default().create().unwrap();
+    let input = EpisodeFeasabilityInput::new(&genes_over_species, &episode_candidates);
+    let result = minimize_infeasible(&input, &mut factory, 100);
+    assert!(matches!(result, Err(MinimizeInfeasibleError::Feasible)));
+}
+
+#[rstest]
+#[case(0.0, 0.0)]
+#[case(0.3, 0.0)]
+#[case(0.2, 0.2)]
+fn test_simulated_instances(#[case] duplication_rate: f64, #[case] loss_rate: f64) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let params = GeneSimulationParams::default()
+        .with_duplication_rate(duplication_rate)
+        .with_loss_rate(loss_rate);
+    for leaves in [1, 3, 6] {
+        let species = simulate_species_tree(&mut rng, leaves);
+        let episode_candidates = HashSet::from([species.root()]);
+        let simulation = simulate_gene_trees(&mut rng, &species, 4, &params);
+        let genes_over_species = simulation.genes_over_species();
+        let output = run(genes_over_species, &episode_candidates, true);
+        assert_eq!(output.result().len(), 4);
+        if duplication_rate == 0.0 && loss_rate == 0.0 {
+            assert!(output.all_feasible());
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc};
+
+use dagex::{core::DirectedGraph, phylo::parse_newick_from_str};
+use dagex_algorithms::{
+    cancellation::{CancellationToken, CancellationTokenSource},
+    depth::{DepthAlgorithmFactoryBuilder, DepthInputValidationError},
+    executor::{BatchExecutor, BatchItemError},
+    traits::{Algorithm, AlgorithmError, AlgorithmFactory, AlgorithmFactoryBuilder}};
+use raf_structural_logging::core::CoreLoggerFactory;
+use rstest::rstest;
+
+const FOREST: &str = "(A,B);((A,B),C);(A,(B,(C,D)));(((A,B),C),((D,E),F));";
+
+fn graphs() -> Vec<DirectedGraph> {
+    FOREST.split_inclusive(';')
+        .map(|tree| parse_newick_from_str(tree).unwrap().network.graph().clone())
+        .collect()
+}
+
+#[rstest]
+#[case(0)]
+#[case(1)]
+#[case(4)]
+fn test_batch_depth(#[case] threads: usize) {
+    let graphs = graphs();
+    let mut builder = DepthAlgorithmFactoryBuilder::default();
+    builder.set_max_nodes(7);
+    let executor = BatchExecutor::new(builder, threads).unwrap();
+    let mut progress = Vec::new();
+    let results = executor.run_with_progress(
+        graphs.iter().collect(),
+        |completed, total| progress.push((completed, total)));
+    let depths: Vec<_> = results.into_iter()
+        .map(|result| result.map(|depth| depth.max_depth()))
+        .collect();
+    assert_eq!(depths, vec![
+        Ok(1),
+        Ok(2),
+        Ok(3),
+        Err(BatchItemError::Validation(DepthInputValidationError::GraphTooBig { limit: 7, size: 11 }))]);
+    assert_eq!(progress, vec![(1, 4), (2, 4), (3, 4), (4, 4)]);
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct NegativeInput;
+
+struct SquareAlgorithm {
+    value: i32,
+    runs: Arc<AtomicUsize>,
+}
+
+impl<'a> Algorithm<'a> for SquareAlgorithm {
+    type Input<'b> = i32;
+
+    type Output<'b> = i32;
+
+    type Error = ();
+
+    fn run_with_cancellation(self, ct: &mut CancellationToken)
+        -> Result<Self::Output<'a>, AlgorithmError<Self::Error>>
+    {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        assert!(self.value != 13, "unlucky input");
+        if ct.is_cancelled() {
+            return Err(AlgorithmError::Cancelled);
+        }
+        Ok(self.value * self.value)
+    }
+}
+
+struct SquareFactory {
+    runs: Arc<AtomicUsize>,
+}
+
+impl AlgorithmFactory for SquareFactory {
+    type Input<'a> = i32;
+
+    type Algo<'a> = SquareAlgorithm;
+
+    type Error = NegativeInput;
+
+    fn create<'a>(&mut self, input: Self::Input<'a>)
+        -> Result<Self::Algo<'a>, Self::Error>
+    {
+        if input < 0 {
+            return Err(NegativeInput);
+        }
+        Ok(SquareAlgorithm { value: input, runs: self.runs.clone() })
+    }
+}
+
+#[derive(Default)]
+struct SquareFactoryBuilder {
+    runs: Arc<AtomicUsize>,
+}
+
+impl AlgorithmFactoryBuilder for SquareFactoryBuilder {
+    type LoggerFactory = CoreLoggerFactory;
+
+    type AlgoFactory = SquareFactory;
+
+    type Error = ();
+
+    fn set_logger_factory(&mut self, _logger_factory: &Arc<Self::LoggerFactory>) { }
+
+    fn create(self) -> Result<Self::AlgoFactory, Self::Error> {
+        Ok(SquareFactory { runs: self.runs })
+    }
+}
+
+#[rstest]
+#[case(0)]
+#[case(2)]
+fn test_batch_mixed_inputs(#[case] threads: usize) {
+    let executor = BatchExecutor::new(SquareFactoryBuilder::default(), threads).unwrap();
+    let expected = vec![
+        Ok(1),
+        Err(BatchItemError::Panicked("unlucky input".to_owned())),
+        Err(BatchItemError::Validation(NegativeInput)),
+        Ok(16),
+        Ok(0)];
+    assert_eq!(executor.run(vec![1, 13, -2, 4, 0]), expected);
+    assert_eq!(executor.run(vec![13, 3]), vec![Err(BatchItemError::Panicked("unlucky input".to_owned())), Ok(9)]);
+    assert!(executor.run(Vec::new()).is_empty());
+}
+
+#[rstest]
+#[case(1)]
+#[case(3)]
+fn test_batch_cancellation(#[case] threads: usize) {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let source = CancellationTokenSource::new();
+    let builder = SquareFactoryBuilder { runs: runs.clone() };
+    let executor = BatchExecutor::new(builder, threads)
+        .unwrap()
+        .with_cancellation(source.token());
+    let results = executor.run_with_progress((0..100).collect(), |completed, _| {
+        if completed == 2 {
+            source.cancel();
+        }
+    });
+    assert_eq!(results.len(), 100);
+    assert!(results.iter().filter(|result| result.is_ok()).count() >= 2);
+    assert!(runs.load(Ordering::SeqCst) <= threads + 2);
+    let cancelled = results.iter()
+        .filter(|result| matches!(result, Err(BatchItemError::Cancelled)))
+        .count();
+    assert!(cancelled >= 100 - threads - 2, "Too few cancelled: {cancelled}");
+    assert_eq!(executor.run(vec![2]), vec![Err(BatchItemError::Cancelled)]);
+}
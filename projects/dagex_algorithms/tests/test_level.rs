@@ -0,0 +1,92 @@
+use dagex::{const_parse_newick, core::{ArrowDTO, DirectedGraph, DirectedGraphDTO}};
+use dagex_algorithms::{
+    level::{LevelAlgorithmFactoryBuilder, LevelInputValidationError},
+    traits::{Algorithm, AlgorithmFactory, AlgorithmFactoryBuilder}};
+
+fn build_graph(arr: &[(i32, i32)]) -> DirectedGraph {
+    let number_of_nodes = arr.iter()
+        .map(|(src, trg)| core::cmp::max(*src, *trg))
+        .max()
+        .unwrap_or(0);
+    let arrows = arr.iter().map(|(src, trg)| ArrowDTO::new(*src, *trg)).collect();
+    let dto = DirectedGraphDTO::new(number_of_nodes + 1, arrows);
+    DirectedGraph::from_dto(&dto).unwrap()
+}
+
+fn level_and_components(graph: &DirectedGraph) -> (usize, Vec<usize>) {
+    let mut factory = LevelAlgorithmFactoryBuilder::default().create().unwrap();
+    let result = factory.create(graph).unwrap().run().unwrap();
+    let mut components = result.component_reticulations().to_vec();
+    components.sort_unstable();
+    (result.level(), components)
+}
+
+#[test]
+fn test_tree_has_level_0() {
+    let network = const_parse_newick!("((A, B), (C, (D, E)));");
+    let (level, components) = level_and_components(network.graph());
+    assert_eq!(level, 0);
+    assert_eq!(network.reticulation_count(), 0);
+    assert_eq!(components.len(), 8);
+    assert!(components.iter().all(|count| *count == 0));
+}
+
+#[test]
+fn test_single_reticulation_has_level_1() {
+    let network = const_parse_newick!("((A,(D)B#1),(B#1,C));");
+    assert_eq!(network.reticulation_count(), 1);
+    let (level, components) = level_and_components(network.graph());
+    assert_eq!(level, 1);
+    assert_eq!(components.iter().filter(|count| **count == 1).count(), 1);
+}
+
+#[test]
+fn test_level_2() {
+    let graph = build_graph(&[
+        (0, 1), (0, 2), (1, 3), (2, 3), (1, 4), (2, 5), (4, 6), (5, 6), (3, 7), (6, 8)]);
+    let (level, components) = level_and_components(&graph);
+    assert_eq!(level, 2);
+    assert_eq!(components, vec![0, 0, 2]);
+}
+
+#[test]
+fn test_separate_blobs() {
+    let graph = build_graph(&[
+        (0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (4, 5), (4, 6), (5, 7), (6, 7), (7, 8),
+        (1, 9), (2, 10), (5, 11), (6, 12)]);
+    let (level, components) = level_and_components(&graph);
+    assert_eq!(level, 1);
+    assert_eq!(components.iter().filter(|count| **count == 1).count(), 2);
+}
+
+#[test]
+fn test_long_chain_of_blobs() {
+    let blobs = 50_000;
+    let mut arrows = Vec::new();
+    for idx in 0..blobs {
+        let top = 4 * idx;
+        arrows.extend([(top, top + 1), (top, top + 2), (top + 1, top + 3), (top + 2, top + 3), (top + 3, top + 4)]);
+    }
+    let graph = build_graph(&arrows);
+    let (level, components) = level_and_components(&graph);
+    assert_eq!(level, 1);
+    assert_eq!(components.iter().sum::<usize>(), 50_000);
+}
+
+#[test]
+fn test_validation() {
+    let graph = build_graph(&[(0, 1), (2, 1)]);
+    let mut factory = LevelAlgorithmFactoryBuilder::default().create().unwrap();
+    let result = factory.create(&graph);
+    assert!(matches!(result, Err(LevelInputValidationError::InputNotRooted)));
+}
+
+#[test]
+fn test_default_run_instrumented() {
+    let network = const_parse_newick!("((A, B), (C, (D, E)));");
+    let mut factory = LevelAlgorithmFactoryBuilder::default().create().unwrap();
+    let (result, metrics) = factory.create(network.graph()).unwrap().run_instrumented().unwrap();
+    assert_eq!(result.level(), 0);
+    assert_eq!(metrics.nodes_visited(), 0);
+    assert_eq!(metrics.allocations_estimate(), 0);
+}
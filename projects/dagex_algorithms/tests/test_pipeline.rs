@@ -0,0 +1,93 @@
+use dagex::phylo::{parse_newick_forest, parse_newick_from_str, NewickParseError, PhylogeneticNetwork};
+use dagex_algorithms::{
+    cancellation::CancellationTokenSource,
+    depth::{DepthAlgorithmFactoryBuilder, DepthInputValidationError},
+    pipeline::{Pipeline, PipelineError},
+    traits::{Algorithm, AlgorithmFactory, AlgorithmFactoryBuilder}};
+use rstest::rstest;
+
+const FOREST: &str = "(A,B);\n((A,B),C)[comment; with semicolon];\n(A,(B,(C,D)));\n(((A,B),C),((D,E),F));\n";
+
+#[allow(dead_code)]
+#[derive(Debug)]
+enum TestError {
+    Parse(NewickParseError),
+    Depth(DepthInputValidationError),
+    TooManyTaxa,
+}
+
+fn source<'a>(bytes: &'a mut &'static [u8]) -> impl Iterator<Item=Result<PhylogeneticNetwork, TestError>> + 'a {
+    parse_newick_forest(bytes)
+        .map(|result| {
+            result
+                .map(|result| result.network)
+                .map_err(TestError::Parse)
+        })
+}
+
+fn depth(network: PhylogeneticNetwork) -> Result<i32, TestError> {
+    let mut factory = DepthAlgorithmFactoryBuilder::default().create().unwrap();
+    let algo = factory.create(network.graph()).map_err(TestError::Depth)?;
+    Ok(algo.run().unwrap().max_depth())
+}
+
+#[rstest]
+#[case(0, 1)]
+#[case(0, 16)]
+#[case(3, 1)]
+#[case(3, 2)]
+fn test_pipeline_depth(#[case] workers: usize, #[case] max_in_flight: usize) {
+    let results: Vec<(usize, i32)> = Pipeline::new(depth)
+        .with_workers(workers)
+        .with_max_in_flight(max_in_flight)
+        .run(source(&mut FOREST.as_bytes()))
+        .map(|(index, result)| (index, result.unwrap()))
+        .collect();
+    assert_eq!(results, vec![(0, 1), (1, 2), (2, 3), (3, 3)]);
+}
+
+#[test]
+fn test_pipeline_validation() {
+    let results: Vec<_> = Pipeline::new(depth)
+        .with_validation(|network| {
+            if network.taxa().len() > 3 {
+                Err(TestError::TooManyTaxa)
+            }
+            else
+            {
+                Ok(())
+            }
+        })
+        .with_workers(2)
+        .run(source(&mut FOREST.as_bytes()))
+        .collect();
+    assert_eq!(results.len(), 4);
+    assert!(matches!(results[1].1, Ok(2)));
+    assert!(matches!(results[2].1, Err(PipelineError::Stage(TestError::TooManyTaxa))));
+    assert!(matches!(results[3].1, Err(PipelineError::Stage(TestError::TooManyTaxa))));
+}
+
+#[test]
+fn test_pipeline_cancellation() {
+    let cancellation = CancellationTokenSource::new();
+    cancellation.cancel();
+    let mut bytes = FOREST.as_bytes();
+    let mut iterator = Pipeline::new(depth)
+        .with_cancellation(cancellation.token())
+        .run(source(&mut bytes));
+    assert!(iterator.next().is_none());
+}
+
+#[test]
+fn test_pipeline_error_source() {
+    use std::error::Error;
+
+    let error = PipelineError::Stage(parse_newick_from_str("(A,B,C);").unwrap_err());
+    assert_eq!(error.to_string(), "pipeline stage failed");
+    let stage = error.source().unwrap().downcast_ref::<NewickParseError>().unwrap();
+    assert!(matches!(stage, NewickParseError::PhylogeneticNetworkError(_)));
+    assert!(stage.source().is_some());
+    let cancelled = PipelineError::<NewickParseError>::Cancelled;
+    assert_eq!(cancelled.to_string(), "pipeline cancelled");
+    assert!(cancelled.source().is_none());
+}
@@ -0,0 +1,85 @@
+use dagex::{const_parse_newick, core::Node, phylo::{GenesOverSpecies, PhylogeneticNetwork}};
+use dagex_algorithms::{
+    reconciliation::{
+        ReconciliationAlgorithmFactoryBuilder,
+        ReconciliationInputValidationError},
+    traits::{
+        Algorithm,
+        AlgorithmFactory,
+        AlgorithmFactoryBuilder}};
+
+fn leaf(network: &PhylogeneticNetwork, taxon: &str) -> Node {
+    network.get_single_by_taxon(taxon).unwrap()
+}
+
+fn parent(network: &PhylogeneticNetwork, node: Node) -> Node {
+    network.graph().get_predecessors(node)[0]
+}
+
+#[test]
+fn test_reconciliation_with_duplication() {
+    let genes = const_parse_newick!("((a, b), (a, c));");
+    let genes_id = genes.id();
+    let gene_b = leaf(&genes, "b");
+    let gene_c = leaf(&genes, "c");
+    let gene_ab = parent(&genes, gene_b);
+    let gene_ac = parent(&genes, gene_c);
+    let gene_root = genes.root();
+
+    let species = const_parse_newick!("((a, b), c);");
+    let species_ab = parent(&species, leaf(&species, "a"));
+    let species_b = leaf(&species, "b");
+    let species_root = species.root();
+
+    let genes_over_species = GenesOverSpecies::new_single_gene(genes, species).unwrap();
+    let mut factory = ReconciliationAlgorithmFactoryBuilder::default().create().unwrap();
+    let algo = factory.create(&genes_over_species).unwrap();
+    let result = algo.run().unwrap();
+
+    assert_eq!(result.image(genes_id, gene_b), species_b);
+    assert_eq!(result.image(genes_id, gene_ab), species_ab);
+    assert_eq!(result.image(genes_id, gene_ac), species_root);
+    assert_eq!(result.image(genes_id, gene_root), species_root);
+    assert_eq!(result.number_of_duplications(), 1);
+    assert_eq!(result.number_of_speciations(), 2);
+}
+
+#[test]
+fn test_reconciliation_of_congruent_trees() {
+    let genes = const_parse_newick!("(((a, b), c), d);");
+    let genes_id = genes.id();
+    let gene_c = leaf(&genes, "c");
+    let gene_abc = parent(&genes, gene_c);
+    let gene_root = genes.root();
+
+    let species = const_parse_newick!("(((a, b), c), (d, e));");
+    let species_abc = parent(&species, leaf(&species, "c"));
+    let species_root = species.root();
+
+    let genes_over_species = GenesOverSpecies::new_single_gene(genes, species).unwrap();
+    let mut factory = ReconciliationAlgorithmFactoryBuilder::default().create().unwrap();
+    let result = factory.create(&genes_over_species).unwrap().run().unwrap();
+
+    assert_eq!(result.image(genes_id, gene_abc), species_abc);
+    assert_eq!(result.image(genes_id, gene_root), species_root);
+    assert_eq!(result.number_of_duplications(), 0);
+    assert_eq!(result.number_of_speciations(), 3);
+}
+
+#[test]
+fn test_reconciliation_rejects_invalid_input() {
+    let mut factory = ReconciliationAlgorithmFactoryBuilder::default().create().unwrap();
+
+    let genes = const_parse_newick!("(A, D);");
+    let species = const_parse_newick!("((A, (D)B#1),(B#1, C));");
+    let genes_over_species = GenesOverSpecies::new_single_gene(genes, species).unwrap();
+    let result = factory.create(&genes_over_species);
+    assert!(matches!(result, Err(ReconciliationInputValidationError::SpeciesNotTree)));
+
+    let genes = const_parse_newick!("(a, );");
+    let genes_id = genes.id();
+    let species = const_parse_newick!("(a, b);");
+    let genes_over_species = GenesOverSpecies::new_single_gene(genes, species).unwrap();
+    let result = factory.create(&genes_over_species);
+    assert!(matches!(result, Err(ReconciliationInputValidationError::UnlabeledGeneLeaf(id, _)) if id == genes_id));
+}
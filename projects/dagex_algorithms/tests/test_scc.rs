@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+
+use dagex::core::{ArrowDTO, DirectedGraph, DirectedGraphDTO, Node};
+use dagex_algorithms::{
+    cancellation::CancellationTokenSource,
+    scc::{SccAlgorithmFactoryBuilder, SccResult},
+    traits::{Algorithm, AlgorithmError, AlgorithmFactory, AlgorithmFactoryBuilder}};
+use rstest::rstest;
+
+fn build_graph(arr: &[(i32, i32)], number_of_nodes: i32) -> DirectedGraph {
+    let arrows = arr.iter().map(|(src, trg)| ArrowDTO::new(*src, *trg)).collect();
+    DirectedGraph::from_dto(&DirectedGraphDTO::new(number_of_nodes, arrows)).unwrap()
+}
+
+fn run(graph: &DirectedGraph) -> SccResult<'_> {
+    let mut factory = SccAlgorithmFactoryBuilder::default().create().unwrap();
+    let Ok(algo) = factory.create(graph) else {
+        panic!("Expected valid input.");
+    };
+    algo.run().unwrap()
+}
+
+/// Components as sets of node ids, independent of numbering.
+fn components(result: &SccResult) -> HashSet<Vec<i32>> {
+    result.iter_components()
+        .map(|nodes| nodes.iter().map(Node::id).collect())
+        .collect()
+}
+
+fn assert_consistent(graph: &DirectedGraph, result: &SccResult) {
+    let condensation = DirectedGraph::from_dto(result.condensation()).unwrap();
+    assert!(condensation.basic_properties().acyclic);
+    assert_eq!(condensation.number_of_nodes() as usize, result.component_count());
+    assert_eq!(result.iter_components().count(), result.component_count());
+    for (component, nodes) in result.iter_components().enumerate() {
+        for node in nodes {
+            assert_eq!(result.component_of(*node) as usize, component);
+        }
+    }
+    for (source, target) in graph.iter_arrows() {
+        let (first, second) = (result.component_of(source), result.component_of(target));
+        assert!(first <= second);
+        if first != second {
+            let expected = ArrowDTO::new(first as i32, second as i32);
+            assert!(result.condensation().arrows().contains(&expected));
+        }
+    }
+}
+
+#[rstest]
+#[case(&[(0, 1), (1, 0), (2, 3), (3, 2)], 4, vec![vec![0, 1], vec![2, 3]], 0)]
+#[case(&[(0, 1), (1, 0), (2, 0)], 3, vec![vec![0, 1], vec![2]], 1)]
+#[case(&[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (1, 4)], 6, vec![vec![0, 1, 2], vec![3, 4], vec![5]], 1)]
+#[case(&[(0, 1), (0, 2), (1, 3), (2, 3)], 4, vec![vec![0], vec![1], vec![2], vec![3]], 4)]
+fn test_scc(
+    #[case] arrows: &[(i32, i32)],
+    #[case] number_of_nodes: i32,
+    #[case] expected: Vec<Vec<i32>>,
+    #[case] condensation_arrows: usize)
+{
+    let graph = build_graph(arrows, number_of_nodes);
+    let result = run(&graph);
+    assert_eq!(components(&result), expected.into_iter().collect());
+    assert_eq!(result.condensation().arrows().len(), condensation_arrows);
+    assert_consistent(&graph, &result);
+}
+
+#[test]
+fn test_rooted_cycle_order() {
+    let graph = build_graph(&[(0, 1), (1, 0), (2, 0)], 3);
+    let result = run(&graph);
+    assert_eq!(result.component_count(), 2);
+    assert_eq!(result.component_of(Node::from(2)), 0);
+    assert_eq!(result.component_of(Node::from(0)), 1);
+    assert_eq!(result.component_of(Node::from(1)), 1);
+    assert_eq!(result.condensation(), &DirectedGraphDTO::new(2, vec![ArrowDTO::new(0, 1)]));
+}
+
+#[test]
+fn test_components_match_reachability() {
+    let size = 40;
+    let arrows: Vec<(i32, i32)> = (0..size)
+        .flat_map(|idx| [(idx, (idx * 7 + 3) % size), (idx, (idx * 11 + 5) % size)])
+        .filter(|(source, target)| source != target && (source + target) % 3 != 0)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let graph = build_graph(&arrows, size);
+    let result = run(&graph);
+    assert_consistent(&graph, &result);
+    assert!(result.component_count() < size as usize);
+    for source in graph.iter_nodes() {
+        for target in graph.iter_nodes() {
+            let same = result.component_of(source) == result.component_of(target);
+            assert_eq!(same, graph.is_reachable(source, target) && graph.is_reachable(target, source));
+        }
+    }
+}
+
+#[test]
+fn test_long_cycle_and_cancellation() {
+    let size = 200_000;
+    let arrows: Vec<(i32, i32)> = (0..size).map(|idx| (idx, (idx + 1) % size)).collect();
+    let graph = build_graph(&arrows, size);
+    let result = run(&graph);
+    assert_eq!(result.component_count(), 1);
+    assert!(result.condensation().arrows().is_empty());
+
+    let mut factory = SccAlgorithmFactoryBuilder::default().create().unwrap();
+    let Ok(algo) = factory.create(&graph) else {
+        panic!("Expected valid input.");
+    };
+    let source = CancellationTokenSource::new();
+    source.cancel();
+    let mut ct = source.token();
+    assert!(matches!(algo.run_with_cancellation(&mut ct), Err(AlgorithmError::Cancelled)));
+}
@@ -0,0 +1,87 @@
+use dagex::{const_parse_newick, core::{ArrowDTO, DirectedGraph, DirectedGraphDTO, Node}};
+use dagex_algorithms::{
+    depth::{DepthAlgorithmFactoryBuilder, DepthInputValidationError},
+    level::LevelAlgorithmFactoryBuilder,
+    traits::{Algorithm, AlgorithmFactory, AlgorithmFactoryBuilder},
+    validated_dag::{DagValidationError, ValidatedRootedDag}};
+use rstest::rstest;
+
+fn build_graph(arr: &[(i32, i32)], number_of_nodes: i32) -> DirectedGraph {
+    let arrows = arr.iter().map(|(src, trg)| ArrowDTO::new(*src, *trg)).collect();
+    DirectedGraph::from_dto(&DirectedGraphDTO::new(number_of_nodes, arrows)).unwrap()
+}
+
+#[rstest]
+#[case(&[(0, 1), (2, 1)], 3, DagValidationError::InputNotRooted)]
+#[case(&[(0, 1), (1, 2), (2, 1)], 3, DagValidationError::InputNotAcyclic)]
+fn test_validation(#[case] arrows: &[(i32, i32)], #[case] number_of_nodes: i32, #[case] expected: DagValidationError) {
+    let graph = build_graph(arrows, number_of_nodes);
+    assert_eq!(ValidatedRootedDag::new(&graph).err(), Some(expected));
+}
+
+#[test]
+fn test_cached_data() {
+    let graph = build_graph(&[(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (0, 4)], 5);
+    let dag = ValidatedRootedDag::new(&graph).unwrap();
+    assert_eq!(dag.computations(), 0);
+    assert_eq!(dag.root(), Node::from(0));
+
+    let order = dag.topological_order().to_vec();
+    assert_eq!(order.len(), 5);
+    for (source, target) in graph.iter_arrows() {
+        let position = |node| order.iter().position(|n| *n == node).unwrap();
+        assert!(position(source) < position(target));
+    }
+    assert_eq!(dag.computations(), 1);
+
+    assert_eq!(dag.max_depth(), 3);
+    assert_eq!(dag.depths().get(&graph, Node::from(4)), Some(&3));
+    assert_eq!(dag.computations(), 2);
+
+    let postorder: Vec<i32> = dag.postorder().iter().map(|n| n.id()).collect();
+    assert_eq!(postorder, vec![4, 3, 1, 2, 0]);
+    assert_eq!(dag.postorder_numbers().get(&graph, Node::from(2)), Some(&3));
+    assert_eq!(dag.computations(), 3);
+
+    dag.topological_order();
+    dag.depths();
+    dag.postorder();
+    assert_eq!(dag.computations(), 3);
+}
+
+#[test]
+fn test_algorithms_share_validated_dag() {
+    let network = const_parse_newick!("(((A, (B)#H1), ((#H1, C), (D, (E)#H2))), #H2);");
+    let graph = network.graph();
+    let dag = ValidatedRootedDag::new(graph).unwrap();
+    let mut depth_factory = DepthAlgorithmFactoryBuilder::default().create().unwrap();
+    let mut level_factory = LevelAlgorithmFactoryBuilder::default().create().unwrap();
+
+    let raw = depth_factory.create(graph).unwrap().run().unwrap();
+    let first = depth_factory.create_validated(&dag).unwrap().run().unwrap();
+    let computations = dag.computations();
+    assert!(computations > 0);
+    let second = depth_factory.create_validated(&dag).unwrap().run().unwrap();
+    assert_eq!(dag.computations(), computations);
+    for result in [&first, &second] {
+        assert_eq!(result.max_depth(), raw.max_depth());
+        assert_eq!(result.depths(), raw.depths());
+        assert_eq!(result.processed_nodes(), raw.processed_nodes());
+    }
+
+    let level = level_factory.create_validated(&dag).unwrap().run().unwrap();
+    let raw_level = level_factory.create(graph).unwrap().run().unwrap();
+    assert_eq!(level.level(), raw_level.level());
+    assert_eq!(dag.computations(), computations);
+}
+
+#[test]
+fn test_validated_dag_size_limit() {
+    let graph = build_graph(&[(0, 1), (1, 2)], 3);
+    let dag = ValidatedRootedDag::new(&graph).unwrap();
+    let mut builder = DepthAlgorithmFactoryBuilder::default();
+    builder.set_max_nodes(2);
+    let mut factory = builder.create().unwrap();
+    let result = factory.create_validated(&dag);
+    assert!(matches!(result, Err(DepthInputValidationError::GraphTooBig { limit: 2, size: 3 })));
+}
@@ -0,0 +1,148 @@
+use std::{fs, io::{BufWriter, Write}, path::Path};
+
+use dagex::phylo::PhylogeneticNetwork;
+
+use crate::{Layout, RenderError, Renderer, SvgOptions, SvgRenderer};
+
+const STYLE: &str = "\
+body { margin: 0; display: flex; height: 100vh; font-family: sans-serif; }
+#properties { width: 14em; padding: 1em; border-right: 1px solid #ccc; }
+#properties dt { font-weight: bold; }
+#view { flex: 1; overflow: hidden; cursor: grab; }
+#view svg { width: 100%; height: 100%; }
+#tooltip { position: fixed; pointer-events: none; padding: 0.3em 0.5em;
+  background: #ffffe0; border: 1px solid #999; font-size: 12px; white-space: pre; }
+";
+
+const SCRIPT: &str = "\
+(function () {
+  const svg = document.querySelector('#view svg');
+  const tooltip = document.getElementById('tooltip');
+  let box = svg.viewBox.baseVal;
+  let drag = null;
+  svg.addEventListener('wheel', function (event) {
+    event.preventDefault();
+    const scale = event.deltaY > 0 ? 1.1 : 1 / 1.1;
+    const rect = svg.getBoundingClientRect();
+    const x = box.x + (event.clientX - rect.left) / rect.width * box.width;
+    const y = box.y + (event.clientY - rect.top) / rect.height * box.height;
+    box.x = x - (x - box.x) * scale;
+    box.y = y - (y - box.y) * scale;
+    box.width *= scale;
+    box.height *= scale;
+  });
+  svg.addEventListener('mousedown', function (event) {
+    drag = { x: event.clientX, y: event.clientY };
+  });
+  window.addEventListener('mouseup', function () { drag = null; });
+  window.addEventListener('mousemove', function (event) {
+    if (drag === null) { return; }
+    const rect = svg.getBoundingClientRect();
+    box.x -= (event.clientX - drag.x) / rect.width * box.width;
+    box.y -= (event.clientY - drag.y) / rect.height * box.height;
+    drag = { x: event.clientX, y: event.clientY };
+  });
+  svg.querySelectorAll('circle[data-node]').forEach(function (circle) {
+    circle.addEventListener('mouseenter', function (event) {
+      const data = circle.dataset;
+      let text = 'node ' + data.node + '\\nin-degree ' + data.inDegree + '\\nout-degree ' + data.outDegree;
+      if (data.taxon !== undefined) { text += '\\ntaxon ' + data.taxon; }
+      tooltip.textContent = text;
+      tooltip.style.left = (event.clientX + 12) + 'px';
+      tooltip.style.top = (event.clientY + 12) + 'px';
+      tooltip.hidden = false;
+    });
+    circle.addEventListener('mouseleave', function () { tooltip.hidden = true; });
+  });
+})();
+";
+
+/// Renders self-contained HTML documents: the SVG drawing of
+/// [`SvgRenderer`] with pan and zoom, tooltips of nodes and a sidebar of
+/// network properties. Everything is inlined, so documents work offline.
+#[derive(Clone, Debug, Default)]
+pub struct HtmlRenderer {
+    svg: SvgRenderer,
+}
+
+impl HtmlRenderer {
+    pub fn new(options: SvgOptions) -> Self {
+        Self { svg: SvgRenderer::new(options) }
+    }
+
+    #[inline(always)]
+    pub fn options(&self) -> &SvgOptions { self.svg.options() }
+
+    /// Lays out `network` and renders it into a string.
+    ///
+    /// # Panics
+    /// Only if `network` breaks its invariants, see [`Layout::new`].
+    pub fn render_to_string(&self, network: &PhylogeneticNetwork) -> String {
+        let layout = Layout::new(network);
+        let mut output = Vec::new();
+        self.render(network, &layout, &mut output)
+            .expect("Writing to memory doesn't fail.");
+        String::from_utf8(output).expect("Rendered HTML is valid utf-8.")
+    }
+
+    /// Lays out `network` and renders it into file at `path`, replacing
+    /// the file if it exists.
+    ///
+    /// # Panics
+    /// Only if `network` breaks its invariants, see [`Layout::new`].
+    ///
+    /// # Errors
+    /// For concrete errors see [`RenderError`] docs.
+    pub fn render_to_file(&self, network: &PhylogeneticNetwork, path: &Path)
+        -> Result<(), RenderError>
+    {
+        let layout = Layout::new(network);
+        let mut output = BufWriter::new(fs::File::create(path)?);
+        self.render(network, &layout, &mut output)?;
+        output.into_inner()
+            .map_err(|err| RenderError::Io(err.into_error()))?
+            .sync_all()?;
+        Ok(())
+    }
+}
+
+impl Renderer for HtmlRenderer {
+    fn render(&self, network: &PhylogeneticNetwork, layout: &Layout, out: &mut impl Write)
+        -> Result<(), RenderError>
+    {
+        let properties = network.graph().basic_properties();
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, r#"<html lang="en">"#)?;
+        writeln!(out, r#"<head><meta charset="utf-8"><title>Phylogenetic network</title>"#)?;
+        writeln!(out, "<style>\n{STYLE}</style>")?;
+        writeln!(out, "</head>")?;
+        writeln!(out, "<body>")?;
+        writeln!(out, r#"<aside id="properties"><h2>Properties</h2><dl>"#)?;
+        let rows = [
+            ("acyclic", properties.acyclic.to_string()),
+            ("rooted", properties.rooted.to_string()),
+            ("binary", properties.binary.to_string()),
+            ("reticulations", network.reticulation_count().to_string()),
+            ("nodes", network.graph().number_of_nodes().to_string()),
+            ("taxa", network.taxa().len().to_string()),
+        ];
+        for (name, value) in rows {
+            writeln!(out, "<dt>{name}</dt><dd>{value}</dd>")?;
+        }
+        writeln!(out, "</dl></aside>")?;
+
+        let (width, height) = self.svg.size(layout);
+        writeln!(out, r#"<main id="view">"#)?;
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width:.2} {height:.2}">"#)?;
+        self.svg.render_content(network, layout, true, out)?;
+        writeln!(out, "</svg>")?;
+        writeln!(out, "</main>")?;
+        writeln!(out, r#"<div id="tooltip" hidden></div>"#)?;
+        writeln!(out, "<script>\n{SCRIPT}</script>")?;
+        writeln!(out, "</body>")?;
+        writeln!(out, "</html>")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use dagex::{core::Node, phylo::PhylogeneticNetwork};
+
+/// Point in layout units, see [`Layout`].
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Layered placement of network nodes. The root is at layer 0, every other
+/// node one layer below its deepest parent, except leaves which all go to
+/// the bottom layer. Leaves get consecutive integer `x` coordinates in
+/// depth-first order, and every other node is centered over its children.
+/// `y` equals the layer. Coordinates are deterministic for a given network.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Layout {
+    positions: Vec<Point>,
+    layers: Vec<usize>,
+    number_of_layers: usize,
+    number_of_leaves: usize,
+}
+
+impl Layout {
+    /// # Panics
+    /// Only if `network` breaks its invariants, i.e. is not rooted or not
+    /// acyclic.
+    #[allow(clippy::cast_sign_loss, clippy::cast_precision_loss)]
+    pub fn new(network: &PhylogeneticNetwork) -> Self {
+        let graph = network.graph();
+        let size = graph.number_of_nodes() as usize;
+        let levels: Vec<Vec<Node>> = graph.iter_levels()
+            .expect("Phylogenetic networks are rooted and acyclic.")
+            .collect();
+        let bottom = levels.len() - 1;
+        let mut layers = vec![0; size];
+        for (layer, nodes) in levels.iter().enumerate() {
+            for node in nodes {
+                layers[idx(*node)] = if graph.is_leaf(*node) { bottom } else { layer };
+            }
+        }
+
+        let mut positions = vec![Point::default(); size];
+        let mut visited = vec![false; size];
+        let mut number_of_leaves = 0;
+        let mut stack = vec![network.root()];
+        while let Some(node) = stack.pop() {
+            if visited[idx(node)] {
+                continue;
+            }
+            visited[idx(node)] = true;
+            if graph.is_leaf(node) {
+                positions[idx(node)].x = number_of_leaves as f64;
+                number_of_leaves += 1;
+            }
+            stack.extend(graph.get_successors(node).iter().rev());
+        }
+
+        for nodes in levels.iter().rev() {
+            for node in nodes {
+                let successors = graph.get_successors(*node);
+                if !successors.is_empty() {
+                    let sum: f64 = successors.iter().map(|child| positions[idx(*child)].x).sum();
+                    positions[idx(*node)].x = sum / successors.len() as f64;
+                }
+                positions[idx(*node)].y = layers[idx(*node)] as f64;
+            }
+        }
+
+        Self { positions, layers, number_of_layers: levels.len(), number_of_leaves }
+    }
+
+    /// # Panics
+    /// When `node` is not in the laid out network.
+    #[inline(always)]
+    pub fn position(&self, node: Node) -> Point { self.positions[idx(node)] }
+
+    /// # Panics
+    /// When `node` is not in the laid out network.
+    #[inline(always)]
+    pub fn layer(&self, node: Node) -> usize { self.layers[idx(node)] }
+
+    #[inline(always)]
+    pub fn number_of_layers(&self) -> usize { self.number_of_layers }
+
+    /// Number of leaves, i.e. of distinct `x` coordinates on the bottom
+    /// layer.
+    #[inline(always)]
+    pub fn number_of_leaves(&self) -> usize { self.number_of_leaves }
+}
+
+#[allow(clippy::cast_sign_loss)]
+#[inline(always)]
+fn idx(node: Node) -> usize {
+    node.id() as usize
+}
@@ -0,0 +1,19 @@
+#![warn(clippy::all, clippy::pedantic)]
+#![allow(
+    clippy::needless_return,
+    clippy::redundant_field_names,
+    clippy::unreadable_literal,
+    clippy::inline_always,
+    clippy::must_use_candidate,
+    clippy::module_name_repetitions,
+)]
+mod html;
+mod layout;
+mod renderer;
+mod svg;
+mod xml;
+
+pub use html::*;
+pub use layout::*;
+pub use renderer::*;
+pub use svg::*;
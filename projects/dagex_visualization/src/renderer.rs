@@ -0,0 +1,43 @@
+use core::fmt::{Display, Formatter};
+use std::io::Write;
+
+use dagex::phylo::PhylogeneticNetwork;
+
+use crate::Layout;
+
+/// Draws a laid out network into some output format.
+pub trait Renderer {
+    /// Writes `network` placed according to `layout` into `out`. `layout`
+    /// has to be computed for `network`.
+    ///
+    /// # Errors
+    /// For concrete errors see [`RenderError`] docs.
+    fn render(&self, network: &PhylogeneticNetwork, layout: &Layout, out: &mut impl Write)
+        -> Result<(), RenderError>;
+}
+
+#[derive(Debug)]
+pub enum RenderError {
+    /// Forwarded error of the output.
+    Io(std::io::Error),
+}
+
+impl From<std::io::Error> for RenderError {
+    fn from(value: std::io::Error) -> Self { Self::Io(value) }
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RenderError::Io(_) => f.write_str("failed to write rendered network"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RenderError::Io(err) => Some(err),
+        }
+    }
+}
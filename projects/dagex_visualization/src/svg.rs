@@ -0,0 +1,197 @@
+use std::io::Write;
+
+use dagex::{core::Node, phylo::PhylogeneticNetwork};
+
+use crate::{xml::Escaped, Layout, Point, RenderError, Renderer};
+
+/// Sizes used by [`SvgRenderer`], in pixels.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct SvgOptions {
+    node_radius: f64,
+    horizontal_spacing: f64,
+    vertical_spacing: f64,
+    margin: f64,
+    font_size: f64,
+}
+
+impl SvgOptions {
+    #[inline(always)]
+    pub fn node_radius(&self) -> f64 { self.node_radius }
+
+    /// Distance between neighbouring leaves.
+    #[inline(always)]
+    pub fn horizontal_spacing(&self) -> f64 { self.horizontal_spacing }
+
+    /// Distance between consecutive layers.
+    #[inline(always)]
+    pub fn vertical_spacing(&self) -> f64 { self.vertical_spacing }
+
+    /// Empty space around the drawing. Leaves additionally get room for
+    /// taxa below them.
+    #[inline(always)]
+    pub fn margin(&self) -> f64 { self.margin }
+
+    #[inline(always)]
+    pub fn font_size(&self) -> f64 { self.font_size }
+
+    #[must_use]
+    pub fn with_node_radius(mut self, value: f64) -> Self {
+        self.node_radius = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_horizontal_spacing(mut self, value: f64) -> Self {
+        self.horizontal_spacing = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_vertical_spacing(mut self, value: f64) -> Self {
+        self.vertical_spacing = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_margin(mut self, value: f64) -> Self {
+        self.margin = value;
+        self
+    }
+
+    #[must_use]
+    pub fn with_font_size(mut self, value: f64) -> Self {
+        self.font_size = value;
+        self
+    }
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            node_radius: 5.0,
+            horizontal_spacing: 40.0,
+            vertical_spacing: 50.0,
+            margin: 20.0,
+            font_size: 12.0,
+        }
+    }
+}
+
+/// Renders standalone SVG documents. Every node is a `<circle>`, every
+/// arrow a `<path>`, with reticulation arrows dashed, and taxa are
+/// `<text>` elements below leaves.
+#[derive(Clone, Debug, Default)]
+pub struct SvgRenderer {
+    options: SvgOptions,
+}
+
+impl SvgRenderer {
+    pub fn new(options: SvgOptions) -> Self {
+        Self { options }
+    }
+
+    #[inline(always)]
+    pub fn options(&self) -> &SvgOptions { &self.options }
+
+    /// Size of the whole drawing of `layout`, as `(width, height)`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn size(&self, layout: &Layout) -> (f64, f64) {
+        let options = &self.options;
+        let columns = layout.number_of_leaves().saturating_sub(1) as f64;
+        let rows = layout.number_of_layers().saturating_sub(1) as f64;
+        let width = columns * options.horizontal_spacing + 2.0 * options.margin;
+        let height = rows * options.vertical_spacing + 2.0 * options.margin + 2.0 * options.font_size;
+        (width, height)
+    }
+
+    /// Position of `node` in the drawing, in pixels.
+    pub fn pixel_position(&self, layout: &Layout, node: Node) -> Point {
+        let options = &self.options;
+        let position = layout.position(node);
+        Point {
+            x: options.margin + position.x * options.horizontal_spacing,
+            y: options.margin + position.y * options.vertical_spacing,
+        }
+    }
+
+    /// Writes `<g>` elements with arrows, nodes and taxa, without the
+    /// enclosing `<svg>` element. Nodes carry their id in `data-node`, and
+    /// with `detailed` also degrees and taxon in further `data-` attributes.
+    pub(crate) fn render_content(
+        &self,
+        network: &PhylogeneticNetwork,
+        layout: &Layout,
+        detailed: bool,
+        out: &mut impl Write) -> Result<(), RenderError>
+    {
+        let graph = network.graph();
+        writeln!(out, r#"<g class="arrows" fill="none" stroke="black">"#)?;
+        for (source, target) in graph.iter_arrows() {
+            let from = self.pixel_position(layout, source);
+            let to = self.pixel_position(layout, target);
+            let style = if network.is_reticulation_node(target) {
+                r#" class="reticulation" stroke-dasharray="4 3""#
+            }
+            else
+            {
+                ""
+            };
+            writeln!(
+                out,
+                r#"<path d="M {:.2} {:.2} L {:.2} {:.2}"{style}/>"#,
+                from.x, from.y, to.x, to.y)?;
+        }
+        writeln!(out, "</g>")?;
+
+        writeln!(out, r#"<g class="nodes" fill="white" stroke="black">"#)?;
+        for node in graph.iter_nodes() {
+            let center = self.pixel_position(layout, node);
+            write!(
+                out,
+                r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" data-node="{}""#,
+                center.x, center.y, self.options.node_radius, node.id())?;
+            if detailed {
+                write!(
+                    out,
+                    r#" data-in-degree="{}" data-out-degree="{}""#,
+                    graph.in_degree(node), graph.out_degree(node))?;
+                if let Some(taxon) = network.taxa().get(&node) {
+                    write!(out, r#" data-taxon="{}""#, Escaped(taxon.value().as_str()))?;
+                }
+            }
+            writeln!(out, "/>")?;
+        }
+        writeln!(out, "</g>")?;
+
+        writeln!(
+            out,
+            r#"<g class="taxa" font-family="sans-serif" font-size="{:.2}" text-anchor="middle">"#,
+            self.options.font_size)?;
+        let mut labeled: Vec<_> = network.taxa().iter().collect();
+        labeled.sort_unstable_by_key(|(node, _)| node.id());
+        for (node, taxon) in labeled {
+            let center = self.pixel_position(layout, *node);
+            let y = center.y + self.options.node_radius + self.options.font_size;
+            writeln!(
+                out,
+                r#"<text x="{:.2}" y="{:.2}">{}</text>"#,
+                center.x, y, Escaped(taxon.value().as_str()))?;
+        }
+        writeln!(out, "</g>")?;
+        Ok(())
+    }
+}
+
+impl Renderer for SvgRenderer {
+    fn render(&self, network: &PhylogeneticNetwork, layout: &Layout, out: &mut impl Write)
+        -> Result<(), RenderError>
+    {
+        let (width, height) = self.size(layout);
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.2}" height="{height:.2}" viewBox="0 0 {width:.2} {height:.2}">"#)?;
+        self.render_content(network, layout, false, out)?;
+        writeln!(out, "</svg>")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use core::fmt::{Display, Formatter};
+
+/// Displays text with XML special characters escaped, so that it can be
+/// embedded in both element content and attribute values.
+pub(crate) struct Escaped<'a>(pub &'a str);
+
+impl Display for Escaped<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut rest = self.0;
+        while let Some(position) = rest.find(['&', '<', '>', '"', '\'']) {
+            f.write_str(&rest[..position])?;
+            let escaped = match rest.as_bytes()[position] {
+                b'&' => "&amp;",
+                b'<' => "&lt;",
+                b'>' => "&gt;",
+                b'"' => "&quot;",
+                _ => "&#39;",
+            };
+            f.write_str(escaped)?;
+            rest = &rest[position + 1..];
+        }
+        f.write_str(rest)
+    }
+}
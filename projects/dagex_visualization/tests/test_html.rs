@@ -0,0 +1,62 @@
+use std::{fs, path::PathBuf};
+
+use dagex::{const_parse_newick, phylo::parse_newick_from_str};
+use dagex_visualization::{HtmlRenderer, RenderError};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("dagex-test-html-{}-{name}", std::process::id()))
+}
+
+#[test]
+fn test_render_to_string() {
+    let network = const_parse_newick!("((A,(D)B#1),(B#1,C));");
+    let html = HtmlRenderer::default().render_to_string(&network);
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.trim_end().ends_with("</html>"));
+    assert_eq!(html.matches("<circle").count(), network.graph().number_of_nodes() as usize);
+    assert_eq!(html.matches("data-in-degree").count(), network.graph().number_of_nodes() as usize);
+    assert_eq!(html.matches("data-taxon").count(), network.taxa().len());
+    assert!(html.contains("<dt>acyclic</dt><dd>true</dd>"));
+    assert!(html.contains("<dt>rooted</dt><dd>true</dd>"));
+    assert!(html.contains("<dt>binary</dt><dd>true</dd>"));
+    assert!(html.contains("<dt>reticulations</dt><dd>1</dd>"));
+    assert!(html.contains("<script>"));
+    assert!(!html.replace("http://www.w3.org/2000/svg", "").contains("http"));
+    assert!(!html.contains(" src="));
+}
+
+#[test]
+fn test_node_details() {
+    let network = const_parse_newick!("(A,B);");
+    let html = HtmlRenderer::default().render_to_string(&network);
+    let root = network.root().id();
+    assert!(html.contains(&format!(r#"data-node="{root}" data-in-degree="0" data-out-degree="2"/>"#)));
+    let a = network.get_single_by_taxon("A").unwrap().id();
+    assert!(html.contains(&format!(r#"data-node="{a}" data-in-degree="1" data-out-degree="0" data-taxon="A"/>"#)));
+}
+
+#[test]
+fn test_escaping() {
+    let network = parse_newick_from_str("(('x<y','a&b'),C);").unwrap().network;
+    let html = HtmlRenderer::default().render_to_string(&network);
+    assert!(html.contains(r#"data-taxon="x&lt;y""#));
+    assert!(html.contains(">x&lt;y</text>"));
+    assert!(html.contains(r#"data-taxon="a&amp;b""#));
+    assert!(html.contains(">a&amp;b</text>"));
+    assert!(!html.contains("x<y"));
+    assert!(!html.contains("a&b"));
+}
+
+#[test]
+fn test_render_to_file() {
+    let network = const_parse_newick!("((A,B),C);");
+    let renderer = HtmlRenderer::default();
+    let path = temp_path("network.html");
+    renderer.render_to_file(&network, &path).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), renderer.render_to_string(&network));
+    fs::remove_file(&path).unwrap();
+
+    let missing = temp_path("missing-dir").join("network.html");
+    let result = renderer.render_to_file(&network, &missing);
+    assert!(matches!(result, Err(RenderError::Io(_))));
+}
@@ -0,0 +1,89 @@
+use dagex::{const_parse_newick, phylo::{parse_newick_from_str, PhylogeneticNetwork}};
+use dagex_visualization::{Layout, Renderer, SvgOptions, SvgRenderer};
+use rstest::rstest;
+
+fn render(network: &PhylogeneticNetwork, renderer: &SvgRenderer) -> String {
+    let layout = Layout::new(network);
+    let mut output = Vec::new();
+    renderer.render(network, &layout, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[rstest]
+#[case("((A,B),C);")]
+#[case("((A,(D)B#1),(B#1,C));")]
+#[case("((A,(B,(C)#H1)),((#H1,D),E));")]
+fn test_structure(#[case] newick: &str) {
+    let network = parse_newick_from_str(newick).unwrap().network;
+    let svg = render(&network, &SvgRenderer::default());
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert_eq!(svg.matches("<circle").count(), network.graph().number_of_nodes() as usize);
+    assert_eq!(svg.matches("<path").count(), network.graph().number_of_arrows());
+    assert_eq!(
+        svg.matches("stroke-dasharray").count(),
+        network.statistics().number_of_reticulation_arrows);
+    assert_eq!(svg.matches("<text").count(), network.taxa().len());
+    for taxon in network.taxa().values() {
+        assert!(svg.contains(&format!(">{}</text>", taxon.value().as_str())));
+    }
+}
+
+#[test]
+fn test_layout() {
+    let network = const_parse_newick!("((A,(D)B#1),(B#1,C));");
+    let layout = Layout::new(&network);
+    let root = network.root();
+    assert_eq!(layout.layer(root), 0);
+    assert_eq!(layout.number_of_leaves(), 3);
+    let bottom = layout.number_of_layers() - 1;
+    let mut leaf_xs: Vec<f64> = network.graph().leaves()
+        .iter()
+        .map(|leaf| {
+            assert_eq!(layout.layer(*leaf), bottom);
+            layout.position(*leaf).x
+        })
+        .collect();
+    leaf_xs.sort_by(f64::total_cmp);
+    assert_eq!(leaf_xs, vec![0.0, 1.0, 2.0]);
+    for (source, target) in network.graph().iter_arrows() {
+        assert!(layout.layer(source) < layout.layer(target));
+    }
+    let root_x = layout.position(root).x;
+    assert!(0.0 < root_x && root_x < 2.0);
+}
+
+#[test]
+fn test_deterministic() {
+    let network = const_parse_newick!("((A,(B,(C)#H1)),((#H1,D),E));");
+    let first = render(&network, &SvgRenderer::default());
+    let second = render(&network.clone(), &SvgRenderer::default());
+    assert_eq!(first, second);
+    assert_eq!(Layout::new(&network), Layout::new(&network));
+}
+
+#[test]
+fn test_options() {
+    let network = const_parse_newick!("(A,B);");
+    let options = SvgOptions::default()
+        .with_node_radius(3.0)
+        .with_horizontal_spacing(100.0)
+        .with_vertical_spacing(10.0)
+        .with_margin(7.0)
+        .with_font_size(8.0);
+    let renderer = SvgRenderer::new(options);
+    assert_eq!(renderer.size(&Layout::new(&network)), (114.0, 40.0));
+    let svg = render(&network, &renderer);
+    let root = network.root().id();
+    assert!(svg.contains(&format!(r#"<circle cx="57.00" cy="7.00" r="3.00" data-node="{root}"/>"#)));
+    assert!(svg.contains(r#"<path d="M 57.00 7.00 L 7.00 17.00"/>"#));
+    assert!(svg.contains(r#"<text x="107.00" y="28.00">B</text>"#));
+}
+
+#[test]
+fn test_escaping() {
+    let network = parse_newick_from_str("('a<b','c&\"d');").unwrap().network;
+    let svg = render(&network, &SvgRenderer::default());
+    assert!(svg.contains(">a&lt;b</text>"));
+    assert!(svg.contains(">c&amp;&quot;d</text>"));
+}